@@ -0,0 +1,432 @@
+// charmed-cli — Pilotage du reveil sans interface graphique (serveur, Raspberry Pi)
+// Lit et ecrit les memes fichiers que l'application de bureau, qui recharge
+// alarms.json des qu'il est modifie (voir storage::watch_alarms)
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+use chrono::Local;
+use clap::{Parser, Subcommand};
+use tracing_subscriber::EnvFilter;
+
+use charmed_core::apple_music::{self, AppleMusicClient};
+use charmed_core::fade::{self, FadeHandle, FadeTarget};
+use charmed_core::i18n::{self, tr, Msg};
+use charmed_core::library::MusicLibrary;
+use charmed_core::podcast::PodcastPlayer;
+use charmed_core::provider::MusicProvider;
+use charmed_core::radio::RadioPlayer;
+use charmed_core::spotify::{self, SpotifyClient};
+use charmed_core::youtube_music::{self, YouTubeMusicClient};
+use charmed_core::storage::AppConfig;
+use charmed_core::{alarm, alarm_list, audio, profiles, readiness, storage, AlarmEntry};
+
+/// Identifiant de l'application de bureau (nom de son dossier de donnees)
+const APP_IDENTIFIER: &str = "com.kuro.charmed";
+
+/// Volume d'une sonnerie de test sans alarme
+const TEST_RING_VOLUME: u8 = 80;
+
+#[derive(Parser)]
+#[command(name = "charmed", version, about = "Reveil Charmed en ligne de commande")]
+struct Cli {
+    /// Dossier de donnees (par defaut celui de l'application de bureau)
+    #[arg(long, global = true)]
+    data_dir: Option<PathBuf>,
+    /// Profil utilisateur (par defaut le profil actif)
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Liste les alarmes
+    List,
+    /// Ajoute une alarme active a l'heure donnee (HH:MM ou HH:MM:SS)
+    Add {
+        time: String,
+        /// Jours de sonnerie (Monday,Tuesday,...), tous les jours si absent
+        #[arg(long, value_delimiter = ',')]
+        days: Vec<String>,
+        /// Playlist Spotify (spotify:playlist:...), son local si absent
+        #[arg(long, default_value = "local")]
+        playlist: String,
+        /// Nom affiche de l'alarme
+        #[arg(long, default_value = "Alarme")]
+        name: String,
+        /// Volume 0-100 (reglage par defaut si absent)
+        #[arg(long)]
+        volume: Option<u8>,
+        /// Fondu d'entree, en secondes (reglage par defaut sans valeur)
+        #[arg(long, num_args = 0..=1)]
+        fade: Option<Option<u16>>,
+        /// Compte Spotify (compte actif si absent)
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Active ou desactive une alarme (identifiant ou debut d'identifiant)
+    Toggle { id: String },
+    /// Fait sonner une alarme tout de suite (son local sans alarme)
+    TestRing {
+        id: Option<String>,
+        /// Duree de la sonnerie, en secondes
+        #[arg(long, default_value_t = 30)]
+        seconds: u64,
+    },
+    /// Affiche la prochaine alarme
+    Next,
+}
+
+/// Dossier de donnees du profil choisi, et nom du profil (None = profil par defaut)
+fn profile_data_dir(cli: &Cli) -> Result<(PathBuf, Option<String>), String> {
+    let root = match cli.data_dir.clone() {
+        Some(dir) => dir,
+        None => directories::BaseDirs::new()
+            .ok_or("Dossier de donnees introuvable")?
+            .data_dir()
+            .join(APP_IDENTIFIER),
+    };
+    let profile = match cli.profile.as_deref() {
+        Some(name) => profiles::normalize_name(name)?,
+        None => profiles::load_active(&root),
+    };
+    Ok((profiles::profile_dir(&root, profile.as_deref()), profile))
+}
+
+fn load_alarms(data_dir: &Path) -> Result<Vec<AlarmEntry>, String> {
+    let (alarms, recovery) = storage::load_alarms(data_dir)?;
+    if let Some(recovery) = recovery {
+        tracing::warn!(reason = %recovery.reason, "alarms.json corrompu, mis de cote dans {}", recovery.quarantined.display());
+    }
+    Ok(alarms)
+}
+
+fn load_config(data_dir: &Path) -> Result<AppConfig, String> {
+    let (config, recovery) = storage::load_config(data_dir)?;
+    if let Some(recovery) = recovery {
+        tracing::warn!(reason = %recovery.reason, "config.json corrompu, mis de cote dans {}", recovery.quarantined.display());
+    }
+    Ok(config)
+}
+
+/// Identifiant complet d'une alarme designee par son identifiant ou un debut non ambigu
+fn resolve_id(alarms: &[AlarmEntry], id: &str, config: &AppConfig) -> Result<String, String> {
+    if alarms.iter().any(|a| a.id == id) {
+        return Ok(id.to_string());
+    }
+    let matches: Vec<&AlarmEntry> = alarms
+        .iter()
+        .filter(|a| !id.is_empty() && a.id.starts_with(id))
+        .collect();
+    match matches[..] {
+        [alarm] => Ok(alarm.id.clone()),
+        [] => Err(tr(config.locale, Msg::AlarmNotFound, &[&id])),
+        _ => Err(format!("Identifiant ambigu: {} ({} alarmes)", id, matches.len())),
+    }
+}
+
+fn describe(alarm: &AlarmEntry) -> String {
+    let days = if alarm.recurrence.is_some() {
+        "recurrence".to_string()
+    } else if alarm.days.is_empty() {
+        "tous les jours".to_string()
+    } else {
+        alarm.days.join(",")
+    };
+    format!(
+        "{}  {}  {}  {:<24}  {}  vol {}",
+        alarm.id.get(..8).unwrap_or(&alarm.id),
+        alarm.time,
+        match (alarm.archived, alarm.active) {
+            (true, _) => "arc",
+            (false, true) => "on ",
+            (false, false) => "off",
+        },
+        days,
+        alarm.playlist_name,
+        alarm.volume,
+    )
+}
+
+/// Client Spotify du compte de l'alarme (compte actif si absent)
+fn spotify_client(
+    data_dir: &Path,
+    user: Option<&str>,
+    config: &AppConfig,
+    account: Option<&str>,
+) -> Result<SpotifyClient, String> {
+    let profile = account
+        .or(config.active_spotify_profile.as_deref())
+        .ok_or_else(|| tr(config.locale, Msg::SpotifyNotConnected, &[]))?;
+    spotify::load_accounts(data_dir, user)
+        .into_iter()
+        .find(|account| account.profile == profile)
+        .map(|account| {
+            SpotifyClient::from_stored(account, &config.spotify_redirect_uri)
+                .with_device_preference(config.spotify_devices.clone())
+        })
+        .ok_or_else(|| tr(config.locale, Msg::SpotifyAccountNotFound, &[&profile]))
+}
+
+/// Sortie de l'alarme: son service, accompagne du son local si la lecture groupee le demande
+fn playback_target(data_dir: &Path, user: Option<&str>, config: &AppConfig, alarm: &AlarmEntry) -> Result<FadeTarget, String> {
+    let target = service_target(data_dir, user, config, alarm)?;
+    let local_sound = alarm.group_playback.as_ref().is_some_and(|g| g.local_sound);
+    Ok(match target {
+        FadeTarget::Spotify(_) | FadeTarget::AppleMusic(_) | FadeTarget::YouTubeMusic(_) if local_sound => {
+            FadeTarget::Group(vec![target, FadeTarget::Local])
+        }
+        target => target,
+    })
+}
+
+/// Service qui joue la playlist d'une alarme (Spotify, Apple Music, YouTube Music, bibliotheque locale, radio, podcast)
+fn service_target(data_dir: &Path, user: Option<&str>, config: &AppConfig, alarm: &AlarmEntry) -> Result<FadeTarget, String> {
+    if AppleMusicClient::handles(&alarm.playlist_uri) {
+        let tokens = apple_music::load_tokens(user)?
+            .ok_or_else(|| tr(config.locale, Msg::AppleMusicNotConnected, &[]))?;
+        return Ok(FadeTarget::AppleMusic(Box::new(AppleMusicClient::new(tokens)?)));
+    }
+    if YouTubeMusicClient::handles(&alarm.playlist_uri) {
+        let credentials = youtube_music::load_credentials(user)?
+            .ok_or_else(|| tr(config.locale, Msg::YouTubeMusicNotConnected, &[]))?;
+        return Ok(FadeTarget::YouTubeMusic(Box::new(YouTubeMusicClient::new(credentials)?)));
+    }
+    if PodcastPlayer::handles(&alarm.playlist_uri) {
+        return Ok(FadeTarget::Podcast(Box::new(PodcastPlayer::new(Vec::new(), data_dir)?)));
+    }
+    if RadioPlayer::handles(&alarm.playlist_uri) {
+        return Ok(FadeTarget::Radio(Box::default()));
+    }
+    if MusicLibrary::handles(&alarm.playlist_uri) {
+        let settings = config.music_library.as_ref()
+            .ok_or_else(|| "Aucune bibliotheque musicale configuree".to_string())?;
+        return Ok(FadeTarget::Library(Box::new(MusicLibrary::new(settings))));
+    }
+    let mut client = spotify_client(data_dir, user, config, alarm.account.as_deref())?;
+    if let Some(device) = alarm.group_playback.as_ref().and_then(|g| g.spotify_device.as_deref()) {
+        client = client.prefer_device(device);
+    }
+    Ok(FadeTarget::Spotify(Box::new(client)))
+}
+
+/// Fait sonner l'alarme (ou le son local) pendant `duration`, avec son fondu d'entree
+async fn test_ring(
+    data_dir: &Path,
+    user: Option<&str>,
+    config: &AppConfig,
+    mut alarm: Option<AlarmEntry>,
+    duration: Duration,
+) -> Result<(), String> {
+    let locale = config.locale;
+    if let Some(alarm) = alarm.as_mut() {
+        alarm::prepare_ring(alarm, Local::now(), None);
+        // Hors ligne, la playlist echouerait: son local directement
+        if readiness::needs_network(&alarm.playlist_uri) && !readiness::network_up().await && alarm::fall_back_offline(alarm) {
+            println!("Reseau coupe: son local a la place de la playlist");
+        }
+    }
+    let volume = alarm.as_ref().map_or(TEST_RING_VOLUME, |a| a.volume);
+    let fade_in = alarm
+        .as_ref()
+        .filter(|a| a.fade_in && a.fade_in_duration > 0)
+        .map(|a| (Duration::from_secs(u64::from(a.fade_in_duration)).min(duration), a.fade_curve));
+
+    let target = match alarm.as_ref().filter(|a| a.playlist_uri != "local") {
+        Some(alarm) => {
+            let target = playback_target(data_dir, user, config, alarm)?;
+            target.set_volume(if fade_in.is_some() { 0 } else { volume }).await
+                .map_err(|e| tr(locale, Msg::VolumeError, &[&e]))?;
+            target.play(&alarm.playlist_uri, alarm.first_track_uri.as_deref()).await
+                .map_err(|e| tr(locale, Msg::PlaybackError, &[&e]))?;
+            println!("Lecture de {} ({} s)", alarm.playlist_name, duration.as_secs());
+            target
+        }
+        None => {
+            let device = alarm.as_ref().and_then(|a| a.output_device.clone()).or(config.output_device.clone());
+            audio::select_output_device(device)?;
+            audio::set_alarm_volume(if fade_in.is_some() { 0 } else { volume })
+                .and_then(|_| audio::play_alarm_sound_for(duration))
+                .map_err(|e| tr(locale, Msg::AudioError, &[&e]))?;
+            println!("Son local ({} s)", duration.as_secs());
+            FadeTarget::Local
+        }
+    };
+
+    let mut remaining = duration;
+    if let Some((fade_duration, curve)) = fade_in {
+        fade::run_fade(&target, 0, volume, fade_duration, curve, &FadeHandle::new()).await?;
+        remaining = remaining.saturating_sub(fade_duration);
+    }
+    tokio::time::sleep(remaining).await;
+    target.pause().await
+}
+
+async fn run(cli: Cli) -> Result<(), String> {
+    let (data_dir, user) = profile_data_dir(&cli)?;
+    let config = load_config(&data_dir)?;
+
+    match cli.command {
+        Command::List => {
+            let alarms = load_alarms(&data_dir)?;
+            if alarms.is_empty() {
+                println!("Aucune alarme");
+            }
+            for alarm in &alarms {
+                println!("{}", describe(alarm));
+            }
+        }
+        Command::Add { time, days, playlist, name, volume, fade, account } => {
+            if let Some(day) = days.iter().find(|d| alarm::string_to_weekday(d).is_none()) {
+                return Err(format!("Jour inconnu: {} (Monday, Tuesday, ...)", day));
+            }
+            let settings = alarm::AlarmSettings {
+                playlist_name: name,
+                playlist_uri: playlist,
+                volume: volume.unwrap_or(config.default_volume),
+                days,
+                fade_in: fade.is_some(),
+                fade_in_duration: fade.flatten().unwrap_or(config.default_fade_in_duration),
+                account,
+                recurrence: None,
+                timezone: None,
+                challenge: None,
+                escalation: None,
+                ring_limit: None,
+                weather_playlists: None,
+                announcement: None,
+                sunrise: false,
+                output_device: None,
+                fade_curve: fade::FadeCurve::default(),
+                lights: None,
+                calendar: None,
+                wake_window: None,
+                first_track_uri: None,
+                weekend_profile: None,
+                group_playback: None,
+                after_wake_queue: None,
+                interval: None,
+                pre_alarm: None,
+                backup: None,
+                group: None,
+                color: None,
+                icon: None,
+            };
+            let alarm = settings.into_alarm(time)?;
+            let mut alarms = load_alarms(&data_dir)?;
+            let warnings = alarm_list::warnings(&alarms, &alarm);
+            alarms.push(alarm.clone());
+            storage::save_alarms(&data_dir, &alarms)?;
+            println!("{}", describe(&alarm));
+            for warning in warnings {
+                eprintln!("charmed: attention: {:?}", warning);
+            }
+        }
+        Command::Toggle { id } => {
+            let mut alarms = load_alarms(&data_dir)?;
+            let id = resolve_id(&alarms, &id, &config)?;
+            alarm_list::toggle(&mut alarms, &id, config.locale)?;
+            storage::save_alarms(&data_dir, &alarms)?;
+            if let Some(alarm) = alarms.iter().find(|a| a.id == id) {
+                println!("{}", describe(alarm));
+            }
+        }
+        Command::TestRing { id, seconds } => {
+            let alarm = match id {
+                Some(id) => {
+                    let alarms = load_alarms(&data_dir)?;
+                    let id = resolve_id(&alarms, &id, &config)?;
+                    alarms.into_iter().find(|a| a.id == id)
+                }
+                None => None,
+            };
+            test_ring(&data_dir, user.as_deref(), &config, alarm, Duration::from_secs(seconds)).await?;
+        }
+        Command::Next => {
+            let alarms = load_alarms(&data_dir)?;
+            match alarm::next_alarm(&alarms, &[], Local::now(), &config) {
+                Some(next) => println!(
+                    "{} {}  dans {}  {}",
+                    next.trigger_at.format("%a %d/%m"),
+                    i18n::format_clock(next.trigger_at.time(), config.time_format, false),
+                    next.label,
+                    describe(&next.alarm)
+                ),
+                None => println!("Aucune alarme a venir"),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn")))
+        .with_writer(std::io::stderr)
+        .init();
+
+    match run(Cli::parse()).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("charmed: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(id: &str) -> AlarmEntry {
+        alarm::AlarmSettings {
+            playlist_name: "Alarme".to_string(),
+            playlist_uri: "local".to_string(),
+            volume: 80,
+            days: vec![],
+            fade_in: false,
+            fade_in_duration: 0,
+            account: None,
+            recurrence: None,
+            timezone: None,
+            challenge: None,
+            escalation: None,
+            ring_limit: None,
+            weather_playlists: None,
+            announcement: None,
+            sunrise: false,
+            output_device: None,
+            fade_curve: fade::FadeCurve::default(),
+            lights: None,
+            calendar: None,
+            wake_window: None,
+            first_track_uri: None,
+            weekend_profile: None,
+            group_playback: None,
+            after_wake_queue: None,
+            interval: None,
+            pre_alarm: None,
+            backup: None,
+            group: None,
+            color: None,
+            icon: None,
+        }
+        .into_alarm("07:30".to_string())
+        .map(|alarm| AlarmEntry { id: id.to_string(), ..alarm })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve_id_by_prefix() {
+        let config = AppConfig::default();
+        let alarms = vec![sample("abc123"), sample("abd456"), sample("ab")];
+        assert_eq!(resolve_id(&alarms, "abc", &config), Ok("abc123".to_string()));
+        assert_eq!(resolve_id(&alarms, "ab", &config), Ok("ab".to_string()));
+        assert!(resolve_id(&alarms, "a", &config).is_err());
+        assert!(resolve_id(&alarms, "zzz", &config).is_err());
+        assert!(resolve_id(&alarms, "", &config).is_err());
+    }
+}
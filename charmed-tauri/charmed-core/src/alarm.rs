@@ -0,0 +1,1559 @@
+// alarm.rs - Logique de gestion des alarmes
+// TODO: Ces fonctions seront utilisées quand l'UI sera connectée
+
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use chrono_tz::Tz;
+use chrono::{DateTime, Local, LocalResult, NaiveDate, NaiveDateTime, NaiveTime, Offset, Utc, Weekday, Datelike, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use crate::calendar::{self, CalendarEvent, CalendarRule};
+use crate::challenge::{Challenge, ChallengeConfig};
+use crate::escalation::{EscalationPolicy, EscalationStage};
+use crate::fade::{FadeCurve, FadeHandle};
+use crate::holidays;
+use crate::i18n::{self, Locale};
+use crate::lights::SunriseLights;
+use crate::readiness;
+use crate::recurrence::RecurrenceRule;
+use crate::storage::AppConfig;
+use crate::tts::Announcement;
+use crate::wake_window::{WakePlan, WakeWindow};
+use crate::weather::{self, WakeWeather, WeatherPlaylists};
+use crate::AlarmEntry;
+
+/// Duree maximale d'une sieste (minutes)
+pub const MAX_NAP_MINUTES: u32 = 720;
+
+/// Duree maximale de sonnerie configurable (minutes)
+pub const MAX_RING_MINUTES: u32 = 120;
+
+/// Nombre maximum de reports automatiques successifs d'une meme alarme
+pub const MAX_AUTO_SNOOZES: u32 = 3;
+
+/// Nombre maximum de titres dans la file d'apres reveil
+const MAX_QUEUE_TRACKS: usize = 20;
+
+/// Titres joues avant la file d'apres reveil (au plus)
+const MAX_QUEUE_AFTER_TRACKS: u32 = 10;
+
+/// Intervalle minimum entre deux rappels d'une alarme a intervalle (minutes)
+pub const MIN_INTERVAL_MINUTES: u32 = 5;
+
+/// Intervalle maximum entre deux rappels d'une alarme a intervalle (minutes)
+pub const MAX_INTERVAL_MINUTES: u32 = 720;
+
+/// Repetition dans la journee (hydratation, medicaments): a partir de l'heure de
+/// l'alarme, toutes les `every_minutes` jusqu'a `until` compris
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntervalRule {
+    pub every_minutes: u32,
+    #[serde(default)]
+    pub until: Option<String>, // "HH:MM" fin de la fenetre (None = jusqu'a minuit)
+}
+
+impl IntervalRule {
+    /// Valide l'intervalle et la fin de fenetre
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_INTERVAL_MINUTES..=MAX_INTERVAL_MINUTES).contains(&self.every_minutes) {
+            return Err(format!(
+                "Intervalle invalide: entre {} et {} minutes",
+                MIN_INTERVAL_MINUTES, MAX_INTERVAL_MINUTES
+            ));
+        }
+        if self.until.as_deref().is_some_and(|until| parse_alarm_time(until).is_none()) {
+            return Err("Fin de fenetre invalide. Utilisez HH:MM ou HH:MM:SS".to_string());
+        }
+        Ok(())
+    }
+
+    /// Heures de la journee a partir de `start`, dans l'ordre
+    pub fn times_from(&self, start: NaiveTime) -> Vec<NaiveTime> {
+        let until = self.until.as_deref().and_then(parse_alarm_time);
+        let step = chrono::Duration::minutes(i64::from(self.every_minutes.max(MIN_INTERVAL_MINUTES)));
+        let mut times = vec![start];
+        let mut cursor = start;
+        loop {
+            let (next, wrapped) = cursor.overflowing_add_signed(step);
+            if wrapped != 0 || until.is_some_and(|until| next > until) {
+                return times;
+            }
+            times.push(next);
+            cursor = next;
+        }
+    }
+}
+
+/// Avance maximale de la pre-alarme (minutes)
+pub const MAX_PRE_ALARM_MINUTES: u32 = 60;
+
+/// Son de la pre-alarme
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreAlarmSound {
+    #[default]
+    Chime, // Un seul carillon discret (son local)
+    Spotify, // Playlist de l'alarme lancee a tres faible volume
+}
+
+/// Avertissement discret quelques minutes avant l'alarme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PreAlarm {
+    pub minutes: u32, // Avance sur l'heure de l'alarme
+    #[serde(default)]
+    pub sound: PreAlarmSound,
+    #[serde(default = "default_pre_alarm_volume")]
+    pub volume: u8, // 0-100
+}
+
+fn default_pre_alarm_volume() -> u8 {
+    15
+}
+
+impl PreAlarm {
+    /// Valide la pre-alarme pour la playlist de l'alarme
+    pub fn validate(&self, playlist_uri: &str) -> Result<(), String> {
+        if self.minutes == 0 || self.minutes > MAX_PRE_ALARM_MINUTES {
+            return Err(format!("Pre-alarme invalide: entre 1 et {} minutes avant l'alarme", MAX_PRE_ALARM_MINUTES));
+        }
+        if self.sound == PreAlarmSound::Spotify && !playlist_uri.starts_with("spotify:") {
+            return Err("La pre-alarme Spotify demande une playlist Spotify".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Action a l'expiration de la duree maximale de sonnerie
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum RingTimeoutAction {
+    #[default]
+    Missed, // Arreter et noter l'alarme comme manquee
+    Snooze { minutes: u32 }, // Arreter et resonner plus tard
+}
+
+/// Lecture dans plusieurs pieces a la fois
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GroupPlayback {
+    #[serde(default)]
+    pub spotify_device: Option<String>, // Groupe (ou appareil) Spotify Connect, par id ou nom
+    #[serde(default)]
+    pub local_sound: bool, // Son local joue en meme temps sur cet ordinateur
+}
+
+impl GroupPlayback {
+    /// Valide la lecture groupee pour la playlist de l'alarme
+    pub fn validate(&self, playlist_uri: &str) -> Result<(), String> {
+        if self.spotify_device.is_none() && !self.local_sound {
+            return Err("Lecture groupee vide: choisissez un groupe Spotify ou le son local".to_string());
+        }
+        if self.spotify_device.is_some() && !playlist_uri.starts_with("spotify:") {
+            return Err("Le groupe Spotify Connect demande une playlist Spotify".to_string());
+        }
+        // Bibliotheque, radio et podcasts occupent deja la sortie audio locale
+        let remote = ["spotify:", "applemusic:", "youtubemusic:"].iter().any(|p| playlist_uri.starts_with(p));
+        if self.local_sound && !remote {
+            return Err("Le son local simultane demande une playlist Spotify, Apple Music ou YouTube Music".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Titres ou episodes Spotify joues apres les premiers titres de la playlist (briefing, podcast)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AfterWakeQueue {
+    pub uris: Vec<String>, // "spotify:track:..." ou "spotify:episode:..."
+    #[serde(default = "default_after_tracks")]
+    pub after_tracks: u32, // Titres de la playlist joues avant la file
+}
+
+fn default_after_tracks() -> u32 {
+    2
+}
+
+impl AfterWakeQueue {
+    /// Valide la file pour la playlist de l'alarme
+    pub fn validate(&self, playlist_uri: &str) -> Result<(), String> {
+        if !playlist_uri.starts_with("spotify:") {
+            return Err("La file d'apres reveil demande une playlist Spotify".to_string());
+        }
+        if self.uris.is_empty() || self.uris.len() > MAX_QUEUE_TRACKS {
+            return Err(format!("La file d'apres reveil doit contenir entre 1 et {} titres", MAX_QUEUE_TRACKS));
+        }
+        if let Some(uri) = self.uris.iter().find(|u| !u.starts_with("spotify:track:") && !u.starts_with("spotify:episode:")) {
+            return Err(format!("Titre de file invalide: {} (spotify:track:... ou spotify:episode:...)", uri));
+        }
+        if self.after_tracks == 0 || self.after_tracks > MAX_QUEUE_AFTER_TRACKS {
+            return Err(format!("La file doit passer apres 1 a {} titres", MAX_QUEUE_AFTER_TRACKS));
+        }
+        Ok(())
+    }
+}
+
+/// Limite de duree de sonnerie d'une alarme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RingLimit {
+    pub max_minutes: u32,
+    #[serde(default)]
+    pub on_timeout: RingTimeoutAction,
+}
+
+/// Alarme de secours: sirene locale a plein volume si la sonnerie reste
+/// sans reponse (ni report ni arret) pendant `after_minutes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupAlarm {
+    pub after_minutes: u32,
+}
+
+impl BackupAlarm {
+    /// Valide le delai, qui doit preceder la duree maximale de sonnerie
+    pub fn validate(&self, ring_limit: Option<&RingLimit>) -> Result<(), String> {
+        if self.after_minutes == 0 || self.after_minutes > MAX_RING_MINUTES {
+            return Err(format!("Delai de l'alarme de secours invalide: entre 1 et {} minutes", MAX_RING_MINUTES));
+        }
+        if ring_limit.is_some_and(|limit| self.after_minutes >= limit.max_minutes) {
+            return Err("L'alarme de secours doit sonner avant la duree maximale de sonnerie".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl RingLimit {
+    /// Valide la limite de sonnerie
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_minutes == 0 || self.max_minutes > MAX_RING_MINUTES {
+            return Err(format!(
+                "Duree de sonnerie invalide: entre 1 et {} minutes",
+                MAX_RING_MINUTES
+            ));
+        }
+        if let RingTimeoutAction::Snooze { minutes } = self.on_timeout {
+            if minutes == 0 || minutes > MAX_NAP_MINUTES {
+                return Err(format!(
+                    "Duree de report invalide: entre 1 et {} minutes",
+                    MAX_NAP_MINUTES
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifie si la sonnerie commencee a `started_at` a depasse la limite
+    pub fn is_exceeded(&self, started_at: DateTime<Local>, now: DateTime<Local>) -> bool {
+        now.signed_duration_since(started_at) >= chrono::Duration::minutes(i64::from(self.max_minutes))
+    }
+}
+
+/// Reglages sonores d'une alarme (le profil de semaine est celui de l'alarme elle-meme)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SoundProfile {
+    pub playlist_name: String,
+    pub playlist_uri: String,
+    pub volume: u8,
+    #[serde(default)]
+    pub fade_in: bool,
+    #[serde(default)]
+    pub fade_in_duration: u16,
+    #[serde(default)]
+    pub fade_curve: FadeCurve,
+}
+
+/// Applique le profil du week-end a une occurrence du samedi ou du dimanche.
+/// Retourne true si les reglages ont change.
+pub fn apply_day_profile(alarm: &mut AlarmEntry, date: NaiveDate) -> bool {
+    let Some(profile) = alarm.weekend_profile.clone().filter(|_| is_weekend_day(date)) else {
+        return false;
+    };
+    alarm.playlist_name = profile.playlist_name;
+    alarm.playlist_uri = profile.playlist_uri;
+    alarm.volume = profile.volume;
+    alarm.fade_in = profile.fade_in;
+    alarm.fade_in_duration = profile.fade_in_duration;
+    alarm.fade_curve = profile.fade_curve;
+    true
+}
+
+/// Reglages d'une alarme, hors heure et etat (base des modeles d'alarmes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmSettings {
+    pub playlist_name: String,
+    pub playlist_uri: String,
+    pub volume: u8,
+    #[serde(default)]
+    pub days: Vec<String>,
+    #[serde(default)]
+    pub fade_in: bool,
+    #[serde(default)]
+    pub fade_in_duration: u16,
+    #[serde(default)]
+    pub account: Option<String>,
+    #[serde(default)]
+    pub recurrence: Option<RecurrenceRule>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    #[serde(default)]
+    pub challenge: Option<ChallengeConfig>,
+    #[serde(default)]
+    pub escalation: Option<EscalationPolicy>,
+    #[serde(default)]
+    pub ring_limit: Option<RingLimit>,
+    #[serde(default)]
+    pub weather_playlists: Option<WeatherPlaylists>,
+    #[serde(default)]
+    pub announcement: Option<Announcement>,
+    #[serde(default)]
+    pub sunrise: bool,
+    #[serde(default)]
+    pub output_device: Option<String>,
+    #[serde(default)]
+    pub fade_curve: FadeCurve,
+    #[serde(default)]
+    pub lights: Option<SunriseLights>,
+    #[serde(default)]
+    pub calendar: Option<CalendarRule>,
+    #[serde(default)]
+    pub wake_window: Option<WakeWindow>,
+    #[serde(default)]
+    pub first_track_uri: Option<String>,
+    #[serde(default)]
+    pub weekend_profile: Option<SoundProfile>,
+    #[serde(default)]
+    pub group_playback: Option<GroupPlayback>,
+    #[serde(default)]
+    pub after_wake_queue: Option<AfterWakeQueue>,
+    #[serde(default)]
+    pub interval: Option<IntervalRule>,
+    #[serde(default)]
+    pub pre_alarm: Option<PreAlarm>,
+    #[serde(default)]
+    pub backup: Option<BackupAlarm>,
+    #[serde(default)]
+    pub group: Option<String>,
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+impl AlarmSettings {
+    /// Valide les reglages (fuseau normalise, volume borne)
+    pub fn normalized(mut self) -> Result<Self, String> {
+        self.volume = self.volume.min(100);
+        self.timezone = self.timezone
+            .map(|tz| tz.trim().to_string())
+            .filter(|tz| !tz.is_empty());
+        self.output_device = self.output_device.filter(|d| !d.trim().is_empty());
+        self.first_track_uri = self.first_track_uri
+            .map(|uri| uri.trim().to_string())
+            .filter(|uri| !uri.is_empty());
+        if let Some(profile) = self.weekend_profile.as_mut() {
+            profile.volume = profile.volume.min(100);
+        }
+        if let Some(pre_alarm) = self.pre_alarm.as_mut() {
+            pre_alarm.volume = pre_alarm.volume.min(100);
+        }
+        if let Some(queue) = self.after_wake_queue.as_mut() {
+            queue.uris = queue.uris.iter().map(|u| u.trim().to_string()).filter(|u| !u.is_empty()).collect();
+        }
+        self.group = self.group.map(|g| g.trim().to_string()).filter(|g| !g.is_empty());
+        self.color = self.color.map(|c| c.trim().to_lowercase()).filter(|c| !c.is_empty());
+        self.icon = self.icon.map(|i| i.trim().to_string()).filter(|i| !i.is_empty());
+        if let Some(group) = self.group_playback.as_mut() {
+            group.spotify_device = group.spotify_device
+                .as_deref()
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .map(str::to_string);
+        }
+
+        if let Some(rule) = self.recurrence.as_ref() {
+            rule.validate()?;
+        }
+        if let Some(color) = self.color.as_deref() {
+            validate_color(color)?;
+        }
+        if let Some(icon) = self.icon.as_deref() {
+            if !ALARM_ICONS.contains(&icon) {
+                return Err(format!("Icone inconnue: {} (valeurs: {})", icon, ALARM_ICONS.join(", ")));
+            }
+        }
+        if let Some(tz) = self.timezone.as_deref() {
+            parse_timezone(tz)?;
+        }
+        if let Some(config) = self.challenge.as_ref() {
+            config.validate()?;
+        }
+        if let Some(policy) = self.escalation.as_ref() {
+            policy.validate()?;
+        }
+        if let Some(limit) = self.ring_limit.as_ref() {
+            limit.validate()?;
+        }
+        if let Some(announcement) = self.announcement.as_ref() {
+            announcement.validate()?;
+        }
+        if self.sunrise && self.fade_in_duration == 0 {
+            return Err("Le lever de soleil sonore demande une duree de fondu".to_string());
+        }
+        if let Some(rule) = self.calendar.as_ref() {
+            rule.validate()?;
+        }
+        if let Some(lights) = self.lights.as_ref() {
+            lights.validate()?;
+            if self.fade_in_duration == 0 {
+                return Err("Le lever de soleil lumineux demande une duree de fondu".to_string());
+            }
+        }
+        if let Some(uri) = self.first_track_uri.as_deref() {
+            if !uri.starts_with("spotify:track:") {
+                return Err(format!("Titre d'ouverture invalide: {} (spotify:track:...)", uri));
+            }
+            if self.playlist_uri == "local" {
+                return Err("Le titre d'ouverture demande une playlist Spotify".to_string());
+            }
+        }
+        if let Some(profile) = self.weekend_profile.as_ref() {
+            if profile.playlist_uri.trim().is_empty() {
+                return Err("Le profil du week-end demande une playlist".to_string());
+            }
+            if (self.sunrise || self.lights.is_some()) && profile.fade_in_duration == 0 {
+                return Err("Le lever de soleil demande une duree de fondu le week-end aussi".to_string());
+            }
+            if profile.playlist_uri == "local" && (self.first_track_uri.is_some() || self.wake_window.is_some()) {
+                return Err("Le titre d'ouverture et la fenetre de reveil demandent une playlist Spotify le week-end aussi".to_string());
+            }
+        }
+        if let Some(group) = self.group_playback.as_ref() {
+            group.validate(&self.playlist_uri)?;
+        }
+        if let Some(queue) = self.after_wake_queue.as_ref() {
+            queue.validate(&self.playlist_uri)?;
+        }
+        if let Some(backup) = self.backup.as_ref() {
+            backup.validate(self.ring_limit.as_ref())?;
+        }
+        if let Some(pre_alarm) = self.pre_alarm.as_ref() {
+            pre_alarm.validate(&self.playlist_uri)?;
+            if pre_alarm.sound == PreAlarmSound::Spotify && self.wake_window.is_some() {
+                return Err("La pre-alarme Spotify ne se combine pas avec la fenetre de reveil".to_string());
+            }
+        }
+        if let Some(interval) = self.interval.as_ref() {
+            interval.validate()?;
+            if self.wake_window.is_some() || self.calendar.is_some() {
+                return Err("La repetition dans la journee ne se combine pas avec la fenetre de reveil ou l'agenda".to_string());
+            }
+        }
+        if let Some(window) = self.wake_window.as_ref() {
+            window.validate()?;
+            if self.playlist_uri == "local" {
+                return Err("La fenetre de reveil demande une playlist Spotify".to_string());
+            }
+            if self.escalation.is_some() || self.sunrise {
+                return Err("La fenetre de reveil ne se combine pas avec l'escalade ou le lever de soleil sonore".to_string());
+            }
+        }
+        Ok(self)
+    }
+
+    /// Cree une nouvelle alarme active a l'heure donnee ("HH:MM" ou "HH:MM:SS")
+    pub fn into_alarm(self, time: String) -> Result<AlarmEntry, String> {
+        let start = parse_alarm_time(&time)
+            .ok_or_else(|| "Format d'heure invalide. Utilisez HH:MM ou HH:MM:SS".to_string())?;
+        let settings = self.normalized()?;
+        let until = settings.interval.as_ref().and_then(|i| i.until.as_deref()).and_then(parse_alarm_time);
+        if until.is_some_and(|until| until <= start) {
+            return Err("La fin de la repetition doit suivre l'heure de l'alarme".to_string());
+        }
+
+        Ok(AlarmEntry {
+            id: uuid::Uuid::new_v4().to_string(),
+            time,
+            playlist_name: settings.playlist_name,
+            playlist_uri: settings.playlist_uri,
+            volume: settings.volume,
+            active: true,
+            archived: false,
+            days: settings.days,
+            fade_in: settings.fade_in,
+            fade_in_duration: settings.fade_in_duration,
+            account: settings.account,
+            skip_date: None,
+            recurrence: settings.recurrence,
+            timezone: settings.timezone,
+            challenge: settings.challenge,
+            escalation: settings.escalation,
+            ring_limit: settings.ring_limit,
+            weather_playlists: settings.weather_playlists,
+            announcement: settings.announcement,
+            sunrise: settings.sunrise,
+            output_device: settings.output_device,
+            fade_curve: settings.fade_curve,
+            lights: settings.lights,
+            calendar: settings.calendar,
+            wake_window: settings.wake_window,
+            first_track_uri: settings.first_track_uri,
+            weekend_profile: settings.weekend_profile,
+            group_playback: settings.group_playback,
+            after_wake_queue: settings.after_wake_queue,
+            interval: settings.interval,
+            pre_alarm: settings.pre_alarm,
+            backup: settings.backup,
+            group: settings.group,
+            color: settings.color,
+            icon: settings.icon,
+            updated_at: Some(chrono::Utc::now()),
+        })
+    }
+}
+
+/// Icones proposees par l'interface pour distinguer les alarmes
+pub const ALARM_ICONS: &[&str] = &[
+    "bell", "sun", "moon", "coffee", "briefcase", "dumbbell", "music", "pill", "book", "baby", "plane", "heart",
+];
+
+/// Couleur d'une alarme: "#rrggbb"
+fn validate_color(color: &str) -> Result<(), String> {
+    let hex = color.strip_prefix('#').unwrap_or_default();
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Couleur invalide: {} (format #rrggbb)", color));
+    }
+    Ok(())
+}
+
+/// Modele d'alarme nomme ("Reveil semaine", ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmTemplate {
+    pub name: String,
+    #[serde(flatten)]
+    pub settings: AlarmSettings,
+}
+
+/// Sieste: alarme ponctuelle relative a l'heure actuelle, jamais persistee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NapTimer {
+    pub alarm: AlarmEntry,
+    pub fires_at: DateTime<Local>,
+    #[serde(default)]
+    pub auto_snoozes: u32, // Reports automatiques deja effectues
+}
+
+impl NapTimer {
+    /// Cree une sieste qui sonnera `minutes` apres `now`
+    pub fn new(
+        now: DateTime<Local>,
+        minutes: u32,
+        playlist_name: String,
+        playlist_uri: String,
+        volume: u8,
+    ) -> Self {
+        let fires_at = now + chrono::Duration::minutes(i64::from(minutes));
+        Self {
+            alarm: AlarmEntry {
+                id: uuid::Uuid::new_v4().to_string(),
+                time: fires_at.format("%H:%M").to_string(),
+                playlist_name,
+                playlist_uri,
+                volume: volume.min(100),
+                active: true,
+                archived: false,
+                days: Vec::new(),
+                fade_in: false,
+                fade_in_duration: 0,
+                account: None,
+                skip_date: None,
+                recurrence: None,
+                timezone: None,
+                challenge: None,
+                escalation: None,
+                ring_limit: None,
+                weather_playlists: None,
+                announcement: None,
+                sunrise: false,
+                output_device: None,
+                fade_curve: FadeCurve::default(),
+                lights: None,
+                calendar: None,
+                wake_window: None,
+                first_track_uri: None,
+                weekend_profile: None,
+                group_playback: None,
+                after_wake_queue: None,
+                interval: None,
+                pre_alarm: None,
+                backup: None,
+                group: None,
+                color: None,
+                icon: None,
+                updated_at: None,
+            },
+            fires_at,
+            auto_snoozes: 0,
+        }
+    }
+
+    /// Report automatique d'une alarme restee sans reponse: memes reglages,
+    /// sonne une seule fois `minutes` apres `now`
+    pub fn snooze(ringing: &RingingAlarm, now: DateTime<Local>, minutes: u32) -> Self {
+        let fires_at = now + chrono::Duration::minutes(i64::from(minutes));
+        Self {
+            alarm: AlarmEntry {
+                time: fires_at.format("%H:%M").to_string(),
+                days: Vec::new(),
+                skip_date: None,
+                recurrence: None,
+                timezone: None,
+                interval: None,
+                pre_alarm: None,
+                ..ringing.alarm.clone()
+            },
+            fires_at,
+            auto_snoozes: ringing.auto_snoozes + 1,
+        }
+    }
+
+    /// Verifie si la sieste doit sonner
+    pub fn is_due(&self, now: DateTime<Local>) -> bool {
+        now >= self.fires_at
+    }
+}
+
+/// Raison pour laquelle une sonnerie a joue le son local au lieu de sa playlist
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackReason {
+    Offline, // Reseau coupe au declenchement
+    PlaybackFailed, // Spotify a accepte la lecture mais rien n'est sorti (apres une nouvelle tentative)
+}
+
+/// Remplace la playlist par le son local quand le reseau est coupe au declenchement,
+/// plutot que de laisser la lecture echouer en silence. Retourne vrai si l'alarme a change.
+pub fn fall_back_offline(alarm: &mut AlarmEntry) -> bool {
+    if !readiness::needs_network(&alarm.playlist_uri) {
+        return false;
+    }
+    alarm.playlist_uri = "local".to_string();
+    alarm.first_track_uri = None;
+    alarm.group_playback = None;
+    alarm.after_wake_queue = None;
+    true
+}
+
+/// Alarme en cours de sonnerie, en attente d'arrêt par l'utilisateur
+#[derive(Debug, Clone, Serialize)]
+pub struct RingingAlarm {
+    pub alarm: AlarmEntry,
+    pub started_at: DateTime<Local>,
+    pub challenge: Option<Challenge>,
+    pub solved: bool,
+    pub escalation_stage: Option<EscalationStage>, // Dernier palier applique
+    pub auto_snoozes: u32, // Reports automatiques ayant mene a cette sonnerie
+    pub announced: bool, // Annonce vocale deja lancee
+    pub fallback: Option<FallbackReason>, // Son local joue a la place de la playlist
+    pub backup_fired: bool, // Alarme de secours declenchee (sirene a plein volume)
+    pub test: bool, // Sonnerie lancee par le test de l'alarme
+    #[serde(skip)]
+    pub after_wake_queue_started: bool, // Suivi de la file d'apres reveil deja lance
+    #[serde(skip)]
+    pub soundscape: Option<FadeHandle>, // Lever de soleil sonore en cours
+    #[serde(skip)]
+    pub fade_in: Option<FadeHandle>, // Fondu d'entree en cours (son local ou Spotify)
+    #[serde(skip)]
+    pub system_volume_checked: bool, // Volume systeme deja traite pour cette sonnerie
+}
+
+impl RingingAlarm {
+    pub fn new(alarm: AlarmEntry, now: DateTime<Local>) -> Self {
+        Self {
+            alarm,
+            started_at: now,
+            challenge: None,
+            solved: false,
+            escalation_stage: None,
+            auto_snoozes: 0,
+            announced: false,
+            fallback: None,
+            backup_fired: false,
+            test: false,
+            after_wake_queue_started: false,
+            soundscape: None,
+            fade_in: None,
+            system_volume_checked: false,
+        }
+    }
+
+    /// Vrai si l'alarme de secours doit sonner a `now` (une seule fois par sonnerie)
+    pub fn backup_due(&self, now: DateTime<Local>) -> bool {
+        !self.backup_fired
+            && self.alarm.backup.is_some_and(|backup| {
+                now.signed_duration_since(self.started_at) >= chrono::Duration::minutes(i64::from(backup.after_minutes))
+            })
+    }
+
+    /// Duree du lever de soleil sonore avant la playlist (zero si desactive)
+    pub fn sunrise_duration(&self) -> std::time::Duration {
+        if self.alarm.sunrise {
+            std::time::Duration::from_secs(u64::from(self.alarm.fade_in_duration))
+        } else {
+            std::time::Duration::ZERO
+        }
+    }
+
+    /// Interrompt les fondus en cours (lever de soleil, fondu d'entree local)
+    pub fn stop_fades(&self) {
+        for handle in [&self.soundscape, &self.fade_in].into_iter().flatten() {
+            handle.cancel();
+        }
+    }
+
+    /// L'alarme ne peut être arrêtée qu'une fois son défi résolu
+    pub fn can_dismiss(&self) -> bool {
+        self.alarm.challenge.is_none() || self.solved
+    }
+}
+
+/// Duree pendant laquelle une occurrence reste declenchable apres son heure
+/// (rattrape un passage du planificateur en retard)
+const TRIGGER_WINDOW_SECS: i64 = 60;
+
+/// Heure d'une alarme: "HH:MM" ou, a la seconde pres, "HH:MM:SS"
+pub fn parse_alarm_time(time: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(time, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+        .ok()
+}
+
+/// Ecrit une heure d'alarme, avec les secondes seulement si elles ne sont pas nulles
+pub fn format_alarm_time(time: NaiveTime) -> String {
+    if time.second() == 0 {
+        time.format("%H:%M").to_string()
+    } else {
+        time.format("%H:%M:%S").to_string()
+    }
+}
+
+/// Alarme evaluee par le planificateur: active et non archivee
+pub fn is_enabled(alarm: &AlarmEntry) -> bool {
+    alarm.active && !alarm.archived
+}
+
+/// Vérifie si une alarme doit se déclencher à l'instant `now`
+pub fn should_trigger(alarm: &AlarmEntry, now: DateTime<Local>, config: &AppConfig) -> bool {
+    if !is_enabled(alarm) {
+        return false;
+    }
+
+    // Heure et date évaluées dans le fuseau de l'alarme
+    let wall = wall_clock(alarm, now);
+    let today = wall.date();
+
+    // Vérifier l'heure, puis le jour (règle de récurrence ou jours spécifiés)
+    current_occurrence(alarm, now).is_some()
+        && is_scheduled_on(alarm, today)
+        && !is_skipped_on(alarm, today, config)
+}
+
+/// Heures de sonnerie de l'alarme dans une journée (plusieurs pour une alarme à intervalle)
+pub fn daily_times(alarm: &AlarmEntry) -> Vec<NaiveTime> {
+    let Some(start) = parse_alarm_time(&alarm.time) else {
+        return Vec::new();
+    };
+    match &alarm.interval {
+        Some(rule) => rule.times_from(start),
+        None => vec![start],
+    }
+}
+
+/// Heure du jour dont la fenêtre de déclenchement contient `now`.
+/// On compare des instants réels et non l'affichage HH:MM, pour sonner
+/// une seule fois au recul d'heure et ne pas sauter l'avance d'heure.
+pub fn current_occurrence(alarm: &AlarmEntry, now: DateTime<Local>) -> Option<NaiveTime> {
+    let today = wall_clock(alarm, now).date();
+    daily_times(alarm).into_iter().find(|time| {
+        occurrence_instant(alarm, today.and_time(*time)).is_some_and(|trigger_at| {
+            let elapsed = now.signed_duration_since(trigger_at);
+            elapsed >= chrono::Duration::zero() && elapsed < chrono::Duration::seconds(TRIGGER_WINDOW_SECS)
+        })
+    })
+}
+
+/// Alarme retenue a `now`, avec les alarmes dues au meme moment qu'elle absorbe
+#[derive(Debug, Clone)]
+pub struct DueAlarm {
+    pub alarm: AlarmEntry,
+    pub absorbed: Vec<String>, // Ids des alarmes en collision, notees comme declenchees sans sonner
+}
+
+/// Ordre de priorite entre alarmes dues ensemble: la plus forte gagne,
+/// puis la plus tot (a la seconde pres), puis l'id pour rester deterministe
+fn collision_order(a: &AlarmEntry, b: &AlarmEntry) -> std::cmp::Ordering {
+    b.volume.cmp(&a.volume)
+        .then_with(|| parse_alarm_time(&a.time).cmp(&parse_alarm_time(&b.time)))
+        .then_with(|| a.id.cmp(&b.id))
+}
+
+/// Alarme a faire sonner a `now`: debut de titre retenu d'une fenetre de reveil,
+/// sinon alarme dont l'heure du jour (recalculee depuis l'agenda) est atteinte.
+/// Si plusieurs alarmes tombent ensemble, une seule sonne (voir `collision_order`).
+/// Les plans expires sont retires et le plan qui sonne est marque.
+pub fn due_alarm(
+    alarms: &[AlarmEntry],
+    plans: &mut HashMap<String, WakePlan>,
+    fired: &FiredLedger,
+    events: &[CalendarEvent],
+    now: DateTime<Local>,
+    config: &AppConfig,
+) -> Option<DueAlarm> {
+    plans.retain(|_, plan| !plan.is_expired(now));
+    // Fenetre de reveil: sonne au debut de titre retenu, a la place de l'heure fixe
+    let planned = alarms.iter().find_map(|alarm| {
+        let plan = plans.get_mut(&alarm.id).filter(|p| !p.rung && p.ring_at <= now)?;
+        plan.rung = true;
+        calendar::effective_alarm(alarm, events, wall_clock(alarm, plan.alarm_at).date())
+    });
+    if let Some(alarm) = planned {
+        return Some(DueAlarm { alarm, absorbed: Vec::new() });
+    }
+
+    let mut due: Vec<AlarmEntry> = alarms.iter()
+        .filter(|alarm| !plans.contains_key(&alarm.id) && !fired_recently(fired, &alarm.id, now))
+        .filter_map(|alarm| calendar::effective_alarm(alarm, events, wall_clock(alarm, now).date()))
+        .filter(|alarm| should_trigger(alarm, now, config))
+        .map(|mut alarm| {
+            // Alarme a intervalle: l'occurrence qui sonne porte sa propre heure (historique, annonces)
+            if alarm.interval.is_some() {
+                if let Some(time) = current_occurrence(&alarm, now) {
+                    alarm.time = format_alarm_time(time);
+                }
+            }
+            alarm
+        })
+        .collect();
+    due.sort_by(collision_order);
+    let mut due = due.into_iter();
+    let alarm = due.next()?;
+    Some(DueAlarm { alarm, absorbed: due.map(|a| a.id).collect() })
+}
+
+/// Dernier declenchement par alarme: should_trigger reste vrai toute la fenetre de
+/// declenchement, le registre garantit qu'une occurrence ne sonne qu'une fois
+pub type FiredLedger = HashMap<String, DateTime<Local>>;
+
+/// Duree pendant laquelle un declenchement reste dans le registre
+const FIRED_RETENTION_HOURS: i64 = 24;
+
+/// Vrai si l'occurrence en cours de l'alarme a deja sonne (declenchement plus recent
+/// que la fenetre de declenchement, les heures pouvant tomber en cours de minute)
+pub fn fired_recently(fired: &FiredLedger, alarm_id: &str, now: DateTime<Local>) -> bool {
+    fired.get(alarm_id).is_some_and(|at| now.signed_duration_since(*at) < chrono::Duration::seconds(TRIGGER_WINDOW_SECS))
+}
+
+/// Note le declenchement d'une alarme et oublie les plus anciens
+pub fn record_fired(fired: &mut FiredLedger, alarm_id: &str, now: DateTime<Local>) {
+    fired.retain(|_, at| now.signed_duration_since(*at) < chrono::Duration::hours(FIRED_RETENTION_HOURS));
+    fired.insert(alarm_id.to_string(), now);
+}
+
+/// Reglages de l'occurrence qui sonne: profil du week-end, puis playlist selon la meteo
+pub fn prepare_ring(alarm: &mut AlarmEntry, now: DateTime<Local>, weather: Option<&WakeWeather>) {
+    if apply_day_profile(alarm, wall_clock(alarm, now).date()) {
+        tracing::info!(alarm_id = %alarm.id, "Profil sonore du week-end applique");
+    }
+    if let Some(weather) = weather.filter(|w| w.is_fresh(now.with_timezone(&Utc))) {
+        if weather::apply_override(alarm, weather) {
+            tracing::info!(alarm_id = %alarm.id, condition = ?weather.condition, "Playlist remplacee selon la meteo");
+        }
+    }
+}
+
+/// Vérifie si une occurrence prévue est annulée ce jour-là
+/// (occurrence sautée, mode vacances, jour férié pour une alarme de semaine)
+pub fn is_skipped_on(alarm: &AlarmEntry, date: NaiveDate, config: &AppConfig) -> bool {
+    if is_suppressed(alarm, date, config.vacation_until) {
+        return true;
+    }
+
+    // Les alarmes de semaine ne sonnent pas les jours fériés
+    let days = match &alarm.recurrence {
+        Some(rule) => rule.weekdays(),
+        None => alarm.days.clone(),
+    };
+    is_workday_alarm(&days)
+        && holidays::is_holiday(date, config.holiday_country.as_deref(), &config.custom_holidays)
+}
+
+/// Prochaine alarme à sonner (résumé pour l'UI et la barre système)
+#[derive(Debug, Clone, Serialize)]
+pub struct NextAlarm {
+    pub alarm: AlarmEntry,
+    pub trigger_at: DateTime<Local>,
+    pub seconds_until: i64,
+    pub label: String, // ex: "7h 23m"
+}
+
+/// Calcule l'instant exact du prochain déclenchement effectif d'une alarme,
+/// en sautant les occurrences annulées (vacances, jours fériés, skip)
+pub fn next_trigger(alarm: &AlarmEntry, now: DateTime<Local>, config: &AppConfig) -> Option<DateTime<Local>> {
+    if !is_enabled(alarm) {
+        return None;
+    }
+
+    let mut cursor = wall_clock(alarm, now);
+    for _ in 0..MAX_LOOKAHEAD_DAYS {
+        let next = next_occurrence(alarm, cursor)?;
+        if !is_skipped_on(alarm, next.date(), config) {
+            return occurrence_instant(alarm, next).map(|instant| instant.with_timezone(&Local));
+        }
+        // Toute la journee est sautee, rappels d'une alarme a intervalle compris
+        cursor = next.date().and_time(NaiveTime::from_hms_opt(23, 59, 59)?);
+    }
+    None
+}
+
+/// Retourne l'alarme (ou la sieste) qui sonnera le plus tôt
+pub fn next_alarm(
+    alarms: &[AlarmEntry],
+    naps: &[NapTimer],
+    now: DateTime<Local>,
+    config: &AppConfig,
+) -> Option<NextAlarm> {
+    next_alarm_by(alarms, naps, now, config.locale, |alarm| next_trigger(alarm, now, config))
+}
+
+/// Comme `next_alarm`, avec un calcul du prochain déclenchement fourni
+/// (heures recalculées depuis l'agenda)
+pub fn next_alarm_by(
+    alarms: &[AlarmEntry],
+    naps: &[NapTimer],
+    now: DateTime<Local>,
+    locale: Locale,
+    trigger: impl Fn(&AlarmEntry) -> Option<DateTime<Local>>,
+) -> Option<NextAlarm> {
+    let scheduled = alarms
+        .iter()
+        .filter_map(|alarm| trigger(alarm).map(|at| (alarm, at)));
+    let pending_naps = naps
+        .iter()
+        .filter(|nap| !nap.is_due(now))
+        .map(|nap| (&nap.alarm, nap.fires_at));
+
+    scheduled
+        .chain(pending_naps)
+        .min_by_key(|(_, at)| *at)
+        .map(|(alarm, trigger_at)| {
+            let seconds_until = trigger_at.signed_duration_since(now).num_seconds().max(0);
+            NextAlarm {
+                alarm: alarm.clone(),
+                trigger_at,
+                seconds_until,
+                label: format_time_until(seconds_until, locale),
+            }
+        })
+}
+
+/// Valide un nom de fuseau horaire IANA (ex: "Europe/Paris")
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>()
+        .map_err(|_| format!("Fuseau horaire inconnu: {}", name))
+}
+
+/// Heure murale de l'alarme: dans son fuseau IANA si défini, sinon heure locale
+pub fn wall_clock(alarm: &AlarmEntry, now: DateTime<Local>) -> NaiveDateTime {
+    match alarm.timezone.as_deref().and_then(|name| parse_timezone(name).ok()) {
+        Some(tz) => now.with_timezone(&tz).naive_local(),
+        None => now.naive_local(),
+    }
+}
+
+/// Convertit une heure murale en instant réel en tenant compte des changements d'heure.
+/// Heure ambiguë (recul d'heure): première occurrence.
+/// Heure inexistante (avance d'heure): décalée de la durée du saut (02:30 -> 03:30).
+pub fn resolve_local<Z: TimeZone>(tz: &Z, naive: NaiveDateTime) -> Option<DateTime<Z>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => {
+            // Les sauts d'heure ne dépassent pas une heure dans les fuseaux usuels
+            let before = tz.from_local_datetime(&(naive - chrono::Duration::hours(1))).earliest()?;
+            let after = tz.from_local_datetime(&(naive + chrono::Duration::hours(1))).latest()?;
+            let gap = before.offset().fix().local_minus_utc() - after.offset().fix().local_minus_utc();
+            tz.from_local_datetime(&(naive - chrono::Duration::seconds(i64::from(gap)))).earliest()
+        }
+    }
+}
+
+/// Instant réel d'une heure murale de l'alarme (dans son fuseau, sinon local)
+pub fn occurrence_instant(alarm: &AlarmEntry, naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    match alarm.timezone.as_deref().and_then(|name| parse_timezone(name).ok()) {
+        Some(tz) => resolve_local(&tz, naive).map(|dt| dt.with_timezone(&Utc)),
+        None => resolve_local(&Local, naive).map(|dt| dt.with_timezone(&Utc)),
+    }
+}
+
+/// Vérifie si l'alarme est prévue à une date.
+/// La règle de récurrence, si présente, remplace la liste de jours.
+pub fn is_scheduled_on(alarm: &AlarmEntry, date: NaiveDate) -> bool {
+    match &alarm.recurrence {
+        Some(rule) => rule.matches(date),
+        None => {
+            alarm.days.is_empty()
+                || alarm.days.iter().any(|d| d == weekday_to_string(date.weekday()))
+        }
+    }
+}
+
+/// Horizon de recherche de la prochaine occurrence (couvre les règles mensuelles espacées)
+const MAX_LOOKAHEAD_DAYS: u64 = 731;
+
+/// Calcule la prochaine occurrence de l'alarme strictement après `now`
+pub fn next_occurrence(alarm: &AlarmEntry, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let times = daily_times(alarm);
+
+    (0..=MAX_LOOKAHEAD_DAYS)
+        .filter_map(|offset| now.date().checked_add_days(chrono::Days::new(offset)))
+        .filter(|date| is_scheduled_on(alarm, *date))
+        .find_map(|date| times.iter().map(|time| date.and_time(*time)).find(|at| *at > now))
+}
+
+/// Vérifie si l'alarme est suspendue ce jour-là (occurrence sautée ou vacances).
+/// Le mode vacances inclut le jour `vacation_until`.
+pub fn is_suppressed(alarm: &AlarmEntry, today: NaiveDate, vacation_until: Option<NaiveDate>) -> bool {
+    alarm.skip_date == Some(today) || vacation_until.is_some_and(|until| today <= until)
+}
+
+/// Convertit un Weekday en String
+pub(crate) fn weekday_to_string(day: Weekday) -> &'static str {
+    match day {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// Convertit une String en Weekday
+pub fn string_to_weekday(s: &str) -> Option<Weekday> {
+    match s {
+        "Monday" => Some(Weekday::Mon),
+        "Tuesday" => Some(Weekday::Tue),
+        "Wednesday" => Some(Weekday::Wed),
+        "Thursday" => Some(Weekday::Thu),
+        "Friday" => Some(Weekday::Fri),
+        "Saturday" => Some(Weekday::Sat),
+        "Sunday" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Calcule le temps restant avant le déclenchement de l'alarme (en secondes).
+/// Ne tient compte que de l'heure: aujourd'hui si elle n'est pas passée, sinon demain.
+pub fn time_until_alarm(alarm: &AlarmEntry, now: DateTime<Local>) -> Option<i64> {
+    let times = daily_times(alarm);
+    let today = wall_clock(alarm, now).date();
+
+    // Aujourd'hui ou demain, en instants réels (un jour de changement d'heure dure 23h ou 25h)
+    (0..=1)
+        .filter_map(|offset| today.checked_add_days(chrono::Days::new(offset)))
+        .flat_map(|date| times.iter().map(move |time| date.and_time(*time)))
+        .filter_map(|naive| occurrence_instant(alarm, naive))
+        .map(|instant| instant.signed_duration_since(now).num_seconds())
+        .find(|seconds| *seconds >= 0)
+}
+
+/// Formate le temps restant en texte lisible
+pub fn format_time_until(seconds: i64, locale: Locale) -> String {
+    i18n::format_duration(locale, seconds)
+}
+
+/// Génère la liste des jours de la semaine
+pub fn get_weekdays() -> Vec<&'static str> {
+    vec!["Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday", "Sunday"]
+}
+
+/// Vérifie si l'alarme est pour un jour de semaine
+pub fn is_weekday_only(days: &[String]) -> bool {
+    let weekdays = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday"];
+    days.iter().all(|d| weekdays.contains(&d.as_str())) && days.len() == 5
+}
+
+/// Vérifie si l'alarme ne sonne que des jours ouvrés (lundi-vendredi)
+pub fn is_workday_alarm(days: &[String]) -> bool {
+    let weekdays = ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday"];
+    !days.is_empty() && days.iter().all(|d| weekdays.contains(&d.as_str()))
+}
+
+/// Vérifie si une date tombe le weekend
+pub fn is_weekend_day(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Vérifie si l'alarme est pour le weekend
+pub fn is_weekend_only(days: &[String]) -> bool {
+    let weekend = ["Saturday", "Sunday"];
+    days.iter().all(|d| weekend.contains(&d.as_str())) && days.len() == 2
+}
+
+/// Alarme de reference des tests des autres modules: les champs propres a chaque
+/// test se surchargent avec `AlarmEntry { .., ..alarm(id, time) }`
+#[cfg(test)]
+pub(crate) mod test_support {
+    use crate::AlarmEntry;
+
+    pub(crate) fn alarm(id: &str, time: &str) -> AlarmEntry {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "time": time,
+            "playlist_name": "Morning",
+            "playlist_uri": "local",
+            "volume": 80,
+            "active": true,
+            "days": ["Monday"],
+            "fade_in": false,
+            "fade_in_duration": 0
+        }))
+        .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_alarm(time: &str, days: &[&str]) -> AlarmEntry {
+        AlarmEntry {
+            id: "test".to_string(),
+            time: time.to_string(),
+            playlist_name: String::new(),
+            playlist_uri: "local".to_string(),
+            volume: 50,
+            active: true,
+            archived: false,
+            days: days.iter().map(|d| d.to_string()).collect(),
+            fade_in: false,
+            fade_in_duration: 0,
+            account: None,
+            skip_date: None,
+            recurrence: None,
+            timezone: None,
+            challenge: None,
+            escalation: None,
+            ring_limit: None,
+            weather_playlists: None,
+            announcement: None,
+            sunrise: false,
+            output_device: None,
+            fade_curve: FadeCurve::default(),
+            lights: None,
+            calendar: None,
+            wake_window: None,
+            first_track_uri: None,
+            weekend_profile: None,
+            group_playback: None,
+            after_wake_queue: None,
+            interval: None,
+            pre_alarm: None,
+            backup: None,
+            group: None,
+            color: None,
+            icon: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_weekday_conversion() {
+        assert_eq!(weekday_to_string(Weekday::Mon), "Monday");
+        assert_eq!(weekday_to_string(Weekday::Sun), "Sunday");
+        
+        assert_eq!(string_to_weekday("Monday"), Some(Weekday::Mon));
+        assert_eq!(string_to_weekday("Invalid"), None);
+    }
+
+    #[test]
+    fn test_time_format() {
+        assert_eq!(format_time_until(30, Locale::Fr), "30 secondes");
+        assert_eq!(format_time_until(1, Locale::Fr), "1 seconde");
+        assert_eq!(format_time_until(90, Locale::Fr), "1 minute");
+        assert_eq!(format_time_until(180, Locale::Fr), "3 minutes");
+        assert_eq!(format_time_until(3600, Locale::Fr), "1 heure");
+        assert_eq!(format_time_until(7200, Locale::Fr), "2 heures");
+        assert_eq!(format_time_until(3661, Locale::Fr), "1h 1m");
+        assert_eq!(format_time_until(3600, Locale::En), "1 hour");
+    }
+
+    #[test]
+    fn test_seconds_precision() {
+        let precise = parse_alarm_time("06:59:30").unwrap();
+        assert_eq!(precise, NaiveTime::from_hms_opt(6, 59, 30).unwrap());
+        assert_eq!(format_alarm_time(precise), "06:59:30");
+        assert_eq!(format_alarm_time(parse_alarm_time("07:00").unwrap()), "07:00");
+        assert!(parse_alarm_time("7h").is_none());
+
+        // Heure a la seconde: sonne des la seconde atteinte, pas avant
+        let alarm = sample_alarm("06:59:30", &[]);
+        let config = AppConfig::default();
+        let at = Local.with_ymd_and_hms(2024, 3, 12, 6, 59, 30).unwrap();
+        assert!(!should_trigger(&alarm, at - chrono::Duration::seconds(1), &config));
+        assert!(should_trigger(&alarm, at, &config));
+        assert!(should_trigger(&alarm, at + chrono::Duration::seconds(59), &config));
+        assert!(!should_trigger(&alarm, at + chrono::Duration::seconds(60), &config));
+    }
+
+    #[test]
+    fn test_interval_alarm() {
+        // Hydratation toutes les 2 heures de 09:00 a 21:00
+        let mut alarm = sample_alarm("09:00", &[]);
+        alarm.interval = Some(IntervalRule { every_minutes: 120, until: Some("21:00".to_string()) });
+        let times: Vec<String> = daily_times(&alarm).into_iter().map(format_alarm_time).collect();
+        assert_eq!(times, ["09:00", "11:00", "13:00", "15:00", "17:00", "19:00", "21:00"]);
+
+        let config = AppConfig::default();
+        let day = |h: u32, m: u32| Local.with_ymd_and_hms(2024, 3, 12, h, m, 0).unwrap();
+        assert!(should_trigger(&alarm, day(13, 0), &config));
+        assert!(!should_trigger(&alarm, day(14, 0), &config));
+        assert_eq!(next_trigger(&alarm, day(13, 30), &config), Some(day(15, 0)));
+        assert_eq!(next_trigger(&alarm, day(21, 30), &config), Some(day(9, 0) + chrono::Duration::days(1)));
+
+        // L'occurrence qui sonne porte son heure
+        let mut plans = HashMap::new();
+        let due = due_alarm(&[alarm], &mut plans, &FiredLedger::new(), &[], day(17, 0), &config).unwrap();
+        assert_eq!(due.alarm.time, "17:00");
+
+        let mut settings: AlarmSettings = serde_json::from_value(serde_json::json!({
+            "playlist_name": "Eau", "playlist_uri": "local", "volume": 50, "days": [],
+            "fade_in": false, "fade_in_duration": 0,
+            "interval": { "every_minutes": 120, "until": "08:00" }
+        })).unwrap();
+        assert!(settings.clone().into_alarm("09:00".to_string()).is_err());
+        settings.interval = Some(IntervalRule { every_minutes: 2, until: None });
+        assert!(settings.into_alarm("09:00".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_weekday_checks() {
+        let weekdays = vec![
+            "Monday".to_string(),
+            "Tuesday".to_string(),
+            "Wednesday".to_string(),
+            "Thursday".to_string(),
+            "Friday".to_string(),
+        ];
+        assert!(is_weekday_only(&weekdays));
+        assert!(!is_weekend_only(&weekdays));
+
+        let weekend = vec!["Saturday".to_string(), "Sunday".to_string()];
+        assert!(is_weekend_only(&weekend));
+        assert!(!is_weekday_only(&weekend));
+    }
+
+    #[test]
+    fn test_weekend_profile() {
+        let mut alarm = sample_alarm("07:00", &[]);
+        alarm.weekend_profile = Some(SoundProfile {
+            playlist_name: "Sunday".to_string(),
+            playlist_uri: "spotify:playlist:soft".to_string(),
+            volume: 30,
+            fade_in: true,
+            fade_in_duration: 600,
+            fade_curve: FadeCurve::SCurve,
+        });
+
+        // Vendredi 15 mars 2024: reglages de l'alarme, dimanche 17: profil du week-end
+        let mut friday = alarm.clone();
+        assert!(!apply_day_profile(&mut friday, NaiveDate::from_ymd_opt(2024, 3, 15).unwrap()));
+        assert_eq!(friday.playlist_uri, "local");
+
+        let mut sunday = alarm;
+        assert!(apply_day_profile(&mut sunday, NaiveDate::from_ymd_opt(2024, 3, 17).unwrap()));
+        assert_eq!(sunday.playlist_uri, "spotify:playlist:soft");
+        assert_eq!(sunday.volume, 30);
+        assert!(sunday.fade_in);
+        assert_eq!(sunday.fade_curve, FadeCurve::SCurve);
+    }
+
+    #[test]
+    fn test_skip_next_and_vacation() {
+        let mut alarm = sample_alarm("07:00", &["Monday", "Friday"]);
+
+        // Mardi 12 mars 2024 08:00 -> prochaine occurrence vendredi 15
+        let tuesday = NaiveDate::from_ymd_opt(2024, 3, 12).unwrap();
+        let friday = NaiveDate::from_ymd_opt(2024, 3, 15).unwrap();
+        let monday = NaiveDate::from_ymd_opt(2024, 3, 18).unwrap();
+        assert_eq!(next_occurrence(&alarm, tuesday.and_hms_opt(8, 0, 0).unwrap()).map(|d| d.date()), Some(friday));
+        // Vendredi 06:59 -> le jour même, vendredi 07:00 -> lundi suivant
+        assert_eq!(next_occurrence(&alarm, friday.and_hms_opt(6, 59, 0).unwrap()).map(|d| d.date()), Some(friday));
+        assert_eq!(next_occurrence(&alarm, friday.and_hms_opt(7, 0, 0).unwrap()).map(|d| d.date()), Some(monday));
+
+        alarm.skip_date = Some(friday);
+        assert!(is_suppressed(&alarm, friday, None));
+        assert!(!is_suppressed(&alarm, monday, None));
+
+        assert!(is_suppressed(&alarm, monday, Some(monday)));
+        assert!(!is_suppressed(&alarm, monday, Some(friday)));
+    }
+
+    #[test]
+    fn test_should_trigger_skips_holidays() {
+        let config = AppConfig {
+            holiday_country: Some("FR".to_string()),
+            ..Default::default()
+        };
+        let workday = sample_alarm("07:00", &["Monday", "Tuesday", "Wednesday", "Thursday", "Friday"]);
+        let everyday = sample_alarm("07:00", &[]);
+
+        // Lundi 1er avril 2024 (lundi de Pâques) puis mardi 2 avril
+        let holiday = Local.with_ymd_and_hms(2024, 4, 1, 7, 0, 0).unwrap();
+        let next_day = Local.with_ymd_and_hms(2024, 4, 2, 7, 0, 0).unwrap();
+        assert!(!should_trigger(&workday, holiday, &config));
+        assert!(should_trigger(&everyday, holiday, &config));
+        assert!(should_trigger(&workday, next_day, &config));
+    }
+
+    #[test]
+    fn test_timezone_alarm() {
+        let mut alarm = sample_alarm("07:00", &[]);
+        alarm.timezone = Some("Europe/Paris".to_string());
+
+        // 06:00 UTC en hiver = 07:00 à Paris, quel que soit le fuseau de la machine
+        let now = chrono::Utc.with_ymd_and_hms(2024, 1, 15, 6, 0, 0).unwrap().with_timezone(&Local);
+        assert!(should_trigger(&alarm, now, &AppConfig::default()));
+
+        alarm.timezone = Some("America/New_York".to_string());
+        assert!(!should_trigger(&alarm, now, &AppConfig::default()));
+
+        assert!(parse_timezone("Mars/Olympus_Mons").is_err());
+    }
+
+    #[test]
+    fn test_dst_transitions() {
+        let config = AppConfig::default();
+        let utc = |m, d, h, min| chrono::Utc.with_ymd_and_hms(2024, m, d, h, min, 0).unwrap().with_timezone(&Local);
+        let mut alarm = sample_alarm("07:00", &[]);
+        alarm.timezone = Some("Europe/Paris".to_string());
+
+        // Veille de l'avance d'heure (31 mars) à 20:00: 10h et non 11h
+        assert_eq!(time_until_alarm(&alarm, utc(3, 30, 19, 0)), Some(10 * 3600));
+        // Veille du recul d'heure (27 octobre) à 20:00: 12h et non 11h
+        assert_eq!(time_until_alarm(&alarm, utc(10, 26, 18, 0)), Some(12 * 3600));
+
+        // 02:30 n'existe pas le 31 mars: l'alarme sonne à 03:30 (01:30 UTC)
+        alarm.time = "02:30".to_string();
+        assert_eq!(time_until_alarm(&alarm, utc(3, 31, 0, 0)), Some(5400));
+        assert!(should_trigger(&alarm, utc(3, 31, 1, 30), &config));
+
+        // 02:30 existe deux fois le 27 octobre: seule la première sonne
+        assert_eq!(time_until_alarm(&alarm, utc(10, 27, 0, 0)), Some(1800));
+        assert!(should_trigger(&alarm, utc(10, 27, 0, 30), &config));
+        assert!(!should_trigger(&alarm, utc(10, 27, 1, 30), &config));
+    }
+
+    #[test]
+    fn test_next_alarm() {
+        let config = AppConfig {
+            holiday_country: Some("FR".to_string()),
+            ..Default::default()
+        };
+        let workday = sample_alarm("07:00", &["Monday", "Tuesday", "Wednesday", "Thursday", "Friday"]);
+        let weekend = sample_alarm("09:30", &["Saturday", "Sunday"]);
+        let mut disabled = sample_alarm("06:00", &[]);
+        disabled.active = false;
+        let alarms = vec![workday.clone(), weekend, disabled];
+
+        // Vendredi 29 mars 2024 22:00 -> samedi 09:30
+        let friday = Local.with_ymd_and_hms(2024, 3, 29, 22, 0, 0).unwrap();
+        let next = next_alarm(&alarms, &[], friday, &config).unwrap();
+        assert_eq!(next.alarm.time, "09:30");
+        assert_eq!(next.seconds_until, 11 * 3600 + 30 * 60);
+        assert_eq!(next.label, "11h 30m");
+
+        // Lundi de Pâques férié: l'alarme de semaine passe au mardi
+        let sunday = Local.with_ymd_and_hms(2024, 3, 31, 10, 0, 0).unwrap();
+        let next = next_trigger(&workday, sunday, &config).unwrap();
+        assert_eq!(next.date_naive(), NaiveDate::from_ymd_opt(2024, 4, 2).unwrap());
+
+        // Une sieste plus proche passe devant
+        let nap = NapTimer::new(friday, 30, "Sieste".to_string(), "local".to_string(), 50);
+        let next = next_alarm(&alarms, &[nap], friday, &config).unwrap();
+        assert_eq!(next.seconds_until, 1800);
+
+        assert!(next_alarm(&[], &[], friday, &config).is_none());
+    }
+
+    #[test]
+    fn test_due_alarm() {
+        let config = AppConfig::default();
+        let mut early = sample_alarm("07:00", &[]);
+        early.id = "early".to_string();
+        let mut planned = sample_alarm("07:30", &[]);
+        planned.id = "planned".to_string();
+        let alarms = vec![early, planned];
+
+        // Fenetre de reveil: sonne au titre retenu, une seule fois, et pas a l'heure fixe
+        let alarm_at = Local.with_ymd_and_hms(2024, 3, 12, 7, 30, 0).unwrap();
+        let ring_at = alarm_at - chrono::Duration::minutes(12);
+        let mut plans = HashMap::from([(
+            "planned".to_string(),
+            WakePlan { alarm_at, ring_at, preroll: true, rung: false },
+        )]);
+        let mut fired = FiredLedger::new();
+        assert_eq!(due_alarm(&alarms, &mut plans, &fired, &[], ring_at, &config).map(|d| d.alarm.id), Some("planned".to_string()));
+        assert!(plans["planned"].rung);
+        assert!(due_alarm(&alarms, &mut plans, &fired, &[], alarm_at, &config).is_none());
+        assert!(due_alarm(&alarms, &mut plans, &fired, &[], alarm_at + chrono::Duration::minutes(1), &config).is_none());
+        assert!(plans.is_empty());
+
+        let seven = Local.with_ymd_and_hms(2024, 3, 12, 7, 0, 30).unwrap();
+        assert_eq!(due_alarm(&alarms, &mut plans, &fired, &[], seven, &config).map(|d| d.alarm.id), Some("early".to_string()));
+
+        // Une seule sonnerie par occurrence, meme si l'alarme reste due toute la minute
+        record_fired(&mut fired, "early", seven);
+        assert!(due_alarm(&alarms, &mut plans, &fired, &[], seven + chrono::Duration::seconds(20), &config).is_none());
+        let next_day = seven + chrono::Duration::days(1);
+        assert!(!fired_recently(&fired, "early", next_day));
+        record_fired(&mut fired, "planned", next_day);
+        assert_eq!(fired.len(), 1);
+
+        // Collision: la plus forte gagne, puis la plus tot, puis l'id; les autres sont absorbees
+        let mut soft = sample_alarm("07:00", &[]);
+        soft.id = "a-soft".to_string();
+        soft.volume = 20;
+        let mut loud_late = sample_alarm("07:00:10", &[]);
+        loud_late.id = "c-loud".to_string();
+        loud_late.volume = 90;
+        let mut loud_early = loud_late.clone();
+        loud_early.id = "d-loud".to_string();
+        loud_early.time = "07:00:05".to_string();
+        let mut loud_twin = loud_early.clone();
+        loud_twin.id = "b-loud".to_string();
+        let colliding = vec![soft, loud_late, loud_early, loud_twin];
+        let due = due_alarm(&colliding, &mut plans, &FiredLedger::new(), &[], seven, &config).unwrap();
+        assert_eq!(due.alarm.id, "b-loud");
+        assert_eq!(due.absorbed, ["d-loud", "c-loud", "a-soft"]);
+    }
+
+    #[test]
+    fn test_nap_timer() {
+        let now = Local.with_ymd_and_hms(2024, 3, 12, 23, 50, 0).unwrap();
+        let nap = NapTimer::new(now, 25, "Sieste".to_string(), "local".to_string(), 120);
+
+        assert_eq!(nap.alarm.time, "00:15");
+        assert_eq!(nap.alarm.volume, 100);
+        assert!(!nap.is_due(now));
+        assert!(!nap.is_due(now + chrono::Duration::minutes(24)));
+        assert!(nap.is_due(now + chrono::Duration::minutes(25)));
+    }
+
+    #[test]
+    fn test_alarm_from_template() {
+        let template: AlarmTemplate = serde_json::from_str(
+            r#"{"name": "Reveil semaine", "playlist_name": "Morning", "playlist_uri": "spotify:playlist:1",
+                "volume": 150, "days": ["Monday", "Friday"], "fade_in": true, "fade_in_duration": 30,
+                "timezone": " "}"#,
+        )
+        .unwrap();
+
+        let alarm = template.settings.clone().into_alarm("06:45".to_string()).unwrap();
+        assert_eq!(alarm.time, "06:45");
+        assert_eq!(alarm.volume, 100);
+        assert_eq!(alarm.days, vec!["Monday", "Friday"]);
+        assert!(alarm.fade_in && alarm.active);
+        assert_eq!(alarm.timezone, None);
+
+        assert!(template.settings.clone().into_alarm("6h45".to_string()).is_err());
+        let mut power_song = template.settings.clone();
+        power_song.first_track_uri = Some(" spotify:track:abc ".to_string());
+        assert_eq!(power_song.clone().into_alarm("06:45".to_string()).unwrap().first_track_uri.as_deref(), Some("spotify:track:abc"));
+        power_song.first_track_uri = Some("spotify:playlist:abc".to_string());
+        assert!(power_song.into_alarm("06:45".to_string()).is_err());
+
+        let mut group = template.settings.clone();
+        group.group_playback = Some(GroupPlayback { spotify_device: Some(" Maison ".to_string()), local_sound: true });
+        let alarm = group.clone().into_alarm("06:45".to_string()).unwrap();
+        assert_eq!(alarm.group_playback.unwrap().spotify_device.as_deref(), Some("Maison"));
+        group.playlist_uri = "radio:https://example.com/live".to_string();
+        assert!(group.into_alarm("06:45".to_string()).is_err());
+
+        let mut briefing = template.settings.clone();
+        briefing.after_wake_queue = Some(AfterWakeQueue { uris: vec![" spotify:episode:abc ".to_string()], after_tracks: 2 });
+        let alarm = briefing.clone().into_alarm("06:45".to_string()).unwrap();
+        assert_eq!(alarm.after_wake_queue.unwrap().uris, vec!["spotify:episode:abc"]);
+        briefing.after_wake_queue = Some(AfterWakeQueue { uris: vec!["spotify:album:abc".to_string()], after_tracks: 2 });
+        assert!(briefing.into_alarm("06:45".to_string()).is_err());
+
+        let mut heads_up = template.settings.clone();
+        heads_up.pre_alarm = Some(PreAlarm { minutes: 5, sound: PreAlarmSound::Spotify, volume: 250 });
+        assert_eq!(heads_up.clone().into_alarm("06:45".to_string()).unwrap().pre_alarm.unwrap().volume, 100);
+        heads_up.playlist_uri = "local".to_string();
+        assert!(heads_up.clone().into_alarm("06:45".to_string()).is_err());
+
+        let mut themed = template.settings.clone();
+        themed.color = Some(" #1DB954 ".to_string());
+        themed.icon = Some("coffee".to_string());
+        let alarm = themed.clone().into_alarm("06:45".to_string()).unwrap();
+        assert_eq!((alarm.color.as_deref(), alarm.icon.as_deref()), (Some("#1db954"), Some("coffee")));
+        themed.color = Some("green".to_string());
+        assert!(themed.clone().into_alarm("06:45".to_string()).is_err());
+        themed.color = None;
+        themed.icon = Some("rocket".to_string());
+        assert!(themed.into_alarm("06:45".to_string()).is_err());
+        heads_up.pre_alarm = Some(PreAlarm { minutes: 90, sound: PreAlarmSound::Chime, volume: 15 });
+        assert!(heads_up.into_alarm("06:45".to_string()).is_err());
+
+        let mut invalid = template.settings;
+        invalid.timezone = Some("Nowhere/City".to_string());
+        assert!(invalid.into_alarm("06:45".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_ring_limit_and_auto_snooze() {
+        let limit = RingLimit {
+            max_minutes: 10,
+            on_timeout: RingTimeoutAction::Snooze { minutes: 9 },
+        };
+        let started = Local.with_ymd_and_hms(2024, 3, 12, 7, 0, 0).unwrap();
+        assert!(!limit.is_exceeded(started, started + chrono::Duration::minutes(9)));
+        assert!(limit.is_exceeded(started, started + chrono::Duration::minutes(10)));
+        assert!(limit.validate().is_ok());
+        assert!(RingLimit { max_minutes: 0, ..limit }.validate().is_err());
+
+        let mut alarm = sample_alarm("07:00", &["Tuesday"]);
+        alarm.ring_limit = Some(limit);
+        let ringing = RingingAlarm::new(alarm, started);
+        let now = started + chrono::Duration::minutes(10);
+        let snooze = NapTimer::snooze(&ringing, now, 9);
+
+        assert_eq!(snooze.alarm.id, "test");
+        assert_eq!(snooze.alarm.time, "07:19");
+        assert!(snooze.alarm.days.is_empty());
+        assert_eq!(snooze.alarm.ring_limit, Some(limit));
+        assert_eq!(snooze.auto_snoozes, 1);
+
+        // Alarme de secours avant la duree maximale, une seule fois
+        let backup = BackupAlarm { after_minutes: 5 };
+        assert!(backup.validate(Some(&limit)).is_ok());
+        assert!(BackupAlarm { after_minutes: 10 }.validate(Some(&limit)).is_err());
+        let mut ringing = ringing;
+        ringing.alarm.backup = Some(backup);
+        assert!(!ringing.backup_due(started + chrono::Duration::minutes(4)));
+        assert!(ringing.backup_due(started + chrono::Duration::minutes(5)));
+        ringing.backup_fired = true;
+        assert!(!ringing.backup_due(started + chrono::Duration::minutes(6)));
+    }
+
+    #[test]
+    fn test_fall_back_offline() {
+        let mut alarm = sample_alarm("07:00", &[]);
+        assert!(!fall_back_offline(&mut alarm));
+        alarm.playlist_uri = "library:folder:Matin".to_string();
+        assert!(!fall_back_offline(&mut alarm));
+
+        alarm.playlist_uri = "spotify:playlist:abc".to_string();
+        alarm.first_track_uri = Some("spotify:track:xyz".to_string());
+        assert!(fall_back_offline(&mut alarm));
+        assert_eq!(alarm.playlist_uri, "local");
+        assert_eq!(alarm.first_track_uri, None);
+    }
+}
\ No newline at end of file
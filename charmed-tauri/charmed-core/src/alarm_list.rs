@@ -0,0 +1,344 @@
+// alarm_list.rs - Modifications de la liste des alarmes (application, API distante, CLI)
+// L'appelant detient la liste (verrou, fichier) et la persiste apres chaque modification
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, Utc};
+use serde::Serialize;
+
+use crate::alarm;
+use crate::i18n::{tr, Locale, Msg};
+use crate::recurrence::Frequency;
+use crate::AlarmEntry;
+
+/// Ecart en dessous duquel deux alarmes sont signalees comme trop proches
+pub const CONFLICT_WINDOW_MINUTES: i64 = 5;
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// Avertissement non bloquant retourne avec une alarme enregistree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AlarmWarning {
+    /// Meme heure et memes jours qu'une alarme existante
+    Duplicate { alarm_id: String },
+    /// Sonne a moins de CONFLICT_WINDOW_MINUTES d'une autre alarme un jour commun
+    TooClose { alarm_id: String, minutes: i64 },
+    /// Volume a 0: l'alarme ne s'entendra pas
+    Silent,
+    /// Repetition hebdomadaire sans jour choisi (seul le jour de depart sonne)
+    NoRepeatDays,
+}
+
+/// Alarme d'identifiant donne
+pub fn find_mut<'a>(alarms: &'a mut [AlarmEntry], alarm_id: &str, locale: Locale) -> Result<&'a mut AlarmEntry, String> {
+    alarms
+        .iter_mut()
+        .find(|a| a.id == alarm_id)
+        .ok_or_else(|| tr(locale, Msg::AlarmNotFound, &[&alarm_id]))
+}
+
+/// Remplace les reglages d'une alarme. `expected_updated_at` est la date de
+/// modification lue par l'appelant: si l'alarme a change depuis (autre fenetre,
+/// machine synchronisee), la modification est refusee au lieu d'ecraser l'autre.
+pub fn update(
+    alarms: &mut [AlarmEntry],
+    alarm_id: &str,
+    expected_updated_at: Option<DateTime<Utc>>,
+    time: String,
+    settings: alarm::AlarmSettings,
+    locale: Locale,
+) -> Result<AlarmEntry, String> {
+    let alarm = find_mut(alarms, alarm_id, locale)?;
+    if alarm.updated_at != expected_updated_at {
+        return Err(tr(locale, Msg::AlarmConflict, &[&alarm_id]));
+    }
+    let updated = AlarmEntry {
+        id: alarm.id.clone(),
+        active: alarm.active,
+        archived: alarm.archived,
+        skip_date: alarm.skip_date,
+        ..settings.into_alarm(time)?
+    };
+    *alarm = updated.clone();
+    Ok(updated)
+}
+
+/// Inverse l'etat actif d'une alarme et retourne le nouvel etat
+pub fn toggle(alarms: &mut [AlarmEntry], alarm_id: &str, locale: Locale) -> Result<bool, String> {
+    let alarm = find_mut(alarms, alarm_id, locale)?;
+    alarm.active = !alarm.active;
+    alarm.updated_at = Some(Utc::now());
+    Ok(alarm.active)
+}
+
+/// Active ou desactive toutes les alarmes d'un groupe (nom sans tenir compte de la casse).
+/// Retourne les identifiants des alarmes du groupe.
+pub fn toggle_group(alarms: &mut [AlarmEntry], group: &str, active: bool, locale: Locale) -> Result<Vec<String>, String> {
+    let name = group.trim();
+    let group = name.to_lowercase();
+    let now = Utc::now();
+    let mut ids = Vec::new();
+    for alarm in alarms
+        .iter_mut()
+        .filter(|a| a.group.as_deref().is_some_and(|g| g.to_lowercase() == group))
+    {
+        if alarm.active != active {
+            alarm.active = active;
+            alarm.updated_at = Some(now);
+        }
+        ids.push(alarm.id.clone());
+    }
+    if ids.is_empty() {
+        return Err(tr(locale, Msg::GroupNotFound, &[&name]));
+    }
+    Ok(ids)
+}
+
+/// Archive ou desarchive une alarme (masquee et jamais evaluee tant qu'elle est archivee)
+pub fn set_archived(alarms: &mut [AlarmEntry], alarm_id: &str, archived: bool, locale: Locale) -> Result<(), String> {
+    let alarm = find_mut(alarms, alarm_id, locale)?;
+    alarm.archived = archived;
+    alarm.updated_at = Some(Utc::now());
+    Ok(())
+}
+
+/// Retire une alarme de la liste et la retourne
+pub fn remove(alarms: &mut Vec<AlarmEntry>, alarm_id: &str, locale: Locale) -> Result<AlarmEntry, String> {
+    let position = alarms
+        .iter()
+        .position(|a| a.id == alarm_id)
+        .ok_or_else(|| tr(locale, Msg::AlarmNotFound, &[&alarm_id]))?;
+    Ok(alarms.remove(position))
+}
+
+/// Saute uniquement la prochaine occurrence d'une alarme.
+/// Retourne la date de l'occurrence sautee.
+pub fn skip_next(
+    alarms: &mut [AlarmEntry],
+    alarm_id: &str,
+    now: DateTime<Local>,
+    locale: Locale,
+) -> Result<NaiveDate, String> {
+    let alarm = find_mut(alarms, alarm_id, locale)?;
+    let skipped = alarm::next_occurrence(alarm, alarm::wall_clock(alarm, now))
+        .map(|next| next.date())
+        .ok_or_else(|| tr(locale, Msg::NoUpcomingOccurrence, &[]))?;
+    alarm.skip_date = Some(skipped);
+    alarm.updated_at = Some(Utc::now());
+    Ok(skipped)
+}
+
+/// Identifiants presents avant et absents apres (suppressions a transmettre aux machines synchronisees)
+pub fn removed_ids(before: &[AlarmEntry], after: &[AlarmEntry]) -> Vec<String> {
+    before
+        .iter()
+        .filter(|a| !after.iter().any(|b| b.id == a.id))
+        .map(|a| a.id.clone())
+        .collect()
+}
+
+/// Jours de la semaine couverts par une alarme (None = tous les jours)
+pub(crate) fn covered_weekdays(alarm: &AlarmEntry) -> Option<Vec<String>> {
+    match &alarm.recurrence {
+        Some(rule) if rule.by_day.is_empty() && rule.freq == Frequency::Weekly => {
+            Some(vec![alarm::weekday_to_string(rule.start.weekday()).to_string()])
+        }
+        Some(rule) if rule.by_day.is_empty() => None,
+        Some(rule) => Some(rule.weekdays()),
+        None if alarm.days.is_empty() => None,
+        None => {
+            let mut days = alarm.days.clone();
+            days.sort();
+            days.dedup();
+            Some(days)
+        }
+    }
+}
+
+fn shares_a_day(a: &AlarmEntry, b: &AlarmEntry) -> bool {
+    match (covered_weekdays(a), covered_weekdays(b)) {
+        (Some(days_a), Some(days_b)) => days_a.iter().any(|d| days_b.contains(d)),
+        _ => true,
+    }
+}
+
+/// `morning` sonne le lendemain d'un jour de `evening` (ecart a cheval sur minuit)
+fn follows_a_day(evening: &AlarmEntry, morning: &AlarmEntry) -> bool {
+    match (covered_weekdays(evening), covered_weekdays(morning)) {
+        (Some(days_evening), Some(days_morning)) => days_evening.iter().any(|d| {
+            alarm::string_to_weekday(d)
+                .is_some_and(|day| days_morning.iter().any(|m| m == alarm::weekday_to_string(day.succ())))
+        }),
+        _ => true,
+    }
+}
+
+/// Incoherences d'une alarme, seule et par rapport aux autres alarmes actives.
+/// `existing` ne doit pas contenir l'alarme verifiee.
+pub fn warnings(existing: &[AlarmEntry], alarm: &AlarmEntry) -> Vec<AlarmWarning> {
+    let mut warnings = Vec::new();
+    if alarm.volume == 0 {
+        warnings.push(AlarmWarning::Silent);
+    }
+    if alarm
+        .recurrence
+        .as_ref()
+        .is_some_and(|rule| rule.freq == Frequency::Weekly && rule.by_day.is_empty())
+    {
+        warnings.push(AlarmWarning::NoRepeatDays);
+    }
+
+    let Some(time) = alarm::parse_alarm_time(&alarm.time) else {
+        return warnings;
+    };
+    for other in existing.iter().filter(|o| alarm::is_enabled(o) && o.id != alarm.id) {
+        let Some(other_time) = alarm::parse_alarm_time(&other.time) else {
+            continue;
+        };
+        // 23:58 et 00:02 sont a 4 minutes d'intervalle, sur deux jours consecutifs
+        let gap = (time - other_time).num_minutes().abs();
+        let wrapped = gap > MINUTES_PER_DAY / 2;
+        let minutes = if wrapped { MINUTES_PER_DAY - gap } else { gap };
+        let on_same_day = if !wrapped {
+            shares_a_day(alarm, other)
+        } else if time > other_time {
+            follows_a_day(alarm, other)
+        } else {
+            follows_a_day(other, alarm)
+        };
+        if !on_same_day {
+            continue;
+        }
+        let same_days = alarm.recurrence == other.recurrence
+            && (alarm.recurrence.is_some() || covered_weekdays(alarm) == covered_weekdays(other));
+        if time == other_time && same_days {
+            warnings.push(AlarmWarning::Duplicate { alarm_id: other.id.clone() });
+        } else if minutes < CONFLICT_WINDOW_MINUTES {
+            warnings.push(AlarmWarning::TooClose { alarm_id: other.id.clone(), minutes });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alarm::AlarmSettings;
+    use crate::fade::FadeCurve;
+    use chrono::TimeZone;
+
+    fn settings() -> AlarmSettings {
+        AlarmSettings {
+            playlist_name: "Alarme".to_string(),
+            playlist_uri: "local".to_string(),
+            volume: 50,
+            days: vec!["Monday".to_string()],
+            fade_in: false,
+            fade_in_duration: 0,
+            account: None,
+            recurrence: None,
+            timezone: None,
+            challenge: None,
+            escalation: None,
+            ring_limit: None,
+            weather_playlists: None,
+            announcement: None,
+            sunrise: false,
+            output_device: None,
+            fade_curve: FadeCurve::default(),
+            lights: None,
+            calendar: None,
+            wake_window: None,
+            first_track_uri: None,
+            weekend_profile: None,
+            group_playback: None,
+            after_wake_queue: None,
+            interval: None,
+            pre_alarm: None,
+            backup: None,
+            group: None,
+            color: None,
+            icon: None,
+        }
+    }
+
+    fn sample(id: &str) -> AlarmEntry {
+        AlarmEntry { id: id.to_string(), ..settings().into_alarm("07:00".to_string()).unwrap() }
+    }
+
+    #[test]
+    fn test_update_conflict() {
+        let mut alarms = vec![sample("a")];
+        let read_at = alarms[0].updated_at;
+        alarms[0].active = false;
+
+        let louder = AlarmSettings { volume: 90, ..settings() };
+        let updated = update(&mut alarms, "a", read_at, "07:15".to_string(), louder.clone(), Locale::Fr).unwrap();
+        assert_eq!((updated.id.as_str(), updated.time.as_str(), updated.volume), ("a", "07:15", 90));
+        assert!(!updated.active);
+
+        // Deuxieme fenetre restee sur l'ancienne version
+        assert!(update(&mut alarms, "a", read_at, "08:00".to_string(), louder, Locale::Fr).is_err());
+        assert_eq!(alarms[0].time, "07:15");
+    }
+
+    #[test]
+    fn test_alarm_list_operations() {
+        let mut alarms = vec![sample("a"), sample("b")];
+        let before = alarms.clone();
+
+        assert_eq!(toggle(&mut alarms, "a", Locale::Fr), Ok(false));
+        assert_eq!(toggle(&mut alarms, "a", Locale::Fr), Ok(true));
+        assert!(toggle(&mut alarms, "zzz", Locale::Fr).is_err());
+
+        set_archived(&mut alarms, "a", true, Locale::Fr).unwrap();
+        assert!(alarms[0].archived && !alarm::is_enabled(&alarms[0]));
+        set_archived(&mut alarms, "a", false, Locale::Fr).unwrap();
+        assert!(alarm::is_enabled(&alarms[0]));
+
+        alarms[1].group = Some("Travail".to_string());
+        assert_eq!(toggle_group(&mut alarms, "travail", false, Locale::Fr), Ok(vec!["b".to_string()]));
+        assert!(alarms[0].active && !alarms[1].active);
+        assert!(toggle_group(&mut alarms, "Maison", true, Locale::Fr).is_err());
+
+        // Mercredi 10 janvier 2024: prochaine occurrence le lundi 15
+        let now = Local.with_ymd_and_hms(2024, 1, 10, 12, 0, 0).unwrap();
+        let skipped = skip_next(&mut alarms, "b", now, Locale::Fr).unwrap();
+        assert_eq!(skipped, NaiveDate::from_ymd_opt(2024, 1, 15).unwrap());
+        assert_eq!(alarms[1].skip_date, Some(skipped));
+
+        assert_eq!(remove(&mut alarms, "a", Locale::Fr).map(|a| a.id), Ok("a".to_string()));
+        assert!(remove(&mut alarms, "a", Locale::Fr).is_err());
+        assert_eq!(removed_ids(&before, &alarms), vec!["a".to_string()]);
+    }
+
+    #[test]
+    fn test_warnings() {
+        let existing = vec![sample("a"), AlarmEntry { time: "07:03".to_string(), ..sample("b") }];
+
+        let duplicate = sample("c");
+        assert_eq!(
+            warnings(&existing, &duplicate),
+            vec![
+                AlarmWarning::Duplicate { alarm_id: "a".to_string() },
+                AlarmWarning::TooClose { alarm_id: "b".to_string(), minutes: 3 },
+            ]
+        );
+
+        // Autre jour: aucun conflit, mais volume nul
+        let silent = AlarmEntry { volume: 0, days: vec!["Sunday".to_string()], ..sample("d") };
+        assert_eq!(warnings(&existing, &silent), vec![AlarmWarning::Silent]);
+
+        let far = AlarmEntry { time: "07:30".to_string(), ..sample("e") };
+        assert!(warnings(&existing, &far).is_empty());
+
+        // A cheval sur minuit: dimanche 23:58 puis lundi 00:02
+        let late = vec![AlarmEntry { time: "23:58".to_string(), days: vec!["Sunday".to_string()], ..sample("f") }];
+        let early = AlarmEntry { time: "00:02".to_string(), ..sample("g") };
+        assert_eq!(
+            warnings(&late, &early),
+            vec![AlarmWarning::TooClose { alarm_id: "f".to_string(), minutes: 4 }]
+        );
+        let tuesday = AlarmEntry { days: vec!["Tuesday".to_string()], ..early };
+        assert!(warnings(&late, &tuesday).is_empty());
+    }
+}
@@ -0,0 +1,257 @@
+// apple_music.rs - Apple Music comme musique d'alarme, pour les comptes sans Spotify Premium
+// La bibliotheque est lue par l'API Apple Music (jeton developpeur MusicKit + jeton utilisateur
+// obtenu par MusicKit JS); la lecture est pilotee dans l'app Musique de macOS par AppleScript
+
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::provider::{MusicProvider, ProviderPlaylist};
+use crate::secrets;
+
+/// Racine de l'API Apple Music (les liens de pagination sont relatifs a l'hote)
+const API_HOST: &str = "https://api.music.apple.com";
+
+/// Delai maximal d'une requete vers l'API
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Playlists demandees par page
+const PLAYLIST_PAGE_SIZE: u32 = 100;
+
+/// Taille des pochettes demandees (pixels)
+const ARTWORK_SIZE: &str = "300";
+
+/// Prefixe des URI de playlists de la bibliotheque ("applemusic:playlist:p.AbC123")
+pub const PLAYLIST_URI_PREFIX: &str = "applemusic:playlist:";
+
+/// Jetons MusicKit, ranges ensemble dans le trousseau
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppleMusicTokens {
+    pub developer_token: String, // JWT signe avec la cle MusicKit du developpeur
+    pub user_token: String,      // Music-User-Token renvoye par MusicKit JS apres autorisation
+}
+
+impl AppleMusicTokens {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.developer_token.trim().is_empty() || self.user_token.trim().is_empty() {
+            return Err("Jetons Apple Music manquants (developpeur et utilisateur)".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Jetons enregistres pour le profil utilisateur (None si Apple Music n'est pas connecte)
+pub fn load_tokens(user: Option<&str>) -> Result<Option<AppleMusicTokens>, String> {
+    secrets::get_secret(&secrets::apple_music_key(user))?
+        .map(|json| serde_json::from_str(&json).map_err(|e| format!("Jetons Apple Music illisibles: {}", e)))
+        .transpose()
+}
+
+pub fn save_tokens(user: Option<&str>, tokens: &AppleMusicTokens) -> Result<(), String> {
+    let json = serde_json::to_string(tokens).map_err(|e| format!("Erreur sérialisation: {}", e))?;
+    secrets::set_secret(&secrets::apple_music_key(user), &json)
+}
+
+pub fn delete_tokens(user: Option<&str>) -> Result<(), String> {
+    secrets::delete_secret(&secrets::apple_music_key(user))
+}
+
+/// Page de playlists de la bibliotheque
+#[derive(Debug, Deserialize)]
+struct PlaylistPage {
+    #[serde(default)]
+    data: Vec<LibraryPlaylist>,
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LibraryPlaylist {
+    id: String,
+    attributes: PlaylistAttributes,
+}
+
+#[derive(Debug, Deserialize)]
+struct PlaylistAttributes {
+    name: String,
+    artwork: Option<Artwork>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Artwork {
+    url: String, // Modele avec {w} et {h}
+}
+
+impl From<LibraryPlaylist> for ProviderPlaylist {
+    fn from(p: LibraryPlaylist) -> Self {
+        Self {
+            uri: format!("{}{}", PLAYLIST_URI_PREFIX, p.id),
+            name: p.attributes.name,
+            image_url: p.attributes.artwork.map(|a| a.url.replace("{w}", ARTWORK_SIZE).replace("{h}", ARTWORK_SIZE)),
+            track_count: None,
+        }
+    }
+}
+
+/// Lit une page de playlists et le lien de la suivante
+fn parse_playlist_page(body: &str) -> Result<(Vec<ProviderPlaylist>, Option<String>), String> {
+    let page: PlaylistPage = serde_json::from_str(body)
+        .map_err(|e| format!("Reponse Apple Music invalide: {}", e))?;
+    Ok((page.data.into_iter().map(ProviderPlaylist::from).collect(), page.next))
+}
+
+/// Identifiant de bibliotheque d'une URI de playlist Apple Music
+pub fn playlist_id(uri: &str) -> Option<&str> {
+    uri.strip_prefix(PLAYLIST_URI_PREFIX).filter(|id| !id.is_empty())
+}
+
+/// Chaine AppleScript entre guillemets
+fn applescript_string(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Execute une commande AppleScript adressee a l'app Musique
+#[cfg(target_os = "macos")]
+async fn music_app(command: &str) -> Result<String, String> {
+    let script = format!("tell application \"Music\" to {}", command);
+    let output = tokio::process::Command::new("osascript")
+        .args(["-e", &script])
+        .output()
+        .await
+        .map_err(|e| format!("App Musique indisponible: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "App Musique en echec: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+async fn music_app(_command: &str) -> Result<String, String> {
+    Err("La lecture Apple Music passe par l'app Musique de macOS".to_string())
+}
+
+/// Client Apple Music d'un profil utilisateur
+#[derive(Clone)]
+pub struct AppleMusicClient {
+    http: reqwest::Client,
+    tokens: AppleMusicTokens,
+}
+
+impl AppleMusicClient {
+    pub fn new(tokens: AppleMusicTokens) -> Result<Self, String> {
+        tokens.validate()?;
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { http, tokens })
+    }
+
+    pub fn tokens(&self) -> &AppleMusicTokens {
+        &self.tokens
+    }
+
+    /// GET authentifie sur l'API (chemin commencant par /v1)
+    async fn get(&self, path: &str) -> Result<String, String> {
+        let response = self.http
+            .get(format!("{}{}", API_HOST, path))
+            .bearer_auth(self.tokens.developer_token.trim())
+            .header("Music-User-Token", self.tokens.user_token.trim())
+            .send()
+            .await
+            .map_err(|e| format!("Apple Music injoignable: {}", e))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            return Err("Jetons Apple Music refuses ou expires: reconnectez Apple Music".to_string());
+        }
+        if !status.is_success() {
+            return Err(format!("Apple Music: requete refusee ({})", status));
+        }
+        response.text().await.map_err(|e| e.to_string())
+    }
+
+    /// Verifie les jetons (et l'abonnement) en lisant la vitrine du compte
+    pub async fn verify(&self) -> Result<(), String> {
+        self.get("/v1/me/storefront").await.map(|_| ())
+    }
+
+    /// Nom d'une playlist de la bibliotheque (l'app Musique ne connait pas les identifiants de l'API)
+    async fn playlist_name(&self, id: &str) -> Result<String, String> {
+        let body = self.get(&format!("/v1/me/library/playlists/{}", id)).await?;
+        let (playlists, _) = parse_playlist_page(&body)?;
+        playlists
+            .into_iter()
+            .next()
+            .map(|p| p.name)
+            .ok_or_else(|| format!("Playlist Apple Music introuvable: {}", id))
+    }
+}
+
+impl MusicProvider for AppleMusicClient {
+    const URI_PREFIX: &'static str = "applemusic:";
+
+    /// Toutes les playlists de la bibliotheque (toutes les pages)
+    async fn playlists(&self) -> Result<Vec<ProviderPlaylist>, String> {
+        let mut result = Vec::new();
+        let mut path = Some(format!("/v1/me/library/playlists?limit={}", PLAYLIST_PAGE_SIZE));
+        while let Some(current) = path {
+            let (playlists, next) = parse_playlist_page(&self.get(&current).await?)?;
+            // Arreter sur une page vide par securite
+            path = next.filter(|_| !playlists.is_empty());
+            result.extend(playlists);
+        }
+        Ok(result)
+    }
+
+    /// Lance la playlist dans l'app Musique; le titre d'ouverture (Spotify) est ignore
+    async fn play(&self, playlist_uri: &str, first_track: Option<&str>) -> Result<(), String> {
+        let id = playlist_id(playlist_uri)
+            .ok_or_else(|| format!("Playlist Apple Music invalide: {}", playlist_uri))?;
+        if first_track.is_some() {
+            tracing::debug!("Titre d'ouverture ignore pour Apple Music");
+        }
+        let name = self.playlist_name(id).await?;
+        music_app(&format!("play (first user playlist whose name is {})", applescript_string(&name)))
+            .await
+            .map(|_| ())
+    }
+
+    async fn set_volume(&self, volume: u8) -> Result<(), String> {
+        music_app(&format!("set sound volume to {}", volume.min(100))).await.map(|_| ())
+    }
+
+    async fn pause(&self) -> Result<(), String> {
+        music_app("pause").await.map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_playlist_page() {
+        let body = r#"{
+            "next": "/v1/me/library/playlists?offset=100",
+            "data": [
+                {"id": "p.AbC123", "type": "library-playlists", "attributes": {
+                    "name": "Reveil \"doux\"",
+                    "artwork": {"url": "https://is1.mzstatic.com/image/{w}x{h}bb.jpg", "width": 0}
+                }},
+                {"id": "p.Xyz", "type": "library-playlists", "attributes": {"name": "Sans pochette"}}
+            ]
+        }"#;
+        let (playlists, next) = parse_playlist_page(body).unwrap();
+        assert_eq!(next.as_deref(), Some("/v1/me/library/playlists?offset=100"));
+        assert_eq!(playlists[0].uri, "applemusic:playlist:p.AbC123");
+        assert_eq!(playlists[0].image_url.as_deref(), Some("https://is1.mzstatic.com/image/300x300bb.jpg"));
+        assert_eq!(playlists[1].image_url, None);
+        assert!(parse_playlist_page("<html>").is_err());
+
+        assert_eq!(playlist_id(&playlists[0].uri), Some("p.AbC123"));
+        assert_eq!(playlist_id("spotify:playlist:37i9"), None);
+        assert!(AppleMusicClient::handles(&playlists[1].uri));
+        assert_eq!(applescript_string(&playlists[0].name), r#""Reveil \"doux\"""#);
+    }
+}
@@ -0,0 +1,662 @@
+// audio.rs - Lecture audio locale (alarme fallback, bibliotheque musicale, radio)
+// Le flux de sortie vit sur un thread dedie, le sink est pilotable depuis n'importe ou
+
+#![allow(dead_code)]
+
+use rodio::cpal::traits::HostTrait;
+use rodio::{Decoder, DeviceTrait, OutputStream, OutputStreamHandle, Sink, Source};
+use rodio::source::SineWave;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use serde::Serialize;
+
+use crate::loudness;
+
+/// Volume par defaut du son local (0.0 - 1.0)
+const DEFAULT_VOLUME: f32 = 0.8;
+
+/// Intervalle de prise en compte du gain de normalisation d'un titre
+const GAIN_REFRESH_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Duree du son d'alarme local (hors fondu d'entree)
+pub const ALARM_SOUND_DURATION: Duration = Duration::from_secs(30);
+
+/// Moteur audio: handle du flux de sortie et sink en cours de lecture
+struct AudioEngine {
+    device: Option<String>, // Peripherique demande a l'ouverture (None = defaut)
+    handle: OutputStreamHandle,
+    sink: Option<Sink>,
+    volume: f32,
+    // Fermer ce canal termine le thread qui possede le flux
+    _shutdown: mpsc::Sender<()>,
+}
+
+lazy_static::lazy_static! {
+    static ref ENGINE: Mutex<Option<AudioEngine>> = Mutex::new(None);
+    static ref SELECTED_DEVICE: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Peripherique de sortie audio
+#[derive(Debug, Clone, Serialize)]
+pub struct AudioDevice {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Liste les peripheriques de sortie disponibles
+pub fn list_output_devices() -> Result<Vec<AudioDevice>, String> {
+    let host = rodio::cpal::default_host();
+    let default_name = host.default_output_device().and_then(|d| d.name().ok());
+    let devices = host
+        .output_devices()
+        .map_err(|e| format!("Impossible de lister les sorties audio: {}", e))?;
+
+    Ok(devices
+        .filter_map(|device| device.name().ok())
+        .map(|name| AudioDevice {
+            is_default: default_name.as_deref() == Some(name.as_str()),
+            name,
+        })
+        .collect())
+}
+
+/// Choisit la sortie des prochaines lectures (None = sortie par defaut).
+/// Le flux est rouvert a la prochaine lecture si le peripherique change.
+pub fn select_output_device(name: Option<String>) -> Result<(), String> {
+    *SELECTED_DEVICE.lock().map_err(|e| e.to_string())? = name;
+    Ok(())
+}
+
+/// Ouvre le peripherique demande, ou la sortie par defaut s'il est introuvable
+fn open_stream(device: Option<&str>) -> Result<(OutputStream, OutputStreamHandle), String> {
+    if let Some(name) = device {
+        let found = rodio::cpal::default_host()
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().is_ok_and(|n| n == name)));
+        match found {
+            Some(found) => {
+                return OutputStream::try_from_device(&found)
+                    .map_err(|e| format!("Impossible d'ouvrir la sortie '{}': {}", name, e));
+            }
+            None => tracing::warn!("Sortie audio '{}' introuvable, sortie par defaut", name),
+        }
+    }
+    OutputStream::try_default().map_err(|e| e.to_string())
+}
+
+/// Ouvre le flux audio sur un thread dedie.
+/// OutputStream n'est pas Send/Sync: il reste sur son thread tant que le
+/// canal de fermeture est ouvert, seul le handle (Send) est renvoye.
+fn open_output(device: Option<String>) -> Result<(OutputStreamHandle, mpsc::Sender<()>), String> {
+    let (handle_tx, handle_rx) = mpsc::channel();
+    let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>();
+
+    thread::spawn(move || match open_stream(device.as_deref()) {
+        Ok((_stream, handle)) => {
+            let _ = handle_tx.send(Ok(handle));
+            // Bloque jusqu'a la fermeture du canal, puis libere le flux
+            let _ = shutdown_rx.recv();
+        }
+        Err(e) => {
+            let _ = handle_tx.send(Err(format!("Impossible d'ouvrir le flux audio: {}", e)));
+        }
+    });
+
+    let handle = handle_rx
+        .recv()
+        .map_err(|e| format!("Thread audio interrompu: {}", e))??;
+    Ok((handle, shutdown_tx))
+}
+
+/// Execute une operation sur le moteur audio (ouvert a la demande,
+/// rouvert si la sortie choisie a change)
+fn with_engine<R>(f: impl FnOnce(&mut AudioEngine) -> Result<R, String>) -> Result<R, String> {
+    let selected = SELECTED_DEVICE.lock().map_err(|e| e.to_string())?.clone();
+    let mut guard = ENGINE.lock().map_err(|e| e.to_string())?;
+    let mut volume = DEFAULT_VOLUME;
+    if guard.as_ref().is_some_and(|engine| engine.device != selected) {
+        if let Some(mut previous) = guard.take() {
+            volume = previous.volume;
+            if let Some(sink) = previous.sink.take() {
+                sink.stop();
+            }
+        }
+    }
+    if guard.is_none() {
+        let (handle, shutdown) = open_output(selected.clone())?;
+        *guard = Some(AudioEngine {
+            device: selected,
+            handle,
+            sink: None,
+            volume,
+            _shutdown: shutdown,
+        });
+    }
+    match guard.as_mut() {
+        Some(engine) => f(engine),
+        None => Err("Moteur audio indisponible".to_string()),
+    }
+}
+
+/// Remplace la lecture en cours par une nouvelle source
+fn play_source<S>(source: S) -> Result<(), String>
+where
+    S: Source<Item = f32> + Send + 'static,
+{
+    play_queue(std::iter::once(source))
+}
+
+/// Remplace la lecture en cours par des sources jouees a la suite
+fn play_queue<S>(sources: impl IntoIterator<Item = S>) -> Result<(), String>
+where
+    S: Source<Item = f32> + Send + 'static,
+{
+    with_engine(|engine| {
+        if let Some(previous) = engine.sink.take() {
+            previous.stop();
+        }
+
+        let sink = Sink::try_new(&engine.handle)
+            .map_err(|e| format!("Impossible de creer le sink audio: {}", e))?;
+
+        for source in sources {
+            sink.append(source);
+        }
+        sink.set_volume(engine.volume);
+        sink.play();
+
+        engine.sink = Some(sink);
+        Ok(())
+    })
+}
+
+/// Joue le son d'alarme local (remplace la lecture en cours)
+pub fn play_alarm_sound() -> Result<(), String> {
+    play_alarm_sound_for(ALARM_SOUND_DURATION)
+}
+
+/// Joue le son d'alarme local pendant `duration` (allonge pour couvrir un fondu d'entree)
+pub fn play_alarm_sound_for(duration: Duration) -> Result<(), String> {
+    // Generer un son de beep avec une source
+    let source = SineWave::new(440.0) // 440 Hz = A4
+        .amplify(0.5)
+        .repeat_infinite()
+        .take_duration(duration);
+
+    play_source(source)
+}
+
+/// Joue des fichiers audio a la suite (bibliotheque locale). Les fichiers
+/// illisibles sont ignores; erreur si aucun ne peut etre decode.
+/// `normalize` egalise leur volume percu: le premier titre est mesure avant la lecture,
+/// les suivants en arriere-plan pendant qu'il joue.
+pub fn play_files(paths: &[PathBuf], normalize: bool) -> Result<(), String> {
+    let mut pending = Vec::new();
+    let sources: Vec<_> = paths
+        .iter()
+        .filter_map(|path| {
+            let decoded = File::open(path)
+                .map_err(|e| e.to_string())
+                .and_then(|file| Decoder::new(BufReader::new(file)).map_err(|e| e.to_string()));
+            match decoded {
+                Ok(decoder) => {
+                    let gain = Arc::new(AtomicU32::new(1.0f32.to_bits()));
+                    if normalize {
+                        pending.push((path.clone(), gain.clone()));
+                    }
+                    Some(
+                        decoder
+                            .convert_samples::<f32>()
+                            .amplify(1.0)
+                            .periodic_access(GAIN_REFRESH_INTERVAL, move |source| {
+                                source.set_factor(f32::from_bits(gain.load(Ordering::Relaxed)));
+                            }),
+                    )
+                }
+                Err(e) => {
+                    tracing::warn!("Titre ignore ({}): {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect();
+    if sources.is_empty() {
+        return Err("Aucun fichier audio lisible".to_string());
+    }
+
+    // Premier titre au bon niveau des le depart
+    if let Some((path, gain)) = pending.first() {
+        apply_gain(path, gain);
+    }
+    if pending.len() > 1 {
+        thread::spawn(move || {
+            for (path, gain) in pending.into_iter().skip(1) {
+                // Seule reference restante: la lecture a ete remplacee ou arretee
+                if Arc::strong_count(&gain) == 1 {
+                    return;
+                }
+                apply_gain(&path, &gain);
+            }
+        });
+    }
+    play_queue(sources)
+}
+
+/// Mesure un titre et transmet son gain a la source qui le joue
+fn apply_gain(path: &Path, gain: &AtomicU32) {
+    match loudness::file_gain(path) {
+        Ok(value) => gain.store(value.to_bits(), Ordering::Relaxed),
+        Err(e) => tracing::warn!("Niveau du titre non mesure ({}): {}", path.display(), e),
+    }
+}
+
+/// Octets recus avant de lancer le decodage d'un flux radio
+const STREAM_PREBUFFER: usize = 64 * 1024;
+
+/// Octets deja lus conserves pour les retours en arriere du decodeur (detection du format)
+const STREAM_REWIND_WINDOW: usize = 256 * 1024;
+
+/// Silence du serveur au-dela duquel le flux est considere comme coupe
+const STREAM_STALL_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Reconnexions consecutives tentees apres une coupure
+const STREAM_MAX_RECONNECTS: u32 = 5;
+
+/// Morceaux en attente entre le telechargement et le decodeur
+const STREAM_QUEUE_CHUNKS: usize = 256;
+
+/// Flux HTTP lu par le decodeur. Les octets arrivent du thread de telechargement;
+/// une fenetre des derniers octets lus permet les retours en arriere du decodeur.
+struct StreamReader {
+    chunks: Mutex<mpsc::Receiver<Vec<u8>>>, // Mutex: le decodeur exige Sync
+    buffer: Vec<u8>,
+    base: u64,     // Position dans le flux du premier octet de buffer
+    position: u64, // Position de lecture dans le flux
+    ended: bool,
+}
+
+impl StreamReader {
+    fn new(chunks: mpsc::Receiver<Vec<u8>>) -> Self {
+        Self { chunks: Mutex::new(chunks), buffer: Vec::new(), base: 0, position: 0, ended: false }
+    }
+
+    /// Attend le morceau suivant (false si le flux est termine ou muet)
+    fn fetch(&mut self) -> bool {
+        if self.ended {
+            return false;
+        }
+        let received = match self.chunks.get_mut() {
+            Ok(chunks) => chunks.recv_timeout(STREAM_STALL_TIMEOUT).ok(),
+            Err(_) => None,
+        };
+        match received {
+            Some(chunk) => {
+                self.buffer.extend_from_slice(&chunk);
+                // Oublier ce qui sort de la fenetre de retour en arriere
+                let consumed = (self.position - self.base) as usize;
+                if consumed > 2 * STREAM_REWIND_WINDOW {
+                    let drop = consumed - STREAM_REWIND_WINDOW;
+                    self.buffer.drain(..drop);
+                    self.base += drop as u64;
+                }
+                true
+            }
+            None => {
+                self.ended = true;
+                false
+            }
+        }
+    }
+
+    /// Remplit le tampon avant la lecture (erreur si le flux ne donne rien)
+    fn prebuffer(&mut self, bytes: usize) -> Result<(), String> {
+        while self.buffer.len() < bytes && self.fetch() {}
+        if self.buffer.is_empty() {
+            return Err("Flux radio injoignable ou muet".to_string());
+        }
+        Ok(())
+    }
+
+    fn available(&self) -> &[u8] {
+        &self.buffer[(self.position - self.base) as usize..]
+    }
+}
+
+impl Read for StreamReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.available().is_empty() && !self.fetch() {
+            return Ok(0);
+        }
+        let count = self.available().len().min(buf.len());
+        buf[..count].copy_from_slice(&self.available()[..count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl Seek for StreamReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+            SeekFrom::End(_) => None, // Flux sans fin
+        };
+        // Seulement dans ce qui a deja ete recu
+        match target.filter(|t| *t >= self.base && *t <= self.base + self.buffer.len() as u64) {
+            Some(target) => {
+                self.position = target;
+                Ok(target)
+            }
+            None => Err(io::Error::new(io::ErrorKind::Unsupported, "Deplacement hors du tampon du flux")),
+        }
+    }
+}
+
+/// Telecharge un flux radio vers le decodeur, avec reconnexion apres une coupure.
+/// S'arrete quand la lecture est arretee (decodeur et canal liberes).
+async fn download_stream(url: &str, chunks: &mpsc::SyncSender<Vec<u8>>) {
+    let client = match reqwest::Client::builder().connect_timeout(Duration::from_secs(10)).build() {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::warn!("Client HTTP du flux indisponible: {}", e);
+            return;
+        }
+    };
+
+    let mut failures = 0;
+    loop {
+        match client.get(url).send().await.and_then(|r| r.error_for_status()) {
+            Ok(mut response) => loop {
+                match tokio::time::timeout(STREAM_STALL_TIMEOUT, response.chunk()).await {
+                    Ok(Ok(Some(chunk))) => {
+                        failures = 0;
+                        // Bloque quand le decodeur a assez d'avance; erreur une fois la lecture arretee
+                        if chunks.send(chunk.to_vec()).is_err() {
+                            return;
+                        }
+                    }
+                    Ok(Ok(None)) => {
+                        tracing::warn!("Flux radio ferme par le serveur");
+                        break;
+                    }
+                    Ok(Err(e)) => {
+                        tracing::warn!("Flux radio interrompu: {}", e);
+                        break;
+                    }
+                    Err(_) => {
+                        tracing::warn!("Flux radio muet depuis {} s", STREAM_STALL_TIMEOUT.as_secs());
+                        break;
+                    }
+                }
+            },
+            Err(e) => tracing::warn!("Connexion au flux radio impossible: {}", e),
+        }
+
+        failures += 1;
+        if failures > STREAM_MAX_RECONNECTS {
+            tracing::error!("Flux radio abandonne apres {} tentatives", STREAM_MAX_RECONNECTS);
+            return;
+        }
+        tokio::time::sleep(Duration::from_secs(1 << failures)).await;
+        tracing::info!(attempt = failures, "Reconnexion au flux radio");
+    }
+}
+
+/// Joue un flux radio HTTP (Icecast, Shoutcast v2; MP3 ou Ogg Vorbis) jusqu'a l'arret
+/// explicite. Bloque le temps de remplir le tampon: a appeler hors du runtime async.
+pub fn play_stream(url: &str) -> Result<(), String> {
+    let (sender, receiver) = mpsc::sync_channel(STREAM_QUEUE_CHUNKS);
+    let url = url.to_string();
+    // Runtime propre au telechargement: la lecture ne depend pas de l'appelant
+    thread::spawn(move || match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+        Ok(runtime) => runtime.block_on(download_stream(&url, &sender)),
+        Err(e) => tracing::warn!("Runtime du flux radio indisponible: {}", e),
+    });
+
+    let mut reader = StreamReader::new(receiver);
+    reader.prebuffer(STREAM_PREBUFFER)?;
+    let decoder = Decoder::new(reader).map_err(|e| format!("Format du flux non pris en charge: {}", e))?;
+    play_source(decoder.convert_samples::<f32>())
+}
+
+/// Joue une sirene deux tons jusqu'a l'arret explicite (escalade)
+pub fn play_siren() -> Result<(), String> {
+    let tones = (0u64..).map(|i| {
+        let frequency = if i % 2 == 0 { 880.0 } else { 660.0 };
+        SineWave::new(frequency).take_duration(Duration::from_millis(500))
+    });
+
+    play_source(rodio::source::from_iter(tones).amplify(0.8))
+}
+
+/// Joue un carillon unique et discret (pre-alarme), volume 0-100 independant
+/// du volume de l'alarme
+pub fn play_soft_chime(volume_percent: u8) -> Result<(), String> {
+    let amplitude = f32::from(volume_percent.min(100)) / 100.0 * 0.5;
+    let chime = SineWave::new(880.0) // A5
+        .take_duration(Duration::from_millis(1200))
+        .fade_in(Duration::from_millis(150))
+        .amplify(amplitude);
+
+    play_source(chime)
+}
+
+/// Repetitions du carillon de fin de minuteur
+const TIMER_CHIME_REPEATS: usize = 3;
+
+/// Joue le carillon de fin de minuteur: trois series de deux notes, puis silence
+pub fn play_timer_chime() -> Result<(), String> {
+    let notes = (0..TIMER_CHIME_REPEATS * 3).map(|i| {
+        let (frequency, amplitude) = match i % 3 {
+            0 => (1046.5, 0.5), // C6
+            1 => (784.0, 0.5),  // G5
+            _ => (440.0, 0.0),  // Pause entre deux series
+        };
+        SineWave::new(frequency).amplify(amplitude).take_duration(Duration::from_millis(300))
+    });
+
+    play_source(rodio::source::from_iter(notes))
+}
+
+/// Frequence d'echantillonnage du paysage sonore
+const DAWN_SAMPLE_RATE: u32 = 44_100;
+
+/// Gazouillis en cours: balayage de frequence sous une enveloppe en cloche
+struct Chirp {
+    from_hz: f32,
+    to_hz: f32,
+    length: u64,
+    position: u64,
+    phase: f32,
+}
+
+/// Paysage sonore "chorus de l'aube": souffle filtre, bourdon grave qui monte
+/// et gazouillis de plus en plus aigus et frequents au fil de la duree
+struct DawnChorus {
+    total: u64,
+    position: u64,
+    rng: StdRng,
+    noise: f32,
+    drone_phase: f32,
+    chirp: Option<Chirp>,
+    next_chirp: u64,
+}
+
+impl DawnChorus {
+    fn new(duration: Duration) -> Self {
+        let total = (duration.as_secs_f64() * f64::from(DAWN_SAMPLE_RATE)) as u64;
+        Self {
+            total,
+            position: 0,
+            rng: StdRng::from_entropy(),
+            noise: 0.0,
+            drone_phase: 0.0,
+            chirp: None,
+            next_chirp: u64::from(DAWN_SAMPLE_RATE) * 2,
+        }
+    }
+
+    /// Avancement de 0.0 (debut) a 1.0 (fin)
+    fn progress(&self) -> f32 {
+        if self.total == 0 {
+            return 1.0;
+        }
+        self.position as f32 / self.total as f32
+    }
+
+    fn start_chirp(&mut self, progress: f32) {
+        let rate = DAWN_SAMPLE_RATE as f32;
+        let base = 1500.0 + 1500.0 * progress + self.rng.gen_range(-200.0..200.0);
+        self.chirp = Some(Chirp {
+            from_hz: base,
+            to_hz: base * self.rng.gen_range(1.2..1.6),
+            length: (rate * self.rng.gen_range(0.08..0.2)) as u64,
+            position: 0,
+            phase: 0.0,
+        });
+        // Ecart entre gazouillis: ~3 s au debut, ~0.4 s a la fin
+        let gap = 3.0 - 2.6 * progress + self.rng.gen_range(0.0..0.5);
+        self.next_chirp = self.position + (rate * gap) as u64;
+    }
+}
+
+impl Iterator for DawnChorus {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.position >= self.total {
+            return None;
+        }
+        let rate = DAWN_SAMPLE_RATE as f32;
+        let progress = self.progress();
+
+        // Bruit blanc passe-bas, plus brillant vers la fin
+        let white: f32 = self.rng.gen_range(-1.0..1.0);
+        self.noise += (0.02 + 0.08 * progress) * (white - self.noise);
+
+        // Bourdon de 110 a 220 Hz
+        let drone_hz = 110.0 + 110.0 * progress;
+        self.drone_phase = (self.drone_phase + drone_hz / rate) % 1.0;
+        let drone = (self.drone_phase * std::f32::consts::TAU).sin();
+
+        if self.chirp.is_none() && self.position >= self.next_chirp {
+            self.start_chirp(progress);
+        }
+        let mut bird = 0.0;
+        if let Some(chirp) = self.chirp.as_mut() {
+            let t = chirp.position as f32 / chirp.length as f32;
+            let hz = chirp.from_hz + (chirp.to_hz - chirp.from_hz) * t;
+            chirp.phase = (chirp.phase + hz / rate) % 1.0;
+            bird = (chirp.phase * std::f32::consts::TAU).sin() * (t * std::f32::consts::PI).sin();
+            chirp.position += 1;
+            if chirp.position >= chirp.length {
+                self.chirp = None;
+            }
+        }
+
+        self.position += 1;
+        Some(self.noise * 0.6 + drone * 0.1 * progress + bird * (0.1 + 0.2 * progress))
+    }
+}
+
+impl Source for DawnChorus {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        1
+    }
+
+    fn sample_rate(&self) -> u32 {
+        DAWN_SAMPLE_RATE
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(self.total as f64 / f64::from(DAWN_SAMPLE_RATE)))
+    }
+}
+
+/// Joue le chorus de l'aube pendant `duration` (remplace la lecture en cours).
+/// Le volume est ensuite pilote par le moteur de fondu, comme pour Spotify.
+pub fn play_dawn_chorus(duration: Duration) -> Result<(), String> {
+    play_source(DawnChorus::new(duration))
+}
+
+/// Arrete le son d'alarme
+pub fn stop_alarm_sound() -> Result<(), String> {
+    let mut guard = ENGINE.lock().map_err(|e| e.to_string())?;
+    if let Some(sink) = guard.as_mut().and_then(|engine| engine.sink.take()) {
+        sink.stop();
+    }
+    Ok(())
+}
+
+/// Regle le volume de l'alarme locale (0-100)
+pub fn set_alarm_volume(volume_percent: u8) -> Result<(), String> {
+    let volume = f32::from(volume_percent.min(100)) / 100.0;
+    with_engine(|engine| {
+        engine.volume = volume;
+        if let Some(sink) = engine.sink.as_ref() {
+            sink.set_volume(volume);
+        }
+        Ok(())
+    })
+}
+
+/// Verifie si l'alarme est en cours de lecture
+pub fn is_playing() -> bool {
+    ENGINE
+        .lock()
+        .ok()
+        .and_then(|guard| {
+            guard
+                .as_ref()
+                .and_then(|engine| engine.sink.as_ref().map(|sink| !sink.empty()))
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_reader() {
+        let (sender, receiver) = mpsc::sync_channel(4);
+        sender.send(b"ID3abc".to_vec()).unwrap();
+        sender.send(b"def".to_vec()).unwrap();
+        drop(sender);
+
+        let mut reader = StreamReader::new(receiver);
+        reader.prebuffer(4).unwrap();
+        let mut head = [0u8; 3];
+        reader.read_exact(&mut head).unwrap();
+        assert_eq!(&head, b"ID3");
+        // Retour au debut pendant la detection du format
+        assert_eq!(reader.seek(SeekFrom::Start(0)).unwrap(), 0);
+        let mut all = Vec::new();
+        reader.read_to_end(&mut all).unwrap();
+        assert_eq!(all, b"ID3abcdef");
+        assert!(reader.seek(SeekFrom::End(0)).is_err());
+        assert!(reader.seek(SeekFrom::Start(100)).is_err());
+    }
+
+    #[test]
+    fn test_dawn_chorus() {
+        let chorus = DawnChorus::new(Duration::from_secs(2));
+        assert_eq!(chorus.total_duration(), Some(Duration::from_secs(2)));
+
+        let samples: Vec<f32> = chorus.collect();
+        assert_eq!(samples.len(), 2 * DAWN_SAMPLE_RATE as usize);
+        assert!(samples.iter().all(|s| s.abs() <= 1.0));
+    }
+}
@@ -0,0 +1,141 @@
+// bedtime.rs - Heure de coucher suggeree selon la prochaine alarme et la duree de sommeil visee
+// Un rappel est notifie peu avant cette heure (une seule fois par alarme)
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::NextAlarm;
+use crate::i18n::{self, Locale, TimeFormat};
+
+/// Duree de sommeil maximale configurable (minutes)
+pub const MAX_SLEEP_MINUTES: u32 = 720;
+
+/// Avance maximale du rappel sur l'heure de coucher (minutes)
+pub const MAX_REMIND_BEFORE_MINUTES: u32 = 180;
+
+/// Reglages du rappel de coucher
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BedtimeSettings {
+    #[serde(default)]
+    pub reminder: bool, // Notifier avant l'heure de coucher
+    #[serde(default = "default_sleep_minutes")]
+    pub sleep_minutes: u32, // Duree de sommeil visee
+    #[serde(default = "default_remind_before_minutes")]
+    pub remind_before_minutes: u32, // Avance du rappel sur l'heure de coucher
+}
+
+fn default_sleep_minutes() -> u32 {
+    480 // 8 heures
+}
+
+fn default_remind_before_minutes() -> u32 {
+    30
+}
+
+impl Default for BedtimeSettings {
+    fn default() -> Self {
+        Self {
+            reminder: false,
+            sleep_minutes: default_sleep_minutes(),
+            remind_before_minutes: default_remind_before_minutes(),
+        }
+    }
+}
+
+impl BedtimeSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sleep_minutes == 0 || self.sleep_minutes > MAX_SLEEP_MINUTES {
+            return Err(format!("La duree de sommeil doit etre entre 1 et {} minutes", MAX_SLEEP_MINUTES));
+        }
+        if self.remind_before_minutes > MAX_REMIND_BEFORE_MINUTES {
+            return Err(format!("Le rappel doit preceder le coucher d'au plus {} minutes", MAX_REMIND_BEFORE_MINUTES));
+        }
+        Ok(())
+    }
+}
+
+/// Heure de coucher conseillee pour la prochaine alarme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SuggestedBedtime {
+    pub alarm_id: String,
+    pub alarm_at: DateTime<Local>,
+    pub bedtime: DateTime<Local>,
+    pub remind_at: DateTime<Local>,
+}
+
+impl SuggestedBedtime {
+    /// Le rappel part entre son heure et l'heure de coucher (pas de rappel en retard)
+    pub fn is_reminder_due(&self, now: DateTime<Local>) -> bool {
+        now >= self.remind_at && now < self.bedtime
+    }
+}
+
+/// Calcule l'heure de coucher pour dormir la duree visee avant l'alarme
+pub fn suggest(next: &NextAlarm, settings: &BedtimeSettings) -> SuggestedBedtime {
+    let bedtime = next.trigger_at - chrono::Duration::minutes(i64::from(settings.sleep_minutes));
+    SuggestedBedtime {
+        alarm_id: next.alarm.id.clone(),
+        alarm_at: next.trigger_at,
+        bedtime,
+        remind_at: bedtime - chrono::Duration::minutes(i64::from(settings.remind_before_minutes)),
+    }
+}
+
+/// Titre et texte de la notification de rappel
+pub fn reminder_text(locale: Locale, time_format: TimeFormat, suggested: &SuggestedBedtime) -> (String, String) {
+    let minutes = suggested.alarm_at.signed_duration_since(suggested.bedtime).num_minutes();
+    let sleep = match minutes % 60 {
+        0 => format!("{} h", minutes / 60),
+        rest => format!("{} h {:02}", minutes / 60, rest),
+    };
+    let bedtime = i18n::format_clock(suggested.bedtime.time(), time_format, false);
+    let alarm_at = i18n::format_clock(suggested.alarm_at.time(), time_format, false);
+
+    match locale {
+        Locale::Fr => (
+            "Heure du coucher".to_string(),
+            format!("Couchez-vous vers {} pour dormir {} avant l'alarme de {}", bedtime, sleep, alarm_at),
+        ),
+        Locale::En => (
+            "Time for bed".to_string(),
+            format!("Go to bed around {} to get {} of sleep before your {} alarm", bedtime, sleep, alarm_at),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::alarm::NapTimer;
+
+    #[test]
+    fn test_suggested_bedtime() {
+        let now = Local.with_ymd_and_hms(2024, 3, 12, 21, 0, 0).unwrap();
+        let alarm_at = Local.with_ymd_and_hms(2024, 3, 13, 6, 30, 0).unwrap();
+        let nap = NapTimer::new(now, 570, "Morning".to_string(), "local".to_string(), 50);
+        let next = NextAlarm {
+            alarm: nap.alarm,
+            trigger_at: alarm_at,
+            seconds_until: alarm_at.signed_duration_since(now).num_seconds(),
+            label: String::new(),
+        };
+        let settings = BedtimeSettings { reminder: true, sleep_minutes: 450, remind_before_minutes: 30 };
+
+        let suggested = suggest(&next, &settings);
+        assert_eq!(suggested.bedtime, Local.with_ymd_and_hms(2024, 3, 12, 23, 0, 0).unwrap());
+        assert_eq!(suggested.remind_at, Local.with_ymd_and_hms(2024, 3, 12, 22, 30, 0).unwrap());
+        assert!(!suggested.is_reminder_due(now));
+        assert!(suggested.is_reminder_due(suggested.remind_at));
+        assert!(!suggested.is_reminder_due(suggested.bedtime));
+
+        let (_, body) = reminder_text(Locale::En, TimeFormat::H24, &suggested);
+        assert_eq!(body, "Go to bed around 23:00 to get 7 h 30 of sleep before your 06:30 alarm");
+        let (_, body) = reminder_text(Locale::En, TimeFormat::H12, &suggested);
+        assert_eq!(body, "Go to bed around 11:00 PM to get 7 h 30 of sleep before your 6:30 AM alarm");
+
+        assert!(settings.validate().is_ok());
+        assert!(BedtimeSettings { sleep_minutes: 0, ..settings }.validate().is_err());
+        assert!(BedtimeSettings { remind_before_minutes: 600, ..settings }.validate().is_err());
+    }
+}
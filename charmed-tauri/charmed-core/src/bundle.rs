@@ -0,0 +1,173 @@
+// bundle.rs - Export/import des alarmes et de la configuration (changement de machine)
+
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::AlarmTemplate;
+use crate::storage::AppConfig;
+use crate::AlarmEntry;
+
+/// Version du format d'export (incrementee a chaque changement incompatible)
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Fichier d'export versionne
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmBundle {
+    pub version: u32,
+    pub exported_at: DateTime<Local>,
+    pub alarms: Vec<AlarmEntry>,
+    pub config: AppConfig,
+    #[serde(default)]
+    pub templates: Vec<AlarmTemplate>,
+}
+
+/// Strategie d'import
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    Replace, // Remplace alarmes, modeles et configuration
+    Merge,   // Ajoute les alarmes absentes, ignore les doublons
+    KeepAll, // Ajoute toutes les alarmes, doublons compris
+}
+
+/// Resultat d'un import
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ImportReport {
+    pub imported: usize,
+    pub skipped: usize,
+}
+
+impl AlarmBundle {
+    /// Prepare un export. Le secret Spotify n'est jamais exporte.
+    pub fn new(alarms: Vec<AlarmEntry>, mut config: AppConfig, templates: Vec<AlarmTemplate>) -> Self {
+        config.spotify_client_secret = None;
+        Self {
+            version: BUNDLE_VERSION,
+            exported_at: Local::now(),
+            alarms,
+            config,
+            templates,
+        }
+    }
+}
+
+/// Ecrit un export dans un fichier JSON
+pub fn write_bundle(path: &Path, bundle: &AlarmBundle) -> Result<(), String> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(bundle)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+
+    fs::write(path, json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+/// Lit un export et verifie sa version
+pub fn read_bundle(path: &Path) -> Result<AlarmBundle, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Erreur lecture fichier: {}", e))?;
+
+    let bundle: AlarmBundle = serde_json::from_str(&content)
+        .map_err(|e| format!("Fichier d'export invalide: {}", e))?;
+
+    if bundle.version == 0 || bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "Version d'export non supportee: {} (maximum {})",
+            bundle.version, BUNDLE_VERSION
+        ));
+    }
+    Ok(bundle)
+}
+
+/// Deux alarmes sont des doublons si elles sonnent au meme moment avec la meme musique
+pub fn is_duplicate(a: &AlarmEntry, b: &AlarmEntry) -> bool {
+    a.time == b.time
+        && a.days == b.days
+        && a.recurrence == b.recurrence
+        && a.timezone == b.timezone
+        && a.playlist_uri == b.playlist_uri
+}
+
+/// Fusionne les alarmes importees dans la liste existante.
+/// Les alarmes importees recoivent toujours un nouvel identifiant.
+pub fn merge_alarms(
+    existing: &[AlarmEntry],
+    imported: Vec<AlarmEntry>,
+    strategy: MergeStrategy,
+) -> (Vec<AlarmEntry>, ImportReport) {
+    let mut result = match strategy {
+        MergeStrategy::Replace => Vec::new(),
+        MergeStrategy::Merge | MergeStrategy::KeepAll => existing.to_vec(),
+    };
+    let mut report = ImportReport::default();
+
+    for mut alarm in imported {
+        let duplicate = result.iter().any(|a| is_duplicate(a, &alarm));
+        if duplicate && strategy != MergeStrategy::KeepAll {
+            report.skipped += 1;
+            continue;
+        }
+        alarm.id = uuid::Uuid::new_v4().to_string();
+        alarm.skip_date = None;
+        alarm.updated_at = Some(chrono::Utc::now());
+        result.push(alarm);
+        report.imported += 1;
+    }
+
+    (result, report)
+}
+
+/// Configuration apres un import en remplacement: les identifiants Spotify
+/// restent ceux de cette machine
+pub fn replace_config(current: &AppConfig, imported: AppConfig) -> AppConfig {
+    AppConfig {
+        spotify_client_id: current.spotify_client_id.clone(),
+        spotify_client_secret: current.spotify_client_secret.clone(),
+        spotify_redirect_uri: current.spotify_redirect_uri.clone(),
+        active_spotify_profile: current.active_spotify_profile.clone(),
+        ..imported
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alarm::test_support::alarm;
+
+    #[test]
+    fn test_merge_strategies() {
+        let existing = vec![alarm("a", "07:00")];
+        let imported = vec![alarm("a", "07:00"), alarm("b", "08:00")];
+
+        let (merged, report) = merge_alarms(&existing, imported.clone(), MergeStrategy::Merge);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(report, ImportReport { imported: 1, skipped: 1 });
+        assert_eq!(merged[0].id, "a");
+        assert_ne!(merged[1].id, "b"); // Identifiant regenere
+
+        let (all, report) = merge_alarms(&existing, imported.clone(), MergeStrategy::KeepAll);
+        assert_eq!(all.len(), 3);
+        assert_eq!(report.skipped, 0);
+
+        let (replaced, report) = merge_alarms(&existing, imported, MergeStrategy::Replace);
+        assert_eq!(replaced.len(), 2);
+        assert_eq!(report.imported, 2);
+        assert!(replaced.iter().all(|a| a.id != "a"));
+    }
+
+    #[test]
+    fn test_bundle_strips_secret() {
+        let config = AppConfig {
+            spotify_client_secret: Some("secret".to_string()),
+            ..Default::default()
+        };
+        let bundle = AlarmBundle::new(vec![alarm("a", "07:00")], config, Vec::new());
+        assert_eq!(bundle.version, BUNDLE_VERSION);
+        assert!(bundle.config.spotify_client_secret.is_none());
+    }
+}
@@ -0,0 +1,300 @@
+// calendar.rs - Reveil aligne sur l'agenda: "90 minutes avant ma premiere reunion,
+// jamais avant 06:30". L'agenda est lu depuis une URL iCalendar (export ICS, webcal://
+// ou collection CalDAV exportable) et mis en cache par le planificateur.
+// Les evenements recurrents (RRULE) ne sont pas developpes: seule leur premiere date compte.
+
+use std::time::Duration;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm;
+use crate::storage::AppConfig;
+use crate::AlarmEntry;
+
+/// Delai maximal d'une requete d'agenda
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Avance maximale sur la premiere reunion (minutes)
+pub const MAX_LEAD_MINUTES: u32 = 12 * 60;
+
+/// Jours examines pour trouver la prochaine sonnerie d'une alarme d'agenda
+const LOOKAHEAD_DAYS: u64 = 14;
+
+/// Regle d'une alarme calee sur l'agenda. Sans reunion ce jour-la, l'alarme
+/// sonne a son heure habituelle (ou pas du tout si `skip_without_events`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarRule {
+    #[serde(default = "default_lead_minutes")]
+    pub lead_minutes: u32, // Avance sur le debut de la premiere reunion
+    #[serde(default)]
+    pub earliest: Option<String>, // "HH:MM": jamais avant cette heure
+    #[serde(default)]
+    pub skip_without_events: bool,
+}
+
+fn default_lead_minutes() -> u32 {
+    90
+}
+
+impl CalendarRule {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.lead_minutes > MAX_LEAD_MINUTES {
+            return Err(format!("L'avance sur la premiere reunion doit etre au plus {} minutes", MAX_LEAD_MINUTES));
+        }
+        if let Some(earliest) = self.earliest.as_deref() {
+            NaiveTime::parse_from_str(earliest, "%H:%M")
+                .map_err(|_| format!("Heure plancher invalide: {}. Utilisez HH:MM", earliest))?;
+        }
+        Ok(())
+    }
+
+    /// Heure de reveil pour `date` selon la premiere reunion du jour (heure murale)
+    pub fn wake_time(&self, date: NaiveDate, first_event: Option<NaiveDateTime>, default: NaiveTime) -> Option<NaiveTime> {
+        let Some(first_event) = first_event else {
+            return (!self.skip_without_events).then_some(default);
+        };
+        let wake = first_event - chrono::Duration::minutes(i64::from(self.lead_minutes));
+        // Reunion tres matinale: pas de reveil la veille
+        let wake = if wake.date() < date { NaiveTime::MIN } else { wake.time() };
+
+        let earliest = self.earliest.as_deref()
+            .and_then(|t| NaiveTime::parse_from_str(t, "%H:%M").ok())
+            .unwrap_or(NaiveTime::MIN);
+        Some(wake.max(earliest))
+    }
+}
+
+/// Evenement d'agenda
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalendarEvent {
+    pub summary: String,
+    pub start: DateTime<Utc>,
+}
+
+/// Evenements lus lors du dernier releve
+#[derive(Debug, Clone)]
+pub struct CalendarCache {
+    pub events: Vec<CalendarEvent>,
+    pub fetched_at: DateTime<Utc>,
+}
+
+/// Accepte http(s):// et webcal:// (converti en https://)
+pub fn normalize_url(url: &str) -> Result<String, String> {
+    let url = url.trim();
+    if let Some(rest) = url.strip_prefix("webcal://") {
+        return Ok(format!("https://{}", rest));
+    }
+    if url.starts_with("http://") || url.starts_with("https://") {
+        return Ok(url.to_string());
+    }
+    Err(format!("URL d'agenda invalide: {}", url))
+}
+
+/// Deplie les lignes de continuation (RFC 5545 3.1)
+fn unfold(body: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in body.lines() {
+        match (line.strip_prefix(' ').or_else(|| line.strip_prefix('\t')), lines.last_mut()) {
+            (Some(rest), Some(last)) => last.push_str(rest),
+            _ => lines.push(line.to_string()),
+        }
+    }
+    lines
+}
+
+/// Separe "NOM;PARAM=X:VALEUR" en (nom, parametres, valeur); les ':' entre guillemets sont ignores
+fn split_property(line: &str) -> Option<(&str, &str, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let (name, params) = head.split_once(';').unwrap_or((head, ""));
+    Some((name, params, value))
+}
+
+fn param<'a>(params: &'a str, name: &str) -> Option<&'a str> {
+    params.split(';')
+        .filter_map(|p| p.split_once('='))
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim_matches('"'))
+}
+
+/// Debut d'un evenement (None pour un evenement sur la journee entiere)
+fn parse_start(params: &str, value: &str) -> Option<DateTime<Utc>> {
+    if param(params, "VALUE").is_some_and(|v| v.eq_ignore_ascii_case("DATE")) || value.len() == 8 {
+        return None;
+    }
+    if let Some(utc) = value.strip_suffix('Z') {
+        let naive = NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S").ok()?;
+        return Some(Utc.from_utc_datetime(&naive));
+    }
+    let naive = NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?;
+    // Fuseau inconnu (noms Windows d'Outlook...) ou heure flottante: heure locale
+    match param(params, "TZID").and_then(|tz| alarm::parse_timezone(tz).ok()) {
+        Some(tz) => alarm::resolve_local(&tz, naive).map(|dt| dt.with_timezone(&Utc)),
+        None => alarm::resolve_local(&Local, naive).map(|dt| dt.with_timezone(&Utc)),
+    }
+}
+
+fn unescape_text(text: &str) -> String {
+    text.replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\\\", "\\")
+}
+
+/// VEVENT en cours de lecture
+#[derive(Default)]
+struct PendingEvent {
+    start: Option<DateTime<Utc>>,
+    summary: String,
+    cancelled: bool,
+}
+
+/// Lit les evenements horaires d'un calendrier (annules et journees entieres exclus),
+/// tries par date de debut
+pub fn parse_ics(body: &str) -> Vec<CalendarEvent> {
+    let mut events = Vec::new();
+    let mut current: Option<PendingEvent> = None;
+
+    for line in unfold(body) {
+        let Some((name, params, value)) = split_property(&line) else {
+            continue;
+        };
+        let name = name.to_ascii_uppercase();
+        if name == "BEGIN" && value.eq_ignore_ascii_case("VEVENT") {
+            current = Some(PendingEvent::default());
+            continue;
+        }
+        if name == "END" && value.eq_ignore_ascii_case("VEVENT") {
+            if let Some(PendingEvent { start: Some(start), summary, cancelled: false }) = current.take() {
+                events.push(CalendarEvent { summary, start });
+            }
+            continue;
+        }
+
+        let Some(event) = current.as_mut() else {
+            continue;
+        };
+        match name.as_str() {
+            "DTSTART" => event.start = parse_start(params, value),
+            "SUMMARY" => event.summary = unescape_text(value),
+            "STATUS" => event.cancelled = value.eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+    events.sort_by_key(|e| e.start);
+    events
+}
+
+/// Debut (heure murale de l'alarme) de la premiere reunion du jour
+fn first_event_on(alarm: &AlarmEntry, events: &[CalendarEvent], date: NaiveDate) -> Option<NaiveDateTime> {
+    events.iter()
+        .map(|e| alarm::wall_clock(alarm, e.start.with_timezone(&Local)))
+        .filter(|start| start.date() == date)
+        .min()
+}
+
+/// Alarme a l'heure effective du jour `date` (heure murale): l'heure est recalculee
+/// depuis l'agenda pour une alarme d'agenda, inchangee sinon.
+/// None si l'alarme d'agenda ne sonne pas ce jour-la faute de reunion.
+pub fn effective_alarm(alarm: &AlarmEntry, events: &[CalendarEvent], date: NaiveDate) -> Option<AlarmEntry> {
+    let Some(rule) = alarm.calendar.as_ref() else {
+        return Some(alarm.clone());
+    };
+    let default = alarm::parse_alarm_time(&alarm.time)?;
+    let wake = rule.wake_time(date, first_event_on(alarm, events, date), default)?;
+
+    let mut effective = alarm.clone();
+    effective.time = alarm::format_alarm_time(wake);
+    Some(effective)
+}
+
+/// Prochaine sonnerie effective, heure de chaque jour recalculee depuis l'agenda
+pub fn next_trigger(
+    alarm: &AlarmEntry,
+    events: &[CalendarEvent],
+    now: DateTime<Local>,
+    config: &AppConfig,
+) -> Option<DateTime<Local>> {
+    if alarm.calendar.is_none() {
+        return alarm::next_trigger(alarm, now, config);
+    }
+    if !alarm::is_enabled(alarm) {
+        return None;
+    }
+
+    let today = alarm::wall_clock(alarm, now).date();
+    (0..=LOOKAHEAD_DAYS)
+        .filter_map(|offset| today.checked_add_days(chrono::Days::new(offset)))
+        .filter(|date| alarm::is_scheduled_on(alarm, *date) && !alarm::is_skipped_on(alarm, *date, config))
+        .find_map(|date| {
+            let effective = effective_alarm(alarm, events, date)?;
+            let time = alarm::parse_alarm_time(&effective.time)?;
+            let at = alarm::occurrence_instant(alarm, date.and_time(time))?.with_timezone(&Local);
+            (at > now).then_some(at)
+        })
+}
+
+/// Telecharge et lit le calendrier
+pub async fn fetch_events(url: &str) -> Result<Vec<CalendarEvent>, String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let body = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Erreur requete agenda: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Erreur requete agenda: {}", e))?;
+
+    if !body.contains("BEGIN:VCALENDAR") {
+        return Err("La reponse n'est pas un calendrier iCalendar".to_string());
+    }
+    Ok(parse_ics(&body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calendar_wake_time() {
+        let body = "BEGIN:VCALENDAR\r\n\
+BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20240312\r\nSUMMARY:Conge\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART:20240312T100000\r\nSUMMARY:Revue\\, sprint\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART:20240312T073000\r\nSUMMARY:Point\r\n  annule\r\nSTATUS:CANCELLED\r\nEND:VEVENT\r\n\
+BEGIN:VEVENT\r\nDTSTART;TZID=\"Europe/Paris\":20240313T080000\r\nSUMMARY:Standup\r\nEND:VEVENT\r\n\
+END:VCALENDAR\r\n";
+        let events = parse_ics(body);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].summary, "Revue, sprint");
+
+        let rule = CalendarRule { lead_minutes: 90, earliest: Some("06:30".to_string()), skip_without_events: false };
+        assert!(rule.validate().is_ok());
+        let date = NaiveDate::from_ymd_opt(2024, 3, 12).unwrap();
+        let default = NaiveTime::from_hms_opt(7, 0, 0).unwrap();
+        let at = |h, m| Some(date.and_hms_opt(h, m, 0).unwrap());
+
+        assert_eq!(rule.wake_time(date, at(10, 0), default), NaiveTime::from_hms_opt(8, 30, 0));
+        assert_eq!(rule.wake_time(date, at(7, 0), default), NaiveTime::from_hms_opt(6, 30, 0)); // Plancher
+        assert_eq!(rule.wake_time(date, None, default), Some(default));
+        let skip = CalendarRule { skip_without_events: true, ..rule };
+        assert_eq!(skip.wake_time(date, None, default), None);
+
+        assert_eq!(normalize_url("webcal://example.com/cal.ics").unwrap(), "https://example.com/cal.ics");
+        assert!(normalize_url("ftp://example.com").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+// challenge.rs - Defis a resoudre pour arreter une alarme (calcul mental, phrase a recopier)
+// La reponse attendue ne quitte jamais le backend
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Difficulte maximale d'un defi de calcul
+pub const MAX_MATH_DIFFICULTY: u8 = 3;
+
+/// Defi configure sur une alarme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ChallengeConfig {
+    Math {
+        #[serde(default = "default_difficulty")]
+        difficulty: u8, // 1 (addition) a 3 (operations combinees)
+    },
+    Typing {
+        phrase: String,
+    },
+}
+
+fn default_difficulty() -> u8 {
+    1
+}
+
+impl ChallengeConfig {
+    /// Valide la configuration du defi
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            ChallengeConfig::Math { difficulty } => {
+                if *difficulty == 0 || *difficulty > MAX_MATH_DIFFICULTY {
+                    return Err(format!(
+                        "Difficulte invalide: entre 1 et {}",
+                        MAX_MATH_DIFFICULTY
+                    ));
+                }
+            }
+            ChallengeConfig::Typing { phrase } => {
+                if phrase.trim().is_empty() {
+                    return Err("La phrase a recopier est vide".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Defi emis pour une alarme en cours de sonnerie
+#[derive(Debug, Clone, Serialize)]
+pub struct Challenge {
+    pub alarm_id: String,
+    pub prompt: String,
+    pub attempts: u32,
+    #[serde(skip)]
+    answer: String,
+}
+
+/// Normalise une saisie: espaces superflus ignores
+fn normalize(input: &str) -> String {
+    input.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+impl Challenge {
+    /// Genere un nouveau defi a partir de la configuration de l'alarme
+    pub fn generate(alarm_id: &str, config: &ChallengeConfig, rng: &mut impl Rng) -> Self {
+        let (prompt, answer) = match config {
+            ChallengeConfig::Math { difficulty } => match difficulty {
+                0 | 1 => {
+                    let (a, b) = (rng.gen_range(10..100), rng.gen_range(10..100));
+                    (format!("{} + {}", a, b), (a + b).to_string())
+                }
+                2 => {
+                    let (a, b, c) = (rng.gen_range(3..13), rng.gen_range(3..13), rng.gen_range(10..100));
+                    (format!("{} x {} + {}", a, b, c), (a * b + c).to_string())
+                }
+                _ => {
+                    let (a, b) = (rng.gen_range(12..31), rng.gen_range(12..31));
+                    let c = rng.gen_range(10..a * b); // Resultat toujours positif
+                    (format!("{} x {} - {}", a, b, c), (a * b - c).to_string())
+                }
+            },
+            ChallengeConfig::Typing { phrase } => (normalize(phrase), normalize(phrase)),
+        };
+
+        Self {
+            alarm_id: alarm_id.to_string(),
+            prompt,
+            attempts: 0,
+            answer,
+        }
+    }
+
+    /// Verifie une reponse (compte la tentative)
+    pub fn check(&mut self, answer: &str) -> bool {
+        self.attempts += 1;
+        normalize(answer) == self.answer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_math_challenge() {
+        let mut rng = rand::thread_rng();
+        for difficulty in 1..=MAX_MATH_DIFFICULTY {
+            let config = ChallengeConfig::Math { difficulty };
+            let mut challenge = Challenge::generate("a1", &config, &mut rng);
+            let expected = challenge.answer.clone();
+
+            assert!(!challenge.check("not a number"));
+            assert!(challenge.check(&format!(" {} ", expected)));
+            assert_eq!(challenge.attempts, 2);
+        }
+        assert!(ChallengeConfig::Math { difficulty: 4 }.validate().is_err());
+    }
+
+    #[test]
+    fn test_typing_challenge() {
+        let config = ChallengeConfig::Typing { phrase: "Je suis  bien reveille".to_string() };
+        let mut challenge = Challenge::generate("a1", &config, &mut rand::thread_rng());
+
+        assert_eq!(challenge.prompt, "Je suis bien reveille");
+        assert!(!challenge.check("je suis bien reveille"));
+        assert!(challenge.check("Je suis bien  reveille "));
+        assert!(ChallengeConfig::Typing { phrase: " ".to_string() }.validate().is_err());
+    }
+}
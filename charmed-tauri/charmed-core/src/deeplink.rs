@@ -0,0 +1,96 @@
+// deeplink.rs - Liens charmed:// (retour OAuth Spotify et YouTube Music, actions rapides)
+// charmed://auth/callback?code=..., charmed://auth/youtube?code=..., charmed://snooze?minutes=10, charmed://dismiss
+
+use reqwest::Url;
+
+/// Schema enregistre aupres du systeme (voir plugins.deep-link dans tauri.conf.json)
+pub const SCHEME: &str = "charmed";
+
+/// URI de redirection OAuth Spotify passant par le protocole
+pub const AUTH_REDIRECT_URI: &str = "charmed://auth/callback";
+
+/// URI de redirection OAuth Google (YouTube Music) passant par le protocole
+pub const YOUTUBE_AUTH_REDIRECT_URI: &str = "charmed://auth/youtube";
+
+/// Action demandee par un lien
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeepLink {
+    AuthCallback { code: String },
+    AuthError { error: String },
+    YouTubeMusicCallback { code: String },
+    Snooze { minutes: Option<u32> },
+    Dismiss,
+}
+
+fn query_param(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+/// Interprete un lien charmed://
+pub fn parse(url: &Url) -> Result<DeepLink, String> {
+    if url.scheme() != SCHEME {
+        return Err(format!("Schema non pris en charge: {}", url.scheme()));
+    }
+
+    let host = url.host_str().unwrap_or_default();
+    let path = url.path().trim_matches('/');
+    match (host, path) {
+        ("auth", "callback") => {
+            if let Some(code) = query_param(url, "code") {
+                Ok(DeepLink::AuthCallback { code })
+            } else {
+                let error = query_param(url, "error").unwrap_or_else(|| "code absent".to_string());
+                Ok(DeepLink::AuthError { error })
+            }
+        }
+        ("auth", "youtube") => {
+            if let Some(code) = query_param(url, "code") {
+                Ok(DeepLink::YouTubeMusicCallback { code })
+            } else {
+                let error = query_param(url, "error").unwrap_or_else(|| "code absent".to_string());
+                Ok(DeepLink::AuthError { error })
+            }
+        }
+        ("snooze", "") => {
+            let minutes = query_param(url, "minutes")
+                .map(|m| m.parse::<u32>().map_err(|_| format!("Duree de report invalide: {}", m)))
+                .transpose()?;
+            Ok(DeepLink::Snooze { minutes })
+        }
+        ("dismiss", "") => Ok(DeepLink::Dismiss),
+        _ => Err(format!("Lien inconnu: {}", url)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(url: &str) -> Result<DeepLink, String> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_parse_deep_link() {
+        assert_eq!(
+            link("charmed://auth/callback?code=AQB12&state=x"),
+            Ok(DeepLink::AuthCallback { code: "AQB12".to_string() })
+        );
+        assert_eq!(
+            link("charmed://auth/callback?error=access_denied"),
+            Ok(DeepLink::AuthError { error: "access_denied".to_string() })
+        );
+        assert_eq!(
+            link("charmed://auth/youtube?code=4/0Ab&scope=x"),
+            Ok(DeepLink::YouTubeMusicCallback { code: "4/0Ab".to_string() })
+        );
+        assert_eq!(link("charmed://snooze"), Ok(DeepLink::Snooze { minutes: None }));
+        assert_eq!(link("charmed://snooze?minutes=10"), Ok(DeepLink::Snooze { minutes: Some(10) }));
+        assert_eq!(link("charmed://dismiss/"), Ok(DeepLink::Dismiss));
+        assert!(link("charmed://snooze?minutes=abc").is_err());
+        assert!(link("charmed://unknown").is_err());
+        assert!(link("https://auth/callback?code=x").is_err());
+    }
+}
@@ -0,0 +1,82 @@
+// escalation.rs - Reveil progressif par paliers: playlist a faible volume,
+// volume augmente chaque minute, puis sirene locale si l'alarme n'est pas arretee
+
+use serde::{Deserialize, Serialize};
+
+/// Delai maximal avant la sirene (minutes)
+pub const MAX_SIREN_AFTER_MINUTES: u32 = 60;
+
+/// Politique d'escalade configuree sur une alarme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EscalationPolicy {
+    #[serde(default = "default_start_volume")]
+    pub start_volume: u8, // Volume de depart (0-100)
+    #[serde(default = "default_step")]
+    pub step: u8, // Hausse de volume par minute
+    pub siren_after_minutes: u32, // Bascule sur la sirene apres N minutes
+}
+
+fn default_start_volume() -> u8 {
+    10
+}
+
+fn default_step() -> u8 {
+    10
+}
+
+/// Etape courante de l'escalade
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+pub enum EscalationStage {
+    Playlist { volume: u8 },
+    Siren,
+}
+
+impl EscalationPolicy {
+    /// Valide la politique d'escalade
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_volume > 100 {
+            return Err("Le volume de depart doit etre entre 0 et 100".to_string());
+        }
+        if self.siren_after_minutes == 0 || self.siren_after_minutes > MAX_SIREN_AFTER_MINUTES {
+            return Err(format!(
+                "Delai avant sirene invalide: entre 1 et {} minutes",
+                MAX_SIREN_AFTER_MINUTES
+            ));
+        }
+        Ok(())
+    }
+
+    /// Etape attendue apres `elapsed_secs` de sonnerie.
+    /// Le volume de la playlist ne depasse jamais celui de l'alarme.
+    pub fn stage_at(&self, max_volume: u8, elapsed_secs: i64) -> EscalationStage {
+        let minutes = (elapsed_secs.max(0) / 60) as u64;
+        if minutes >= u64::from(self.siren_after_minutes) {
+            return EscalationStage::Siren;
+        }
+        let volume = u64::from(self.start_volume) + u64::from(self.step) * minutes;
+        EscalationStage::Playlist {
+            volume: volume.min(u64::from(max_volume.min(100))) as u8,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stage_at() {
+        let policy = EscalationPolicy { start_volume: 10, step: 15, siren_after_minutes: 5 };
+
+        assert_eq!(policy.stage_at(60, 0), EscalationStage::Playlist { volume: 10 });
+        assert_eq!(policy.stage_at(60, 59), EscalationStage::Playlist { volume: 10 });
+        assert_eq!(policy.stage_at(60, 60), EscalationStage::Playlist { volume: 25 });
+        // Plafonne au volume de l'alarme
+        assert_eq!(policy.stage_at(60, 4 * 60), EscalationStage::Playlist { volume: 60 });
+        assert_eq!(policy.stage_at(60, 5 * 60), EscalationStage::Siren);
+
+        assert!(policy.validate().is_ok());
+        assert!(EscalationPolicy { siren_after_minutes: 0, ..policy }.validate().is_err());
+    }
+}
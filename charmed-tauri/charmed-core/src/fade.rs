@@ -0,0 +1,337 @@
+// fade.rs - Moteur de fondu de volume (reveil progressif, minuteur de sommeil)
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+use crate::apple_music::AppleMusicClient;
+use crate::audio;
+use crate::library::MusicLibrary;
+use crate::podcast::PodcastPlayer;
+use crate::provider::MusicProvider;
+use crate::radio::RadioPlayer;
+use crate::spotify::SpotifyClient;
+use crate::youtube_music::YouTubeMusicClient;
+
+/// Nombre maximum de paliers d'un fondu (limite les appels a l'API Spotify)
+const MAX_FADE_STEPS: u64 = 60;
+
+/// Intervalle minimum entre deux paliers
+const MIN_STEP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Duree du fondu enchaine du son local vers une playlist qui demarre en retard
+pub const CROSSFADE_DURATION: Duration = Duration::from_secs(8);
+
+/// Sortie dont le volume est pilote par le fondu
+#[derive(Clone)]
+pub enum FadeTarget {
+    Spotify(Box<SpotifyClient>),
+    AppleMusic(Box<AppleMusicClient>),
+    YouTubeMusic(Box<YouTubeMusicClient>),
+    Library(Box<MusicLibrary>),
+    Radio(Box<RadioPlayer>),
+    Podcast(Box<PodcastPlayer>),
+    Local,
+    Group(Vec<FadeTarget>), // Plusieurs sorties en meme temps (sans groupe imbrique)
+}
+
+impl FadeTarget {
+    /// Sorties pilotees (les membres pour un groupe)
+    fn members(&self) -> &[FadeTarget] {
+        match self {
+            FadeTarget::Group(members) => members,
+            single => std::slice::from_ref(single),
+        }
+    }
+
+    /// Client Spotify de la sortie (premier membre Spotify pour un groupe)
+    pub fn spotify(&self) -> Option<&SpotifyClient> {
+        self.members().iter().find_map(|member| match member {
+            FadeTarget::Spotify(client) => Some(client.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// Lance la playlist de l'alarme sur la sortie (le son integre pour Local).
+    /// Un groupe joue si au moins un de ses membres joue.
+    pub async fn play(&self, playlist_uri: &str, first_track: Option<&str>) -> Result<(), String> {
+        let mut errors = Vec::new();
+        for member in self.members() {
+            if let Err(e) = member.play_one(playlist_uri, first_track).await {
+                errors.push(e);
+            }
+        }
+        if errors.len() == self.members().len() {
+            return Err(errors.join(", "));
+        }
+        for e in errors {
+            tracing::warn!("Sortie du groupe muette: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Applique un volume (0-100) a la sortie
+    pub async fn set_volume(&self, volume: u8) -> Result<(), String> {
+        let mut result = Ok(());
+        for member in self.members() {
+            result = result.and(member.set_volume_one(volume).await);
+        }
+        result
+    }
+
+    /// Met la lecture en pause (arret pour le son local)
+    pub async fn pause(&self) -> Result<(), String> {
+        let mut result = Ok(());
+        for member in self.members() {
+            result = result.and(member.pause_one().await);
+        }
+        result
+    }
+
+    async fn play_one(&self, playlist_uri: &str, first_track: Option<&str>) -> Result<(), String> {
+        match self {
+            FadeTarget::Spotify(client) => client
+                .play_alarm_playlist(playlist_uri, first_track)
+                .await
+                .map_err(|e| format!("Erreur Spotify: {}", e)),
+            FadeTarget::AppleMusic(client) => client.play(playlist_uri, first_track).await,
+            FadeTarget::YouTubeMusic(client) => client.play(playlist_uri, first_track).await,
+            FadeTarget::Library(library) => library.play(playlist_uri, first_track).await,
+            FadeTarget::Radio(radio) => radio.play(playlist_uri, first_track).await,
+            FadeTarget::Podcast(podcast) => podcast.play(playlist_uri, first_track).await,
+            FadeTarget::Local => audio::play_alarm_sound(),
+            FadeTarget::Group(_) => Err("Groupe imbrique non pris en charge".to_string()),
+        }
+    }
+
+    async fn set_volume_one(&self, volume: u8) -> Result<(), String> {
+        match self {
+            FadeTarget::Spotify(client) => client
+                .set_volume(volume)
+                .await
+                .map_err(|e| format!("Erreur volume: {}", e)),
+            FadeTarget::AppleMusic(client) => MusicProvider::set_volume(client.as_ref(), volume).await,
+            FadeTarget::YouTubeMusic(client) => MusicProvider::set_volume(client.as_ref(), volume).await,
+            FadeTarget::Library(library) => MusicProvider::set_volume(library.as_ref(), volume).await,
+            FadeTarget::Radio(radio) => MusicProvider::set_volume(radio.as_ref(), volume).await,
+            FadeTarget::Podcast(podcast) => MusicProvider::set_volume(podcast.as_ref(), volume).await,
+            FadeTarget::Local => audio::set_alarm_volume(volume),
+            FadeTarget::Group(_) => Err("Groupe imbrique non pris en charge".to_string()),
+        }
+    }
+
+    async fn pause_one(&self) -> Result<(), String> {
+        match self {
+            FadeTarget::Spotify(client) => client
+                .pause()
+                .await
+                .map_err(|e| format!("Erreur pause: {}", e)),
+            FadeTarget::AppleMusic(client) => MusicProvider::pause(client.as_ref()).await,
+            FadeTarget::YouTubeMusic(client) => MusicProvider::pause(client.as_ref()).await,
+            FadeTarget::Library(library) => MusicProvider::pause(library.as_ref()).await,
+            FadeTarget::Radio(radio) => MusicProvider::pause(radio.as_ref()).await,
+            FadeTarget::Podcast(podcast) => MusicProvider::pause(podcast.as_ref()).await,
+            FadeTarget::Local => audio::stop_alarm_sound(),
+            FadeTarget::Group(_) => Err("Groupe imbrique non pris en charge".to_string()),
+        }
+    }
+}
+
+/// Jeton d'annulation partage entre un fondu et son proprietaire
+#[derive(Debug, Clone, Default)]
+pub struct FadeHandle {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl FadeHandle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Demande l'arret du fondu au prochain palier
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Minuteur de sommeil en cours
+#[derive(Debug, Clone, Serialize)]
+pub struct SleepTimer {
+    pub id: String,
+    pub playlist_uri: String,
+    pub ends_at: String, // RFC 3339
+    #[serde(skip)]
+    pub handle: FadeHandle,
+}
+
+/// Courbe de progression d'un fondu
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FadeCurve {
+    #[default]
+    Linear,
+    Exponential, // Lent au debut: les faibles volumes durent plus longtemps
+    SCurve,      // Depart et arrivee en douceur
+}
+
+/// Pente de la courbe exponentielle
+const EXPONENTIAL_STEEPNESS: f64 = 4.0;
+
+impl FadeCurve {
+    /// Progression effective (0.0 - 1.0) pour une progression temporelle donnee
+    pub fn apply(self, progress: f64) -> f64 {
+        let p = progress.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => p,
+            FadeCurve::Exponential => {
+                ((EXPONENTIAL_STEEPNESS * p).exp() - 1.0) / (EXPONENTIAL_STEEPNESS.exp() - 1.0)
+            }
+            FadeCurve::SCurve => p * p * (3.0 - 2.0 * p),
+        }
+    }
+}
+
+/// Volume a un instant donne d'un fondu suivant la courbe choisie
+pub fn volume_at(from: u8, to: u8, elapsed: Duration, total: Duration, curve: FadeCurve) -> u8 {
+    if total.is_zero() || elapsed >= total {
+        return to;
+    }
+    let progress = curve.apply(elapsed.as_secs_f64() / total.as_secs_f64());
+    let volume = f64::from(from) + (f64::from(to) - f64::from(from)) * progress;
+    volume.round().clamp(0.0, 100.0) as u8
+}
+
+/// Execute un fondu de `from` a `to` sur `duration`.
+/// Retourne Ok(false) si le fondu a ete annule avant la fin.
+pub async fn run_fade(
+    target: &FadeTarget,
+    from: u8,
+    to: u8,
+    duration: Duration,
+    curve: FadeCurve,
+    handle: &FadeHandle,
+) -> Result<bool, String> {
+    let steps = duration.as_secs().clamp(1, MAX_FADE_STEPS);
+    let interval = duration
+        .checked_div(steps as u32)
+        .unwrap_or(MIN_STEP_INTERVAL)
+        .max(MIN_STEP_INTERVAL);
+
+    target.set_volume(from).await?;
+    let mut last_volume = from;
+    let start = tokio::time::Instant::now();
+
+    loop {
+        if handle.is_cancelled() {
+            return Ok(false);
+        }
+
+        let elapsed = start.elapsed();
+        let volume = volume_at(from, to, elapsed, duration, curve);
+        // Un palier rate n'interrompt pas le fondu: le suivant rattrapera
+        if volume != last_volume && target.set_volume(volume).await.is_ok() {
+            last_volume = volume;
+        }
+
+        if elapsed >= duration {
+            return Ok(true);
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Volumes (sortie quittee, sortie rejointe) d'un fondu enchaine a un instant donne:
+/// leur somme reste egale a `volume` pour eviter un creux ou un pic sonore
+pub fn crossfade_levels(volume: u8, elapsed: Duration, total: Duration, curve: FadeCurve) -> (u8, u8) {
+    let incoming = volume_at(0, volume, elapsed, total, curve);
+    (volume.saturating_sub(incoming), incoming)
+}
+
+/// Fondu enchaine: `from` descend de `volume` a 0 pendant que `to` monte de 0 a `volume`,
+/// puis `from` est arrete. Retourne Ok(false) si le fondu a ete annule avant la fin.
+pub async fn run_crossfade(
+    from: &FadeTarget,
+    to: &FadeTarget,
+    volume: u8,
+    duration: Duration,
+    curve: FadeCurve,
+    handle: &FadeHandle,
+) -> Result<bool, String> {
+    let steps = duration.as_secs().clamp(1, MAX_FADE_STEPS);
+    let interval = duration
+        .checked_div(steps as u32)
+        .unwrap_or(MIN_STEP_INTERVAL)
+        .max(MIN_STEP_INTERVAL);
+
+    to.set_volume(0).await?;
+    let mut last = (volume, 0);
+    let start = tokio::time::Instant::now();
+
+    loop {
+        if handle.is_cancelled() {
+            return Ok(false);
+        }
+
+        let elapsed = start.elapsed();
+        let (outgoing, incoming) = crossfade_levels(volume, elapsed, duration, curve);
+        // Comme pour un fondu simple, un palier rate est rattrape au suivant
+        if outgoing != last.0 && from.set_volume(outgoing).await.is_ok() {
+            last.0 = outgoing;
+        }
+        if incoming != last.1 && to.set_volume(incoming).await.is_ok() {
+            last.1 = incoming;
+        }
+
+        if elapsed >= duration {
+            from.pause().await?;
+            return Ok(true);
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_volume_at() {
+        let total = Duration::from_secs(100);
+        assert_eq!(volume_at(80, 0, Duration::ZERO, total, FadeCurve::Linear), 80);
+        assert_eq!(volume_at(80, 0, Duration::from_secs(50), total, FadeCurve::Linear), 40);
+        assert_eq!(volume_at(80, 0, Duration::from_secs(100), total, FadeCurve::Linear), 0);
+        assert_eq!(volume_at(10, 60, Duration::from_secs(20), total, FadeCurve::Linear), 20);
+        assert_eq!(volume_at(10, 60, Duration::from_secs(500), total, FadeCurve::Linear), 60);
+        assert_eq!(volume_at(10, 60, Duration::ZERO, Duration::ZERO, FadeCurve::Linear), 60);
+    }
+
+    #[test]
+    fn test_crossfade_levels() {
+        let total = Duration::from_secs(8);
+        assert_eq!(crossfade_levels(80, Duration::ZERO, total, FadeCurve::Linear), (80, 0));
+        assert_eq!(crossfade_levels(80, Duration::from_secs(2), total, FadeCurve::Linear), (60, 20));
+        assert_eq!(crossfade_levels(80, Duration::from_secs(9), total, FadeCurve::Linear), (0, 80));
+        let (outgoing, incoming) = crossfade_levels(75, Duration::from_secs(3), total, FadeCurve::SCurve);
+        assert_eq!(u16::from(outgoing) + u16::from(incoming), 75);
+    }
+
+    #[test]
+    fn test_fade_curves() {
+        let total = Duration::from_secs(100);
+        let quarter = Duration::from_secs(25);
+        assert_eq!(volume_at(0, 100, quarter, total, FadeCurve::Linear), 25);
+        assert_eq!(volume_at(0, 100, quarter, total, FadeCurve::Exponential), 3);
+        assert_eq!(volume_at(0, 100, quarter, total, FadeCurve::SCurve), 16);
+
+        for curve in [FadeCurve::Linear, FadeCurve::Exponential, FadeCurve::SCurve] {
+            assert_eq!(curve.apply(0.0), 0.0);
+            assert!((curve.apply(1.0) - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(volume_at(0, 100, Duration::from_secs(50), total, FadeCurve::SCurve), 50);
+    }
+}
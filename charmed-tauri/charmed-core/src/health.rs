@@ -0,0 +1,97 @@
+// health.rs - Etat de sante de l'application pour le panneau de diagnostic
+// Le planificateur note l'heure de chaque tour: un battement trop ancien signale une boucle
+// bloquee, que le superviseur de l'application relance
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Local, Utc};
+use serde::Serialize;
+
+use crate::query::ProviderKind;
+
+/// Silence du planificateur au-dela duquel il est considere comme bloque (secondes)
+pub const STALL_SECONDS: i64 = 30;
+
+/// Fichier ecrit puis supprime pour verifier l'acces en ecriture
+const PROBE_FILE: &str = ".health-check";
+
+/// Boucle du planificateur
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchedulerHealth {
+    pub alive: bool,
+    pub last_tick: Option<DateTime<Local>>,
+    pub restarts: u32, // Relances par le superviseur depuis le demarrage
+    pub last_restart: Option<DateTime<Local>>,
+}
+
+/// Dossier de donnees du profil actif
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageHealth {
+    pub path: String,
+    pub writable: bool,
+    pub error: Option<String>,
+}
+
+/// Compte d'un service de musique
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AuthHealth {
+    pub provider: ProviderKind,
+    pub connected: bool,
+    pub expires_at: Option<DateTime<Utc>>, // Expiration du jeton d'acces (si connue)
+    pub valid: bool,                       // Connecte avec un jeton encore valable
+}
+
+/// Rapport de `health_check`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HealthReport {
+    pub checked_at: DateTime<Local>,
+    pub scheduler: SchedulerHealth,
+    pub storage: StorageHealth,
+    pub auth: Vec<AuthHealth>,
+}
+
+impl HealthReport {
+    /// Planificateur vivant et stockage accessible (un service non connecte n'est pas une panne)
+    pub fn is_healthy(&self) -> bool {
+        self.scheduler.alive && self.storage.writable && self.auth.iter().all(|a| !a.connected || a.valid)
+    }
+}
+
+/// Le planificateur n'a pas tourne depuis trop longtemps (ou jamais)
+pub fn is_stalled(last_tick: Option<DateTime<Local>>, now: DateTime<Local>) -> bool {
+    last_tick.is_none_or(|tick| now - tick > chrono::Duration::seconds(STALL_SECONDS))
+}
+
+/// Verifie que le dossier accepte l'ecriture d'un fichier
+pub fn check_storage(data_dir: &Path) -> StorageHealth {
+    let probe = data_dir.join(PROBE_FILE);
+    let result = fs::create_dir_all(data_dir)
+        .and_then(|_| fs::write(&probe, b"ok"))
+        .and_then(|_| fs::remove_file(&probe));
+    StorageHealth {
+        path: data_dir.display().to_string(),
+        writable: result.is_ok(),
+        error: result.err().map(|e| e.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_health() {
+        let now = Local.with_ymd_and_hms(2024, 3, 12, 7, 0, 0).unwrap();
+        assert!(!is_stalled(Some(now - chrono::Duration::seconds(2)), now));
+        assert!(is_stalled(Some(now - chrono::Duration::minutes(5)), now));
+        assert!(is_stalled(None, now));
+
+        let dir = std::env::temp_dir().join(format!("charmed-health-{}", uuid::Uuid::new_v4()));
+        let storage = check_storage(&dir);
+        assert!(storage.writable && storage.error.is_none());
+        assert!(!dir.join(PROBE_FILE).exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,234 @@
+// history.rs - Historique des sonneries (arretees, repoussees, manquees)
+
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::{FallbackReason, FiredLedger, RingingAlarm};
+use crate::readiness::ReadinessReport;
+use crate::AlarmEntry;
+
+const HISTORY_FILE: &str = "history.json";
+const READINESS_FILE: &str = "readiness.json";
+const FIRED_FILE: &str = "fired.json";
+const RINGING_FILE: &str = "ringing.json";
+
+/// Age au-dela duquel une sonnerie interrompue n'est plus reprise mais notee manquee (heures)
+pub const RESUME_MAX_HOURS: i64 = 12;
+
+/// Nombre maximum d'entrees conservees (les plus anciennes sont supprimees)
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+/// Issue d'une sonnerie
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlarmOutcome {
+    Dismissed,
+    Snoozed,
+    Missed,
+}
+
+/// Une sonnerie terminee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub alarm_id: String,
+    pub alarm_time: String, // "HH:MM"
+    pub playlist_name: String,
+    pub started_at: DateTime<Local>,
+    pub ended_at: DateTime<Local>,
+    pub outcome: AlarmOutcome,
+    #[serde(default)]
+    pub fallback: Option<FallbackReason>, // Son local joue a la place de la playlist
+    #[serde(default)]
+    pub reminder: bool, // Rappel d'une alarme a intervalle (hors statistiques de reveil)
+    #[serde(default)]
+    pub test: bool, // Test de l'alarme (hors statistiques de reveil)
+}
+
+impl HistoryEntry {
+    pub fn new(ringing: &RingingAlarm, ended_at: DateTime<Local>, outcome: AlarmOutcome) -> Self {
+        Self {
+            alarm_id: ringing.alarm.id.clone(),
+            alarm_time: ringing.alarm.time.clone(),
+            playlist_name: ringing.alarm.playlist_name.clone(),
+            started_at: ringing.started_at,
+            ended_at,
+            outcome,
+            fallback: ringing.fallback,
+            reminder: ringing.alarm.interval.is_some(),
+            test: ringing.test,
+        }
+    }
+}
+
+/// Charge l'historique (vide si absent ou illisible)
+pub fn load_history(data_dir: &Path) -> Vec<HistoryEntry> {
+    fs::read_to_string(data_dir.join(HISTORY_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Sauvegarde l'historique
+pub fn save_history(data_dir: &Path, entries: &[HistoryEntry]) -> Result<(), String> {
+    save_file(data_dir, HISTORY_FILE, entries)
+}
+
+fn save_file<T: Serialize>(data_dir: &Path, file_name: &str, entries: &[T]) -> Result<(), String> {
+    if !data_dir.exists() {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(entries)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+
+    fs::write(data_dir.join(file_name), json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+/// Ajoute une entree en ne gardant que les plus recentes
+pub fn push_entry<T>(entries: &mut Vec<T>, entry: T) {
+    entries.push(entry);
+    if entries.len() > MAX_HISTORY_ENTRIES {
+        let excess = entries.len() - MAX_HISTORY_ENTRIES;
+        entries.drain(..excess);
+    }
+}
+
+/// Ajoute une entree a l'historique persiste
+pub fn record(data_dir: &Path, entry: HistoryEntry) -> Result<(), String> {
+    let mut entries = load_history(data_dir);
+    push_entry(&mut entries, entry);
+    save_history(data_dir, &entries)
+}
+
+/// Charge les verifications d'avant sonnerie (vide si absent ou illisible)
+pub fn load_readiness(data_dir: &Path) -> Vec<ReadinessReport> {
+    fs::read_to_string(data_dir.join(READINESS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Ajoute une verification d'avant sonnerie a l'historique persiste
+pub fn record_readiness(data_dir: &Path, report: ReadinessReport) -> Result<(), String> {
+    let mut reports = load_readiness(data_dir);
+    push_entry(&mut reports, report);
+    save_file(data_dir, READINESS_FILE, &reports)
+}
+
+/// Charge le registre des derniers declenchements (vide si absent ou illisible)
+pub fn load_fired(data_dir: &Path) -> FiredLedger {
+    fs::read_to_string(data_dir.join(FIRED_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Sauvegarde le registre des derniers declenchements
+pub fn save_fired(data_dir: &Path, fired: &FiredLedger) -> Result<(), String> {
+    if !data_dir.exists() {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(fired)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+
+    fs::write(data_dir.join(FIRED_FILE), json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+/// Sonnerie en cours, ecrite sur disque des son debut pour survivre a un arret
+/// brutal de l'application (le defi et les fondus sont recrees a la reprise)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RingingRecord {
+    pub alarm: AlarmEntry,
+    pub started_at: DateTime<Local>,
+    #[serde(default)]
+    pub auto_snoozes: u32,
+    #[serde(default)]
+    pub fallback: Option<FallbackReason>,
+}
+
+impl RingingRecord {
+    pub fn of(ringing: &RingingAlarm) -> Self {
+        Self {
+            alarm: ringing.alarm.clone(),
+            started_at: ringing.started_at,
+            auto_snoozes: ringing.auto_snoozes,
+            fallback: ringing.fallback,
+        }
+    }
+
+    /// Trop ancienne pour etre reprise
+    pub fn is_stale(&self, now: DateTime<Local>) -> bool {
+        now - self.started_at > chrono::Duration::hours(RESUME_MAX_HOURS)
+    }
+
+    /// Sonnerie reprise avec son heure de debut d'origine: escalade, duree maximale
+    /// et alarme de secours repartent la ou elles en etaient
+    pub fn resume(self) -> RingingAlarm {
+        let mut ringing = RingingAlarm::new(self.alarm, self.started_at);
+        ringing.auto_snoozes = self.auto_snoozes;
+        ringing.fallback = self.fallback;
+        ringing
+    }
+}
+
+/// Charge la sonnerie interrompue (None si aucune ou illisible)
+pub fn load_ringing(data_dir: &Path) -> Option<RingingRecord> {
+    fs::read_to_string(data_dir.join(RINGING_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+/// Enregistre la sonnerie en cours, ou efface le fichier quand plus rien ne sonne
+pub fn save_ringing(data_dir: &Path, record: Option<&RingingRecord>) -> Result<(), String> {
+    let path = data_dir.join(RINGING_FILE);
+    let Some(record) = record else {
+        return match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Erreur suppression fichier: {}", e)),
+            _ => Ok(()),
+        };
+    };
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    let json = serde_json::to_string_pretty(record)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+    fs::write(path, json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_ringing_record() {
+        let started_at = Local.with_ymd_and_hms(2024, 3, 12, 7, 0, 0).unwrap();
+        let alarm: AlarmEntry = serde_json::from_str(
+            r#"{"id":"a","time":"07:00","playlist_name":"Alarme","playlist_uri":"local","volume":50,"active":true,"days":[],"fade_in":false,"fade_in_duration":0}"#,
+        )
+        .unwrap();
+        let mut ringing = RingingAlarm::new(alarm, started_at);
+        ringing.auto_snoozes = 2;
+
+        let dir = std::env::temp_dir().join(format!("charmed-ringing-{}", uuid::Uuid::new_v4()));
+        save_ringing(&dir, Some(&RingingRecord::of(&ringing))).unwrap();
+        let record = load_ringing(&dir).unwrap();
+        assert!(!record.is_stale(started_at + chrono::Duration::minutes(20)));
+        assert!(record.is_stale(started_at + chrono::Duration::hours(13)));
+
+        let resumed = record.resume();
+        assert_eq!((resumed.alarm.id.as_str(), resumed.started_at, resumed.auto_snoozes), ("a", started_at, 2));
+
+        save_ringing(&dir, None).unwrap();
+        assert!(load_ringing(&dir).is_none());
+        save_ringing(&dir, None).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
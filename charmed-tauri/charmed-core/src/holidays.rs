@@ -0,0 +1,147 @@
+// holidays.rs - Calendriers de jours feries (presets par pays + dates personnalisees)
+
+use chrono::{Datelike, Days, NaiveDate, Weekday};
+
+/// Pays dont les jours feries nationaux sont connus
+pub const SUPPORTED_COUNTRIES: [&str; 4] = ["FR", "BE", "DE", "US"];
+
+/// Calcule le dimanche de Paques (algorithme de Meeus/Jones/Butcher)
+pub fn easter_sunday(year: i32) -> Option<NaiveDate> {
+    let a = year % 19;
+    let b = year / 100;
+    let c = year % 100;
+    let d = b / 4;
+    let e = b % 4;
+    let f = (b + 8) / 25;
+    let g = (b - f + 1) / 3;
+    let h = (19 * a + b - d - g + 15) % 30;
+    let i = c / 4;
+    let k = c % 4;
+    let l = (32 + 2 * e + 2 * i - h - k) % 7;
+    let m = (a + 11 * h + 22 * l) / 451;
+    let month = (h + l - 7 * m + 114) / 31;
+    let day = (h + l - 7 * m + 114) % 31 + 1;
+    NaiveDate::from_ymd_opt(year, month as u32, day as u32)
+}
+
+/// Decale une date d'un nombre de jours (positif ou negatif)
+fn shift(date: NaiveDate, days: i64) -> Option<NaiveDate> {
+    if days >= 0 {
+        date.checked_add_days(Days::new(days as u64))
+    } else {
+        date.checked_sub_days(Days::new(days.unsigned_abs()))
+    }
+}
+
+/// Retourne les jours feries nationaux d'un pays pour une annee
+pub fn country_holidays(country: &str, year: i32) -> Result<Vec<NaiveDate>, String> {
+    let fixed = |month: u32, day: u32| NaiveDate::from_ymd_opt(year, month, day);
+    let easter = easter_sunday(year).ok_or_else(|| format!("Annee invalide: {}", year))?;
+    let from_easter = |days: i64| shift(easter, days);
+    let nth = |month: u32, weekday: Weekday, n: u8| {
+        NaiveDate::from_weekday_of_month_opt(year, month, weekday, n)
+    };
+
+    let dates = match country.to_uppercase().as_str() {
+        "FR" => vec![
+            fixed(1, 1),
+            from_easter(1),  // Lundi de Paques
+            fixed(5, 1),
+            fixed(5, 8),
+            from_easter(39), // Ascension
+            from_easter(50), // Lundi de Pentecote
+            fixed(7, 14),
+            fixed(8, 15),
+            fixed(11, 1),
+            fixed(11, 11),
+            fixed(12, 25),
+        ],
+        "BE" => vec![
+            fixed(1, 1),
+            from_easter(1),
+            fixed(5, 1),
+            from_easter(39),
+            from_easter(50),
+            fixed(7, 21),
+            fixed(8, 15),
+            fixed(11, 1),
+            fixed(11, 11),
+            fixed(12, 25),
+        ],
+        "DE" => vec![
+            fixed(1, 1),
+            from_easter(-2), // Vendredi saint
+            from_easter(1),
+            fixed(5, 1),
+            from_easter(39),
+            from_easter(50),
+            fixed(10, 3),
+            fixed(12, 25),
+            fixed(12, 26),
+        ],
+        "US" => vec![
+            fixed(1, 1),
+            nth(1, Weekday::Mon, 3),  // Martin Luther King Jr. Day
+            nth(2, Weekday::Mon, 3),  // Presidents' Day
+            nth(5, Weekday::Mon, 5).or_else(|| nth(5, Weekday::Mon, 4)), // Memorial Day
+            fixed(6, 19),
+            fixed(7, 4),
+            nth(9, Weekday::Mon, 1),  // Labor Day
+            nth(10, Weekday::Mon, 2), // Columbus Day
+            fixed(11, 11),
+            nth(11, Weekday::Thu, 4), // Thanksgiving
+            fixed(12, 25),
+        ],
+        other => return Err(format!("Calendrier de jours feries inconnu: {}", other)),
+    };
+
+    Ok(dates.into_iter().flatten().collect())
+}
+
+/// Verifie si une date est feriee (preset du pays ou date personnalisee)
+pub fn is_holiday(date: NaiveDate, country: Option<&str>, custom_dates: &[NaiveDate]) -> bool {
+    if custom_dates.contains(&date) {
+        return true;
+    }
+    country
+        .and_then(|c| country_holidays(c, date.year()).ok())
+        .is_some_and(|holidays| holidays.contains(&date))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_easter() {
+        assert_eq!(easter_sunday(2024), Some(date(2024, 3, 31)));
+        assert_eq!(easter_sunday(2025), Some(date(2025, 4, 20)));
+    }
+
+    #[test]
+    fn test_country_holidays() {
+        let fr = country_holidays("fr", 2024).unwrap();
+        assert!(fr.contains(&date(2024, 4, 1)));  // Lundi de Paques
+        assert!(fr.contains(&date(2024, 5, 9)));  // Ascension
+        assert!(fr.contains(&date(2024, 5, 20))); // Pentecote
+        assert_eq!(fr.len(), 11);
+
+        let us = country_holidays("US", 2024).unwrap();
+        assert!(us.contains(&date(2024, 5, 27)));  // Memorial Day
+        assert!(us.contains(&date(2024, 11, 28))); // Thanksgiving
+
+        assert!(country_holidays("XX", 2024).is_err());
+    }
+
+    #[test]
+    fn test_is_holiday() {
+        assert!(is_holiday(date(2024, 7, 14), Some("FR"), &[]));
+        assert!(!is_holiday(date(2024, 7, 15), Some("FR"), &[]));
+        assert!(is_holiday(date(2024, 7, 15), None, &[date(2024, 7, 15)]));
+        assert!(!is_holiday(date(2024, 7, 14), None, &[]));
+    }
+}
@@ -0,0 +1,229 @@
+// i18n.rs - Messages renvoyes par les commandes IPC, traduits selon la langue configuree
+// Chaque message est une variante de Msg avec ses gabarits francais et anglais
+
+use std::fmt::Display;
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+/// Langue des messages du backend
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    Fr,
+    En,
+}
+
+/// Affichage des heures: 24 h ("07:30") ou 12 h ("7:30 AM")
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TimeFormat {
+    #[default]
+    #[serde(rename = "24h")]
+    H24,
+    #[serde(rename = "12h")]
+    H12,
+}
+
+/// Heure lisible selon le format choisi
+pub fn format_clock(time: NaiveTime, format: TimeFormat, with_seconds: bool) -> String {
+    let pattern = match (format, with_seconds) {
+        (TimeFormat::H24, false) => "%H:%M",
+        (TimeFormat::H24, true) => "%H:%M:%S",
+        (TimeFormat::H12, false) => "%-I:%M %p",
+        (TimeFormat::H12, true) => "%-I:%M:%S %p",
+    };
+    time.format(pattern).to_string()
+}
+
+/// Duree lisible ("3 minutes", "1h 5m") dans la langue configuree
+pub fn format_duration(locale: Locale, seconds: i64) -> String {
+    let plural = |n: i64| if n > 1 { "s" } else { "" };
+    let (second, minute, hour) = match locale {
+        Locale::Fr => ("seconde", "minute", "heure"),
+        Locale::En => ("second", "minute", "hour"),
+    };
+    if seconds < 60 {
+        format!("{} {}{}", seconds, second, plural(seconds))
+    } else if seconds < 3600 {
+        let minutes = seconds / 60;
+        format!("{} {}{}", minutes, minute, plural(minutes))
+    } else {
+        let hours = seconds / 3600;
+        match (seconds % 3600) / 60 {
+            0 => format!("{} {}{}", hours, hour, plural(hours)),
+            minutes => format!("{}h {}m", hours, minutes),
+        }
+    }
+}
+
+/// Messages connus (les `{}` sont remplaces dans l'ordre par les arguments)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    AlarmNotFound,
+    AlarmConflict,
+    AlarmNotRinging,
+    NoRingingAlarm,
+    AlarmAlreadyRinging,
+    NoUpcomingOccurrence,
+    GroupNotFound,
+    NapNotFound,
+    TimerNotFound,
+    TimerDurationInvalid,
+    StopwatchNotRunning,
+    TemplateNameEmpty,
+    TemplateNotFound,
+    NoActiveChallenge,
+    ChallengeUnsolved,
+    UnknownHolidayCalendar,
+    ConfigSaveError,
+    AudioError,
+    PauseError,
+    PlaybackError,
+    VolumeError,
+    SpotifyNotConnected,
+    SpotifyNotInitialized,
+    SpotifyAuthError,
+    SpotifyAccountNotFound,
+    PlaylistFetchError,
+    UnknownLogLevel,
+    HueNotPaired,
+    InvalidDuration,
+    ProfileSwitchWhileRinging,
+    SyncNotConfigured,
+    SyncSecretMissing,
+    AppleMusicNotConnected,
+    YouTubeMusicNotConnected,
+    YouTubeMusicNotInitialized,
+    YouTubeMusicAuthError,
+    UpdateError,
+    UpdateNotAvailable,
+    UpdaterNotConfigured,
+    UpdateTooCloseToAlarm,
+    ExportWeeksInvalid,
+    DefaultNapName,
+    DefaultTimerName,
+    SpotifyNoActiveDevice,
+    SpotifyRateLimited,
+    NetworkError,
+    HourInvalid,
+}
+
+impl Msg {
+    /// Gabarits (francais, anglais)
+    fn templates(self) -> (&'static str, &'static str) {
+        match self {
+            Msg::AlarmNotFound => ("Alarme '{}' introuvable", "Alarm '{}' not found"),
+            Msg::AlarmConflict => (
+                "L'alarme '{}' a ete modifiee ailleurs: rechargez-la avant de l'enregistrer",
+                "Alarm '{}' was changed elsewhere: reload it before saving",
+            ),
+            Msg::AlarmNotRinging => ("L'alarme '{}' ne sonne pas", "Alarm '{}' is not ringing"),
+            Msg::NoRingingAlarm => ("Aucune alarme ne sonne", "No alarm is ringing"),
+            Msg::AlarmAlreadyRinging => ("Une alarme sonne deja", "An alarm is already ringing"),
+            Msg::NoUpcomingOccurrence => ("Aucune occurrence a venir", "No upcoming occurrence"),
+            Msg::GroupNotFound => ("Aucune alarme dans le groupe '{}'", "No alarm in group '{}'"),
+            Msg::NapNotFound => ("Sieste '{}' introuvable", "Nap '{}' not found"),
+            Msg::TimerNotFound => ("Minuteur '{}' introuvable", "Timer '{}' not found"),
+            Msg::TimerDurationInvalid => ("Duree invalide: entre 1 et {} secondes", "Invalid duration: between 1 and {} seconds"),
+            Msg::StopwatchNotRunning => ("Le chronometre est arrete", "The stopwatch is not running"),
+            Msg::TemplateNameEmpty => ("Le nom du modele est vide", "Template name is empty"),
+            Msg::TemplateNotFound => ("Modele '{}' introuvable", "Template '{}' not found"),
+            Msg::NoActiveChallenge => ("Aucun defi en cours pour cette alarme", "No challenge in progress for this alarm"),
+            Msg::ChallengeUnsolved => ("Resolvez le defi pour arreter l'alarme", "Solve the challenge to stop the alarm"),
+            Msg::UnknownHolidayCalendar => ("Calendrier de jours feries inconnu: {}", "Unknown holiday calendar: {}"),
+            Msg::ConfigSaveError => ("Erreur sauvegarde config: {}", "Failed to save settings: {}"),
+            Msg::AudioError => ("Erreur audio: {}", "Audio error: {}"),
+            Msg::PauseError => ("Erreur pause: {}", "Pause error: {}"),
+            Msg::PlaybackError => ("Erreur lecture: {}", "Playback error: {}"),
+            Msg::VolumeError => ("Erreur volume: {}", "Volume error: {}"),
+            Msg::SpotifyNotConnected => ("Non connecte a Spotify", "Not connected to Spotify"),
+            Msg::SpotifyNotInitialized => ("Client Spotify non initialise", "Spotify client not initialized"),
+            Msg::SpotifyAuthError => ("Erreur auth Spotify: {}", "Spotify authentication error: {}"),
+            Msg::SpotifyAccountNotFound => ("Compte Spotify '{}' introuvable", "Spotify account '{}' not found"),
+            Msg::PlaylistFetchError => ("Erreur recuperation playlists: {}", "Failed to fetch playlists: {}"),
+            Msg::UnknownLogLevel => ("Niveau de journal inconnu: {}", "Unknown log level: {}"),
+            Msg::HueNotPaired => ("Aucun pont Hue appaire", "No Hue bridge paired"),
+            Msg::InvalidDuration => ("Duree invalide: entre 1 et {} minutes", "Invalid duration: between 1 and {} minutes"),
+            Msg::ProfileSwitchWhileRinging => ("Impossible de changer de profil pendant une sonnerie", "Cannot switch profiles while an alarm is ringing"),
+            Msg::SyncNotConfigured => ("Synchronisation non configuree", "Sync is not configured"),
+            Msg::SyncSecretMissing => ("Mot de passe de synchronisation absent du trousseau", "Sync password missing from the keychain"),
+            Msg::AppleMusicNotConnected => ("Non connecte a Apple Music", "Not connected to Apple Music"),
+            Msg::YouTubeMusicNotConnected => ("Non connecte a YouTube Music", "Not connected to YouTube Music"),
+            Msg::YouTubeMusicNotInitialized => ("Connexion YouTube Music non initialisee", "YouTube Music login not initialized"),
+            Msg::YouTubeMusicAuthError => ("Erreur auth YouTube Music: {}", "YouTube Music authentication error: {}"),
+            Msg::UpdateError => ("Erreur mise a jour: {}", "Update error: {}"),
+            Msg::UpdateNotAvailable => ("Aucune mise a jour disponible", "No update available"),
+            Msg::UpdaterNotConfigured => (
+                "Mises a jour non configurees: cle publique de signature absente",
+                "Updater not configured: signing public key missing",
+            ),
+            Msg::UpdateTooCloseToAlarm => (
+                "Mise a jour reportee: une alarme sonne ou va sonner ({})",
+                "Update postponed: an alarm is ringing or about to ring ({})",
+            ),
+            Msg::ExportWeeksInvalid => ("Nombre de semaines invalide: entre 1 et {}", "Invalid number of weeks: between 1 and {}"),
+            Msg::DefaultNapName => ("Sieste", "Nap"),
+            Msg::DefaultTimerName => ("Minuteur", "Timer"),
+            Msg::SpotifyNoActiveDevice => (
+                "Aucun appareil Spotify actif. Ouvrez Spotify sur un appareil.",
+                "No active Spotify device. Open Spotify on a device.",
+            ),
+            Msg::SpotifyRateLimited => (
+                "Limite de requetes Spotify atteinte, reessayez dans {}s",
+                "Spotify rate limit reached, try again in {}s",
+            ),
+            Msg::NetworkError => ("Erreur reseau: {}", "Network error: {}"),
+            Msg::HourInvalid => ("L'heure doit etre entre 0 et 23", "Hour must be between 0 and 23"),
+        }
+    }
+}
+
+/// Traduit un message et remplace ses `{}` par les arguments
+pub fn tr(locale: Locale, msg: Msg, args: &[&dyn Display]) -> String {
+    let (fr, en) = msg.templates();
+    let template = match locale {
+        Locale::Fr => fr,
+        Locale::En => en,
+    };
+
+    let mut text = String::with_capacity(template.len());
+    let mut args = args.iter();
+    let mut parts = template.split("{}");
+    if let Some(first) = parts.next() {
+        text.push_str(first);
+    }
+    for part in parts {
+        if let Some(arg) = args.next() {
+            text.push_str(&arg.to_string());
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tr() {
+        assert_eq!(tr(Locale::Fr, Msg::AlarmNotFound, &[&"a1"]), "Alarme 'a1' introuvable");
+        assert_eq!(tr(Locale::En, Msg::AlarmNotFound, &[&"a1"]), "Alarm 'a1' not found");
+        assert_eq!(tr(Locale::En, Msg::SpotifyNotConnected, &[]), "Not connected to Spotify");
+        assert_eq!(tr(Locale::En, Msg::SpotifyRateLimited, &[&30]), "Spotify rate limit reached, try again in 30s");
+        assert_eq!(serde_json::from_str::<Locale>("\"en\"").unwrap(), Locale::En);
+    }
+
+    #[test]
+    fn test_time_formatting() {
+        let evening = NaiveTime::from_hms_opt(19, 5, 9).unwrap();
+        assert_eq!(format_clock(evening, TimeFormat::H24, false), "19:05");
+        assert_eq!(format_clock(evening, TimeFormat::H12, false), "7:05 PM");
+        assert_eq!(format_clock(evening, TimeFormat::H12, true), "7:05:09 PM");
+        assert_eq!(serde_json::from_str::<TimeFormat>("\"12h\"").unwrap(), TimeFormat::H12);
+
+        assert_eq!(format_duration(Locale::En, 1), "1 second");
+        assert_eq!(format_duration(Locale::En, 7200), "2 hours");
+        assert_eq!(format_duration(Locale::Fr, 180), "3 minutes");
+    }
+}
@@ -0,0 +1,162 @@
+// ical.rs - Export du planning des alarmes au format iCalendar (RFC 5545)
+// Chaque occurrence devient un VEVENT, pour superposer les reveils a un agenda
+
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Local, Utc};
+
+use crate::alarm;
+use crate::storage::AppConfig;
+use crate::AlarmEntry;
+
+/// Horizon maximal d'export (semaines)
+pub const MAX_EXPORT_WEEKS: u32 = 52;
+
+/// Duree affichee d'un evenement de reveil (minutes)
+const EVENT_DURATION_MINUTES: i64 = 15;
+
+/// Longueur maximale d'une ligne iCalendar (octets, hors CRLF)
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Echappe un texte iCalendar (virgules, points-virgules, retours a la ligne)
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Replie une ligne trop longue (continuation prefixee d'un espace)
+fn fold_line(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
+
+fn format_utc(dt: DateTime<Utc>) -> String {
+    dt.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Occurrences effectives d'une alarme entre `now` et `until`
+/// (vacances, jours feries et occurrence sautee exclus)
+fn occurrences(
+    entry: &AlarmEntry,
+    now: DateTime<Local>,
+    until: DateTime<Local>,
+    config: &AppConfig,
+) -> Vec<DateTime<Utc>> {
+    let mut result = Vec::new();
+    if !alarm::is_enabled(entry) {
+        return result;
+    }
+
+    let mut cursor = alarm::wall_clock(entry, now);
+    while let Some(next) = alarm::next_occurrence(entry, cursor) {
+        cursor = next;
+        if alarm::is_skipped_on(entry, next.date(), config) {
+            continue;
+        }
+        let Some(instant) = alarm::occurrence_instant(entry, next) else {
+            continue;
+        };
+        if instant > until {
+            break;
+        }
+        result.push(instant);
+    }
+    result
+}
+
+/// Construit un calendrier contenant les reveils des `weeks` prochaines semaines
+pub fn build_calendar(alarms: &[AlarmEntry], config: &AppConfig, now: DateTime<Local>, weeks: u32) -> String {
+    let until = now + chrono::Duration::weeks(i64::from(weeks.min(MAX_EXPORT_WEEKS)));
+    let stamp = format_utc(now.with_timezone(&Utc));
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Charmed//Alarmes//FR".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:Charmed".to_string(),
+    ];
+
+    for entry in alarms {
+        for start in occurrences(entry, now, until, config) {
+            let end = start + chrono::Duration::minutes(EVENT_DURATION_MINUTES);
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}-{}@charmed", entry.id, start.format("%Y%m%dT%H%M")),
+                format!("DTSTAMP:{}", stamp),
+                format!("DTSTART:{}", format_utc(start)),
+                format!("DTEND:{}", format_utc(end)),
+                format!("SUMMARY:{}", escape_text(&format!("Reveil - {}", entry.playlist_name))),
+                "TRANSP:TRANSPARENT".to_string(),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = lines.iter().map(|l| fold_line(l)).collect::<Vec<_>>().join("\r\n");
+    calendar.push_str("\r\n");
+    calendar
+}
+
+/// Ecrit le calendrier dans un fichier .ics
+pub fn write_calendar(path: &Path, calendar: &str) -> Result<(), String> {
+    fs::write(path, calendar)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::alarm::test_support;
+
+    fn alarm(time: &str, days: &[&str], active: bool) -> AlarmEntry {
+        AlarmEntry {
+            playlist_name: "Morning, Coffee".to_string(),
+            active,
+            days: days.iter().map(|d| d.to_string()).collect(),
+            timezone: Some("Europe/Paris".to_string()),
+            ..test_support::alarm("a1", time)
+        }
+    }
+
+    #[test]
+    fn test_build_calendar() {
+        // Lundi 11 mars 2024 12:00 UTC, deux semaines
+        let now = Utc.with_ymd_and_hms(2024, 3, 11, 12, 0, 0).unwrap().with_timezone(&Local);
+        let alarms = vec![
+            alarm("07:00", &["Monday", "Wednesday"], true),
+            alarm("09:00", &[], false),
+        ];
+        let calendar = build_calendar(&alarms, &AppConfig::default(), now, 2);
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        // Mer 13, Lun 18, Mer 20 et Lun 25 (07:00 Paris = 06:00 UTC)
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 4);
+        assert!(calendar.contains("DTSTART:20240313T060000Z"));
+        assert!(calendar.contains("DTEND:20240313T061500Z"));
+        assert!(calendar.contains("SUMMARY:Reveil - Morning\\, Coffee"));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let line = format!("SUMMARY:{}", "x".repeat(100));
+        let folded = fold_line(&line);
+        assert!(folded.split("\r\n").all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
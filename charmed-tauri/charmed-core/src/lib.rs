@@ -0,0 +1,128 @@
+// charmed-core — Logique du reveil sans dependance a Tauri
+// Partagee par l'application de bureau (src-tauri) et la ligne de commande (charmed-cli)
+
+// Les fonctions async ne gardent jamais un verrou std::sync::Mutex pendant un .await
+#![deny(clippy::await_holding_lock)]
+
+pub mod alarm;
+pub mod alarm_list;
+pub mod spotify;
+pub mod storage;
+pub mod audio;
+pub mod fade;
+pub mod holidays;
+pub mod recurrence;
+pub mod challenge;
+pub mod escalation;
+pub mod history;
+pub mod bundle;
+pub mod ical;
+pub mod secrets;
+pub mod i18n;
+pub mod weather;
+pub mod tts;
+pub mod system_volume;
+pub mod shortcuts;
+pub mod deeplink;
+pub mod remote;
+pub mod lights;
+pub mod calendar;
+pub mod wake_window;
+pub mod stats;
+pub mod bedtime;
+pub mod winddown;
+pub mod profiles;
+pub mod sync;
+pub mod provider;
+pub mod apple_music;
+pub mod youtube_music;
+pub mod library;
+pub mod radio;
+pub mod podcast;
+pub mod readiness;
+pub mod moods;
+pub mod timer;
+pub mod stopwatch;
+pub mod worldclock;
+pub mod preview;
+pub mod trash;
+pub mod query;
+pub mod miniclock;
+pub mod nightmode;
+pub mod power;
+pub mod health;
+pub mod update;
+pub mod telemetry;
+pub mod loudness;
+
+use serde::{Deserialize, Serialize};
+
+// -- STRUCTURES DE DONNÉES --
+
+/// Représente une alarme programmée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlarmEntry {
+    pub id: String,
+    pub time: String,           // Format "HH:MM" ou "HH:MM:SS"
+    pub playlist_name: String,
+    pub playlist_uri: String,
+    pub volume: u8,             // 0-100
+    pub active: bool,
+    pub days: Vec<String>,      // ["Monday", "Tuesday", ...]
+    pub fade_in: bool,
+    pub fade_in_duration: u16,  // Secondes
+    #[serde(default)]
+    pub account: Option<String>, // Profil Spotify (None = compte actif)
+    #[serde(default)]
+    pub skip_date: Option<chrono::NaiveDate>, // Occurrence sautee une seule fois
+    #[serde(default)]
+    pub recurrence: Option<recurrence::RecurrenceRule>, // Remplace `days` si present
+    #[serde(default)]
+    pub timezone: Option<String>, // Fuseau IANA (None = heure locale)
+    #[serde(default)]
+    pub challenge: Option<challenge::ChallengeConfig>, // Defi requis pour arreter l'alarme
+    #[serde(default)]
+    pub escalation: Option<escalation::EscalationPolicy>, // Lecture pilotee par le backend
+    #[serde(default)]
+    pub ring_limit: Option<alarm::RingLimit>, // Arret automatique apres une duree maximale
+    #[serde(default)]
+    pub weather_playlists: Option<weather::WeatherPlaylists>, // Playlist selon la meteo du reveil
+    #[serde(default)]
+    pub announcement: Option<tts::Announcement>, // Annonce vocale de l'heure et de la meteo
+    #[serde(default)]
+    pub sunrise: bool, // Chorus de l'aube pendant le fondu, avant la playlist
+    #[serde(default)]
+    pub output_device: Option<String>, // Sortie audio locale (None = reglage global)
+    #[serde(default)]
+    pub fade_curve: fade::FadeCurve, // Courbe du fondu d'entree
+    #[serde(default)]
+    pub lights: Option<lights::SunriseLights>, // Lampes Hue allumees pendant le fondu, avant l'alarme
+    #[serde(default)]
+    pub calendar: Option<calendar::CalendarRule>, // Heure recalculee chaque jour depuis l'agenda
+    #[serde(default)]
+    pub wake_window: Option<wake_window::WakeWindow>, // Sonne au debut d'un titre dans la fenetre precedant l'heure
+    #[serde(default)]
+    pub first_track_uri: Option<String>, // Titre joue avant la playlist a chaque reveil
+    #[serde(default)]
+    pub weekend_profile: Option<alarm::SoundProfile>, // Playlist, volume et fondu du samedi et du dimanche
+    #[serde(default)]
+    pub group_playback: Option<alarm::GroupPlayback>, // Groupe Spotify Connect et/ou son local en meme temps
+    #[serde(default)]
+    pub after_wake_queue: Option<alarm::AfterWakeQueue>, // Titres joues apres les premiers titres de la playlist
+    #[serde(default)]
+    pub interval: Option<alarm::IntervalRule>, // Repetition dans la journee a partir de `time`
+    #[serde(default)]
+    pub pre_alarm: Option<alarm::PreAlarm>, // Carillon discret ou playlist tres basse avant l'alarme
+    #[serde(default)]
+    pub backup: Option<alarm::BackupAlarm>, // Sirene a plein volume si la sonnerie reste sans reponse
+    #[serde(default)]
+    pub group: Option<String>, // Groupe ("Travail"...) active ou desactive d'un coup
+    #[serde(default)]
+    pub color: Option<String>, // Couleur d'affichage "#rrggbb" (interface, barre systeme, notifications)
+    #[serde(default)]
+    pub icon: Option<String>, // Icone d'affichage (voir alarm::ALARM_ICONS)
+    #[serde(default)]
+    pub archived: bool, // Rangee hors de la liste et jamais evaluee (alarmes saisonnieres)
+    #[serde(default)]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>, // Derniere modification (resolution des conflits de synchronisation)
+}
@@ -0,0 +1,284 @@
+// library.rs - Bibliotheque musicale locale comme musique d'alarme (sans reseau)
+// Chaque dossier contenant des fichiers audio et chaque liste .m3u du dossier configure
+// devient une playlist, jouee par le moteur audio local
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::audio;
+use crate::provider::{MusicProvider, ProviderPlaylist};
+
+/// Extensions decodees par le moteur audio
+const AUDIO_EXTENSIONS: [&str; 4] = ["mp3", "flac", "ogg", "wav"];
+
+/// Extensions des listes de lecture
+const PLAYLIST_EXTENSIONS: [&str; 2] = ["m3u", "m3u8"];
+
+/// Profondeur maximale parcourue sous le dossier configure
+const MAX_SCAN_DEPTH: usize = 6;
+
+/// Titres mis en file d'attente par sonnerie (chaque titre garde son fichier ouvert)
+const MAX_QUEUE_TRACKS: usize = 100;
+
+/// Prefixes des URI ("library:folder:Matin", "library:m3u:Listes/reveil.m3u", "library:track:Matin/01.mp3")
+const FOLDER_URI_PREFIX: &str = "library:folder:";
+const M3U_URI_PREFIX: &str = "library:m3u:";
+pub const TRACK_URI_PREFIX: &str = "library:track:";
+
+/// Reglages de la bibliotheque locale
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibrarySettings {
+    pub folder: String, // Dossier racine de la bibliotheque
+    #[serde(default = "default_shuffle")]
+    pub shuffle: bool,
+    #[serde(default = "default_normalize_loudness")]
+    pub normalize_loudness: bool, // Egaliser le volume percu des titres (facon ReplayGain)
+}
+
+fn default_shuffle() -> bool {
+    true
+}
+
+fn default_normalize_loudness() -> bool {
+    true
+}
+
+impl LibrarySettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let folder = Path::new(&self.folder);
+        if !folder.is_absolute() || !folder.is_dir() {
+            return Err(format!("Dossier de musique introuvable: {}", self.folder));
+        }
+        Ok(())
+    }
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| extensions.iter().any(|known| known.eq_ignore_ascii_case(e)))
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.starts_with('.'))
+}
+
+/// Chemin relatif a la racine, avec des / quel que soit le systeme
+fn relative_uri(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<&str> = relative.components().filter_map(|c| c.as_os_str().to_str()).collect();
+    Some(parts.join("/"))
+}
+
+/// Chemin designe par une URI, refuse s'il sort de la racine
+fn resolve(root: &Path, relative: &str) -> Result<PathBuf, String> {
+    let relative = Path::new(relative);
+    if relative.components().any(|c| !matches!(c, Component::Normal(_))) {
+        return Err(format!("Chemin hors de la bibliotheque: {}", relative.display()));
+    }
+    Ok(root.join(relative))
+}
+
+/// Fichiers audio directement contenus dans un dossier, par ordre alphabetique
+fn folder_tracks(folder: &Path) -> Vec<PathBuf> {
+    let mut tracks: Vec<PathBuf> = fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| path.is_file() && !is_hidden(path) && has_extension(path, &AUDIO_EXTENSIONS))
+                .collect()
+        })
+        .unwrap_or_default();
+    tracks.sort();
+    tracks
+}
+
+/// Titres d'une liste m3u (chemins relatifs a la liste ou absolus; commentaires ignores)
+fn parse_m3u(content: &str, base: &Path) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line))
+        .filter(|path| has_extension(path, &AUDIO_EXTENSIONS))
+        .collect()
+}
+
+/// Parcourt la bibliotheque: dossiers contenant des titres, puis listes m3u
+pub fn scan(root: &Path) -> Vec<ProviderPlaylist> {
+    let mut folders = Vec::new();
+    let mut lists = Vec::new();
+    let mut pending = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = pending.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut track_count = 0;
+        for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+            if is_hidden(&path) {
+                continue;
+            }
+            if path.is_dir() {
+                if depth < MAX_SCAN_DEPTH {
+                    pending.push((path, depth + 1));
+                }
+            } else if has_extension(&path, &AUDIO_EXTENSIONS) {
+                track_count += 1;
+            } else if has_extension(&path, &PLAYLIST_EXTENSIONS) {
+                let Some(relative) = relative_uri(root, &path) else {
+                    continue;
+                };
+                let count = fs::read_to_string(&path)
+                    .map(|content| parse_m3u(&content, path.parent().unwrap_or(root)).len())
+                    .unwrap_or(0);
+                lists.push(ProviderPlaylist {
+                    uri: format!("{}{}", M3U_URI_PREFIX, relative),
+                    name: path.file_stem().and_then(|n| n.to_str()).unwrap_or_default().to_string(),
+                    image_url: None,
+                    track_count: Some(count as u32),
+                });
+            }
+        }
+        if track_count > 0 {
+            if let Some(relative) = relative_uri(root, &dir) {
+                let name = if relative.is_empty() {
+                    root.file_name().and_then(|n| n.to_str()).unwrap_or("Musique").to_string()
+                } else {
+                    relative.clone()
+                };
+                folders.push(ProviderPlaylist {
+                    uri: format!("{}{}", FOLDER_URI_PREFIX, relative),
+                    name,
+                    image_url: None,
+                    track_count: Some(track_count),
+                });
+            }
+        }
+    }
+
+    folders.sort_by(|a, b| a.name.cmp(&b.name));
+    lists.sort_by(|a, b| a.name.cmp(&b.name));
+    folders.into_iter().chain(lists).collect()
+}
+
+/// Bibliotheque configuree
+#[derive(Debug, Clone)]
+pub struct MusicLibrary {
+    root: PathBuf,
+    shuffle: bool,
+    normalize_loudness: bool,
+}
+
+impl MusicLibrary {
+    pub fn new(settings: &LibrarySettings) -> Self {
+        Self {
+            root: PathBuf::from(&settings.folder),
+            shuffle: settings.shuffle,
+            normalize_loudness: settings.normalize_loudness,
+        }
+    }
+
+    /// Titres d'une playlist, dans l'ordre de lecture (titre d'ouverture en tete)
+    pub fn queue(&self, playlist_uri: &str, first_track: Option<&str>) -> Result<Vec<PathBuf>, String> {
+        let mut tracks = if let Some(relative) = playlist_uri.strip_prefix(FOLDER_URI_PREFIX) {
+            folder_tracks(&resolve(&self.root, relative)?)
+        } else if let Some(relative) = playlist_uri.strip_prefix(M3U_URI_PREFIX) {
+            let path = resolve(&self.root, relative)?;
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("Liste illisible ({}): {}", path.display(), e))?;
+            parse_m3u(&content, path.parent().unwrap_or(&self.root))
+                .into_iter()
+                .filter(|track| track.is_file())
+                .collect()
+        } else {
+            return Err(format!("Playlist locale invalide: {}", playlist_uri));
+        };
+        if tracks.is_empty() {
+            return Err(format!("Aucun titre lisible dans {}", playlist_uri));
+        }
+
+        if self.shuffle {
+            tracks.shuffle(&mut rand::thread_rng());
+        }
+        if let Some(first) = first_track.and_then(|t| t.strip_prefix(TRACK_URI_PREFIX)) {
+            let first = resolve(&self.root, first)?;
+            tracks.retain(|track| *track != first);
+            tracks.insert(0, first);
+        }
+        tracks.truncate(MAX_QUEUE_TRACKS);
+        Ok(tracks)
+    }
+}
+
+impl MusicProvider for MusicLibrary {
+    const URI_PREFIX: &'static str = "library:";
+
+    async fn playlists(&self) -> Result<Vec<ProviderPlaylist>, String> {
+        let root = self.root.clone();
+        tokio::task::spawn_blocking(move || scan(&root))
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn play(&self, playlist_uri: &str, first_track: Option<&str>) -> Result<(), String> {
+        let library = self.clone();
+        let playlist_uri = playlist_uri.to_string();
+        let first_track = first_track.map(str::to_string);
+        // Lecture du disque et ouverture des fichiers hors des threads du runtime
+        tokio::task::spawn_blocking(move || {
+            audio::play_files(&library.queue(&playlist_uri, first_track.as_deref())?, library.normalize_loudness)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+
+    async fn set_volume(&self, volume: u8) -> Result<(), String> {
+        audio::set_alarm_volume(volume)
+    }
+
+    async fn pause(&self) -> Result<(), String> {
+        audio::stop_alarm_sound()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_and_queue() {
+        let root = std::env::temp_dir().join(format!("charmed-library-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(root.join("Matin/Calme")).unwrap();
+        fs::create_dir_all(root.join(".cache")).unwrap();
+        for file in ["Matin/01.mp3", "Matin/02.FLAC", "Matin/notes.txt", "Matin/Calme/pluie.ogg", ".cache/x.mp3"] {
+            fs::write(root.join(file), b"").unwrap();
+        }
+        fs::write(root.join("reveil.m3u"), "#EXTM3U\n#EXTINF:1,Pluie\nMatin/Calme/pluie.ogg\nabsent.mp3\n").unwrap();
+
+        let playlists = scan(&root);
+        let uris: Vec<&str> = playlists.iter().map(|p| p.uri.as_str()).collect();
+        assert_eq!(uris, vec!["library:folder:Matin", "library:folder:Matin/Calme", "library:m3u:reveil.m3u"]);
+        assert_eq!(playlists[0].track_count, Some(2));
+        assert_eq!(playlists[2].track_count, Some(2));
+
+        let settings = LibrarySettings { folder: root.display().to_string(), shuffle: false, normalize_loudness: true };
+        assert!(settings.validate().is_ok());
+        let library = MusicLibrary::new(&settings);
+        assert_eq!(
+            library.queue("library:folder:Matin", Some("library:track:Matin/02.FLAC")).unwrap(),
+            vec![root.join("Matin/02.FLAC"), root.join("Matin/01.mp3")]
+        );
+        // Les titres absents de la liste sont ignores
+        assert_eq!(library.queue("library:m3u:reveil.m3u", None).unwrap(), vec![root.join("Matin/Calme/pluie.ogg")]);
+        assert!(library.queue("library:folder:../etc", None).is_err());
+        assert!(library.queue("library:folder:Vide", None).is_err());
+        assert!(MusicLibrary::handles("library:m3u:reveil.m3u"));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,243 @@
+// lights.rs - Lever de soleil lumineux via un pont Philips Hue (API locale v1)
+// Les lampes choisies s'allument au minimum puis montent pendant la duree de fondu
+// de l'alarme, pour atteindre leur luminosite finale a l'heure du reveil
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Service de decouverte des ponts Hue du reseau local
+const DISCOVERY_URL: &str = "https://discovery.meethue.com";
+
+/// Delai maximal d'une requete vers le pont
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Nom de l'application presente au pont lors de l'appairage
+const DEVICE_TYPE: &str = "charmed#desktop";
+
+/// Luminosite maximale d'une lampe Hue
+const HUE_MAX_BRIGHTNESS: u32 = 254;
+
+/// Transition maximale acceptee par le pont (dixiemes de seconde, ~1h49)
+const HUE_MAX_TRANSITION: u64 = 65535;
+
+/// Temperature de couleur au debut et a la fin du lever (mireds: 500 = 2000K, 250 = 4000K)
+const SUNRISE_START_CT: u16 = 500;
+const SUNRISE_END_CT: u16 = 250;
+
+/// Lampes a allumer avant une alarme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SunriseLights {
+    pub lights: Vec<String>, // Ids des lampes sur le pont
+    #[serde(default = "default_brightness")]
+    pub brightness: u8, // Luminosite finale (1-100 %)
+}
+
+fn default_brightness() -> u8 {
+    100
+}
+
+impl SunriseLights {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.lights.is_empty() {
+            return Err("Choisissez au moins une lampe pour le lever de soleil".to_string());
+        }
+        if self.brightness == 0 || self.brightness > 100 {
+            return Err("La luminosite doit etre entre 1 et 100".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Pont trouve sur le reseau local
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HueBridge {
+    pub id: String,
+    #[serde(rename(deserialize = "internalipaddress"))]
+    pub address: String,
+}
+
+/// Lampe declaree sur le pont
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HueLight {
+    pub id: String,
+    pub name: String,
+    pub on: bool,
+}
+
+#[derive(Deserialize)]
+struct LightInfo {
+    name: String,
+    state: LightState,
+}
+
+#[derive(Deserialize)]
+struct LightState {
+    #[serde(default)]
+    on: bool,
+}
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Premiere erreur d'une reponse du pont ([{"error": {"description": ...}}])
+fn response_error(results: &[serde_json::Value]) -> Option<String> {
+    results.iter()
+        .find_map(|r| r.get("error"))
+        .map(|e| e.get("description").and_then(|d| d.as_str()).unwrap_or("erreur inconnue").to_string())
+}
+
+fn parse_results(body: &str) -> Result<Vec<serde_json::Value>, String> {
+    serde_json::from_str(body).map_err(|e| format!("Reponse du pont Hue invalide: {}", e))
+}
+
+/// Lit la reponse d'appairage et retourne l'identifiant attribue par le pont
+fn parse_pairing(body: &str) -> Result<String, String> {
+    let results = parse_results(body)?;
+    if let Some(error) = response_error(&results) {
+        return Err(format!("Appairage Hue refuse: {}", error));
+    }
+    results.iter()
+        .find_map(|r| r.pointer("/success/username").and_then(|u| u.as_str()))
+        .map(str::to_string)
+        .ok_or_else(|| "Appairage Hue: identifiant absent de la reponse".to_string())
+}
+
+/// Lit la liste des lampes (objet id -> lampe), triee par id
+fn parse_lights(body: &str) -> Result<Vec<HueLight>, String> {
+    // Une erreur (identifiant revoque...) est renvoyee sous forme de tableau
+    if let Ok(results) = serde_json::from_str::<Vec<serde_json::Value>>(body) {
+        return Err(format!("Pont Hue: {}", response_error(&results).unwrap_or_default()));
+    }
+    let lights: BTreeMap<String, LightInfo> = serde_json::from_str(body)
+        .map_err(|e| format!("Reponse du pont Hue invalide: {}", e))?;
+    Ok(lights.into_iter()
+        .map(|(id, light)| HueLight { id, name: light.name, on: light.state.on })
+        .collect())
+}
+
+/// Luminosite Hue (1-254) pour un pourcentage
+fn brightness_value(percent: u8) -> u32 {
+    ((u32::from(percent.min(100)) * HUE_MAX_BRIGHTNESS + 50) / 100).max(1)
+}
+
+/// Duree de transition Hue (dixiemes de seconde, bornee par le pont)
+fn transition_time(duration: Duration) -> u64 {
+    (duration.as_millis() / 100).min(u128::from(HUE_MAX_TRANSITION)) as u64
+}
+
+/// Recherche les ponts du reseau local
+pub async fn discover_bridges() -> Result<Vec<HueBridge>, String> {
+    let body = client()?
+        .get(DISCOVERY_URL)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Decouverte Hue impossible: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Decouverte Hue impossible: {}", e))?;
+    serde_json::from_str(&body).map_err(|e| format!("Reponse de decouverte Hue invalide: {}", e))
+}
+
+/// Appaire Charmed au pont (le bouton du pont doit avoir ete presse juste avant)
+pub async fn pair(address: &str) -> Result<String, String> {
+    let body = client()?
+        .post(format!("http://{}/api", address))
+        .body(serde_json::json!({ "devicetype": DEVICE_TYPE }).to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Pont Hue injoignable: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Pont Hue injoignable: {}", e))?;
+    parse_pairing(&body)
+}
+
+/// Liste les lampes du pont
+pub async fn list_lights(address: &str, username: &str) -> Result<Vec<HueLight>, String> {
+    let body = client()?
+        .get(format!("http://{}/api/{}/lights", address, username))
+        .send()
+        .await
+        .map_err(|e| format!("Pont Hue injoignable: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Pont Hue injoignable: {}", e))?;
+    parse_lights(&body)
+}
+
+async fn set_light_state(address: &str, username: &str, light: &str, state: serde_json::Value) -> Result<(), String> {
+    let body = client()?
+        .put(format!("http://{}/api/{}/lights/{}/state", address, username, light))
+        .body(state.to_string())
+        .send()
+        .await
+        .map_err(|e| format!("Pont Hue injoignable: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Pont Hue injoignable: {}", e))?;
+    // Les lampes sans temperature de couleur refusent "ct" mais appliquent le reste
+    match response_error(&parse_results(&body)?) {
+        Some(error) if !error.contains("parameter, ct,") => Err(format!("Lampe {}: {}", light, error)),
+        _ => Ok(()),
+    }
+}
+
+/// Allume les lampes au minimum puis les fait monter jusqu'a leur luminosite
+/// finale sur `duration`. La transition est confiee au pont.
+pub async fn start_sunrise(
+    address: &str,
+    username: &str,
+    settings: &SunriseLights,
+    duration: Duration,
+) -> Result<(), String> {
+    let dawn = serde_json::json!({ "on": true, "bri": 1, "ct": SUNRISE_START_CT, "transitiontime": 0 });
+    let day = serde_json::json!({
+        "bri": brightness_value(settings.brightness),
+        "ct": SUNRISE_END_CT,
+        "transitiontime": transition_time(duration),
+    });
+
+    let mut last_error = None;
+    for light in &settings.lights {
+        let result = match set_light_state(address, username, light, dawn.clone()).await {
+            Ok(()) => set_light_state(address, username, light, day.clone()).await,
+            Err(e) => Err(e),
+        };
+        // Une lampe debranchee n'empeche pas les autres de s'allumer
+        if let Err(e) = result {
+            tracing::warn!(light = %light, "Lever de soleil lumineux: {}", e);
+            last_error = Some(e);
+        }
+    }
+    last_error.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hue_responses() {
+        assert_eq!(parse_pairing(r#"[{"success":{"username":"abc123"}}]"#), Ok("abc123".to_string()));
+        assert!(parse_pairing(r#"[{"error":{"type":101,"address":"","description":"link button not pressed"}}]"#)
+            .unwrap_err()
+            .contains("link button not pressed"));
+
+        let lights = parse_lights(r#"{"2":{"name":"Plafond","state":{"on":false,"bri":1}},"1":{"name":"Chevet","state":{"on":true}}}"#).unwrap();
+        assert_eq!(lights.len(), 2);
+        assert_eq!(lights[0], HueLight { id: "1".to_string(), name: "Chevet".to_string(), on: true });
+        assert!(parse_lights(r#"[{"error":{"type":1,"description":"unauthorized user"}}]"#).is_err());
+
+        assert_eq!(brightness_value(100), 254);
+        assert_eq!(brightness_value(1), 3);
+        assert_eq!(transition_time(Duration::from_secs(300)), 3000);
+        assert_eq!(transition_time(Duration::from_secs(3 * 3600)), HUE_MAX_TRANSITION);
+        assert!(SunriseLights { lights: Vec::new(), brightness: 100 }.validate().is_err());
+    }
+}
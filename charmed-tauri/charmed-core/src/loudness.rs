@@ -0,0 +1,128 @@
+// loudness.rs - Normalisation du volume percu des fichiers audio locaux, facon ReplayGain
+// Le niveau d'un titre est le 95e centile de l'energie de fenetres de 50 ms (methode ReplayGain,
+// sans filtre de ponderation), mesure sur ses premieres secondes. Le gain ramene chaque titre au
+// meme niveau cible, sans jamais faire saturer son pic.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+use rodio::{Decoder, Source};
+
+/// Niveau cible (dBFS RMS), proche de celui des services de streaming
+pub const TARGET_DB: f32 = -14.0;
+
+/// Correction maximale dans un sens ou dans l'autre (dB)
+pub const MAX_GAIN_DB: f32 = 15.0;
+
+/// Duree analysee au debut de chaque titre (secondes)
+const ANALYSIS_SECONDS: usize = 30;
+
+/// Fenetre de mesure de l'energie (millisecondes)
+const WINDOW_MS: usize = 50;
+
+/// Centile des fenetres retenu comme niveau du titre
+const PERCENTILE: f64 = 0.95;
+
+/// Fichier identifie par son chemin, sa taille et sa date de modification
+type FileKey = (PathBuf, u64, Option<SystemTime>);
+
+lazy_static::lazy_static! {
+    // Gains deja mesures: un titre rejoue n'est pas analyse de nouveau
+    static ref GAINS: Mutex<HashMap<FileKey, f32>> = Mutex::new(HashMap::new());
+}
+
+/// Niveau mesure d'un titre
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Loudness {
+    pub level_db: f32, // Niveau en dBFS RMS
+    pub peak: f32,     // Pic d'echantillon (0.0 - 1.0)
+}
+
+impl Loudness {
+    /// Gain lineaire qui amene le titre au niveau cible (limite par son pic)
+    pub fn gain(&self) -> f32 {
+        let db = (TARGET_DB - self.level_db).clamp(-MAX_GAIN_DB, MAX_GAIN_DB);
+        let gain = 10f32.powf(db / 20.0);
+        if self.peak > 0.0 {
+            gain.min(1.0 / self.peak)
+        } else {
+            gain
+        }
+    }
+}
+
+/// Mesure le niveau d'echantillons entrelaces (None pour un silence ou un extrait trop court)
+pub fn measure(samples: impl Iterator<Item = f32>, channels: u16, sample_rate: u32) -> Option<Loudness> {
+    let window = (sample_rate as usize * usize::from(channels) * WINDOW_MS / 1000).max(1);
+    let mut energies = Vec::new();
+    let (mut sum, mut count, mut peak) = (0.0f64, 0usize, 0.0f32);
+    for sample in samples {
+        peak = peak.max(sample.abs());
+        sum += f64::from(sample) * f64::from(sample);
+        count += 1;
+        if count == window {
+            energies.push(sum / count as f64);
+            sum = 0.0;
+            count = 0;
+        }
+    }
+    if energies.is_empty() {
+        return None;
+    }
+
+    energies.sort_by(f64::total_cmp);
+    let index = ((energies.len() as f64 * PERCENTILE).ceil() as usize).clamp(1, energies.len()) - 1;
+    let energy = energies[index];
+    if energy <= 0.0 {
+        return None;
+    }
+    Some(Loudness { level_db: (10.0 * energy.log10()) as f32, peak })
+}
+
+/// Gain de normalisation d'un fichier (1.0 s'il est silencieux), mesure une seule fois
+pub fn file_gain(path: &Path) -> Result<f32, String> {
+    let metadata = fs::metadata(path).map_err(|e| e.to_string())?;
+    let key = (path.to_path_buf(), metadata.len(), metadata.modified().ok());
+    if let Some(gain) = GAINS.lock().map_err(|e| e.to_string())?.get(&key) {
+        return Ok(*gain);
+    }
+
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let decoder = Decoder::new(BufReader::new(file)).map_err(|e| e.to_string())?;
+    let (channels, sample_rate) = (decoder.channels(), decoder.sample_rate());
+    let limit = ANALYSIS_SECONDS * sample_rate as usize * usize::from(channels);
+    let loudness = measure(decoder.convert_samples::<f32>().take(limit), channels, sample_rate);
+    let gain = loudness.map_or(1.0, |l| l.gain());
+    tracing::debug!(path = %path.display(), level_db = ?loudness.map(|l| l.level_db), gain, "Niveau du titre mesure");
+
+    GAINS.lock().map_err(|e| e.to_string())?.insert(key, gain);
+    Ok(gain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(amplitude: f32) -> impl Iterator<Item = f32> {
+        (0..44_100).map(move |i| amplitude * (i as f32 * 440.0 * std::f32::consts::TAU / 44_100.0).sin())
+    }
+
+    #[test]
+    fn test_loudness_gain() {
+        let quiet = measure(sine(0.1), 1, 44_100).unwrap();
+        let loud = measure(sine(0.8), 1, 44_100).unwrap();
+        assert!(quiet.level_db < loud.level_db);
+        // Meme niveau percu apres normalisation
+        assert!((quiet.gain() * 0.1 - loud.gain() * 0.8).abs() < 0.01);
+        assert!(loud.gain() < 1.0 && quiet.gain() > 1.0);
+
+        // Le pic limite le gain: pas de saturation
+        let clipped = Loudness { level_db: -40.0, peak: 0.9 };
+        assert!(clipped.gain() * clipped.peak <= 1.0);
+        assert!(measure(std::iter::repeat(0.0).take(44_100), 1, 44_100).is_none());
+    }
+}
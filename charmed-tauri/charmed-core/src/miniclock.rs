@@ -0,0 +1,109 @@
+// miniclock.rs - Petite horloge flottante (heure et prochaine alarme) pour la nuit
+// La fenetre elle-meme est geree par l'application; ici son contenu et sa position
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::NextAlarm;
+use crate::i18n::{self, TimeFormat};
+
+const MINI_CLOCK_FILE: &str = "mini_clock.json";
+
+/// Position de la fenetre a l'ecran (pixels physiques)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Reglages de la mini horloge, propres a la machine (hors profils et synchronisation)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MiniClockSettings {
+    #[serde(default)]
+    pub placement: Option<Placement>, // None = coin choisi par le systeme
+    #[serde(default = "default_always_on_top")]
+    pub always_on_top: bool,
+}
+
+fn default_always_on_top() -> bool {
+    true
+}
+
+impl Default for MiniClockSettings {
+    fn default() -> Self {
+        Self { placement: None, always_on_top: default_always_on_top() }
+    }
+}
+
+/// Contenu affiche par la mini horloge
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MiniClockView {
+    pub time: String,
+    pub next_alarm: Option<String>, // Heure de la prochaine sonnerie
+    pub next_in: Option<String>,    // Temps restant ("7h 23m")
+    pub ringing: bool,
+}
+
+/// Contenu de la mini horloge a `now`
+pub fn view(now: DateTime<Local>, next: Option<&NextAlarm>, ringing: bool, time_format: TimeFormat) -> MiniClockView {
+    MiniClockView {
+        time: i18n::format_clock(now.time(), time_format, false),
+        next_alarm: next.map(|n| i18n::format_clock(n.trigger_at.time(), time_format, false)),
+        next_in: next.map(|n| n.label.clone()),
+        ringing,
+    }
+}
+
+/// Charge les reglages (par defaut si absents ou illisibles)
+pub fn load(data_dir: &Path) -> MiniClockSettings {
+    fs::read_to_string(data_dir.join(MINI_CLOCK_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Sauvegarde les reglages
+pub fn save(data_dir: &Path, settings: &MiniClockSettings) -> Result<(), String> {
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Impossible de creer le dossier: {}", e))?;
+    let json = serde_json::to_string_pretty(settings)
+        .map_err(|e| format!("Erreur serialisation: {}", e))?;
+    fs::write(data_dir.join(MINI_CLOCK_FILE), json)
+        .map_err(|e| format!("Erreur ecriture fichier: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_view() {
+        let now = Local.with_ymd_and_hms(2024, 3, 12, 23, 37, 12).unwrap();
+        let alarm = serde_json::from_value(serde_json::json!({
+            "id": "a1", "time": "07:00", "playlist_name": "Alarme", "playlist_uri": "local",
+            "volume": 50, "active": true, "days": [], "fade_in": false, "fade_in_duration": 0,
+        }))
+        .unwrap();
+        let next = NextAlarm {
+            alarm,
+            trigger_at: Local.with_ymd_and_hms(2024, 3, 13, 7, 0, 0).unwrap(),
+            seconds_until: 26_568,
+            label: "7h 22m".to_string(),
+        };
+
+        let shown = view(now, Some(&next), false, TimeFormat::H24);
+        assert_eq!(shown.time, "23:37");
+        assert_eq!(shown.next_alarm.as_deref(), Some("07:00"));
+        assert_eq!(shown.next_in.as_deref(), Some("7h 22m"));
+
+        let idle = view(now, None, false, TimeFormat::H12);
+        assert_eq!((idle.time.as_str(), idle.next_alarm), ("11:37 PM", None));
+
+        let settings: MiniClockSettings = serde_json::from_str("{}").unwrap();
+        assert!(settings.always_on_top && settings.placement.is_none());
+    }
+}
@@ -0,0 +1,122 @@
+// moods.rs - Humeurs attribuees aux playlists et recommandations selon l'heure
+// Les humeurs sont des metadonnees locales (par URI de playlist), jamais envoyees au service
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+const MOODS_FILE: &str = "playlist_moods.json";
+
+/// Nombre maximum d'humeurs par playlist
+pub const MAX_MOODS_PER_PLAYLIST: usize = 4;
+
+/// Humeur d'une playlist, choisie par l'utilisateur
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Mood {
+    Calm,
+    Gentle,
+    Upbeat,
+    Energetic,
+    Focus,
+    Chill,
+}
+
+/// Humeurs par URI de playlist
+pub type PlaylistMoods = BTreeMap<String, Vec<Mood>>;
+
+/// Playlist recommandee pour l'heure demandee
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Recommendation {
+    pub uri: String,
+    pub moods: Vec<Mood>,
+    pub score: u32,
+}
+
+/// Humeurs adaptees a l'heure, de la plus a la moins pertinente
+pub fn moods_for_hour(hour: u32) -> &'static [Mood] {
+    match hour {
+        4..=6 => &[Mood::Gentle, Mood::Calm, Mood::Upbeat],
+        7..=9 => &[Mood::Upbeat, Mood::Gentle, Mood::Energetic],
+        10..=13 => &[Mood::Energetic, Mood::Focus, Mood::Upbeat],
+        14..=17 => &[Mood::Focus, Mood::Chill, Mood::Energetic],
+        18..=21 => &[Mood::Chill, Mood::Calm, Mood::Upbeat],
+        _ => &[Mood::Calm, Mood::Chill, Mood::Gentle],
+    }
+}
+
+/// Playlists dont une humeur convient a l'heure, les plus pertinentes d'abord
+pub fn recommend(moods: &PlaylistMoods, hour: u32) -> Vec<Recommendation> {
+    let wanted = moods_for_hour(hour);
+    let mut result: Vec<Recommendation> = moods
+        .iter()
+        .filter_map(|(uri, tagged)| {
+            // L'humeur la plus pertinente pese le plus
+            let score: u32 = tagged
+                .iter()
+                .filter_map(|mood| wanted.iter().position(|w| w == mood))
+                .map(|rank| (wanted.len() - rank) as u32)
+                .sum();
+            (score > 0).then(|| Recommendation { uri: uri.clone(), moods: tagged.clone(), score })
+        })
+        .collect();
+    result.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.uri.cmp(&b.uri)));
+    result
+}
+
+/// Charge les humeurs des playlists (vide si absent ou illisible)
+pub fn load_moods(data_dir: &Path) -> PlaylistMoods {
+    fs::read_to_string(data_dir.join(MOODS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Remplace les humeurs d'une playlist (une liste vide les retire)
+pub fn set_moods(data_dir: &Path, uri: &str, mut moods: Vec<Mood>) -> Result<PlaylistMoods, String> {
+    let uri = uri.trim();
+    if uri.is_empty() {
+        return Err("URI de playlist vide".to_string());
+    }
+    moods.sort();
+    moods.dedup();
+    if moods.len() > MAX_MOODS_PER_PLAYLIST {
+        return Err(format!("Au plus {} humeurs par playlist", MAX_MOODS_PER_PLAYLIST));
+    }
+
+    let mut all = load_moods(data_dir);
+    if moods.is_empty() {
+        all.remove(uri);
+    } else {
+        all.insert(uri.to_string(), moods);
+    }
+
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Impossible de creer le dossier: {}", e))?;
+    let json = serde_json::to_string_pretty(&all)
+        .map_err(|e| format!("Erreur serialisation: {}", e))?;
+    fs::write(data_dir.join(MOODS_FILE), json)
+        .map_err(|e| format!("Erreur ecriture fichier: {}", e))?;
+    Ok(all)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recommend() {
+        let mut moods = PlaylistMoods::new();
+        moods.insert("spotify:playlist:douce".to_string(), vec![Mood::Gentle]);
+        moods.insert("spotify:playlist:matin".to_string(), vec![Mood::Upbeat, Mood::Gentle]);
+        moods.insert("spotify:playlist:soir".to_string(), vec![Mood::Chill]);
+
+        let picks = recommend(&moods, 7);
+        let uris: Vec<&str> = picks.iter().map(|r| r.uri.as_str()).collect();
+        assert_eq!(uris, ["spotify:playlist:matin", "spotify:playlist:douce"]);
+        assert_eq!(picks[0].score, 5);
+
+        assert_eq!(recommend(&moods, 23)[0].uri, "spotify:playlist:soir");
+    }
+}
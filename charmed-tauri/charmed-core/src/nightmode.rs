@@ -0,0 +1,244 @@
+// nightmode.rs - Mode nuit de l'ecran: luminosite baissee et couleurs chaudes pendant les heures de nuit
+// La luminosite d'origine est relue avant d'assombrir puis restauree au reveil.
+// macOS: outil `brightness`, Linux: xrandr (X11), Windows: WMI et rampe gamma via PowerShell.
+
+use std::process::Command;
+
+use chrono::NaiveTime;
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::parse_alarm_time;
+
+/// Temperature de couleur neutre de l'ecran (Kelvin)
+pub const NEUTRAL_TEMPERATURE: u32 = 6500;
+
+/// Temperature la plus chaude acceptee (Kelvin)
+pub const MIN_TEMPERATURE: u32 = 1000;
+
+/// Reglages du mode nuit
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NightModeSettings {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub start: String, // "HH:MM" debut de la nuit
+    pub end: String,   // "HH:MM" fin de la nuit (le lendemain si avant le debut)
+    #[serde(default = "default_brightness")]
+    pub brightness: u8, // Luminosite pendant la nuit (1-100)
+    #[serde(default = "default_color_temperature")]
+    pub color_temperature: u32, // Kelvin (6500 = neutre)
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_brightness() -> u8 {
+    20
+}
+
+fn default_color_temperature() -> u32 {
+    3400
+}
+
+impl NightModeSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        let (Some(start), Some(end)) = (parse_alarm_time(&self.start), parse_alarm_time(&self.end)) else {
+            return Err("Format d'heure invalide. Utilisez HH:MM".to_string());
+        };
+        if start == end {
+            return Err("La nuit doit avoir un debut et une fin differents".to_string());
+        }
+        if self.brightness == 0 || self.brightness > 100 {
+            return Err("La luminosite de nuit doit etre entre 1 et 100".to_string());
+        }
+        if !(MIN_TEMPERATURE..=NEUTRAL_TEMPERATURE).contains(&self.color_temperature) {
+            return Err(format!(
+                "La temperature de couleur doit etre entre {} et {} K",
+                MIN_TEMPERATURE, NEUTRAL_TEMPERATURE
+            ));
+        }
+        Ok(())
+    }
+
+    /// `time` tombe dans les heures de nuit (plage passant minuit comprise)
+    pub fn is_night(&self, time: NaiveTime) -> bool {
+        let (Some(start), Some(end)) = (parse_alarm_time(&self.start), parse_alarm_time(&self.end)) else {
+            return false;
+        };
+        if start < end {
+            time >= start && time < end
+        } else {
+            time >= start || time < end
+        }
+    }
+}
+
+/// Facteurs rouge, vert, bleu (0-1) d'une temperature de couleur
+/// (approximation du corps noir de Tanner Helland, 6500 K et plus = neutre)
+pub fn temperature_rgb(kelvin: u32) -> (f64, f64, f64) {
+    if kelvin >= NEUTRAL_TEMPERATURE {
+        return (1.0, 1.0, 1.0);
+    }
+    let t = f64::from(kelvin.max(MIN_TEMPERATURE)) / 100.0;
+    let channel = |value: f64| (value / 255.0).clamp(0.0, 1.0);
+    let red = 1.0;
+    let green = channel(99.470_802_586_1 * t.ln() - 161.119_568_166_1);
+    let blue = if t <= 19.0 { 0.0 } else { channel(138.517_731_223_1 * (t - 10.0).ln() - 305.044_792_730_7) };
+    (red, green, blue)
+}
+
+/// Execute une commande et retourne sa sortie standard
+fn run(command: &mut Command) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Reglage de l'ecran indisponible: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Reglage de l'ecran en echec: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Ecrans branches et luminosite (pourcentage) d'une sortie `xrandr --verbose`
+#[cfg_attr(any(target_os = "macos", target_os = "windows"), allow(dead_code))]
+fn parse_xrandr(text: &str) -> Vec<(String, Option<u8>)> {
+    let mut outputs: Vec<(String, Option<u8>)> = Vec::new();
+    for line in text.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            if let Some(name) = line.split_once(" connected").map(|(name, _)| name.trim()) {
+                outputs.push((name.to_string(), None));
+            }
+        } else if let (Some(value), Some(last)) = (line.trim().strip_prefix("Brightness:"), outputs.last_mut()) {
+            last.1 = value.trim().parse::<f64>().ok().map(|b| (b * 100.0).round().clamp(0.0, 100.0) as u8);
+        }
+    }
+    outputs
+}
+
+#[cfg(target_os = "macos")]
+pub fn get_brightness() -> Result<u8, String> {
+    let listing = run(Command::new("brightness").arg("-l"))?;
+    listing
+        .lines()
+        .find_map(|line| line.split_once("brightness ").and_then(|(_, v)| v.trim().parse::<f64>().ok()))
+        .map(|b| (b * 100.0).round().clamp(0.0, 100.0) as u8)
+        .ok_or_else(|| format!("Luminosite illisible: {}", listing))
+}
+
+/// Night Shift n'a pas d'interface en ligne de commande: seule la luminosite change
+#[cfg(target_os = "macos")]
+pub fn set_display(brightness: u8, _color_temperature: u32) -> Result<(), String> {
+    let level = format!("{:.2}", f64::from(brightness.min(100)) / 100.0);
+    run(Command::new("brightness").arg(&level)).map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+const GAMMA_SCRIPT: &str = r#"
+Add-Type -TypeDefinition @'
+using System;
+using System.Runtime.InteropServices;
+public static class Gamma {
+    [DllImport("user32.dll")] static extern IntPtr GetDC(IntPtr hwnd);
+    [DllImport("user32.dll")] static extern int ReleaseDC(IntPtr hwnd, IntPtr dc);
+    [DllImport("gdi32.dll")] static extern bool SetDeviceGammaRamp(IntPtr dc, ushort[] ramp);
+    public static void Set(double r, double g, double b) {
+        var ramp = new ushort[3 * 256];
+        for (int i = 0; i < 256; i++) {
+            ramp[i] = (ushort)Math.Min(65535, i * 257 * r);
+            ramp[256 + i] = (ushort)Math.Min(65535, i * 257 * g);
+            ramp[512 + i] = (ushort)Math.Min(65535, i * 257 * b);
+        }
+        var dc = GetDC(IntPtr.Zero);
+        try { if (!SetDeviceGammaRamp(dc, ramp)) throw new InvalidOperationException("SetDeviceGammaRamp"); }
+        finally { ReleaseDC(IntPtr.Zero, dc); }
+    }
+}
+'@
+"#;
+
+#[cfg(target_os = "windows")]
+fn powershell(script: &str) -> Result<String, String> {
+    run(Command::new("powershell").args(["-NoProfile", "-NonInteractive", "-Command", script]))
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_brightness() -> Result<u8, String> {
+    let level = powershell(
+        "(Get-CimInstance -Namespace root/WMI -ClassName WmiMonitorBrightness | Select-Object -First 1).CurrentBrightness",
+    )?;
+    level
+        .trim()
+        .parse::<u8>()
+        .map(|l| l.min(100))
+        .map_err(|_| format!("Luminosite illisible: {}", level))
+}
+
+/// Luminosite par WMI (ecrans integres), teinte par la rampe gamma (tous les ecrans)
+#[cfg(target_os = "windows")]
+pub fn set_display(brightness: u8, color_temperature: u32) -> Result<(), String> {
+    let (red, green, blue) = temperature_rgb(color_temperature);
+    let body = format!(
+        "Get-CimInstance -Namespace root/WMI -ClassName WmiMonitorBrightnessMethods | \
+         Invoke-CimMethod -MethodName WmiSetBrightness -Arguments @{{Timeout=0; Brightness={}}} | Out-Null; \
+         [Gamma]::Set({}, {}, {})",
+        brightness.min(100),
+        red,
+        green,
+        blue
+    );
+    powershell(&format!("{}{}", GAMMA_SCRIPT, body)).map(|_| ())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn get_brightness() -> Result<u8, String> {
+    let verbose = run(Command::new("xrandr").arg("--verbose"))?;
+    parse_xrandr(&verbose)
+        .into_iter()
+        .find_map(|(_, brightness)| brightness)
+        .ok_or_else(|| "Aucun ecran detecte par xrandr".to_string())
+}
+
+/// Luminosite et teinte logicielles (gamma) appliquees a chaque ecran branche
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn set_display(brightness: u8, color_temperature: u32) -> Result<(), String> {
+    let outputs = parse_xrandr(&run(Command::new("xrandr").arg("--verbose"))?);
+    if outputs.is_empty() {
+        return Err("Aucun ecran detecte par xrandr".to_string());
+    }
+    let (red, green, blue) = temperature_rgb(color_temperature);
+    // xrandr applique un gamma inverse: 1/facteur, borne pour eviter une division par zero
+    let gamma = format!("{:.3}:{:.3}:{:.3}", 1.0 / red.max(0.1), 1.0 / green.max(0.1), 1.0 / blue.max(0.1));
+    let level = format!("{:.2}", f64::from(brightness.min(100)) / 100.0);
+    for (output, _) in outputs {
+        run(Command::new("xrandr").args(["--output", &output, "--brightness", &level, "--gamma", &gamma]))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_night_hours() {
+        let settings: NightModeSettings =
+            serde_json::from_str(r#"{"start":"22:30","end":"06:45"}"#).unwrap();
+        assert!(settings.enabled && settings.validate().is_ok());
+        let at = |h, m| NaiveTime::from_hms_opt(h, m, 0).unwrap();
+        assert!(settings.is_night(at(23, 0)) && settings.is_night(at(3, 0)));
+        assert!(!settings.is_night(at(6, 45)) && !settings.is_night(at(12, 0)));
+
+        let nap = NightModeSettings { start: "13:00".to_string(), end: "14:00".to_string(), ..settings.clone() };
+        assert!(nap.is_night(at(13, 30)) && !nap.is_night(at(23, 0)));
+        assert!(NightModeSettings { brightness: 0, ..settings }.validate().is_err());
+
+        assert_eq!(temperature_rgb(NEUTRAL_TEMPERATURE), (1.0, 1.0, 1.0));
+        let (red, green, blue) = temperature_rgb(3400);
+        assert!(red == 1.0 && green < 1.0 && blue < green);
+
+        let xrandr = "Screen 0: minimum 8 x 8\nHDMI-1 connected primary 1920x1080+0+0\n\tBrightness: 0.80\nDP-1 disconnected\n";
+        assert_eq!(parse_xrandr(xrandr), vec![("HDMI-1".to_string(), Some(80))]);
+    }
+}
@@ -0,0 +1,302 @@
+// podcast.rs - Podcasts (flux RSS) comme musique d'alarme, hors Spotify
+// Au moment de sonner, le dernier episode du flux est telecharge dans le cache du
+// profil (une seule fois par episode) puis joue par le moteur audio local
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+use crate::audio;
+use crate::provider::{MusicProvider, ProviderPlaylist};
+
+/// Dossier du cache des episodes, dans le dossier de donnees du profil
+const CACHE_DIR: &str = "podcasts";
+
+/// Delai maximal de lecture d'un flux RSS
+const FEED_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Silence du serveur au-dela duquel le telechargement d'un episode echoue
+const DOWNLOAD_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Flux RSS suivi
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PodcastFeed {
+    pub name: String,
+    pub url: String,
+}
+
+impl PodcastFeed {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Nom de podcast vide".to_string());
+        }
+        match reqwest::Url::parse(&self.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+            _ => Err(format!("Adresse de flux RSS invalide: {}", self.url)),
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        format!("{}{}", PodcastPlayer::URI_PREFIX, self.url)
+    }
+}
+
+/// Episode d'un flux
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Episode {
+    pub title: String,
+    pub audio_url: String,
+    pub published: Option<DateTime<FixedOffset>>,
+}
+
+/// Remplace les entites XML courantes
+fn decode_entities(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Contenus des elements `<tag>...</tag>` (les elements homonymes prefixes, comme itunes:title, sont ignores)
+fn elements<'a>(xml: &'a str, tag: &str) -> Vec<&'a str> {
+    let open = format!("<{}", tag);
+    let close = format!("</{}>", tag);
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        // <item> ou <item attr=...>, mais pas <itemized>
+        if !after.starts_with(|c: char| c == '>' || c.is_whitespace()) {
+            rest = after;
+            continue;
+        }
+        let Some(body_start) = after.find('>') else {
+            break;
+        };
+        let body = &after[body_start + 1..];
+        let Some(end) = body.find(&close) else {
+            break;
+        };
+        found.push(&body[..end]);
+        rest = &body[end + close.len()..];
+    }
+    found
+}
+
+/// Texte du premier element `<tag>` (CDATA et entites decodes)
+fn element_text(xml: &str, tag: &str) -> Option<String> {
+    let content = elements(xml, tag).into_iter().next()?.trim();
+    let text = match content.strip_prefix("<![CDATA[").and_then(|c| c.strip_suffix("]]>")) {
+        Some(cdata) => cdata.to_string(),
+        None => decode_entities(content),
+    };
+    Some(text.trim().to_string())
+}
+
+/// Valeur d'un attribut dans la balise ouvrante qui commence `xml`
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    for quote in ['"', '\''] {
+        let pattern = format!(" {}={}", name, quote);
+        if let Some(start) = tag.find(&pattern) {
+            let value = &tag[start + pattern.len()..];
+            let end = value.find(quote)?;
+            return Some(decode_entities(&value[..end]));
+        }
+    }
+    None
+}
+
+/// Adresse audio de l'episode (balise enclosure)
+fn enclosure_url(item: &str) -> Option<String> {
+    let start = item.find("<enclosure")?;
+    let tag = &item[start..start + item[start..].find('>')?];
+    attribute(tag, "url")
+}
+
+/// Episodes d'un flux RSS, dans l'ordre du flux (ceux sans fichier audio sont ignores)
+pub fn parse_feed(xml: &str) -> Result<Vec<Episode>, String> {
+    if elements(xml, "channel").is_empty() {
+        return Err("Flux RSS invalide (channel absent)".to_string());
+    }
+    Ok(elements(xml, "item")
+        .into_iter()
+        .filter_map(|item| {
+            Some(Episode {
+                title: element_text(item, "title").unwrap_or_default(),
+                audio_url: enclosure_url(item)?,
+                published: element_text(item, "pubDate")
+                    .and_then(|date| DateTime::parse_from_rfc2822(&date).ok()),
+            })
+        })
+        .collect())
+}
+
+/// Episode le plus recent (date de publication, sinon le premier du flux)
+pub fn latest_episode(episodes: Vec<Episode>) -> Option<Episode> {
+    let first = episodes.first().cloned();
+    episodes.into_iter().filter(|e| e.published.is_some()).max_by_key(|e| e.published).or(first)
+}
+
+/// Adresse du flux RSS d'une URI de podcast
+pub fn feed_url(uri: &str) -> Option<&str> {
+    uri.strip_prefix(PodcastPlayer::URI_PREFIX).filter(|url| !url.is_empty())
+}
+
+/// Fichier du cache d'un episode: nom derive de son adresse, extension conservee
+fn cache_file_name(audio_url: &str) -> String {
+    let hash = Sha256::digest(audio_url.as_bytes());
+    let id: String = hash.iter().take(16).map(|b| format!("{:02x}", b)).collect();
+    let extension = reqwest::Url::parse(audio_url)
+        .ok()
+        .and_then(|url| Path::new(url.path()).extension().and_then(|e| e.to_str()).map(str::to_lowercase))
+        .filter(|e| matches!(e.as_str(), "mp3" | "ogg" | "flac" | "wav"))
+        .unwrap_or_else(|| "mp3".to_string());
+    format!("{}.{}", id, extension)
+}
+
+/// Lecteur des podcasts suivis
+#[derive(Debug, Clone)]
+pub struct PodcastPlayer {
+    feeds: Vec<PodcastFeed>,
+    cache_dir: PathBuf,
+    http: reqwest::Client,
+}
+
+impl PodcastPlayer {
+    pub fn new(feeds: Vec<PodcastFeed>, data_dir: &Path) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .connect_timeout(FEED_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { feeds, cache_dir: data_dir.join(CACHE_DIR), http })
+    }
+
+    /// Dernier episode d'un flux
+    pub async fn latest(&self, feed_url: &str) -> Result<Episode, String> {
+        let xml = self.http
+            .get(feed_url)
+            .timeout(FEED_TIMEOUT)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Flux RSS injoignable: {}", e))?
+            .text()
+            .await
+            .map_err(|e| format!("Flux RSS injoignable: {}", e))?;
+        latest_episode(parse_feed(&xml)?).ok_or_else(|| format!("Aucun episode dans {}", feed_url))
+    }
+
+    /// Telecharge l'episode dans le cache (sauf s'il y est deja) et retourne son fichier
+    async fn download(&self, episode: &Episode) -> Result<PathBuf, String> {
+        let path = self.cache_dir.join(cache_file_name(&episode.audio_url));
+        if path.is_file() {
+            return Ok(path);
+        }
+        tokio::fs::create_dir_all(&self.cache_dir).await.map_err(|e| e.to_string())?;
+
+        let mut response = self.http
+            .get(&episode.audio_url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Episode injoignable: {}", e))?;
+        // Fichier partiel renomme une fois complet: jamais d'episode tronque dans le cache
+        let partial = path.with_extension("part");
+        let mut file = tokio::fs::File::create(&partial).await.map_err(|e| e.to_string())?;
+        loop {
+            let chunk = tokio::time::timeout(DOWNLOAD_STALL_TIMEOUT, response.chunk())
+                .await
+                .map_err(|_| "Telechargement de l'episode bloque".to_string())?
+                .map_err(|e| format!("Telechargement de l'episode interrompu: {}", e))?;
+            match chunk {
+                Some(chunk) => file.write_all(&chunk).await.map_err(|e| e.to_string())?,
+                None => break,
+            }
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+        tokio::fs::rename(&partial, &path).await.map_err(|e| e.to_string())?;
+        tracing::info!(title = %episode.title, "Episode de podcast telecharge");
+        Ok(path)
+    }
+}
+
+impl MusicProvider for PodcastPlayer {
+    const URI_PREFIX: &'static str = "podcast:";
+
+    async fn playlists(&self) -> Result<Vec<ProviderPlaylist>, String> {
+        Ok(self
+            .feeds
+            .iter()
+            .map(|feed| ProviderPlaylist {
+                uri: feed.uri(),
+                name: feed.name.clone(),
+                image_url: None,
+                track_count: None,
+            })
+            .collect())
+    }
+
+    /// Joue le dernier episode du flux, resolu au moment de sonner
+    async fn play(&self, playlist_uri: &str, _first_track: Option<&str>) -> Result<(), String> {
+        let url = feed_url(playlist_uri).ok_or_else(|| format!("Podcast invalide: {}", playlist_uri))?;
+        let episode = self.latest(url).await?;
+        let path = self.download(&episode).await?;
+        tracing::info!(title = %episode.title, "Lecture du dernier episode");
+        tokio::task::spawn_blocking(move || audio::play_files(&[path], false))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    async fn set_volume(&self, volume: u8) -> Result<(), String> {
+        audio::set_alarm_volume(volume)
+    }
+
+    async fn pause(&self) -> Result<(), String> {
+        audio::stop_alarm_sound()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_feed() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:itunes="http://www.itunes.com/dtds/podcast-1.0.dtd">
+  <channel>
+    <title>Le Journal</title>
+    <item>
+      <title>Episode 1</title>
+      <itunes:title>Ignore</itunes:title>
+      <pubDate>Mon, 08 Jan 2024 06:00:00 +0100</pubDate>
+      <enclosure url="https://cdn.example.com/ep1.mp3?a=1&amp;b=2" length="1" type="audio/mpeg"/>
+    </item>
+    <item>
+      <title><![CDATA[Episode 2 & bonus]]></title>
+      <pubDate>Tue, 09 Jan 2024 06:00:00 +0100</pubDate>
+      <enclosure type="audio/mpeg" url='https://cdn.example.com/ep2.MP3' />
+    </item>
+    <item><title>Annonce sans audio</title></item>
+  </channel>
+</rss>"#;
+        let episodes = parse_feed(xml).unwrap();
+        assert_eq!(episodes.len(), 2);
+        assert_eq!(episodes[0].title, "Episode 1");
+        assert_eq!(episodes[0].audio_url, "https://cdn.example.com/ep1.mp3?a=1&b=2");
+
+        // Le plus recent, meme s'il n'est pas en tete du flux
+        let latest = latest_episode(episodes).unwrap();
+        assert_eq!(latest.title, "Episode 2 & bonus");
+        assert!(cache_file_name(&latest.audio_url).ends_with(".mp3"));
+
+        assert!(parse_feed("<html></html>").is_err());
+        assert_eq!(feed_url("podcast:https://example.com/rss"), Some("https://example.com/rss"));
+        assert!(PodcastFeed { name: "X".into(), url: "ftp://example.com/rss".into() }.validate().is_err());
+    }
+}
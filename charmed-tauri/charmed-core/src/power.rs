@@ -0,0 +1,273 @@
+// power.rs - Alimentation de l'ordinateur (portables): batterie et mise en veille automatique
+// Un portable sur batterie qui se met en veille tout seul dormira pendant l'alarme.
+// macOS: pmset, Linux: /sys/class/power_supply et gsettings (GNOME), Windows: WMI et powercfg.
+// Reveil materiel programme avant l'alarme: rtcwake (Linux), pmset schedule (macOS),
+// tache planifiee "WakeToRun" (Windows). Ces commandes demandent souvent les droits administrateur.
+
+use std::process::Command;
+
+use chrono::{DateTime, Local, Timelike};
+use serde::Serialize;
+
+use crate::i18n::{self, Locale, TimeFormat};
+
+/// Heure a partir de laquelle l'alimentation est surveillee le soir
+pub const EVENING_HOUR: u32 = 20;
+
+/// Seules les alarmes des prochaines heures justifient un avertissement
+pub const WARNING_HORIZON_HOURS: i64 = 16;
+
+/// Avance du reveil materiel sur l'alarme (demarrage, reseau, connexion Spotify)
+pub const WAKE_LEAD_SECONDS: i64 = 120;
+
+/// Nom de la tache planifiee de reveil (Windows)
+#[cfg_attr(not(target_os = "windows"), allow(dead_code))]
+const WAKE_TASK: &str = "CharmedWake";
+
+/// Etat de l'alimentation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct PowerStatus {
+    pub has_battery: bool,
+    pub on_battery: bool, // Debranche du secteur
+    pub battery_percent: Option<u8>,
+    pub sleep_on_idle: Option<bool>, // Veille automatique sur batterie (None = inconnue)
+}
+
+impl PowerStatus {
+    /// La machine risque de s'endormir: sur batterie et veille active (ou inconnue)
+    pub fn at_risk(&self) -> bool {
+        self.on_battery && self.sleep_on_idle != Some(false)
+    }
+}
+
+/// L'alimentation n'est surveillee que le soir (jusqu'a minuit)
+pub fn is_evening(now: DateTime<Local>) -> bool {
+    now.hour() >= EVENING_HOUR
+}
+
+/// Avertir ce soir pour l'alarme de `alarm_at`
+pub fn should_warn(status: &PowerStatus, now: DateTime<Local>, alarm_at: DateTime<Local>) -> bool {
+    is_evening(now)
+        && status.at_risk()
+        && alarm_at > now
+        && alarm_at - now <= chrono::Duration::hours(WARNING_HORIZON_HOURS)
+}
+
+/// Titre et texte de la notification d'avertissement
+pub fn warning_text(
+    locale: Locale,
+    time_format: TimeFormat,
+    status: &PowerStatus,
+    alarm_at: DateTime<Local>,
+) -> (String, String) {
+    let at = i18n::format_clock(alarm_at.time(), time_format, false);
+    let level = status.battery_percent.map(|p| format!(" ({}%)", p)).unwrap_or_default();
+    match locale {
+        Locale::Fr => (
+            "Ordinateur sur batterie".to_string(),
+            format!(
+                "Branchez le chargeur{}: la mise en veille automatique risque de faire manquer l'alarme de {}",
+                level, at
+            ),
+        ),
+        Locale::En => (
+            "Running on battery".to_string(),
+            format!("Plug in the charger{}: sleeping on idle could make you miss your {} alarm", level, at),
+        ),
+    }
+}
+
+/// Heure de sortie de veille pour une alarme a `alarm_at`
+pub fn wake_time(alarm_at: DateTime<Local>) -> DateTime<Local> {
+    alarm_at - chrono::Duration::seconds(WAKE_LEAD_SECONDS)
+}
+
+/// Date au format attendu par `pmset schedule`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn pmset_date(at: DateTime<Local>) -> String {
+    at.format("%m/%d/%Y %H:%M:%S").to_string()
+}
+
+/// Execute une commande et retourne sa sortie standard
+fn run(command: &mut Command) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Etat de l'alimentation indisponible: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Etat de l'alimentation en echec: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Delai de veille de la section "Battery Power" d'une sortie `pmset -g custom` (0 = jamais)
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_pmset_sleep(text: &str) -> Option<bool> {
+    text.lines()
+        .skip_while(|line| !line.starts_with("Battery Power"))
+        .skip(1)
+        .take_while(|line| line.starts_with(char::is_whitespace))
+        .find_map(|line| {
+            let mut words = line.split_whitespace();
+            (words.next() == Some("sleep")).then(|| words.next()?.parse::<u32>().ok()).flatten()
+        })
+        .map(|minutes| minutes > 0)
+}
+
+#[cfg(target_os = "macos")]
+pub fn read_status() -> Result<PowerStatus, String> {
+    let batt = run(Command::new("pmset").args(["-g", "batt"]))?;
+    let battery_percent = batt
+        .split_whitespace()
+        .find_map(|word| word.trim_end_matches(';').strip_suffix('%'))
+        .and_then(|value| value.parse::<u8>().ok());
+    let sleep_on_idle = run(Command::new("pmset").args(["-g", "custom"]))
+        .ok()
+        .and_then(|custom| parse_pmset_sleep(&custom));
+    Ok(PowerStatus {
+        has_battery: batt.contains("InternalBattery"),
+        on_battery: batt.contains("'Battery Power'"),
+        battery_percent,
+        sleep_on_idle,
+    })
+}
+
+#[cfg(target_os = "windows")]
+pub fn read_status() -> Result<PowerStatus, String> {
+    let battery = run(Command::new("powershell").args([
+        "-NoProfile",
+        "-NonInteractive",
+        "-Command",
+        "$b = Get-CimInstance Win32_Battery | Select-Object -First 1; if ($b) { \"$($b.BatteryStatus);$($b.EstimatedChargeRemaining)\" }",
+    ]))?;
+    let (status, percent) = battery.split_once(';').unwrap_or(("", ""));
+    // Libelles de powercfg traduits selon la langue: l'index sur batterie (DC) est la derniere valeur
+    let sleep_on_idle = run(Command::new("powercfg").args(["/query", "SCHEME_CURRENT", "SUB_SLEEP", "STANDBYIDLE"]))
+        .ok()
+        .and_then(|query| {
+            query
+                .lines()
+                .filter_map(|line| line.rsplit_once("0x").and_then(|(_, hex)| u32::from_str_radix(hex.trim(), 16).ok()))
+                .last()
+        })
+        .map(|seconds| seconds > 0);
+    Ok(PowerStatus {
+        has_battery: !battery.is_empty(),
+        on_battery: status.trim() == "1", // BatteryStatus 1 = en decharge
+        battery_percent: percent.trim().parse::<u8>().ok().map(|p| p.min(100)),
+        sleep_on_idle,
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn read_status() -> Result<PowerStatus, String> {
+    use std::fs;
+
+    let supplies = fs::read_dir("/sys/class/power_supply")
+        .map_err(|e| format!("Etat de l'alimentation indisponible: {}", e))?;
+    let mut status = PowerStatus::default();
+    for supply in supplies.flatten() {
+        let read = |name: &str| fs::read_to_string(supply.path().join(name)).map(|v| v.trim().to_string()).ok();
+        if read("type").as_deref() == Some("Battery") {
+            status.has_battery = true;
+            status.on_battery |= read("status").as_deref() == Some("Discharging");
+            status.battery_percent = status.battery_percent.or(read("capacity").and_then(|c| c.parse().ok()));
+        }
+    }
+    // Reglage de GNOME ('suspend', 'hibernate' ou 'nothing'); inconnu ailleurs
+    status.sleep_on_idle = run(Command::new("gsettings").args([
+        "get",
+        "org.gnome.settings-daemon.plugins.power",
+        "sleep-inactive-battery-type",
+    ]))
+    .ok()
+    .map(|kind| kind != "'nothing'");
+    Ok(status)
+}
+
+/// Programme l'horloge materielle pour sortir l'ordinateur de veille ou d'hibernation a `at`.
+/// Remplace le reveil precedent (`previous`).
+#[cfg(target_os = "macos")]
+pub fn schedule_wake(at: DateTime<Local>, previous: Option<DateTime<Local>>) -> Result<(), String> {
+    // Un reveil passe ou retire a la main n'est plus annulable: erreur sans consequence
+    if let Some(previous) = previous {
+        let _ = cancel_wake(previous);
+    }
+    run(Command::new("pmset").args(["schedule", "wake", &pmset_date(at)])).map(|_| ())
+}
+
+/// Annule le reveil materiel programme a `at`
+#[cfg(target_os = "macos")]
+pub fn cancel_wake(at: DateTime<Local>) -> Result<(), String> {
+    run(Command::new("pmset").args(["schedule", "cancel", "wake", &pmset_date(at)])).map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+pub fn schedule_wake(at: DateTime<Local>, _previous: Option<DateTime<Local>>) -> Result<(), String> {
+    // -Force remplace la tache existante: un seul reveil programme a la fois
+    let script = format!(
+        "Register-ScheduledTask -TaskName '{}' -Force \
+         -Trigger (New-ScheduledTaskTrigger -Once -At '{}') \
+         -Settings (New-ScheduledTaskSettingsSet -WakeToRun) \
+         -Action (New-ScheduledTaskAction -Execute 'cmd.exe' -Argument '/c exit') | Out-Null",
+        WAKE_TASK,
+        at.format("%Y-%m-%dT%H:%M:%S")
+    );
+    run(Command::new("powershell").args(["-NoProfile", "-NonInteractive", "-Command", &script])).map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+pub fn cancel_wake(_at: DateTime<Local>) -> Result<(), String> {
+    let script = format!(
+        "Unregister-ScheduledTask -TaskName '{}' -Confirm:$false -ErrorAction SilentlyContinue",
+        WAKE_TASK
+    );
+    run(Command::new("powershell").args(["-NoProfile", "-NonInteractive", "-Command", &script])).map(|_| ())
+}
+
+/// `rtcwake -m no` programme l'horloge sans mettre la machine en veille
+/// (l'alarme RTC est unique: la nouvelle remplace la precedente)
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn schedule_wake(at: DateTime<Local>, _previous: Option<DateTime<Local>>) -> Result<(), String> {
+    let timestamp = at.timestamp().to_string();
+    run(Command::new("rtcwake").args(["-m", "no", "-t", &timestamp])).map(|_| ())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn cancel_wake(_at: DateTime<Local>) -> Result<(), String> {
+    run(Command::new("rtcwake").args(["-m", "disable"])).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_power_warning() {
+        let status = PowerStatus { has_battery: true, on_battery: true, battery_percent: Some(42), sleep_on_idle: None };
+        let evening = Local.with_ymd_and_hms(2024, 3, 12, 22, 0, 0).unwrap();
+        let alarm_at = Local.with_ymd_and_hms(2024, 3, 13, 7, 0, 0).unwrap();
+        assert!(should_warn(&status, evening, alarm_at));
+        assert!(!should_warn(&status, Local.with_ymd_and_hms(2024, 3, 12, 15, 0, 0).unwrap(), alarm_at));
+        assert!(!should_warn(&PowerStatus { sleep_on_idle: Some(false), ..status }, evening, alarm_at));
+        assert!(!should_warn(&PowerStatus { on_battery: false, ..status }, evening, alarm_at));
+
+        let (_, body) = warning_text(Locale::En, TimeFormat::H24, &status, alarm_at);
+        assert_eq!(body, "Plug in the charger (42%): sleeping on idle could make you miss your 07:00 alarm");
+
+        let pmset = "Battery Power:\n displaysleep         2\n sleep                1\nAC Power:\n sleep                0\n";
+        assert_eq!(parse_pmset_sleep(pmset), Some(true));
+        assert_eq!(parse_pmset_sleep("AC Power:\n sleep 0\n"), None);
+    }
+
+    #[test]
+    fn test_wake_time() {
+        let alarm_at = Local.with_ymd_and_hms(2024, 3, 13, 7, 0, 0).unwrap();
+        let wake = wake_time(alarm_at);
+        assert_eq!(wake, Local.with_ymd_and_hms(2024, 3, 13, 6, 58, 0).unwrap());
+        assert_eq!(pmset_date(wake), "03/13/2024 06:58:00");
+    }
+}
@@ -0,0 +1,151 @@
+// preview.rs - Apercu des sonneries a venir (vue semaine)
+// Chaque alarme active est developpee en instants concrets; les occurrences annulees
+// restent visibles avec leur raison pour reperer une erreur de reglage
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::Serialize;
+
+use crate::alarm::{self, NapTimer};
+use crate::calendar::{self, CalendarEvent};
+use crate::holidays;
+use crate::storage::AppConfig;
+use crate::AlarmEntry;
+
+/// Nombre maximum de jours couverts par l'apercu
+pub const MAX_PREVIEW_DAYS: u32 = 31;
+
+/// Raison pour laquelle une occurrence prevue ne sonnera pas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    SkippedOnce, // "Sauter la prochaine"
+    Vacation,
+    Holiday, // Alarme de semaine un jour ferie
+}
+
+/// Sonnerie prevue
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScheduledRing {
+    pub alarm_id: String,
+    pub playlist_name: String, // Playlist du jour (profil du week-end compris)
+    pub at: DateTime<Local>,
+    pub one_shot: bool, // Sieste ou report: ne sonne qu'une fois
+    pub skipped: Option<SkipReason>,
+}
+
+/// Raison d'annulation de l'occurrence du jour `date`, dans l'ordre de `alarm::is_skipped_on`
+fn skip_reason(alarm: &AlarmEntry, date: NaiveDate, config: &AppConfig) -> Option<SkipReason> {
+    if alarm.skip_date == Some(date) {
+        return Some(SkipReason::SkippedOnce);
+    }
+    if config.vacation_until.is_some_and(|until| date <= until) {
+        return Some(SkipReason::Vacation);
+    }
+    let days = match &alarm.recurrence {
+        Some(rule) => rule.weekdays(),
+        None => alarm.days.clone(),
+    };
+    (alarm::is_workday_alarm(&days) && holidays::is_holiday(date, config.holiday_country.as_deref(), &config.custom_holidays))
+        .then_some(SkipReason::Holiday)
+}
+
+/// Sonneries des `days` prochains jours (siestes comprises), dans l'ordre chronologique
+pub fn preview(
+    alarms: &[AlarmEntry],
+    naps: &[NapTimer],
+    events: &[CalendarEvent],
+    now: DateTime<Local>,
+    days: u32,
+    config: &AppConfig,
+) -> Vec<ScheduledRing> {
+    let days = days.min(MAX_PREVIEW_DAYS);
+    let until = now + chrono::Duration::days(i64::from(days));
+    let mut rings: Vec<ScheduledRing> = naps
+        .iter()
+        .filter(|nap| nap.fires_at > now && nap.fires_at <= until)
+        .map(|nap| ScheduledRing {
+            alarm_id: nap.alarm.id.clone(),
+            playlist_name: nap.alarm.playlist_name.clone(),
+            at: nap.fires_at,
+            one_shot: true,
+            skipped: None,
+        })
+        .collect();
+
+    for entry in alarms.iter().filter(|a| alarm::is_enabled(a)) {
+        let first = alarm::wall_clock(entry, now).date();
+        // Un jour de plus: le fuseau de l'alarme peut etre en avance sur l'heure locale
+        for date in (0..=u64::from(days) + 1).filter_map(|offset| first.checked_add_days(chrono::Days::new(offset))) {
+            if !alarm::is_scheduled_on(entry, date) {
+                continue;
+            }
+            // Heure recalculee depuis l'agenda; aucune reunion = pas de sonnerie ce jour-la
+            let Some(mut effective) = calendar::effective_alarm(entry, events, date) else {
+                continue;
+            };
+            alarm::apply_day_profile(&mut effective, date);
+            let skipped = skip_reason(entry, date, config);
+            for time in alarm::daily_times(&effective) {
+                let Some(at) = alarm::occurrence_instant(entry, date.and_time(time)).map(|at| at.with_timezone(&Local)) else {
+                    continue;
+                };
+                if at > now && at <= until {
+                    rings.push(ScheduledRing {
+                        alarm_id: entry.id.clone(),
+                        playlist_name: effective.playlist_name.clone(),
+                        at,
+                        one_shot: false,
+                        skipped,
+                    });
+                }
+            }
+        }
+    }
+
+    rings.sort_by(|a, b| a.at.cmp(&b.at).then_with(|| a.alarm_id.cmp(&b.alarm_id)));
+    rings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::alarm::test_support;
+
+    fn alarm(id: &str, time: &str, days: &[&str]) -> AlarmEntry {
+        AlarmEntry { days: days.iter().map(|d| d.to_string()).collect(), ..test_support::alarm(id, time) }
+    }
+
+    #[test]
+    fn test_preview() {
+        // Lundi 11 mars 2024, 12:00
+        let now = Local.with_ymd_and_hms(2024, 3, 11, 12, 0, 0).unwrap();
+        let mut work = alarm("work", "07:00", &["Monday", "Tuesday", "Wednesday", "Thursday", "Friday"]);
+        work.skip_date = NaiveDate::from_ymd_opt(2024, 3, 12);
+        let mut off = alarm("off", "09:00", &[]);
+        off.active = false;
+        let config = AppConfig {
+            custom_holidays: vec![NaiveDate::from_ymd_opt(2024, 3, 14).unwrap()],
+            ..Default::default()
+        };
+        let nap = NapTimer::new(now, 30, "Sieste".to_string(), "local".to_string(), 50);
+
+        let rings = preview(&[work, off], &[nap], &[], now, 7, &config);
+        let summary: Vec<(u32, Option<SkipReason>, bool)> = rings
+            .iter()
+            .map(|r| (chrono::Datelike::day(&r.at), r.skipped, r.one_shot))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (11, None, true), // Sieste de 12:30
+                (12, Some(SkipReason::SkippedOnce), false),
+                (13, None, false),
+                (14, Some(SkipReason::Holiday), false),
+                (15, None, false),
+                (18, None, false), // Lundi suivant, 07:00 < 12:00 + 7 jours
+            ]
+        );
+    }
+}
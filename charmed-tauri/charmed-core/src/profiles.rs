@@ -0,0 +1,102 @@
+// profiles.rs - Profils utilisateur pour partager la machine a plusieurs
+// Chaque profil a ses alarmes, sa configuration et ses comptes Spotify (profiles/{nom}/);
+// le profil par defaut reste a la racine du dossier de donnees
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+
+const PROFILES_DIR: &str = "profiles";
+const ACTIVE_PROFILE_FILE: &str = "active_profile.json";
+
+/// Nom du profil stocke a la racine
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Longueur maximale d'un nom de profil
+const MAX_NAME_LEN: usize = 40;
+
+/// Profil actif memorise entre deux lancements
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ActiveProfile {
+    name: Option<String>, // None = profil par defaut
+}
+
+/// Valide un nom de profil (utilise comme nom de dossier).
+/// Retourne None pour le profil par defaut.
+pub fn normalize_name(name: &str) -> Result<Option<String>, String> {
+    let name = name.trim();
+    if name.is_empty() || name.eq_ignore_ascii_case(DEFAULT_PROFILE) {
+        return Ok(None);
+    }
+    let valid = name.len() <= MAX_NAME_LEN
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == ' ');
+    if !valid {
+        return Err(format!(
+            "Nom de profil invalide: {} (lettres, chiffres, espaces, - et _, {} caracteres au plus)",
+            name, MAX_NAME_LEN
+        ));
+    }
+    Ok(Some(name.to_string()))
+}
+
+/// Dossier de donnees d'un profil (la racine pour le profil par defaut)
+pub fn profile_dir(root: &Path, name: Option<&str>) -> PathBuf {
+    match name {
+        Some(name) => root.join(PROFILES_DIR).join(name),
+        None => root.to_path_buf(),
+    }
+}
+
+/// Liste les profils existants, profil par defaut en tete
+pub fn list_profiles(root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(root.join(PROFILES_DIR))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().into_string().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.insert(0, DEFAULT_PROFILE.to_string());
+    names
+}
+
+/// Profil actif au dernier lancement (None = profil par defaut)
+pub fn load_active(root: &Path) -> Option<String> {
+    fs::read_to_string(root.join(ACTIVE_PROFILE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str::<ActiveProfile>(&content).ok())
+        .and_then(|active| active.name)
+        .and_then(|name| normalize_name(&name).ok().flatten())
+}
+
+/// Memorise le profil actif et cree son dossier
+pub fn save_active(root: &Path, name: Option<&str>) -> Result<(), String> {
+    fs::create_dir_all(profile_dir(root, name))
+        .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+
+    let json = serde_json::to_string_pretty(&ActiveProfile { name: name.map(str::to_string) })
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+    fs::write(root.join(ACTIVE_PROFILE_FILE), json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_names_and_dirs() {
+        assert_eq!(normalize_name(" Alice ").unwrap(), Some("Alice".to_string()));
+        assert_eq!(normalize_name("Default").unwrap(), None);
+        assert_eq!(normalize_name("").unwrap(), None);
+        assert!(normalize_name("../bob").is_err());
+        assert!(normalize_name("a/b").is_err());
+
+        let root = Path::new("/data");
+        assert_eq!(profile_dir(root, None), PathBuf::from("/data"));
+        assert_eq!(profile_dir(root, Some("Alice")), PathBuf::from("/data/profiles/Alice"));
+    }
+}
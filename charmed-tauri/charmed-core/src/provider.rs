@@ -0,0 +1,68 @@
+// provider.rs - Services de musique qui jouent les alarmes (Spotify, Apple Music, YouTube Music)
+// Chaque service reconnait ses URI a leur prefixe; "local" designe le son integre
+
+use std::future::Future;
+use serde::{Deserialize, Serialize};
+
+use crate::spotify::SpotifyClient;
+
+/// Playlist d'un service, proposee comme musique d'alarme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProviderPlaylist {
+    pub uri: String,
+    pub name: String,
+    pub image_url: Option<String>,
+    pub track_count: Option<u32>, // Absent si le service ne le donne pas dans la liste
+}
+
+/// Service de musique capable de lancer la playlist d'une alarme
+pub trait MusicProvider {
+    /// Prefixe des URI du service ("spotify:", "applemusic:", "youtubemusic:")
+    const URI_PREFIX: &'static str;
+
+    /// Indique si une URI d'alarme appartient au service
+    fn handles(uri: &str) -> bool {
+        uri.starts_with(Self::URI_PREFIX)
+    }
+
+    /// Playlists de la bibliotheque de l'utilisateur
+    fn playlists(&self) -> impl Future<Output = Result<Vec<ProviderPlaylist>, String>> + Send;
+
+    /// Lance une playlist, precedee du titre d'ouverture si le service le permet
+    fn play(&self, playlist_uri: &str, first_track: Option<&str>) -> impl Future<Output = Result<(), String>> + Send;
+
+    /// Regle le volume de lecture (0-100)
+    fn set_volume(&self, volume: u8) -> impl Future<Output = Result<(), String>> + Send;
+
+    /// Met la lecture en pause
+    fn pause(&self) -> impl Future<Output = Result<(), String>> + Send;
+}
+
+impl MusicProvider for SpotifyClient {
+    const URI_PREFIX: &'static str = "spotify:";
+
+    async fn playlists(&self) -> Result<Vec<ProviderPlaylist>, String> {
+        let playlists = self.get_playlists().await.map_err(|e| e.to_string())?;
+        Ok(playlists
+            .into_iter()
+            .map(|p| ProviderPlaylist {
+                uri: p.uri,
+                name: p.name,
+                image_url: p.image_url,
+                track_count: Some(p.track_count),
+            })
+            .collect())
+    }
+
+    async fn play(&self, playlist_uri: &str, first_track: Option<&str>) -> Result<(), String> {
+        self.play_alarm_playlist(playlist_uri, first_track).await.map_err(|e| e.to_string())
+    }
+
+    async fn set_volume(&self, volume: u8) -> Result<(), String> {
+        SpotifyClient::set_volume(self, volume).await.map_err(|e| e.to_string())
+    }
+
+    async fn pause(&self) -> Result<(), String> {
+        SpotifyClient::pause(self).await.map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,177 @@
+// query.rs - Recherche, filtres et tri des alarmes cote backend
+// Le frontend ne recoit que les alarmes demandees, dans l'ordre voulu
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm;
+use crate::alarm_list;
+use crate::apple_music::AppleMusicClient;
+use crate::library::MusicLibrary;
+use crate::podcast::PodcastPlayer;
+use crate::provider::MusicProvider;
+use crate::radio::RadioPlayer;
+use crate::spotify::SpotifyClient;
+use crate::youtube_music::YouTubeMusicClient;
+use crate::AlarmEntry;
+
+/// Service qui joue une alarme, deduit du prefixe de son URI
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProviderKind {
+    Spotify,
+    AppleMusic,
+    YoutubeMusic,
+    Library,
+    Radio,
+    Podcast,
+    Local, // Son integre ou URI inconnue
+}
+
+impl ProviderKind {
+    /// Service d'une URI d'alarme
+    pub fn of(uri: &str) -> Self {
+        if SpotifyClient::handles(uri) {
+            ProviderKind::Spotify
+        } else if AppleMusicClient::handles(uri) {
+            ProviderKind::AppleMusic
+        } else if YouTubeMusicClient::handles(uri) {
+            ProviderKind::YoutubeMusic
+        } else if MusicLibrary::handles(uri) {
+            ProviderKind::Library
+        } else if RadioPlayer::handles(uri) {
+            ProviderKind::Radio
+        } else if PodcastPlayer::handles(uri) {
+            ProviderKind::Podcast
+        } else {
+            ProviderKind::Local
+        }
+    }
+}
+
+/// Ordre de tri des resultats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Time, // Heure de la journee
+    Next, // Prochain declenchement (alarmes sans occurrence a la fin)
+    Playlist,
+    Volume,
+    Updated, // Derniere modification
+}
+
+/// Criteres de recherche (tous optionnels, combines par "et")
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AlarmFilter {
+    pub search: Option<String>, // Texte cherche dans la playlist et le groupe (sans casse)
+    pub active: Option<bool>,
+    pub day: Option<String>, // "Monday"...: alarmes prevues ce jour de la semaine
+    pub provider: Option<ProviderKind>,
+    pub group: Option<String>,
+    pub include_archived: bool,
+    pub sort: SortOrder,
+    pub descending: bool,
+    pub limit: Option<usize>,
+}
+
+impl AlarmFilter {
+    fn matches(&self, alarm: &AlarmEntry) -> bool {
+        if alarm.archived && !self.include_archived {
+            return false;
+        }
+        if self.active.is_some_and(|active| alarm.active != active) {
+            return false;
+        }
+        if let Some(search) = self.search.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+            let search = search.to_lowercase();
+            let found = alarm.playlist_name.to_lowercase().contains(&search)
+                || alarm.group.as_deref().is_some_and(|g| g.to_lowercase().contains(&search));
+            if !found {
+                return false;
+            }
+        }
+        if let Some(day) = self.day.as_deref() {
+            let scheduled = alarm_list::covered_weekdays(alarm)
+                .is_none_or(|days| days.iter().any(|d| d.eq_ignore_ascii_case(day.trim())));
+            if !scheduled {
+                return false;
+            }
+        }
+        if self.provider.is_some_and(|provider| ProviderKind::of(&alarm.playlist_uri) != provider) {
+            return false;
+        }
+        if let Some(group) = self.group.as_deref() {
+            if !alarm.group.as_deref().is_some_and(|g| g.eq_ignore_ascii_case(group.trim())) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Alarmes retenues par le filtre, triees. `next` donne le prochain declenchement
+/// (calendrier compris) pour le tri `Next`.
+pub fn query<F>(alarms: &[AlarmEntry], filter: &AlarmFilter, next: F) -> Vec<AlarmEntry>
+where
+    F: Fn(&AlarmEntry) -> Option<DateTime<Local>>,
+{
+    let mut found: Vec<AlarmEntry> = alarms.iter().filter(|a| filter.matches(a)).cloned().collect();
+    match filter.sort {
+        SortOrder::Time => found.sort_by_key(|a| alarm::parse_alarm_time(&a.time)),
+        // Les alarmes sans occurrence a venir passent en dernier
+        SortOrder::Next => found.sort_by_cached_key(|a| next(a).map_or((1, None), |at| (0, Some(at)))),
+        SortOrder::Playlist => found.sort_by_cached_key(|a| a.playlist_name.to_lowercase()),
+        SortOrder::Volume => found.sort_by_key(|a| a.volume),
+        SortOrder::Updated => found.sort_by_key(|a| a.updated_at),
+    }
+    if filter.descending {
+        found.reverse();
+    }
+    if let Some(limit) = filter.limit {
+        found.truncate(limit);
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::alarm::test_support;
+
+    fn alarm(id: &str, time: &str, playlist_uri: &str, days: &[&str]) -> AlarmEntry {
+        AlarmEntry {
+            playlist_name: format!("Playlist {}", id),
+            playlist_uri: playlist_uri.to_string(),
+            volume: 50,
+            days: days.iter().map(|d| d.to_string()).collect(),
+            ..test_support::alarm(id, time)
+        }
+    }
+
+    #[test]
+    fn test_query() {
+        let mut work = alarm("b", "07:30", "spotify:playlist:1", &["Monday", "Tuesday"]);
+        work.group = Some("Travail".to_string());
+        let alarms = vec![
+            alarm("a", "09:00", "radio:https://example.com/live", &["Saturday"]),
+            work,
+            alarm("c", "06:00", "local", &[]),
+            AlarmEntry { archived: true, ..alarm("d", "05:00", "local", &[]) },
+        ];
+        let ids = |filter: AlarmFilter| -> Vec<String> {
+            query(&alarms, &filter, |_| None).into_iter().map(|a| a.id).collect()
+        };
+
+        assert_eq!(ids(AlarmFilter::default()), vec!["c", "b", "a"]);
+        assert_eq!(ids(AlarmFilter { day: Some("monday".to_string()), ..Default::default() }), vec!["c", "b"]);
+        assert_eq!(ids(AlarmFilter { search: Some("TRAV".to_string()), ..Default::default() }), vec!["b"]);
+        assert_eq!(ids(AlarmFilter { provider: Some(ProviderKind::Radio), ..Default::default() }), vec!["a"]);
+        assert_eq!(
+            ids(AlarmFilter { include_archived: true, descending: true, limit: Some(2), ..Default::default() }),
+            vec!["a", "b"]
+        );
+    }
+}
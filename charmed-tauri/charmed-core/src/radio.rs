@@ -0,0 +1,129 @@
+// radio.rs - Radios en ligne comme musique d'alarme
+// Une station est designee par l'URI "radio:" suivie de l'adresse de son flux
+// (radio:https://icecast.radiofrance.fr/fip-midfi.mp3), jouee par le moteur audio local
+
+use serde::{Deserialize, Serialize};
+
+use crate::audio;
+use crate::provider::{MusicProvider, ProviderPlaylist};
+
+/// Station de radio proposee comme musique d'alarme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RadioStation {
+    pub name: String,
+    pub url: String, // Flux HTTP(S) MP3 ou Ogg Vorbis
+}
+
+impl RadioStation {
+    fn new(name: &str, url: &str) -> Self {
+        Self { name: name.to_string(), url: url.to_string() }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("Nom de station vide".to_string());
+        }
+        match reqwest::Url::parse(&self.url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => Ok(()),
+            _ => Err(format!("Adresse de flux invalide: {}", self.url)),
+        }
+    }
+
+    pub fn uri(&self) -> String {
+        format!("{}{}", RadioPlayer::URI_PREFIX, self.url)
+    }
+}
+
+/// Stations proposees par defaut (flux MP3 publics)
+pub fn default_stations() -> Vec<RadioStation> {
+    vec![
+        RadioStation::new("FIP", "https://icecast.radiofrance.fr/fip-midfi.mp3"),
+        RadioStation::new("France Inter", "https://icecast.radiofrance.fr/franceinter-midfi.mp3"),
+        RadioStation::new("France Musique", "https://icecast.radiofrance.fr/francemusique-midfi.mp3"),
+        RadioStation::new("SomaFM Groove Salad", "https://ice1.somafm.com/groovesalad-128-mp3"),
+        RadioStation::new("Radio Paradise", "https://stream.radioparadise.com/mp3-128"),
+    ]
+}
+
+/// Verifie une liste de stations (une adresse n'apparait qu'une fois)
+pub fn validate_stations(stations: &[RadioStation]) -> Result<(), String> {
+    for (i, station) in stations.iter().enumerate() {
+        station.validate()?;
+        if stations[..i].iter().any(|s| s.url == station.url) {
+            return Err(format!("Station en double: {}", station.url));
+        }
+    }
+    Ok(())
+}
+
+/// Adresse du flux d'une URI de station
+pub fn stream_url(uri: &str) -> Option<&str> {
+    uri.strip_prefix(RadioPlayer::URI_PREFIX).filter(|url| !url.is_empty())
+}
+
+/// Lecteur des stations configurees
+#[derive(Debug, Clone, Default)]
+pub struct RadioPlayer {
+    stations: Vec<RadioStation>,
+}
+
+impl RadioPlayer {
+    pub fn new(stations: Vec<RadioStation>) -> Self {
+        Self { stations }
+    }
+}
+
+impl MusicProvider for RadioPlayer {
+    const URI_PREFIX: &'static str = "radio:";
+
+    async fn playlists(&self) -> Result<Vec<ProviderPlaylist>, String> {
+        Ok(self
+            .stations
+            .iter()
+            .map(|station| ProviderPlaylist {
+                uri: station.uri(),
+                name: station.name.clone(),
+                image_url: None,
+                track_count: None,
+            })
+            .collect())
+    }
+
+    /// Lance le flux; toute adresse "radio:" est acceptee, meme hors de la liste
+    async fn play(&self, playlist_uri: &str, _first_track: Option<&str>) -> Result<(), String> {
+        let url = stream_url(playlist_uri)
+            .ok_or_else(|| format!("Station invalide: {}", playlist_uri))?
+            .to_string();
+        // Connexion et remplissage du tampon hors des threads du runtime
+        tokio::task::spawn_blocking(move || audio::play_stream(&url))
+            .await
+            .map_err(|e| e.to_string())?
+    }
+
+    async fn set_volume(&self, volume: u8) -> Result<(), String> {
+        audio::set_alarm_volume(volume)
+    }
+
+    async fn pause(&self) -> Result<(), String> {
+        audio::stop_alarm_sound()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stations() {
+        let stations = default_stations();
+        assert!(validate_stations(&stations).is_ok());
+        assert_eq!(stream_url(&stations[0].uri()), Some("https://icecast.radiofrance.fr/fip-midfi.mp3"));
+        assert!(RadioPlayer::handles(&stations[0].uri()));
+        assert_eq!(stream_url("radio:"), None);
+
+        let duplicated = vec![stations[0].clone(), RadioStation::new("FIP bis", &stations[0].url)];
+        assert!(validate_stations(&duplicated).is_err());
+        assert!(RadioStation::new("Locale", "file:///tmp/a.mp3").validate().is_err());
+        assert!(RadioStation::new(" ", "https://example.com/live").validate().is_err());
+    }
+}
@@ -0,0 +1,179 @@
+// readiness.rs - Verification d'une alarme peu avant qu'elle sonne (reseau, compte, appareil)
+// Les problemes sont signales tant qu'il reste le temps de les corriger
+
+use std::path::Path;
+use std::time::Duration;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::apple_music::AppleMusicClient;
+use crate::i18n::{self, Locale, TimeFormat};
+use crate::library::{LibrarySettings, MusicLibrary};
+use crate::provider::MusicProvider;
+use crate::spotify::SpotifyClient;
+use crate::youtube_music::YouTubeMusicClient;
+use crate::AlarmEntry;
+
+/// Adresse interrogee pour savoir si le reseau repond (toute reponse HTTP suffit)
+const NETWORK_PROBE_URL: &str = "https://api.spotify.com/";
+
+/// Delai maximal de la verification du reseau
+const NETWORK_PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Probleme qui empecherait l'alarme de jouer sa playlist
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ReadinessIssue {
+    NetworkDown,
+    NotConnected { service: String },
+    AuthFailed { service: String, error: String }, // Jeton refuse ou impossible a rafraichir
+    NoSpotifyDevice,
+    LibraryUnavailable,
+}
+
+/// Resultat de la verification d'une occurrence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadinessReport {
+    pub alarm_id: String,
+    pub alarm_at: DateTime<Local>,
+    pub playlist_name: String,
+    pub checked_at: DateTime<Local>,
+    pub issues: Vec<ReadinessIssue>,
+}
+
+impl ReadinessReport {
+    pub fn is_ready(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Comptes et reglages du profil dont depend la lecture
+#[derive(Clone, Default)]
+pub struct ReadinessContext {
+    pub spotify: Option<SpotifyClient>,
+    pub apple_music: Option<AppleMusicClient>,
+    pub youtube_music: Option<YouTubeMusicClient>,
+    pub music_library: Option<LibrarySettings>,
+}
+
+/// Vrai si le reseau repond
+pub async fn network_up() -> bool {
+    let Ok(http) = reqwest::Client::builder().timeout(NETWORK_PROBE_TIMEOUT).build() else {
+        return false;
+    };
+    http.head(NETWORK_PROBE_URL).send().await.is_ok()
+}
+
+/// Vrai si la playlist passe par le reseau (tout sauf le son local et la bibliotheque locale)
+pub fn needs_network(uri: &str) -> bool {
+    uri != "local" && !MusicLibrary::handles(uri)
+}
+
+/// Verifie que la playlist de l'alarme pourra etre jouee
+pub async fn check(alarm: &AlarmEntry, context: &ReadinessContext) -> Vec<ReadinessIssue> {
+    let uri = alarm.playlist_uri.as_str();
+    if MusicLibrary::handles(uri) {
+        let available = context.music_library.as_ref().is_some_and(|settings| Path::new(&settings.folder).is_dir());
+        return if available { Vec::new() } else { vec![ReadinessIssue::LibraryUnavailable] };
+    }
+    // Les autres services (radio et podcasts compris) passent par le reseau
+    if !needs_network(uri) {
+        return Vec::new();
+    }
+    if !network_up().await {
+        return vec![ReadinessIssue::NetworkDown];
+    }
+
+    if AppleMusicClient::handles(uri) {
+        return match &context.apple_music {
+            Some(client) => auth_issue("Apple Music", client.verify().await),
+            None => vec![not_connected("Apple Music")],
+        };
+    }
+    if YouTubeMusicClient::handles(uri) {
+        return match &context.youtube_music {
+            Some(client) => auth_issue("YouTube Music", client.verify().await),
+            None => vec![not_connected("YouTube Music")],
+        };
+    }
+    if !SpotifyClient::handles(uri) {
+        return Vec::new();
+    }
+    let Some(client) = context.spotify.as_ref().filter(|c| c.is_authenticated()) else {
+        return vec![not_connected("Spotify")];
+    };
+    // La liste des appareils valide aussi le jeton (rafraichi au besoin)
+    match client.get_devices().await {
+        Ok(devices) if devices.is_empty() => vec![ReadinessIssue::NoSpotifyDevice],
+        Ok(_) => Vec::new(),
+        Err(e) => auth_issue("Spotify", Err(e.to_string())),
+    }
+}
+
+fn not_connected(service: &str) -> ReadinessIssue {
+    ReadinessIssue::NotConnected { service: service.to_string() }
+}
+
+fn auth_issue(service: &str, result: Result<(), String>) -> Vec<ReadinessIssue> {
+    match result {
+        Ok(()) => Vec::new(),
+        Err(error) => vec![ReadinessIssue::AuthFailed { service: service.to_string(), error }],
+    }
+}
+
+fn describe(locale: Locale, issue: &ReadinessIssue) -> String {
+    match (locale, issue) {
+        (Locale::Fr, ReadinessIssue::NetworkDown) => "pas de connexion internet".to_string(),
+        (Locale::En, ReadinessIssue::NetworkDown) => "no internet connection".to_string(),
+        (Locale::Fr, ReadinessIssue::NotConnected { service }) => format!("{} n'est pas connecte", service),
+        (Locale::En, ReadinessIssue::NotConnected { service }) => format!("{} is not connected", service),
+        (Locale::Fr, ReadinessIssue::AuthFailed { service, error }) => format!("acces {} refuse ({})", service, error),
+        (Locale::En, ReadinessIssue::AuthFailed { service, error }) => format!("{} access refused ({})", service, error),
+        (Locale::Fr, ReadinessIssue::NoSpotifyDevice) => "aucun appareil Spotify disponible".to_string(),
+        (Locale::En, ReadinessIssue::NoSpotifyDevice) => "no Spotify device available".to_string(),
+        (Locale::Fr, ReadinessIssue::LibraryUnavailable) => "bibliotheque musicale introuvable".to_string(),
+        (Locale::En, ReadinessIssue::LibraryUnavailable) => "music library not found".to_string(),
+    }
+}
+
+/// Titre et texte de la notification d'une alarme qui risque de ne pas jouer sa playlist
+pub fn warning_text(locale: Locale, time_format: TimeFormat, report: &ReadinessReport) -> (String, String) {
+    let issues: Vec<String> = report.issues.iter().map(|issue| describe(locale, issue)).collect();
+    let alarm_at = i18n::format_clock(report.alarm_at.time(), time_format, false);
+    match locale {
+        Locale::Fr => (
+            format!("Alarme de {} en danger", alarm_at),
+            format!("{}: {}. A corriger avant la sonnerie", report.playlist_name, issues.join(", ")),
+        ),
+        Locale::En => (
+            format!("{} alarm at risk", alarm_at),
+            format!("{}: {}. Fix it before the alarm goes off", report.playlist_name, issues.join(", ")),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_warning_text() {
+        let alarm_at = Local.with_ymd_and_hms(2024, 1, 8, 6, 30, 0).unwrap();
+        let report = ReadinessReport {
+            alarm_id: "a".to_string(),
+            alarm_at,
+            playlist_name: "Matin".to_string(),
+            checked_at: alarm_at - chrono::Duration::minutes(30),
+            issues: vec![ReadinessIssue::NetworkDown, not_connected("Spotify")],
+        };
+        assert!(!report.is_ready());
+
+        let (title, body) = warning_text(Locale::En, TimeFormat::H24, &report);
+        assert_eq!(title, "06:30 alarm at risk");
+        assert_eq!(body, "Matin: no internet connection, Spotify is not connected. Fix it before the alarm goes off");
+
+        let json = serde_json::to_value(&report.issues[1]).unwrap();
+        assert_eq!(json, serde_json::json!({"kind": "not_connected", "service": "Spotify"}));
+    }
+}
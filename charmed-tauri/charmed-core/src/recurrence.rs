@@ -0,0 +1,220 @@
+// recurrence.rs - Regles de recurrence avancees (inspirees de RRULE)
+// Exemples: "un jour sur deux", "le 2e samedi du mois",
+// "en semaine sauf le dernier vendredi du mois"
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::string_to_weekday;
+
+/// Unite de repetition (FREQ)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+/// Jour de la semaine, eventuellement qualifie par son rang dans le mois
+/// (BYDAY: 2 = deuxieme, -1 = dernier)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DaySpec {
+    pub day: String, // "Monday", "Tuesday", ...
+    #[serde(default)]
+    pub ordinal: Option<i8>,
+}
+
+/// Regle de recurrence attachee a une alarme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecurrenceRule {
+    pub freq: Frequency,
+    #[serde(default = "default_interval")]
+    pub interval: u32, // Toutes les N unites
+    #[serde(default)]
+    pub by_day: Vec<DaySpec>,
+    #[serde(default)]
+    pub except: Vec<DaySpec>, // Jours exclus (ex: dernier vendredi du mois)
+    pub start: NaiveDate, // Date d'ancrage pour l'intervalle (DTSTART)
+    #[serde(default)]
+    pub until: Option<NaiveDate>,
+}
+
+fn default_interval() -> u32 {
+    1
+}
+
+/// Nombre de jours d'un mois
+fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 { (year + 1, 1) } else { (year, month + 1) };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|d| d.pred_opt())
+        .map(|d| d.day())
+        .unwrap_or(28)
+}
+
+impl DaySpec {
+    fn weekday(&self) -> Option<Weekday> {
+        string_to_weekday(&self.day)
+    }
+
+    /// Verifie si la date correspond a ce jour (et a son rang dans le mois)
+    fn matches(&self, date: NaiveDate) -> bool {
+        if self.weekday() != Some(date.weekday()) {
+            return false;
+        }
+        match self.ordinal {
+            None => true,
+            Some(n) if n > 0 => (date.day() - 1) / 7 + 1 == n as u32,
+            Some(n) => {
+                let from_end = (days_in_month(date.year(), date.month()) - date.day()) / 7 + 1;
+                from_end == n.unsigned_abs() as u32
+            }
+        }
+    }
+}
+
+impl RecurrenceRule {
+    /// Valide la regle (intervalle, noms de jours, rangs)
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval == 0 {
+            return Err("L'intervalle de recurrence doit etre >= 1".to_string());
+        }
+        for spec in self.by_day.iter().chain(self.except.iter()) {
+            if spec.weekday().is_none() {
+                return Err(format!("Jour invalide: {}", spec.day));
+            }
+            if let Some(n) = spec.ordinal {
+                if n == 0 || !(-5..=5).contains(&n) {
+                    return Err(format!("Rang invalide pour {}: {}", spec.day, n));
+                }
+            }
+        }
+        if self.until.is_some_and(|until| until < self.start) {
+            return Err("La date de fin precede la date de debut".to_string());
+        }
+        Ok(())
+    }
+
+    /// Verifie si la regle produit une occurrence a cette date
+    pub fn matches(&self, date: NaiveDate) -> bool {
+        if date < self.start || self.until.is_some_and(|until| date > until) {
+            return false;
+        }
+        if self.except.iter().any(|spec| spec.matches(date)) {
+            return false;
+        }
+
+        let interval = i64::from(self.interval.max(1));
+        match self.freq {
+            Frequency::Daily => {
+                let days = (date - self.start).num_days();
+                days % interval == 0 && self.matches_by_day(date, true)
+            }
+            Frequency::Weekly => {
+                // Semaines comptees du lundi de la semaine de depart
+                let week_start = |d: NaiveDate| d.num_days_from_ce() - d.weekday().num_days_from_monday() as i32;
+                let weeks = i64::from(week_start(date) - week_start(self.start)) / 7;
+                let day_ok = if self.by_day.is_empty() {
+                    date.weekday() == self.start.weekday()
+                } else {
+                    self.matches_by_day(date, true)
+                };
+                weeks % interval == 0 && day_ok
+            }
+            Frequency::Monthly => {
+                let months = i64::from(date.year() - self.start.year()) * 12
+                    + i64::from(date.month()) - i64::from(self.start.month());
+                let day_ok = if self.by_day.is_empty() {
+                    date.day() == self.start.day()
+                } else {
+                    self.matches_by_day(date, false)
+                };
+                months % interval == 0 && day_ok
+            }
+        }
+    }
+
+    /// BYDAY: aucune contrainte si vide.
+    /// Les rangs ne sont pris en compte qu'en frequence mensuelle.
+    fn matches_by_day(&self, date: NaiveDate, ignore_ordinal: bool) -> bool {
+        if self.by_day.is_empty() {
+            return true;
+        }
+        self.by_day.iter().any(|spec| {
+            if ignore_ordinal {
+                spec.weekday() == Some(date.weekday())
+            } else {
+                spec.matches(date)
+            }
+        })
+    }
+
+    /// Jours de la semaine couverts par la regle (vide = tous)
+    pub fn weekdays(&self) -> Vec<String> {
+        self.by_day.iter().map(|spec| spec.day.clone()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn spec(day: &str, ordinal: Option<i8>) -> DaySpec {
+        DaySpec { day: day.to_string(), ordinal }
+    }
+
+    #[test]
+    fn test_every_other_day() {
+        let rule = RecurrenceRule {
+            freq: Frequency::Daily,
+            interval: 2,
+            by_day: Vec::new(),
+            except: Vec::new(),
+            start: date(2024, 3, 1),
+            until: None,
+        };
+        assert!(rule.matches(date(2024, 3, 1)));
+        assert!(!rule.matches(date(2024, 3, 2)));
+        assert!(rule.matches(date(2024, 3, 3)));
+        assert!(!rule.matches(date(2024, 2, 28)));
+    }
+
+    #[test]
+    fn test_second_saturday_of_month() {
+        let rule = RecurrenceRule {
+            freq: Frequency::Monthly,
+            interval: 1,
+            by_day: vec![spec("Saturday", Some(2))],
+            except: Vec::new(),
+            start: date(2024, 1, 1),
+            until: None,
+        };
+        assert!(rule.matches(date(2024, 3, 9)));
+        assert!(!rule.matches(date(2024, 3, 2)));
+        assert!(!rule.matches(date(2024, 3, 16)));
+    }
+
+    #[test]
+    fn test_weekdays_except_last_friday() {
+        let rule = RecurrenceRule {
+            freq: Frequency::Weekly,
+            interval: 1,
+            by_day: ["Monday", "Tuesday", "Wednesday", "Thursday", "Friday"]
+                .iter()
+                .map(|d| spec(d, None))
+                .collect(),
+            except: vec![spec("Friday", Some(-1))],
+            start: date(2024, 1, 1),
+            until: None,
+        };
+        assert!(rule.matches(date(2024, 3, 22))); // Avant-dernier vendredi
+        assert!(!rule.matches(date(2024, 3, 29))); // Dernier vendredi
+        assert!(!rule.matches(date(2024, 3, 30))); // Samedi
+        assert!(rule.validate().is_ok());
+    }
+}
@@ -0,0 +1,48 @@
+// remote.rs - Reglages de l'API REST locale (serveur dans l'application de bureau)
+
+use serde::{Deserialize, Serialize};
+
+/// Port d'ecoute par defaut
+pub const DEFAULT_PORT: u16 = 8787;
+
+/// Configuration de l'API distante
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteApiSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+impl Default for RemoteApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+impl RemoteApiSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.port < 1024 {
+            return Err(format!("Port invalide: {} (1024 ou plus)", self.port));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remote_api_settings() {
+        assert!(RemoteApiSettings::default().validate().is_ok());
+        assert!(RemoteApiSettings { enabled: true, port: 80 }.validate().is_err());
+    }
+}
@@ -0,0 +1,96 @@
+// secrets.rs - Secrets Spotify dans le trousseau du systeme (Keychain, Credential Manager,
+// Secret Service) plutot qu'en clair dans les fichiers JSON
+
+use std::path::Path;
+use keyring::Entry;
+
+use crate::storage::{self, AppConfig};
+
+/// Service sous lequel les secrets sont ranges dans le trousseau
+const SERVICE: &str = "charmed";
+
+/// Cle du secret client Spotify
+pub const CLIENT_SECRET_KEY: &str = "spotify-client-secret";
+
+/// Cle du jeton d'acces a l'API REST locale
+pub const REMOTE_API_TOKEN_KEY: &str = "remote-api-token";
+
+/// Cle de l'identifiant attribue par le pont Philips Hue
+pub const HUE_USERNAME_KEY: &str = "hue-username";
+
+/// Cle du jeton de rafraichissement d'un profil Spotify, propre au profil
+/// utilisateur (None = profil par defaut, cle d'origine)
+pub fn refresh_token_key(user: Option<&str>, profile: &str) -> String {
+    match user {
+        Some(user) => format!("spotify-refresh-token:{}/{}", user, profile),
+        None => format!("spotify-refresh-token:{}", profile),
+    }
+}
+
+/// Cle du mot de passe WebDAV ou de la cle secrete S3 de la synchronisation,
+/// propre au profil utilisateur
+pub fn sync_secret_key(user: Option<&str>) -> String {
+    match user {
+        Some(user) => format!("sync-secret:{}", user),
+        None => "sync-secret".to_string(),
+    }
+}
+
+/// Cle des jetons MusicKit (developpeur et utilisateur) d'Apple Music,
+/// propre au profil utilisateur
+pub fn apple_music_key(user: Option<&str>) -> String {
+    match user {
+        Some(user) => format!("apple-music-tokens:{}", user),
+        None => "apple-music-tokens".to_string(),
+    }
+}
+
+/// Cle des identifiants OAuth Google de YouTube Music (client et jeton de
+/// rafraichissement), propre au profil utilisateur
+pub fn youtube_music_key(user: Option<&str>) -> String {
+    match user {
+        Some(user) => format!("youtube-music-credentials:{}", user),
+        None => "youtube-music-credentials".to_string(),
+    }
+}
+
+fn entry(key: &str) -> Result<Entry, String> {
+    Entry::new(SERVICE, key).map_err(|e| format!("Trousseau indisponible: {}", e))
+}
+
+/// Enregistre un secret dans le trousseau
+pub fn set_secret(key: &str, value: &str) -> Result<(), String> {
+    entry(key)?
+        .set_password(value)
+        .map_err(|e| format!("Erreur écriture trousseau: {}", e))
+}
+
+/// Lit un secret (None s'il n'existe pas)
+pub fn get_secret(key: &str) -> Result<Option<String>, String> {
+    match entry(key)?.get_password() {
+        Ok(value) => Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(format!("Erreur lecture trousseau: {}", e)),
+    }
+}
+
+/// Supprime un secret (absent = deja supprime)
+pub fn delete_secret(key: &str) -> Result<(), String> {
+    match entry(key)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Erreur suppression trousseau: {}", e)),
+    }
+}
+
+/// Deplace le secret client encore present dans config.json vers le trousseau.
+/// Le fichier n'est reecrit que si le trousseau a accepte le secret.
+pub fn migrate_config_secret(data_dir: &Path, config: &mut AppConfig) -> Result<(), String> {
+    let Some(secret) = config.spotify_client_secret.clone() else {
+        return Ok(());
+    };
+    if !secret.is_empty() {
+        set_secret(CLIENT_SECRET_KEY, &secret)?;
+    }
+    config.spotify_client_secret = None;
+    storage::save_config(data_dir, config)
+}
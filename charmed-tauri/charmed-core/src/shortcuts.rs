@@ -0,0 +1,61 @@
+// shortcuts.rs - Reglages des raccourcis de report et d'arret de la sonnerie
+// (l'enregistrement aupres du systeme est fait par l'application de bureau)
+
+use serde::{Deserialize, Serialize};
+
+/// Raccourcis configures (None = desactive), au format "Space", "Escape", "Ctrl+Shift+S"...
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShortcutSettings {
+    #[serde(default = "default_snooze")]
+    pub snooze: Option<String>,
+    #[serde(default = "default_dismiss")]
+    pub dismiss: Option<String>,
+}
+
+fn default_snooze() -> Option<String> {
+    Some("Space".to_string())
+}
+
+fn default_dismiss() -> Option<String> {
+    Some("Escape".to_string())
+}
+
+impl Default for ShortcutSettings {
+    fn default() -> Self {
+        Self {
+            snooze: default_snooze(),
+            dismiss: default_dismiss(),
+        }
+    }
+}
+
+/// Action declenchee par un raccourci
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    Snooze,
+    Dismiss,
+}
+
+impl ShortcutSettings {
+    /// Raccourcis actifs, avec l'action de chacun
+    pub fn entries(&self) -> impl Iterator<Item = (ShortcutAction, &str)> {
+        [
+            (ShortcutAction::Snooze, self.snooze.as_deref()),
+            (ShortcutAction::Dismiss, self.dismiss.as_deref()),
+        ]
+        .into_iter()
+        .filter_map(|(action, keys)| keys.map(|keys| (action, keys)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcut_entries() {
+        let settings = ShortcutSettings { snooze: None, ..ShortcutSettings::default() };
+        let entries: Vec<_> = settings.entries().collect();
+        assert_eq!(entries, vec![(ShortcutAction::Dismiss, "Escape")]);
+    }
+}
@@ -0,0 +1,1289 @@
+// spotify.rs - Integration Spotify Web API via rspotify
+
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::path::Path;
+use std::process::Command;
+use std::sync::Arc;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use rspotify::{
+    http::HttpError,
+    model::{SearchResult, SearchType, SimplifiedArtist, SimplifiedPlaylist},
+    prelude::*,
+    sync::Mutex as FuturesMutex,
+    AuthCodePkceSpotify, ClientError, ClientResult, Credentials, OAuth, Token,
+};
+
+use crate::i18n::{tr, Locale, Msg};
+use crate::secrets;
+
+/// Playlist Spotify avec metadonnees pour l'affichage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotifyPlaylist {
+    pub id: String,
+    pub name: String,
+    pub uri: String,
+    pub image_url: Option<String>,
+    pub track_count: u32,
+    pub owner: String,
+}
+
+impl From<SimplifiedPlaylist> for SpotifyPlaylist {
+    fn from(p: SimplifiedPlaylist) -> Self {
+        Self {
+            id: p.id.id().to_string(),
+            name: p.name,
+            uri: p.id.uri(),
+            image_url: p.images.first().map(|img| img.url.clone()),
+            track_count: p.tracks.total,
+            owner: p.owner.display_name.unwrap_or_else(|| "Unknown".to_string()),
+        }
+    }
+}
+
+/// Album Spotify retourne par la recherche
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotifyAlbum {
+    pub id: String,
+    pub name: String,
+    pub uri: String,
+    pub image_url: Option<String>,
+    pub artist: String,
+}
+
+/// Titre Spotify retourne par la recherche
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotifyTrack {
+    pub id: String,
+    pub name: String,
+    pub uri: String,
+    pub image_url: Option<String>,
+    pub artist: String,
+    pub album: String,
+    pub duration_ms: u64,
+}
+
+/// Resultats d'une recherche dans le catalogue Spotify
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpotifySearchResults {
+    pub playlists: Vec<SpotifyPlaylist>,
+    pub albums: Vec<SpotifyAlbum>,
+    pub tracks: Vec<SpotifyTrack>,
+}
+
+/// Nombre maximum de resultats par type autorise par l'API Spotify
+const SEARCH_MAX_LIMIT: u32 = 50;
+
+/// Taille de page maximale pour la liste des playlists
+const PLAYLIST_PAGE_SIZE: u32 = 50;
+
+/// Playlists personnalisees par Spotify ("Made for you"), absentes des playlists de l'utilisateur
+const MADE_FOR_YOU_NAMES: [&str; 8] = [
+    "Discover Weekly",
+    "Release Radar",
+    "Daily Mix 1",
+    "Daily Mix 2",
+    "Daily Mix 3",
+    "Daily Mix 4",
+    "Daily Mix 5",
+    "Daily Mix 6",
+];
+
+/// Proprietaire des playlists personnalisees
+const SPOTIFY_OWNER: &str = "Spotify";
+
+/// Resultats examines par recherche de playlist personnalisee
+const MADE_FOR_YOU_SEARCH_LIMIT: u32 = 10;
+
+/// Playlist personnalisee parmi des resultats de recherche: nom exact, publiee par Spotify
+fn find_made_for_you(name: &str, candidates: Vec<SpotifyPlaylist>) -> Option<SpotifyPlaylist> {
+    candidates
+        .into_iter()
+        .find(|p| p.name.eq_ignore_ascii_case(name) && p.owner == SPOTIFY_OWNER)
+}
+
+/// Convertit les types demandes ("playlist", "album", "track") en SearchType.
+/// Une liste vide signifie "tous les types".
+fn parse_search_types(types: &[String]) -> Result<Vec<SearchType>, String> {
+    if types.is_empty() {
+        return Ok(vec![SearchType::Playlist, SearchType::Album, SearchType::Track]);
+    }
+
+    let mut parsed = Vec::new();
+    for t in types {
+        let search_type = match t.to_lowercase().as_str() {
+            "playlist" => SearchType::Playlist,
+            "album" => SearchType::Album,
+            "track" => SearchType::Track,
+            other => return Err(format!("Type de recherche inconnu: {}", other)),
+        };
+        if !parsed.contains(&search_type) {
+            parsed.push(search_type);
+        }
+    }
+    Ok(parsed)
+}
+
+/// Joint les noms d'artistes pour l'affichage
+fn join_artists(artists: &[SimplifiedArtist]) -> String {
+    artists
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Erreur typee des appels Spotify
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpotifyError {
+    NotInitialized,
+    NotAuthenticated,
+    NoActiveDevice,
+    /// HTTP 429: l'API demande d'attendre `retry_after_secs` secondes
+    RateLimited { retry_after_secs: u64 },
+    /// Erreur reseau ou 5xx, susceptible de disparaitre en reessayant
+    Transient(String),
+    InvalidInput(String),
+    Api(String),
+}
+
+impl SpotifyError {
+    /// Vrai si l'erreur peut disparaitre en reessayant plus tard (reseau, limite de requetes)
+    pub fn is_transient(&self) -> bool {
+        matches!(self, SpotifyError::Transient(_) | SpotifyError::RateLimited { .. })
+    }
+}
+
+impl SpotifyError {
+    /// Message de l'erreur dans la langue configuree
+    pub fn localized(&self, locale: Locale) -> String {
+        match self {
+            SpotifyError::NotInitialized => tr(locale, Msg::SpotifyNotInitialized, &[]),
+            SpotifyError::NotAuthenticated => tr(locale, Msg::SpotifyNotConnected, &[]),
+            SpotifyError::NoActiveDevice => tr(locale, Msg::SpotifyNoActiveDevice, &[]),
+            SpotifyError::RateLimited { retry_after_secs } => tr(locale, Msg::SpotifyRateLimited, &[retry_after_secs]),
+            SpotifyError::Transient(msg) => tr(locale, Msg::NetworkError, &[msg]),
+            SpotifyError::InvalidInput(msg) | SpotifyError::Api(msg) => msg.clone(),
+        }
+    }
+}
+
+impl fmt::Display for SpotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.localized(Locale::default()))
+    }
+}
+
+impl From<ClientError> for SpotifyError {
+    fn from(err: ClientError) -> Self {
+        match err {
+            ClientError::Http(http_err) => match *http_err {
+                HttpError::StatusCode(response) => {
+                    let status = response.status().as_u16();
+                    if status == 429 {
+                        let retry_after_secs = response
+                            .headers()
+                            .get("retry-after")
+                            .and_then(|v| v.to_str().ok())
+                            .and_then(|v| v.trim().parse().ok())
+                            .unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+                        SpotifyError::RateLimited { retry_after_secs }
+                    } else if status >= 500 {
+                        SpotifyError::Transient(format!("statut HTTP {}", status))
+                    } else {
+                        SpotifyError::Api(format!("Erreur API: statut HTTP {}", status))
+                    }
+                }
+                HttpError::Client(e) => SpotifyError::Transient(e.to_string()),
+            },
+            ClientError::Io(e) => SpotifyError::Transient(e.to_string()),
+            other => SpotifyError::Api(format!("Erreur API: {}", other)),
+        }
+    }
+}
+
+/// Nombre maximum de nouvelles tentatives apres un echec transitoire
+const MAX_RETRIES: u32 = 3;
+
+/// Delai de base du backoff exponentiel (millisecondes)
+const RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Attente par defaut si un 429 ne fournit pas d'en-tete Retry-After
+const DEFAULT_RETRY_AFTER_SECS: u64 = 1;
+
+/// Au-dela de ce delai, on remonte le RateLimited plutot que de bloquer l'appel
+const MAX_RETRY_AFTER_SECS: u64 = 30;
+
+/// Calcule l'attente avant la prochaine tentative, ou None s'il faut abandonner
+fn retry_delay(err: &SpotifyError, attempt: u32) -> Option<Duration> {
+    if attempt >= MAX_RETRIES {
+        return None;
+    }
+    match err {
+        SpotifyError::RateLimited { retry_after_secs } if *retry_after_secs <= MAX_RETRY_AFTER_SECS => {
+            Some(Duration::from_secs(*retry_after_secs))
+        }
+        SpotifyError::Transient(_) => Some(Duration::from_millis(RETRY_BASE_DELAY_MS << attempt)),
+        _ => None,
+    }
+}
+
+/// Nouvelles tentatives de renouvellement du jeton avant d'abandonner
+const TOKEN_REFRESH_RETRIES: u32 = 4;
+
+/// Delai de base entre deux tentatives de renouvellement du jeton (millisecondes)
+const TOKEN_REFRESH_BASE_DELAY_MS: u64 = 2_000;
+
+/// Attente avant un nouvel essai de renouvellement du jeton: backoff exponentiel allonge
+/// d'un alea (`jitter` entre 0 et 1, jusqu'a +50 %) pour etaler les comptes qui expirent
+/// ensemble. None si l'erreur est definitive ou les essais epuises
+fn refresh_retry_delay(err: &SpotifyError, attempt: u32, jitter: f64) -> Option<Duration> {
+    if attempt >= TOKEN_REFRESH_RETRIES {
+        return None;
+    }
+    let base = match err {
+        SpotifyError::RateLimited { retry_after_secs } => Duration::from_secs(*retry_after_secs),
+        SpotifyError::Transient(_) => Duration::from_millis(TOKEN_REFRESH_BASE_DELAY_MS << attempt),
+        _ => return None,
+    };
+    Some(base.mul_f64(1.0 + jitter.clamp(0.0, 1.0) / 2.0))
+}
+
+/// Execute un appel rspotify en respectant Retry-After et en reessayant
+/// les erreurs transitoires avec un backoff exponentiel
+async fn with_retry<T, F, Fut>(mut call: F) -> Result<T, SpotifyError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = ClientResult<T>>,
+{
+    let mut attempt = 0;
+    loop {
+        match call().await {
+            Ok(value) => return Ok(value),
+            Err(e) => {
+                let err = SpotifyError::from(e);
+                match retry_delay(&err, attempt) {
+                    Some(delay) => {
+                        tracing::warn!(attempt, delay_ms = delay.as_millis() as u64, "Requete Spotify en echec, nouvel essai: {}", err);
+                        tokio::time::sleep(delay).await;
+                        attempt += 1;
+                    }
+                    None => {
+                        tracing::error!("Requete Spotify en echec: {}", err);
+                        return Err(err);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Efface le cache des playlists sur le disque
+pub fn clear_playlist_cache(data_dir: &Path) -> Result<(), String> {
+    let cache_path = data_dir.join(PLAYLIST_CACHE_FILE);
+    if cache_path.exists() {
+        fs::remove_file(&cache_path)
+            .map_err(|e| format!("Erreur suppression fichier: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Profil utilise quand aucun nom de compte n'est fourni
+pub const DEFAULT_PROFILE: &str = "default";
+
+const ACCOUNTS_FILE: &str = "spotify_accounts.json";
+
+/// Compte Spotify authentifie, persiste par nom de profil
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredAccount {
+    pub profile: String,
+    pub client_id: String,
+    pub token: Token,
+}
+
+/// Lit le fichier des comptes tel quel (jetons de rafraichissement absents si migres)
+fn read_accounts_file(data_dir: &Path) -> Vec<StoredAccount> {
+    fs::read_to_string(data_dir.join(ACCOUNTS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Charge les comptes Spotify persistes (liste vide si absent ou illisible).
+/// Les jetons de rafraichissement sont relus depuis le trousseau, sous les cles
+/// du profil utilisateur `user`.
+pub fn load_accounts(data_dir: &Path, user: Option<&str>) -> Vec<StoredAccount> {
+    let mut accounts = read_accounts_file(data_dir);
+    for account in accounts.iter_mut().filter(|a| a.token.refresh_token.is_none()) {
+        if let Ok(Some(refresh_token)) = secrets::get_secret(&secrets::refresh_token_key(user, &account.profile)) {
+            account.token.refresh_token = Some(refresh_token);
+        }
+    }
+    accounts
+}
+
+/// Sauvegarde les comptes Spotify. Les jetons de rafraichissement vont dans le
+/// trousseau; ils ne restent dans le fichier que si le trousseau est indisponible.
+pub fn save_accounts(data_dir: &Path, user: Option<&str>, accounts: &[StoredAccount]) -> Result<(), String> {
+    if !data_dir.exists() {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    }
+
+    let on_disk: Vec<StoredAccount> = accounts
+        .iter()
+        .cloned()
+        .map(|mut account| {
+            if let Some(refresh_token) = account.token.refresh_token.as_deref() {
+                match secrets::set_secret(&secrets::refresh_token_key(user, &account.profile), refresh_token) {
+                    Ok(()) => account.token.refresh_token = None,
+                    Err(e) => tracing::warn!(profile = %account.profile, "Jeton conserve dans le fichier: {}", e),
+                }
+            }
+            account
+        })
+        .collect();
+
+    let json = serde_json::to_string_pretty(&on_disk)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+
+    fs::write(data_dir.join(ACCOUNTS_FILE), json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+/// Ajoute ou remplace le compte d'un profil dans le fichier des comptes
+pub fn upsert_account(data_dir: &Path, user: Option<&str>, account: StoredAccount) -> Result<(), String> {
+    let mut accounts = load_accounts(data_dir, user);
+    accounts.retain(|a| a.profile != account.profile);
+    accounts.push(account);
+    save_accounts(data_dir, user, &accounts)
+}
+
+/// Supprime le compte d'un profil (et donc ses tokens) du fichier des comptes
+pub fn remove_account(data_dir: &Path, user: Option<&str>, profile: &str) -> Result<(), String> {
+    let _ = secrets::delete_secret(&secrets::refresh_token_key(user, profile));
+    let mut accounts = load_accounts(data_dir, user);
+    let before = accounts.len();
+    accounts.retain(|a| a.profile != profile);
+    if accounts.len() < before {
+        save_accounts(data_dir, user, &accounts)?;
+    }
+    Ok(())
+}
+
+/// Deplace vers le trousseau les jetons encore stockes en clair dans le fichier des comptes
+pub fn migrate_account_secrets(data_dir: &Path, user: Option<&str>) -> Result<(), String> {
+    let accounts = read_accounts_file(data_dir);
+    if accounts.iter().any(|a| a.token.refresh_token.is_some()) {
+        save_accounts(data_dir, user, &accounts)?;
+    }
+    Ok(())
+}
+
+/// Configuration OAuth commune (scopes et redirection configuree)
+fn oauth_config(redirect_uri: &str) -> OAuth {
+    OAuth {
+        scopes: rspotify::scopes!(
+            "user-library-read",
+            "user-read-playback-state",
+            "user-modify-playback-state",
+            "playlist-read-private",
+            "playlist-read-collaborative"
+        ),
+        redirect_uri: redirect_uri.to_string(),
+        ..Default::default()
+    }
+}
+
+/// Delai maximum d'attente de l'enregistrement du client Spotify local
+const DEVICE_WAIT_TIMEOUT_SECS: u64 = 15;
+
+/// Intervalle de sondage des appareils pendant cette attente
+const DEVICE_POLL_INTERVAL_MS: u64 = 1000;
+
+/// Delai accorde au lecteur pour confirmer qu'il joue apres une commande de lecture
+pub const PLAYBACK_VERIFY_SECS: u64 = 10;
+
+/// Intervalle de sondage de l'etat du lecteur pendant cette verification
+const PLAYBACK_POLL_INTERVAL_MS: u64 = 1000;
+
+/// Lance le client Spotify installe localement
+pub fn launch_local_spotify() -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    let result = Command::new("cmd").args(["/C", "start", "", "spotify:"]).spawn();
+    #[cfg(target_os = "macos")]
+    let result = Command::new("open").args(["-a", "Spotify"]).spawn();
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let result = Command::new("spotify").spawn();
+
+    result
+        .map(|_| ())
+        .map_err(|e| format!("Impossible de lancer Spotify: {}", e))
+}
+
+const PLAYLIST_CACHE_FILE: &str = "playlists_cache.json";
+
+/// Cache des playlists (memoire + disque) pour garder le selecteur instantane
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlaylistCache {
+    pub fetched_at: i64, // Timestamp Unix (secondes)
+    pub playlists: Vec<SpotifyPlaylist>,
+}
+
+impl PlaylistCache {
+    /// Cree un cache horodate maintenant
+    pub fn new(playlists: Vec<SpotifyPlaylist>) -> Self {
+        Self {
+            fetched_at: chrono::Utc::now().timestamp(),
+            playlists,
+        }
+    }
+
+    /// Verifie si le cache est encore valide pour le TTL donne (en secondes)
+    pub fn is_fresh(&self, ttl_secs: u64, now: i64) -> bool {
+        let age = now - self.fetched_at;
+        age >= 0 && (age as u64) < ttl_secs
+    }
+}
+
+/// Charge le cache des playlists depuis le disque (None si absent ou illisible)
+pub fn load_playlist_cache(data_dir: &Path) -> Option<PlaylistCache> {
+    let content = fs::read_to_string(data_dir.join(PLAYLIST_CACHE_FILE)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// Sauvegarde le cache des playlists sur le disque
+pub fn save_playlist_cache(data_dir: &Path, cache: &PlaylistCache) -> Result<(), String> {
+    if !data_dir.exists() {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(cache)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+
+    fs::write(data_dir.join(PLAYLIST_CACHE_FILE), json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+/// Client Spotify avec support OAuth PKCE
+#[derive(Clone)]
+pub struct SpotifyClient {
+    client: Option<AuthCodePkceSpotify>,
+    client_id: String,
+    profile: String,
+    authenticated: bool,
+    device_preference: Vec<String>, // Appareils preferes, par ordre de priorite (id ou nom)
+}
+
+impl SpotifyClient {
+    /// Cree un nouveau client Spotify pour un profil
+    pub fn new(client_id: String, _client_secret: String, profile: String) -> Self {
+        Self {
+            client: None,
+            client_id,
+            profile,
+            authenticated: false,
+            device_preference: Vec::new(),
+        }
+    }
+
+    /// Restaure un client authentifie depuis un compte persiste
+    pub fn from_stored(account: StoredAccount, redirect_uri: &str) -> Self {
+        let creds = Credentials::new_pkce(&account.client_id);
+        let mut spotify = AuthCodePkceSpotify::new(creds, oauth_config(redirect_uri));
+        spotify.token = Arc::new(FuturesMutex::new(Some(account.token)));
+
+        Self {
+            client: Some(spotify),
+            client_id: account.client_id,
+            profile: account.profile,
+            authenticated: true,
+            device_preference: Vec::new(),
+        }
+    }
+
+    /// Appareils a essayer en premier pour la lecture, par ordre de priorite
+    pub fn with_device_preference(mut self, devices: Vec<String>) -> Self {
+        self.device_preference = devices;
+        self
+    }
+
+    /// Place un appareil (ou groupe Spotify Connect) en tete des appareils preferes
+    pub fn prefer_device(mut self, device: &str) -> Self {
+        self.device_preference.insert(0, device.to_string());
+        self
+    }
+
+    /// Exporte le compte (profil + token courant) pour la persistance
+    pub async fn to_stored(&self) -> Option<StoredAccount> {
+        let spotify = self.client.as_ref()?;
+        if !self.authenticated {
+            return None;
+        }
+        let token = spotify.token.lock().await.ok()?.clone()?;
+
+        Some(StoredAccount {
+            profile: self.profile.clone(),
+            client_id: self.client_id.clone(),
+            token,
+        })
+    }
+
+    /// Nom du profil associe a ce client
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    /// Genere l'URL d'authentification OAuth
+    pub fn get_auth_url(&mut self, redirect_uri: &str) -> String {
+        let creds = Credentials::new_pkce(&self.client_id);
+        
+        let mut spotify = AuthCodePkceSpotify::new(creds, oauth_config(redirect_uri));
+        
+        // Generer l'URL d'autorisation
+        let url = spotify.get_authorize_url(None).unwrap_or_default();
+        
+        // Sauvegarder pour completer l'auth plus tard
+        self.client = Some(spotify);
+        
+        url
+    }
+
+    /// Complete l'authentification avec le code callback
+    pub async fn complete_auth(&mut self, code: String) -> Result<(), SpotifyError> {
+        if let Some(ref mut spotify) = self.client {
+            // Echanger le code contre un token (pas de retry: un code ne sert qu'une fois)
+            spotify
+                .request_token(&code)
+                .await
+                .map_err(|e| SpotifyError::Api(format!("Erreur token: {}", e)))?;
+            
+            self.authenticated = true;
+            Ok(())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Verifie si l'utilisateur est authentifie
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    /// Expiration du jeton d'acces courant
+    pub async fn token_expires_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let spotify = self.client.as_ref()?;
+        let token = spotify.token.lock().await.ok()?;
+        token.as_ref()?.expires_at
+    }
+
+    /// Renouvelle d'avance le jeton d'acces avec le refresh token, en reessayant les echecs
+    /// reseau; une autre erreur signifie que le compte doit etre reconnecte
+    pub async fn refresh_access_token(&self) -> Result<(), SpotifyError> {
+        let Some(ref spotify) = self.client else {
+            return Err(SpotifyError::NotInitialized);
+        };
+        if !self.authenticated {
+            return Err(SpotifyError::NotAuthenticated);
+        }
+        let has_refresh_token = match spotify.token.lock().await {
+            Ok(token) => token.as_ref().is_some_and(|t| t.refresh_token.is_some()),
+            Err(_) => false,
+        };
+        if !has_refresh_token {
+            return Err(SpotifyError::Api("Aucun refresh token, reconnectez le compte".to_string()));
+        }
+
+        let mut attempt = 0;
+        loop {
+            match spotify.refresh_token().await {
+                Ok(()) => {
+                    tracing::info!(profile = %self.profile, "Jeton Spotify renouvele");
+                    return Ok(());
+                }
+                Err(e) => {
+                    let err = SpotifyError::from(e);
+                    match refresh_retry_delay(&err, attempt, rand::random()) {
+                        Some(delay) => {
+                            tracing::warn!(profile = %self.profile, attempt, delay_ms = delay.as_millis() as u64, "Renouvellement du jeton en echec, nouvel essai: {}", err);
+                            tokio::time::sleep(delay).await;
+                            attempt += 1;
+                        }
+                        None => return Err(err),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Recupere toutes les playlists de l'utilisateur (toutes les pages)
+    pub async fn get_playlists(&self) -> Result<Vec<SpotifyPlaylist>, SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let mut result: Vec<SpotifyPlaylist> = Vec::new();
+            let mut offset = 0;
+
+            loop {
+                let page = with_retry(|| {
+                    spotify.current_user_playlists_manual(Some(PLAYLIST_PAGE_SIZE), Some(offset))
+                })
+                .await?;
+
+                let fetched = page.items.len() as u32;
+                result.extend(page.items.into_iter().map(SpotifyPlaylist::from));
+
+                // Arreter quand il n'y a plus de page suivante (ou une page vide par securite)
+                if page.next.is_none() || fetched == 0 {
+                    break;
+                }
+                offset += fetched;
+            }
+
+            Ok(result)
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Playlists personnalisees du compte (Discover Weekly, Release Radar, Daily Mix 1-6),
+    /// retrouvees par recherche; celles que le compte n'a pas sont omises
+    pub async fn made_for_you_playlists(&self) -> Result<Vec<SpotifyPlaylist>, SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let mut result = Vec::new();
+            for name in MADE_FOR_YOU_NAMES {
+                let found = with_retry(|| {
+                    spotify.search(name, SearchType::Playlist, None, None, Some(MADE_FOR_YOU_SEARCH_LIMIT), None)
+                })
+                .await?;
+                if let SearchResult::Playlists(page) = found {
+                    let candidates = page.items.into_iter().map(SpotifyPlaylist::from).collect();
+                    result.extend(find_made_for_you(name, candidates));
+                }
+            }
+
+            tracing::info!(profile = %self.profile, count = result.len(), "Playlists personnalisees trouvees");
+            Ok(result)
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Recherche des playlists, albums et titres dans le catalogue Spotify
+    pub async fn search_spotify(
+        &self,
+        query: &str,
+        types: &[String],
+        limit: u32,
+    ) -> Result<SpotifySearchResults, SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let query = query.trim();
+            if query.is_empty() {
+                return Err(SpotifyError::InvalidInput("Recherche vide".to_string()));
+            }
+
+            let search_types = parse_search_types(types).map_err(SpotifyError::InvalidInput)?;
+            let limit = limit.clamp(1, SEARCH_MAX_LIMIT);
+            let mut results = SpotifySearchResults::default();
+
+            // L'API ne renvoie qu'un type par requete pour SearchResult
+            for search_type in search_types {
+                let result = with_retry(|| {
+                    spotify.search(query, search_type, None, None, Some(limit), None)
+                })
+                .await?;
+
+                match result {
+                    SearchResult::Playlists(page) => {
+                        results.playlists = page.items.into_iter().map(SpotifyPlaylist::from).collect();
+                    }
+                    SearchResult::Albums(page) => {
+                        results.albums = page
+                            .items
+                            .into_iter()
+                            .filter_map(|a| {
+                                let id = a.id?;
+                                Some(SpotifyAlbum {
+                                    id: id.id().to_string(),
+                                    uri: id.uri(),
+                                    name: a.name,
+                                    image_url: a.images.first().map(|img| img.url.clone()),
+                                    artist: join_artists(&a.artists),
+                                })
+                            })
+                            .collect();
+                    }
+                    SearchResult::Tracks(page) => {
+                        results.tracks = page
+                            .items
+                            .into_iter()
+                            .filter_map(|t| {
+                                // Les titres locaux n'ont pas d'ID et ne sont pas lisibles a distance
+                                let id = t.id?;
+                                Some(SpotifyTrack {
+                                    id: id.id().to_string(),
+                                    uri: id.uri(),
+                                    name: t.name,
+                                    image_url: t.album.images.first().map(|img| img.url.clone()),
+                                    artist: join_artists(&t.artists),
+                                    album: t.album.name,
+                                    duration_ms: t.duration.num_milliseconds().max(0) as u64,
+                                })
+                            })
+                            .collect();
+                    }
+                    _ => {}
+                }
+            }
+
+            Ok(results)
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Lance la lecture d'une playlist
+    pub async fn play_playlist(&self, playlist_uri: &str) -> Result<(), SpotifyError> {
+        self.start_playlist(playlist_uri, false).await
+    }
+
+    /// Lance une playlist depuis son premier titre, aleatoire desactive
+    /// (l'ordre de lecture doit suivre celui de `playlist_track_durations`)
+    pub async fn play_playlist_in_order(&self, playlist_uri: &str) -> Result<(), SpotifyError> {
+        self.start_playlist(playlist_uri, true).await
+    }
+
+    /// Lance la playlist d'une alarme, en commencant par son titre d'ouverture s'il y en a un
+    pub async fn play_alarm_playlist(&self, playlist_uri: &str, first_track: Option<&str>) -> Result<(), SpotifyError> {
+        self.start_playlist(playlist_uri, false).await?;
+        match first_track {
+            Some(track_uri) => self.play_track_next(track_uri).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Joue un titre immediatement puis laisse le lecteur reprendre le contexte en cours:
+    /// le titre est place en tete de file d'attente et le lecteur y saute
+    pub async fn play_track_next(&self, track_uri: &str) -> Result<(), SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let track_id = rspotify::model::TrackId::from_id_or_uri(track_uri)
+                .map_err(|e| SpotifyError::InvalidInput(format!("ID titre invalide: {:?}", e)))?;
+            with_retry(|| spotify.add_item_to_queue(rspotify::model::PlayableId::Track(track_id.clone()), None)).await?;
+            with_retry(|| spotify.next_track(None)).await?;
+
+            tracing::info!(profile = %self.profile, track = %track_uri, "Titre d'ouverture lance");
+            Ok(())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Ajoute des titres ou episodes a la file d'attente, dans l'ordre: ils passent
+    /// apres le titre en cours, avant la suite de la playlist
+    pub async fn queue_tracks(&self, uris: &[String]) -> Result<(), SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let ids = uris.iter().map(|uri| playable_id(uri)).collect::<Result<Vec<_>, _>>()?;
+            for id in ids {
+                with_retry(|| spotify.add_item_to_queue(id.clone(), None)).await?;
+            }
+
+            tracing::info!(profile = %self.profile, count = uris.len(), "Titres ajoutes a la file d'attente");
+            Ok(())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// URI du titre (ou episode) en cours de lecture
+    pub async fn current_track_uri(&self) -> Result<Option<String>, SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let episodes = [rspotify::model::AdditionalType::Episode];
+            let playing = with_retry(|| spotify.current_playing(None, Some(&episodes))).await?;
+            Ok(playing
+                .and_then(|p| p.item)
+                .and_then(|item| item.id().map(|id| id.uri())))
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Etat du lecteur (None si aucun appareil ne lit ni n'est en pause)
+    pub async fn playback_status(&self) -> Result<Option<PlaybackStatus>, SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let episodes = [rspotify::model::AdditionalType::Episode];
+            let playback = with_retry(|| spotify.current_playback(None, Some(&episodes))).await?;
+            Ok(playback.map(|p| PlaybackStatus {
+                is_playing: p.is_playing,
+                has_item: p.item.is_some(),
+                device: p.device.name,
+                volume_percent: p.device.volume_percent.map(|v| v.min(100) as u8),
+            }))
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Sonde le lecteur jusqu'a `PLAYBACK_VERIFY_SECS` pour confirmer qu'un titre joue vraiment
+    /// (une commande acceptee par l'API ne garantit pas que la musique sorte).
+    /// `require_volume` exige un volume non nul (faux pendant un fondu d'entree, qui part de 0).
+    pub async fn verify_playback(&self, require_volume: bool) -> Result<bool, SpotifyError> {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(PLAYBACK_VERIFY_SECS);
+        loop {
+            let audible = self.playback_status().await?.is_some_and(|s| s.is_audible(require_volume));
+            if audible {
+                return Ok(true);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(Duration::from_millis(PLAYBACK_POLL_INTERVAL_MS)).await;
+        }
+    }
+
+    async fn start_playlist(&self, playlist_uri: &str, in_order: bool) -> Result<(), SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            // Verifier qu'un appareil actif existe (sinon lancer Spotify localement)
+            let target_device = self.ensure_device(spotify).await?;
+
+            // Demarrer la lecture avec l'URI de contexte
+            // Extraire l'ID de la playlist depuis l'URI (format: spotify:playlist:ID)
+            let playlist_id = playlist_uri
+                .strip_prefix("spotify:playlist:")
+                .unwrap_or(playlist_uri);
+            
+            let context = rspotify::model::PlayContextId::Playlist(
+                rspotify::model::PlaylistId::from_id(playlist_id)
+                    .map_err(|e| SpotifyError::InvalidInput(format!("ID playlist invalide: {:?}", e)))?
+            );
+            
+            if in_order {
+                with_retry(|| spotify.shuffle(false, target_device.as_deref())).await?;
+            }
+            with_retry(|| {
+                spotify.start_context_playback(context.clone(), target_device.as_deref(), None, None)
+            })
+            .await?;
+
+            tracing::info!(profile = %self.profile, playlist = %playlist_uri, device = ?target_device, "Lecture Spotify demarree");
+            Ok(())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Durees des premiers titres d'une playlist, dans l'ordre de la playlist
+    pub async fn playlist_track_durations(&self, playlist_uri: &str, limit: u32) -> Result<Vec<Duration>, SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let playlist_id = rspotify::model::PlaylistId::from_id_or_uri(playlist_uri)
+                .map_err(|e| SpotifyError::InvalidInput(format!("ID playlist invalide: {:?}", e)))?;
+            let page = with_retry(|| {
+                spotify.playlist_items_manual(playlist_id.clone(), None, None, Some(limit.min(PLAYLIST_PAGE_SIZE)), Some(0))
+            })
+            .await?;
+
+            // Les titres indisponibles (track absent) sont sautes par le lecteur
+            Ok(page.items
+                .into_iter()
+                .filter_map(|item| match item.track? {
+                    rspotify::model::PlayableItem::Track(t) => Some(t.duration),
+                    rspotify::model::PlayableItem::Episode(e) => Some(e.duration),
+                })
+                .map(|d| d.to_std().unwrap_or_default())
+                .collect())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// S'assure qu'un appareil peut recevoir la lecture.
+    /// Les appareils preferes joignables sont essayes dans l'ordre; a defaut, l'appareil
+    /// actif garde la lecture, sinon elle est transferee vers le premier appareil connu
+    /// (apres lancement du client local si besoin).
+    /// Retourne None si l'appareil retenu est deja actif, sinon l'ID de l'appareil
+    /// vers lequel la lecture a ete transferee.
+    async fn ensure_device(&self, spotify: &AuthCodePkceSpotify) -> Result<Option<String>, SpotifyError> {
+        let devices: Vec<SpotifyDevice> = with_retry(|| spotify.device()).await?
+            .into_iter()
+            .map(SpotifyDevice::from)
+            .filter(|d| !d.id.is_empty())
+            .collect();
+
+        for device in preferred_devices(&devices, &self.device_preference) {
+            if device.is_active {
+                return Ok(None);
+            }
+            match with_retry(|| spotify.transfer_playback(&device.id, Some(false))).await {
+                Ok(()) => return Ok(Some(device.id.clone())),
+                Err(e) => tracing::warn!(device = %device.name, "Appareil prefere injoignable: {}", e),
+            }
+        }
+
+        if devices.iter().any(|d| d.is_active) {
+            return Ok(None);
+        }
+
+        // Un appareil inactif est deja connu: inutile de lancer le client local
+        if let Some(device) = devices.first() {
+            with_retry(|| spotify.transfer_playback(&device.id, Some(false))).await?;
+            return Ok(Some(device.id.clone()));
+        }
+
+        if launch_local_spotify().is_err() {
+            return Err(SpotifyError::NoActiveDevice);
+        }
+
+        // Attendre que le client local s'enregistre aupres de Spotify Connect
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(DEVICE_WAIT_TIMEOUT_SECS);
+        while tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(DEVICE_POLL_INTERVAL_MS)).await;
+
+            let devices = with_retry(|| spotify.device()).await?;
+            if let Some(id) = devices.into_iter().find_map(|d| d.id) {
+                with_retry(|| spotify.transfer_playback(&id, Some(false))).await?;
+                return Ok(Some(id));
+            }
+        }
+
+        Err(SpotifyError::NoActiveDevice)
+    }
+
+    /// Garde un appareil Spotify Connect eveille avant une alarme, sans lancer de lecture:
+    /// le premier appareil prefere joignable (sinon l'appareil actif, sinon le premier connu)
+    /// recoit la lecture en pause s'il est inactif, puis un reglage de volume inchange.
+    /// Retourne le nom de l'appareil reveille.
+    pub async fn keep_device_alive(&self) -> Result<String, SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let devices: Vec<SpotifyDevice> = with_retry(|| spotify.device()).await?
+                .into_iter()
+                .map(SpotifyDevice::from)
+                .filter(|d| !d.id.is_empty())
+                .collect();
+            let device = preferred_devices(&devices, &self.device_preference)
+                .into_iter()
+                .next()
+                .or_else(|| devices.iter().find(|d| d.is_active))
+                .or_else(|| devices.first())
+                .ok_or(SpotifyError::NoActiveDevice)?;
+
+            if !device.is_active {
+                with_retry(|| spotify.transfer_playback(&device.id, Some(false))).await?;
+            }
+            with_retry(|| spotify.volume(device.volume_percent, Some(&device.id))).await?;
+
+            tracing::info!(profile = %self.profile, device = %device.name, "Appareil Spotify garde eveille");
+            Ok(device.name.clone())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Met la lecture en pause
+    pub async fn pause(&self) -> Result<(), SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            with_retry(|| spotify.pause_playback(None)).await?;
+
+            Ok(())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Regle le volume de lecture
+    pub async fn set_volume(&self, volume_percent: u8) -> Result<(), SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let volume = volume_percent.min(100);
+
+            with_retry(|| spotify.volume(volume, None)).await?;
+
+            Ok(())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Regle le volume d'un appareil precis
+    pub async fn set_device_volume(&self, device_id: &str, volume_percent: u8) -> Result<(), SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            with_retry(|| spotify.volume(volume_percent.min(100), Some(device_id))).await?;
+
+            Ok(())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+
+    /// Recupere les appareils disponibles
+    pub async fn get_devices(&self) -> Result<Vec<SpotifyDevice>, SpotifyError> {
+        if let Some(ref spotify) = self.client {
+            if !self.authenticated {
+                return Err(SpotifyError::NotAuthenticated);
+            }
+
+            let devices = with_retry(|| spotify.device()).await?;
+
+            Ok(devices.into_iter().map(SpotifyDevice::from).collect())
+        } else {
+            Err(SpotifyError::NotInitialized)
+        }
+    }
+}
+
+/// Etat du lecteur Spotify Connect
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PlaybackStatus {
+    pub is_playing: bool,
+    pub has_item: bool, // Un titre ou episode est charge
+    pub device: String,
+    pub volume_percent: Option<u8>, // Inconnu pour certains appareils
+}
+
+impl PlaybackStatus {
+    /// La musique sort reellement (un volume inconnu est presume audible)
+    pub fn is_audible(&self, require_volume: bool) -> bool {
+        self.is_playing && self.has_item && (!require_volume || self.volume_percent != Some(0))
+    }
+}
+
+/// Appareil Spotify pour l'affichage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpotifyDevice {
+    pub id: String,
+    pub name: String,
+    pub device_type: String,
+    pub is_active: bool,
+    pub volume_percent: u8,
+}
+
+impl From<rspotify::model::Device> for SpotifyDevice {
+    fn from(d: rspotify::model::Device) -> Self {
+        Self {
+            id: d.id.unwrap_or_default(),
+            name: d.name,
+            device_type: format!("{:?}", d._type),
+            is_active: d.is_active,
+            volume_percent: d.volume_percent.unwrap_or(0).min(100) as u8,
+        }
+    }
+}
+
+/// Volumes des appareils Spotify releves avant qu'une sonnerie n'impose le sien
+#[derive(Debug, Clone)]
+pub struct SavedDeviceVolumes {
+    pub started_at: chrono::DateTime<chrono::Local>, // Sonnerie concernee
+    pub account: Option<String>,                     // Compte Spotify de l'alarme
+    pub devices: Vec<SpotifyDevice>,
+}
+
+impl SavedDeviceVolumes {
+    /// Appareil actif dont le volume a change depuis le releve, avec son volume d'origine
+    pub fn to_restore(&self, current: &[SpotifyDevice]) -> Option<(String, u8)> {
+        let active = current.iter().find(|d| d.is_active && !d.id.is_empty())?;
+        let before = self.devices.iter().find(|d| d.id == active.id)?;
+        (before.volume_percent != active.volume_percent).then(|| (active.id.clone(), before.volume_percent))
+    }
+}
+
+/// Titre ("spotify:track:...") ou episode ("spotify:episode:...") a mettre en file d'attente
+fn playable_id(uri: &str) -> Result<rspotify::model::PlayableId<'static>, SpotifyError> {
+    let id = if uri.starts_with("spotify:episode:") {
+        rspotify::model::EpisodeId::from_uri(uri).map(|id| rspotify::model::PlayableId::Episode(id.into_static()))
+    } else {
+        rspotify::model::TrackId::from_uri(uri).map(|id| rspotify::model::PlayableId::Track(id.into_static()))
+    };
+    id.map_err(|e| SpotifyError::InvalidInput(format!("URI de titre invalide {}: {:?}", uri, e)))
+}
+
+/// Appareils joignables de la liste de preference, dans l'ordre de la liste
+/// (une entree designe un appareil par son id ou son nom, sans tenir compte de la casse)
+pub fn preferred_devices<'a>(devices: &'a [SpotifyDevice], preference: &[String]) -> Vec<&'a SpotifyDevice> {
+    let mut result: Vec<&SpotifyDevice> = Vec::new();
+    for wanted in preference.iter().map(|w| w.trim()).filter(|w| !w.is_empty()) {
+        let found = devices.iter().find(|d| d.id == wanted || d.name.eq_ignore_ascii_case(wanted));
+        if let Some(device) = found.filter(|d| !result.iter().any(|r| r.id == d.id)) {
+            result.push(device);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_types() {
+        assert_eq!(parse_search_types(&[]).unwrap().len(), 3);
+
+        let types = vec!["Track".to_string(), "album".to_string(), "track".to_string()];
+        assert_eq!(
+            parse_search_types(&types).unwrap(),
+            vec![SearchType::Track, SearchType::Album]
+        );
+
+        assert!(parse_search_types(&["artist".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_find_made_for_you() {
+        let playlist = |name: &str, owner: &str| SpotifyPlaylist {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            uri: format!("spotify:playlist:{}", name.len()),
+            image_url: None,
+            track_count: 30,
+            owner: owner.to_string(),
+        };
+        let candidates = vec![
+            playlist("Daily Mix 1 - Best of", "Spotify"),
+            playlist("Daily Mix 1", "Fan"),
+            playlist("Daily Mix 1", "Spotify"),
+        ];
+        let found = find_made_for_you("daily mix 1", candidates).unwrap();
+        assert_eq!((found.name.as_str(), found.owner.as_str()), ("Daily Mix 1", "Spotify"));
+        assert!(find_made_for_you("Release Radar", vec![playlist("Release Radar", "Fan")]).is_none());
+    }
+
+    #[test]
+    fn test_preferred_devices() {
+        let device = |id: &str, name: &str, is_active: bool| SpotifyDevice {
+            id: id.to_string(),
+            name: name.to_string(),
+            device_type: "Speaker".to_string(),
+            is_active,
+            volume_percent: 50,
+        };
+        let devices = vec![device("d1", "Bureau", true), device("s1", "Enceinte chambre", false)];
+        let preference = vec![
+            "Cuisine".to_string(), // Hors ligne: ignore
+            "enceinte CHAMBRE".to_string(),
+            "d1".to_string(),
+            "s1".to_string(),
+        ];
+        let ids: Vec<&str> = preferred_devices(&devices, &preference).iter().map(|d| d.id.as_str()).collect();
+        assert_eq!(ids, vec!["s1", "d1"]);
+        assert!(preferred_devices(&devices, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_playback_audible() {
+        let status = PlaybackStatus {
+            is_playing: true,
+            has_item: true,
+            device: "Enceinte chambre".to_string(),
+            volume_percent: Some(0),
+        };
+        assert!(status.is_audible(false));
+        assert!(!status.is_audible(true));
+        assert!(PlaybackStatus { volume_percent: None, ..status.clone() }.is_audible(true));
+        assert!(!PlaybackStatus { is_playing: false, volume_percent: Some(60), ..status.clone() }.is_audible(true));
+        assert!(!PlaybackStatus { has_item: false, volume_percent: Some(60), ..status }.is_audible(true));
+    }
+
+    #[test]
+    fn test_volume_to_restore() {
+        let device = |id: &str, is_active: bool, volume_percent: u8| SpotifyDevice {
+            id: id.to_string(),
+            name: id.to_string(),
+            device_type: "Speaker".to_string(),
+            is_active,
+            volume_percent,
+        };
+        let saved = SavedDeviceVolumes {
+            started_at: chrono::Local::now(),
+            account: None,
+            devices: vec![device("d1", true, 25), device("s1", false, 40)],
+        };
+        // L'alarme a ete transferee sur l'enceinte puis montee a 80 %
+        assert_eq!(saved.to_restore(&[device("d1", false, 25), device("s1", true, 80)]), Some(("s1".to_string(), 40)));
+        assert_eq!(saved.to_restore(&[device("d1", true, 25)]), None);
+        assert_eq!(saved.to_restore(&[device("x1", true, 80)]), None);
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let transient = SpotifyError::Transient("timeout".to_string());
+        assert_eq!(retry_delay(&transient, 0), Some(Duration::from_millis(500)));
+        assert_eq!(retry_delay(&transient, 2), Some(Duration::from_millis(2000)));
+        assert_eq!(retry_delay(&transient, MAX_RETRIES), None);
+
+        let limited = SpotifyError::RateLimited { retry_after_secs: 4 };
+        assert_eq!(retry_delay(&limited, 0), Some(Duration::from_secs(4)));
+        let too_long = SpotifyError::RateLimited { retry_after_secs: 120 };
+        assert_eq!(retry_delay(&too_long, 0), None);
+
+        assert_eq!(retry_delay(&SpotifyError::NotAuthenticated, 0), None);
+
+        assert_eq!(refresh_retry_delay(&transient, 1, 0.0), Some(Duration::from_secs(4)));
+        assert_eq!(refresh_retry_delay(&transient, 1, 1.0), Some(Duration::from_secs(6)));
+        assert_eq!(refresh_retry_delay(&transient, TOKEN_REFRESH_RETRIES, 0.0), None);
+        let invalid_grant = SpotifyError::Api("Erreur API: statut HTTP 400".to_string());
+        assert_eq!(refresh_retry_delay(&invalid_grant, 0, 0.0), None);
+    }
+
+    #[test]
+    fn test_playlist_cache_freshness() {
+        let cache = PlaylistCache {
+            fetched_at: 1_000,
+            playlists: Vec::new(),
+        };
+        assert!(cache.is_fresh(600, 1_000));
+        assert!(cache.is_fresh(600, 1_599));
+        assert!(!cache.is_fresh(600, 1_600));
+        assert!(!cache.is_fresh(0, 1_000));
+        // Horloge revenue en arriere: on considere le cache perime
+        assert!(!cache.is_fresh(600, 999));
+    }
+}
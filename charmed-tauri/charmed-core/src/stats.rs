@@ -0,0 +1,176 @@
+// stats.rs - Statistiques de reveil calculees depuis l'historique des sonneries
+// (heure moyenne de lever, reports par jour, delai d'arret, serie sans report)
+
+use std::collections::BTreeMap;
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Weekday};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::weekday_to_string;
+use crate::history::{AlarmOutcome, HistoryEntry};
+
+/// Periode couverte par les statistiques
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatsRange {
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+impl StatsRange {
+    fn days(self) -> Option<i64> {
+        match self {
+            StatsRange::Week => Some(7),
+            StatsRange::Month => Some(30),
+            StatsRange::Year => Some(365),
+            StatsRange::All => None,
+        }
+    }
+}
+
+/// Reports d'un jour de la semaine
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WeekdaySnoozes {
+    pub weekday: String, // "Monday", ...
+    pub snoozes: u32,
+}
+
+/// Statistiques pour l'ecran dedie
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WakeStats {
+    pub wake_ups: u32, // Jours avec au moins une alarme arretee
+    pub average_wake_time: Option<String>, // "HH:MM", heure moyenne du premier arret du jour
+    pub average_dismiss_seconds: Option<u64>, // Delai moyen entre la sonnerie et son arret
+    pub snoozes_by_weekday: Vec<WeekdaySnoozes>, // Lundi a dimanche
+    pub missed: u32,
+    pub no_snooze_streak: u32, // Jours de reveil consecutifs sans report (le plus recent compris)
+}
+
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun,
+];
+
+/// Bilan d'une journee
+#[derive(Default)]
+struct Day {
+    woke_at: Option<DateTime<Local>>, // Premier arret
+    snoozed: bool,
+}
+
+/// Calcule les statistiques des entrees commencees dans la periode
+/// (les rappels des alarmes a intervalle et les tests ne sont pas des reveils)
+pub fn compute(entries: &[HistoryEntry], range: StatsRange, now: DateTime<Local>) -> WakeStats {
+    let since = range.days().map(|days| now - chrono::Duration::days(days));
+    let entries: Vec<_> = entries.iter()
+        .filter(|e| !e.reminder && !e.test && since.is_none_or(|since| e.started_at >= since))
+        .collect();
+
+    let mut days: BTreeMap<NaiveDate, Day> = BTreeMap::new();
+    let mut snoozes = [0u32; 7];
+    let mut dismiss_seconds = Vec::new();
+    let mut missed = 0;
+
+    for entry in &entries {
+        let day = days.entry(entry.started_at.date_naive()).or_default();
+        match entry.outcome {
+            AlarmOutcome::Dismissed => {
+                if day.woke_at.is_none_or(|woke_at| entry.ended_at < woke_at) {
+                    day.woke_at = Some(entry.ended_at);
+                }
+                dismiss_seconds.push(entry.ended_at.signed_duration_since(entry.started_at).num_seconds().max(0) as u64);
+            }
+            AlarmOutcome::Snoozed => {
+                day.snoozed = true;
+                snoozes[entry.started_at.weekday().num_days_from_monday() as usize] += 1;
+            }
+            AlarmOutcome::Missed => missed += 1,
+        }
+    }
+
+    let wake_times: Vec<u64> = days.values()
+        .filter_map(|day| day.woke_at)
+        .map(|woke_at| u64::from(woke_at.num_seconds_from_midnight()))
+        .collect();
+    let average_wake_time = average(&wake_times)
+        .map(|seconds| format!("{:02}:{:02}", seconds / 3600, seconds % 3600 / 60));
+
+    // Les jours sans reveil (alarme manquee seulement) n'interrompent pas la serie
+    let no_snooze_streak = days.values()
+        .rev()
+        .filter(|day| day.woke_at.is_some() || day.snoozed)
+        .take_while(|day| !day.snoozed)
+        .count() as u32;
+
+    WakeStats {
+        wake_ups: wake_times.len() as u32,
+        average_wake_time,
+        average_dismiss_seconds: average(&dismiss_seconds),
+        snoozes_by_weekday: WEEKDAYS.iter()
+            .zip(snoozes)
+            .map(|(day, snoozes)| WeekdaySnoozes { weekday: weekday_to_string(*day).to_string(), snoozes })
+            .collect(),
+        missed,
+        no_snooze_streak,
+    }
+}
+
+fn average(values: &[u64]) -> Option<u64> {
+    (!values.is_empty()).then(|| values.iter().sum::<u64>() / values.len() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(day: u32, start: (u32, u32), seconds: i64, outcome: AlarmOutcome) -> HistoryEntry {
+        let started_at = Local.with_ymd_and_hms(2024, 3, day, start.0, start.1, 0).unwrap();
+        HistoryEntry {
+            alarm_id: "a1".to_string(),
+            alarm_time: "07:00".to_string(),
+            playlist_name: "Morning".to_string(),
+            started_at,
+            ended_at: started_at + chrono::Duration::seconds(seconds),
+            outcome,
+            fallback: None,
+            reminder: false,
+            test: false,
+        }
+    }
+
+    #[test]
+    fn test_wake_stats() {
+        let entries = vec![
+            // Lundi 11: report puis arret a 7:10
+            entry(11, (7, 0), 60, AlarmOutcome::Snoozed),
+            entry(11, (7, 9), 60, AlarmOutcome::Dismissed),
+            // Mardi 12 et mercredi 13: arret direct a 6:50 et 7:00
+            entry(12, (6, 49), 60, AlarmOutcome::Dismissed),
+            entry(13, (6, 59), 60, AlarmOutcome::Dismissed),
+            // Jeudi 14: manquee
+            entry(14, (7, 0), 600, AlarmOutcome::Missed),
+            // Rappel d'hydratation arrete a 10:00: pas un reveil
+            HistoryEntry { reminder: true, ..entry(14, (9, 59), 60, AlarmOutcome::Dismissed) },
+            // Test de l'alarme le soir: pas un reveil non plus
+            HistoryEntry { test: true, ..entry(14, (22, 0), 60, AlarmOutcome::Dismissed) },
+        ];
+        let now = Local.with_ymd_and_hms(2024, 3, 15, 12, 0, 0).unwrap();
+
+        let stats = compute(&entries, StatsRange::Week, now);
+        assert_eq!(stats.wake_ups, 3);
+        assert_eq!(stats.average_wake_time.as_deref(), Some("07:00"));
+        assert_eq!(stats.average_dismiss_seconds, Some(60));
+        assert_eq!(stats.snoozes_by_weekday[0], WeekdaySnoozes { weekday: "Monday".to_string(), snoozes: 1 });
+        assert_eq!(stats.snoozes_by_weekday[1].snoozes, 0);
+        assert_eq!(stats.missed, 1);
+        assert_eq!(stats.no_snooze_streak, 2);
+
+        // Hors periode: rien a compter
+        let later = now + chrono::Duration::days(30);
+        let empty = compute(&entries, StatsRange::Week, later);
+        assert_eq!(empty.wake_ups, 0);
+        assert_eq!(empty.average_wake_time, None);
+        assert_eq!(compute(&entries, StatsRange::All, later).wake_ups, 3);
+    }
+}
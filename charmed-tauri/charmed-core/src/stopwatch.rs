@@ -0,0 +1,132 @@
+// stopwatch.rs - Chronometre avec tours, persiste sur disque
+// Le depart est note en heure murale: un chronometre lance continue de tourner
+// pendant que l'application est fermee
+
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+const STOPWATCH_FILE: &str = "stopwatch.json";
+
+/// Tour enregistre
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lap {
+    pub index: u32,    // 1 pour le premier tour
+    pub lap_ms: i64,   // Duree du tour
+    pub split_ms: i64, // Temps total au moment du tour
+}
+
+/// Chronometre: temps cumule des periodes terminees, plus la periode en cours
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Stopwatch {
+    #[serde(default)]
+    pub running_since: Option<DateTime<Local>>,
+    #[serde(default)]
+    pub accumulated_ms: i64,
+    #[serde(default)]
+    pub laps: Vec<Lap>,
+}
+
+/// Etat du chronometre transmis au frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct StopwatchStatus {
+    pub running: bool,
+    pub elapsed_ms: i64,
+    pub laps: Vec<Lap>,
+}
+
+impl Stopwatch {
+    /// Temps total ecoule (l'horloge reculee ne retire jamais de temps)
+    pub fn elapsed_ms(&self, now: DateTime<Local>) -> i64 {
+        let current = self.running_since
+            .map(|since| now.signed_duration_since(since).num_milliseconds().max(0))
+            .unwrap_or(0);
+        self.accumulated_ms + current
+    }
+
+    /// Demarre ou reprend (sans effet s'il tourne deja)
+    pub fn start(&mut self, now: DateTime<Local>) {
+        self.running_since.get_or_insert(now);
+    }
+
+    /// Met en pause en cumulant la periode en cours (sans effet s'il est arrete)
+    pub fn stop(&mut self, now: DateTime<Local>) {
+        if self.running_since.is_some() {
+            self.accumulated_ms = self.elapsed_ms(now);
+            self.running_since = None;
+        }
+    }
+
+    /// Enregistre un tour (None si le chronometre est arrete)
+    pub fn lap(&mut self, now: DateTime<Local>) -> Option<Lap> {
+        self.running_since?;
+        let split_ms = self.elapsed_ms(now);
+        let previous = self.laps.last().map(|l| l.split_ms).unwrap_or(0);
+        let lap = Lap { index: self.laps.len() as u32 + 1, lap_ms: split_ms - previous, split_ms };
+        self.laps.push(lap.clone());
+        Some(lap)
+    }
+
+    /// Remet a zero et efface les tours
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn status(&self, now: DateTime<Local>) -> StopwatchStatus {
+        StopwatchStatus {
+            running: self.running_since.is_some(),
+            elapsed_ms: self.elapsed_ms(now),
+            laps: self.laps.clone(),
+        }
+    }
+}
+
+/// Charge le chronometre (remis a zero si absent ou illisible)
+pub fn load(data_dir: &Path) -> Stopwatch {
+    fs::read_to_string(data_dir.join(STOPWATCH_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Sauvegarde le chronometre
+pub fn save(data_dir: &Path, stopwatch: &Stopwatch) -> Result<(), String> {
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Impossible de creer le dossier: {}", e))?;
+    let json = serde_json::to_string_pretty(stopwatch)
+        .map_err(|e| format!("Erreur serialisation: {}", e))?;
+    fs::write(data_dir.join(STOPWATCH_FILE), json)
+        .map_err(|e| format!("Erreur ecriture fichier: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_stopwatch_laps() {
+        let t0 = Local.with_ymd_and_hms(2024, 3, 12, 12, 0, 0).unwrap();
+        let at = |secs: i64| t0 + chrono::Duration::seconds(secs);
+        let mut watch = Stopwatch::default();
+        assert!(watch.lap(t0).is_none());
+
+        watch.start(t0);
+        watch.start(at(5)); // Deja lance: le depart ne bouge pas
+        assert_eq!(watch.lap(at(10)).unwrap().lap_ms, 10_000);
+        watch.stop(at(15));
+        assert_eq!(watch.elapsed_ms(at(100)), 15_000);
+
+        // Reprise apres une pause (ou un redemarrage de l'application)
+        watch = serde_json::from_str(&serde_json::to_string(&watch).unwrap()).unwrap();
+        watch.start(at(100));
+        let lap = watch.lap(at(110)).unwrap();
+        assert_eq!(lap, Lap { index: 2, lap_ms: 15_000, split_ms: 25_000 });
+        assert!(watch.status(at(110)).running);
+
+        watch.reset();
+        assert_eq!(watch.elapsed_ms(at(200)), 0);
+        assert!(watch.laps.is_empty());
+    }
+}
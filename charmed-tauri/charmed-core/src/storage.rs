@@ -0,0 +1,782 @@
+// storage.rs - Persistance des données (alarmes, configuration)
+// TODO: AppConfig sera utilisé pour la configuration Spotify
+
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use notify::{RecursiveMode, Watcher};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+
+use crate::alarm::{AlarmTemplate, MAX_NAP_MINUTES};
+use crate::bedtime::BedtimeSettings;
+use crate::deeplink;
+use crate::library::LibrarySettings;
+use crate::nightmode::NightModeSettings;
+use crate::podcast::PodcastFeed;
+use crate::radio::{self, RadioStation};
+use crate::i18n::{Locale, TimeFormat};
+use crate::remote::RemoteApiSettings;
+use crate::shortcuts::ShortcutSettings;
+use crate::sync::SyncSettings;
+use crate::telemetry::{self, TelemetrySettings};
+use crate::update::UpdateChannel;
+use crate::weather::Location;
+use crate::winddown::WindDown;
+use crate::worldclock;
+use crate::AlarmEntry;
+
+const ALARMS_FILE: &str = "alarms.json";
+const TEMPLATES_FILE: &str = "templates.json";
+
+/// Copies des fichiers precedents, pour recuperer un fichier corrompu
+const BACKUPS_DIR: &str = "backups";
+
+/// Nombre de copies gardees par fichier
+const MAX_BACKUPS: usize = 5;
+
+/// Evenement Tauri emis quand un fichier corrompu a ete mis de cote
+pub const RECOVERED_EVENT: &str = "storage-recovered";
+
+/// Evenement Tauri emis quand la liste des alarmes change (commande, API distante,
+/// synchronisation ou modification de alarms.json par un autre programme)
+pub const ALARMS_CHANGED_EVENT: &str = "alarms-changed";
+
+/// Evenement Tauri emis quand la configuration change
+pub const CONFIG_CHANGED_EVENT: &str = "config-changed";
+
+/// Regroupement des evenements du systeme de fichiers (un editeur ecrit souvent en plusieurs fois)
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Surveillance active de alarms.json (arretee quand elle est liberee)
+pub type AlarmsWatcher = notify::RecommendedWatcher;
+
+/// Migration d'un fichier d'une version de schema a la suivante
+type Migration = fn(serde_json::Value) -> Result<serde_json::Value, String>;
+
+/// Migrations de alarms.json: l'entree `i` passe du schema `i` au schema `i + 1`
+const ALARMS_MIGRATIONS: &[Migration] = &[alarms_v0_to_v1];
+
+/// Migrations de config.json
+const CONFIG_MIGRATIONS: &[Migration] = &[config_v0_to_v1];
+
+/// Version courante du schema de alarms.json
+pub const ALARMS_SCHEMA_VERSION: u32 = ALARMS_MIGRATIONS.len() as u32;
+
+/// Version courante du schema de config.json
+pub const CONFIG_SCHEMA_VERSION: u32 = CONFIG_MIGRATIONS.len() as u32;
+
+/// v1: la liste d'alarmes est enveloppee avec son numero de schema
+fn alarms_v0_to_v1(value: serde_json::Value) -> Result<serde_json::Value, String> {
+    match value {
+        serde_json::Value::Array(_) => Ok(serde_json::json!({ "alarms": value })),
+        _ => Err("liste d'alarmes attendue".to_string()),
+    }
+}
+
+/// v1: ajout du numero de schema, contenu inchange
+fn config_v0_to_v1(value: serde_json::Value) -> Result<serde_json::Value, String> {
+    match value {
+        serde_json::Value::Object(_) => Ok(value),
+        _ => Err("objet de configuration attendu".to_string()),
+    }
+}
+
+/// Version de schema d'un fichier (0 = fichier anterieur aux versions)
+fn schema_version(value: &serde_json::Value) -> u64 {
+    value.get("schema_version").and_then(serde_json::Value::as_u64).unwrap_or(0)
+}
+
+/// Refuse un fichier ecrit par une version plus recente de l'application plutot que de l'ecraser.
+/// Retourne sa version de schema.
+fn check_schema(file: &str, value: &serde_json::Value, current: u32) -> Result<u64, String> {
+    let version = schema_version(value);
+    if version > u64::from(current) {
+        return Err(format!(
+            "{} provient d'une version plus recente de l'application (schema {}, maximum {})",
+            file, version, current
+        ));
+    }
+    Ok(version)
+}
+
+/// Amene un fichier a la version courante en appliquant les migrations manquantes dans l'ordre.
+/// Retourne le contenu migre et s'il a change de version.
+fn migrate(file: &str, mut value: serde_json::Value, migrations: &[Migration]) -> Result<(serde_json::Value, bool), String> {
+    let current = migrations.len() as u32;
+    let version = check_schema(file, &value, current)?;
+
+    for (from, migration) in migrations.iter().enumerate().skip(version as usize) {
+        value = migration(value).map_err(|e| format!("Migration de {} (schema {}): {}", file, from, e))?;
+        let object = value.as_object_mut()
+            .ok_or_else(|| format!("Migration de {} (schema {}): objet attendu", file, from))?;
+        object.insert("schema_version".to_string(), (from + 1).into());
+        tracing::info!(file, from, to = from + 1, "Fichier migre");
+    }
+    Ok((value, version < u64::from(current)))
+}
+
+/// Contenu de alarms.json
+#[derive(Serialize)]
+struct AlarmsFileRef<'a> {
+    schema_version: u32,
+    alarms: &'a [AlarmEntry],
+}
+
+#[derive(Deserialize)]
+struct AlarmsFile {
+    alarms: Vec<AlarmEntry>,
+}
+
+/// Contenu de config.json (numero de schema en tete de la configuration)
+#[derive(Serialize)]
+struct ConfigFileRef<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    config: &'a AppConfig,
+}
+
+/// Sauvegarde les alarmes dans un fichier JSON
+pub fn save_alarms(data_dir: &Path, alarms: &[AlarmEntry]) -> Result<(), String> {
+    // Créer le dossier de données si nécessaire
+    if !data_dir.exists() {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    }
+
+    let file_path = data_dir.join(ALARMS_FILE);
+    let file = AlarmsFileRef { schema_version: ALARMS_SCHEMA_VERSION, alarms };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+    
+    fs::write(&file_path, json)
+        .map_err(|e| {
+            tracing::error!(path = %file_path.display(), "Sauvegarde des alarmes impossible: {}", e);
+            format!("Erreur écriture fichier: {}", e)
+        })?;
+    backup_file(data_dir, ALARMS_FILE);
+    
+    tracing::debug!(count = alarms.len(), "Alarmes sauvegardees");
+    Ok(())
+}
+
+/// Charge les alarmes depuis le fichier JSON
+pub fn load_alarms(data_dir: &Path) -> Result<(Vec<AlarmEntry>, Option<Recovery>), String> {
+    let file_path = data_dir.join(ALARMS_FILE);
+    
+    if !file_path.exists() {
+        return Ok((Vec::new(), None));
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Erreur lecture fichier: {}", e))?;
+    
+    let (alarms, migrated, recovery) = match parse_versioned::<AlarmsFile>(ALARMS_FILE, &content, ALARMS_MIGRATIONS)? {
+        Parsed::Valid(file, migrated) => (file.alarms, migrated, None),
+        Parsed::Corrupt(reason) => {
+            tracing::error!(path = %file_path.display(), "Fichier d'alarmes illisible: {}", reason);
+            let (restored, recovery) = recover::<AlarmsFile>(data_dir, ALARMS_FILE, ALARMS_MIGRATIONS, reason)?;
+            let restored = restored.map(|file| file.alarms);
+            let found = restored.is_some();
+            (restored.unwrap_or_default(), found, Some(recovery))
+        }
+    };
+
+    // Reecrire le fichier migre (ou restaure) pour ne plus repasser par les migrations
+    if migrated {
+        save_alarms(data_dir, &alarms)?;
+    }
+    
+    tracing::info!(count = alarms.len(), "Alarmes chargees");
+    Ok((alarms, recovery))
+}
+
+/// Relit alarms.json sans le modifier (ni migration reecrite, ni recuperation):
+/// un fichier en cours d'edition ou invalide est signale par une erreur
+pub fn read_alarms(data_dir: &Path) -> Result<Vec<AlarmEntry>, String> {
+    let content = fs::read_to_string(data_dir.join(ALARMS_FILE))
+        .map_err(|e| format!("Erreur lecture fichier: {}", e))?;
+    match parse_versioned::<AlarmsFile>(ALARMS_FILE, &content, ALARMS_MIGRATIONS)? {
+        Parsed::Valid(file, _) => Ok(file.alarms),
+        Parsed::Corrupt(reason) => Err(format!("Fichier d'alarmes illisible: {}", reason)),
+    }
+}
+
+/// Surveille alarms.json et appelle `on_change` une fois par rafale de modifications.
+/// Le dossier est surveille plutot que le fichier, que les editeurs remplacent souvent.
+/// La surveillance s'arrete quand le watcher retourne est libere.
+pub fn watch_alarms(
+    data_dir: &Path,
+    on_change: impl Fn() + Send + 'static,
+) -> Result<AlarmsWatcher, String> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        let Ok(event) = result else {
+            return;
+        };
+        let touches_alarms = !event.kind.is_access()
+            && event.paths.iter().any(|p| p.file_name().is_some_and(|name| name == ALARMS_FILE));
+        if touches_alarms {
+            let _ = sender.send(());
+        }
+    })
+    .map_err(|e| format!("Surveillance des alarmes impossible: {}", e))?;
+
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    watcher.watch(data_dir, RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Surveillance des alarmes impossible: {}", e))?;
+
+    // Fin du fil quand le watcher (et son emetteur) est libere
+    std::thread::spawn(move || {
+        while receiver.recv().is_ok() {
+            while receiver.recv_timeout(WATCH_DEBOUNCE).is_ok() {}
+            on_change();
+        }
+    });
+    Ok(watcher)
+}
+
+/// Sauvegarde les modeles d'alarmes
+pub fn save_templates(data_dir: &Path, templates: &[AlarmTemplate]) -> Result<(), String> {
+    if !data_dir.exists() {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    }
+
+    let json = serde_json::to_string_pretty(templates)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+
+    fs::write(data_dir.join(TEMPLATES_FILE), json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+/// Charge les modeles d'alarmes
+pub fn load_templates(data_dir: &Path) -> Result<Vec<AlarmTemplate>, String> {
+    let file_path = data_dir.join(TEMPLATES_FILE);
+
+    if !file_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Erreur lecture fichier: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Erreur désérialisation: {}", e))
+}
+
+/// Configuration de l'application
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppConfig {
+    pub spotify_client_id: Option<String>,
+    pub spotify_client_secret: Option<String>,
+    pub spotify_redirect_uri: String,
+    pub default_volume: u8,
+    pub default_fade_in_duration: u16,
+    #[serde(default = "default_playlist_cache_ttl")]
+    pub playlist_cache_ttl: u64, // Secondes
+    #[serde(default)]
+    pub active_spotify_profile: Option<String>,
+    #[serde(default)]
+    pub vacation_until: Option<NaiveDate>, // Alarmes suspendues jusqu'a cette date incluse
+    #[serde(default)]
+    pub holiday_country: Option<String>, // Preset de jours feries ("FR", "BE", ...)
+    #[serde(default)]
+    pub custom_holidays: Vec<NaiveDate>,
+    #[serde(default)]
+    pub weather_location: Option<Location>, // Lieu pour la meteo du reveil
+    #[serde(default)]
+    pub output_device: Option<String>, // Sortie audio locale (None = sortie par defaut)
+    #[serde(default)]
+    pub raise_system_volume: bool, // Reactiver et remonter le volume du systeme pendant une sonnerie
+    #[serde(default = "default_system_volume_min")]
+    pub system_volume_min: u8, // Volume systeme minimum pendant une sonnerie (0-100)
+    #[serde(default)]
+    pub wake_from_sleep: bool, // Programmer le reveil materiel de l'ordinateur avant chaque alarme
+    #[serde(default = "default_snooze_minutes")]
+    pub snooze_minutes: u32, // Duree d'un report manuel
+    #[serde(default)]
+    pub shortcuts: ShortcutSettings, // Raccourcis globaux actifs pendant une sonnerie
+    #[serde(default)]
+    pub calendar_url: Option<String>, // Agenda iCalendar des alarmes calees sur les reunions
+    #[serde(default)]
+    pub hue_bridge: Option<String>, // Adresse du pont Philips Hue appaire
+    #[serde(default)]
+    pub remote_api: RemoteApiSettings, // API REST locale pour piloter l'alarme a distance
+    #[serde(default)]
+    pub bedtime: BedtimeSettings, // Duree de sommeil visee et rappel de coucher
+    #[serde(default)]
+    pub wind_down: Option<WindDown>, // Routine du soir (None = desactivee)
+    #[serde(default)]
+    pub night_mode: Option<NightModeSettings>, // Ecran assombri la nuit (None = desactive)
+    #[serde(default)]
+    pub sync: Option<SyncSettings>, // Synchronisation avec d'autres machines (None = desactivee)
+    #[serde(default)]
+    pub music_library: Option<LibrarySettings>, // Bibliotheque musicale locale (None = aucune)
+    #[serde(default = "radio::default_stations")]
+    pub radio_stations: Vec<RadioStation>, // Stations proposees comme musique d'alarme
+    #[serde(default)]
+    pub podcast_feeds: Vec<PodcastFeed>, // Flux RSS suivis (dernier episode joue au reveil)
+    #[serde(default = "default_spotify_keep_alive_minutes")]
+    pub spotify_keep_alive_minutes: u32, // Reveil de l'appareil Spotify avant l'alarme (0 = desactive)
+    #[serde(default)]
+    pub spotify_devices: Vec<String>, // Appareils Spotify Connect preferes, par priorite (id ou nom)
+    #[serde(default = "default_readiness_check_minutes")]
+    pub readiness_check_minutes: u32, // Verification reseau/compte/appareil avant l'alarme (0 = desactivee)
+    #[serde(default)]
+    pub locale: Locale, // Langue des messages renvoyes par le backend
+    #[serde(default)]
+    pub time_format: TimeFormat, // Affichage des heures (24 h ou 12 h)
+    #[serde(default)]
+    pub world_clocks: Vec<String>, // Fuseaux IANA du panneau d'horloges mondiales
+    #[serde(default)]
+    pub update_channel: UpdateChannel, // Canal des mises a jour automatiques
+    #[serde(default)]
+    pub telemetry: TelemetrySettings, // Telemetrie d'usage anonyme (desactivee par defaut)
+}
+
+fn default_playlist_cache_ttl() -> u64 {
+    3600 // 1 heure
+}
+
+fn default_system_volume_min() -> u8 {
+    70
+}
+
+fn default_snooze_minutes() -> u32 {
+    9
+}
+
+fn default_spotify_keep_alive_minutes() -> u32 {
+    3
+}
+
+fn default_readiness_check_minutes() -> u32 {
+    30
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            spotify_client_id: None,
+            spotify_client_secret: None,
+            spotify_redirect_uri: deeplink::AUTH_REDIRECT_URI.to_string(),
+            default_volume: 80,
+            default_fade_in_duration: 300, // 5 minutes
+            playlist_cache_ttl: default_playlist_cache_ttl(),
+            active_spotify_profile: None,
+            vacation_until: None,
+            holiday_country: None,
+            custom_holidays: Vec::new(),
+            weather_location: None,
+            output_device: None,
+            raise_system_volume: false,
+            system_volume_min: default_system_volume_min(),
+            wake_from_sleep: false,
+            snooze_minutes: default_snooze_minutes(),
+            shortcuts: ShortcutSettings::default(),
+            calendar_url: None,
+            hue_bridge: None,
+            remote_api: RemoteApiSettings::default(),
+            bedtime: BedtimeSettings::default(),
+            wind_down: None,
+            night_mode: None,
+            sync: None,
+            music_library: None,
+            radio_stations: radio::default_stations(),
+            podcast_feeds: Vec::new(),
+            spotify_keep_alive_minutes: default_spotify_keep_alive_minutes(),
+            spotify_devices: Vec::new(),
+            readiness_check_minutes: default_readiness_check_minutes(),
+            locale: Locale::default(),
+            time_format: TimeFormat::default(),
+            world_clocks: Vec::new(),
+            update_channel: UpdateChannel::default(),
+            telemetry: TelemetrySettings::default(),
+        }
+    }
+}
+
+/// Mise a jour partielle de la configuration (champs absents inchanges)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ConfigPatch {
+    pub spotify_client_id: Option<String>,
+    pub spotify_redirect_uri: Option<String>,
+    pub default_volume: Option<u8>,
+    pub default_fade_in_duration: Option<u16>,
+    pub playlist_cache_ttl: Option<u64>,
+    pub locale: Option<Locale>,
+    pub time_format: Option<TimeFormat>,
+    pub raise_system_volume: Option<bool>,
+    pub system_volume_min: Option<u8>,
+    pub wake_from_sleep: Option<bool>,
+    pub snooze_minutes: Option<u32>,
+    pub spotify_keep_alive_minutes: Option<u32>,
+    pub spotify_devices: Option<Vec<String>>,
+    pub readiness_check_minutes: Option<u32>,
+    pub world_clocks: Option<Vec<String>>,
+    pub update_channel: Option<UpdateChannel>,
+    pub telemetry_endpoint: Option<String>, // Chaine vide = aucune adresse de collecte
+}
+
+impl AppConfig {
+    /// Applique une mise a jour partielle apres validation
+    pub fn apply(&mut self, patch: ConfigPatch) -> Result<(), String> {
+        if let Some(uri) = patch.spotify_redirect_uri.as_deref() {
+            let scheme_prefix = format!("{}://", deeplink::SCHEME);
+            if !["http://", "https://", scheme_prefix.as_str()].iter().any(|p| uri.starts_with(p)) {
+                return Err(format!("URI de redirection invalide: {}", uri));
+            }
+        }
+        if patch.default_volume.is_some_and(|v| v > 100) {
+            return Err("Le volume par défaut doit être entre 0 et 100".to_string());
+        }
+        if patch.system_volume_min.is_some_and(|v| v > 100) {
+            return Err("Le volume système minimum doit être entre 0 et 100".to_string());
+        }
+        if patch.snooze_minutes.is_some_and(|m| m == 0 || m > MAX_NAP_MINUTES) {
+            return Err(format!("La durée de report doit être entre 1 et {} minutes", MAX_NAP_MINUTES));
+        }
+        if patch.spotify_keep_alive_minutes.is_some_and(|m| m > MAX_SPOTIFY_KEEP_ALIVE_MINUTES) {
+            return Err(format!(
+                "Le réveil de l'appareil Spotify doit avoir lieu au plus {} minutes avant l'alarme",
+                MAX_SPOTIFY_KEEP_ALIVE_MINUTES
+            ));
+        }
+        if patch.readiness_check_minutes.is_some_and(|m| m > MAX_READINESS_CHECK_MINUTES) {
+            return Err(format!(
+                "La vérification doit avoir lieu au plus {} minutes avant l'alarme",
+                MAX_READINESS_CHECK_MINUTES
+            ));
+        }
+        let world_clocks = patch.world_clocks.as_deref().map(worldclock::normalize_zones).transpose()?;
+        let telemetry_endpoint = patch.telemetry_endpoint.map(|e| e.trim().to_string());
+        if let Some(endpoint) = telemetry_endpoint.as_deref().filter(|e| !e.is_empty()) {
+            telemetry::validate_endpoint(endpoint)?;
+        }
+
+        if let Some(client_id) = patch.spotify_client_id {
+            let client_id = client_id.trim().to_string();
+            self.spotify_client_id = (!client_id.is_empty()).then_some(client_id);
+        }
+        if let Some(uri) = patch.spotify_redirect_uri {
+            self.spotify_redirect_uri = uri;
+        }
+        if let Some(volume) = patch.default_volume {
+            self.default_volume = volume;
+        }
+        if let Some(duration) = patch.default_fade_in_duration {
+            self.default_fade_in_duration = duration;
+        }
+        if let Some(ttl) = patch.playlist_cache_ttl {
+            self.playlist_cache_ttl = ttl;
+        }
+        if let Some(locale) = patch.locale {
+            self.locale = locale;
+        }
+        if let Some(format) = patch.time_format {
+            self.time_format = format;
+        }
+        if let Some(raise) = patch.raise_system_volume {
+            self.raise_system_volume = raise;
+        }
+        if let Some(level) = patch.system_volume_min {
+            self.system_volume_min = level;
+        }
+        if let Some(wake) = patch.wake_from_sleep {
+            self.wake_from_sleep = wake;
+        }
+        if let Some(minutes) = patch.snooze_minutes {
+            self.snooze_minutes = minutes;
+        }
+        if let Some(minutes) = patch.readiness_check_minutes {
+            self.readiness_check_minutes = minutes;
+        }
+        if let Some(minutes) = patch.spotify_keep_alive_minutes {
+            self.spotify_keep_alive_minutes = minutes;
+        }
+        if let Some(devices) = patch.spotify_devices {
+            self.spotify_devices = devices
+                .iter()
+                .map(|d| d.trim().to_string())
+                .filter(|d| !d.is_empty())
+                .collect();
+        }
+        if let Some(zones) = world_clocks {
+            self.world_clocks = zones;
+        }
+        if let Some(channel) = patch.update_channel {
+            self.update_channel = channel;
+        }
+        if let Some(endpoint) = telemetry_endpoint {
+            self.telemetry.endpoint = (!endpoint.is_empty()).then_some(endpoint);
+        }
+        Ok(())
+    }
+}
+
+const CONFIG_FILE: &str = "config.json";
+
+/// Avance maximale du reveil de l'appareil Spotify (au-dela, il se rendort avant l'alarme)
+const MAX_SPOTIFY_KEEP_ALIVE_MINUTES: u32 = 30;
+
+/// Avance maximale de la verification d'avant sonnerie
+const MAX_READINESS_CHECK_MINUTES: u32 = 12 * 60;
+
+/// Sauvegarde la configuration
+pub fn save_config(data_dir: &Path, config: &AppConfig) -> Result<(), String> {
+    if !data_dir.exists() {
+        fs::create_dir_all(data_dir)
+            .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+    }
+
+    let file_path = data_dir.join(CONFIG_FILE);
+    let file = ConfigFileRef { schema_version: CONFIG_SCHEMA_VERSION, config };
+    let json = serde_json::to_string_pretty(&file)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+    
+    fs::write(&file_path, json)
+        .map_err(|e| {
+            tracing::error!(path = %file_path.display(), "Sauvegarde de la configuration impossible: {}", e);
+            format!("Erreur écriture fichier: {}", e)
+        })?;
+    backup_file(data_dir, CONFIG_FILE);
+    
+    Ok(())
+}
+
+/// Derniere modification du fichier de configuration (date de la config pour la synchronisation)
+pub fn config_modified_at(data_dir: &Path) -> Option<DateTime<Utc>> {
+    fs::metadata(data_dir.join(CONFIG_FILE))
+        .and_then(|m| m.modified())
+        .ok()
+        .map(DateTime::<Utc>::from)
+}
+
+/// Charge la configuration
+pub fn load_config(data_dir: &Path) -> Result<(AppConfig, Option<Recovery>), String> {
+    let file_path = data_dir.join(CONFIG_FILE);
+    
+    if !file_path.exists() {
+        return Ok((AppConfig::default(), None));
+    }
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| format!("Erreur lecture fichier: {}", e))?;
+    
+    // Une configuration plus recente est refusee plutot que remplacee par les valeurs par defaut
+    match parse_versioned::<AppConfig>(CONFIG_FILE, &content, CONFIG_MIGRATIONS)? {
+        Parsed::Valid(config, migrated) => {
+            if migrated {
+                save_config(data_dir, &config)?;
+            }
+            Ok((config, None))
+        }
+        Parsed::Corrupt(reason) => {
+            tracing::error!(path = %file_path.display(), "Configuration illisible: {}", reason);
+            let (restored, recovery) = recover::<AppConfig>(data_dir, CONFIG_FILE, CONFIG_MIGRATIONS, reason)?;
+            match restored {
+                Some(config) => {
+                    save_config(data_dir, &config)?;
+                    Ok((config, Some(recovery)))
+                }
+                None => {
+                    tracing::warn!("Aucune sauvegarde lisible, valeurs par defaut utilisees");
+                    Ok((AppConfig::default(), Some(recovery)))
+                }
+            }
+        }
+    }
+}
+
+// -- SAUVEGARDES ET RECUPERATION --
+
+/// Fichier corrompu mis de cote au chargement
+#[derive(Debug, Clone, Serialize)]
+pub struct Recovery {
+    pub file: String,
+    pub reason: String,
+    pub quarantined: PathBuf,            // Fichier corrompu renomme avec la date
+    pub restored_from: Option<PathBuf>,  // Sauvegarde restauree (None = valeurs par defaut)
+    pub recovered_at: DateTime<Local>,
+}
+
+/// Lecture d'un fichier versionne
+enum Parsed<T> {
+    Valid(T, bool),  // Contenu et migration effectuee
+    Corrupt(String), // Illisible: a mettre de cote
+}
+
+/// Lit un fichier versionne en appliquant les migrations.
+/// Erreur uniquement pour un fichier d'une version plus recente de l'application.
+fn parse_versioned<T: DeserializeOwned>(file: &str, content: &str, migrations: &[Migration]) -> Result<Parsed<T>, String> {
+    let value: serde_json::Value = match serde_json::from_str(content) {
+        Ok(value) => value,
+        Err(e) => return Ok(Parsed::Corrupt(e.to_string())),
+    };
+    check_schema(file, &value, migrations.len() as u32)?;
+
+    let parsed = migrate(file, value, migrations).and_then(|(value, migrated)| {
+        serde_json::from_value::<T>(value)
+            .map(|content| (content, migrated))
+            .map_err(|e| e.to_string())
+    });
+    Ok(match parsed {
+        Ok((content, migrated)) => Parsed::Valid(content, migrated),
+        Err(e) => Parsed::Corrupt(e),
+    })
+}
+
+/// Sauvegardes d'un fichier, de la plus recente a la plus ancienne
+fn list_backups(data_dir: &Path, file: &str) -> Vec<PathBuf> {
+    let prefix = format!("{}.", file);
+    let mut backups: Vec<PathBuf> = fs::read_dir(data_dir.join(BACKUPS_DIR))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+                .map(|entry| entry.path())
+                .collect()
+        })
+        .unwrap_or_default();
+    // Noms horodates: l'ordre alphabetique est l'ordre chronologique
+    backups.sort();
+    backups.reverse();
+    backups
+}
+
+/// Copie le fichier qui vient d'etre enregistre et ne garde que les dernieres copies.
+/// Un echec n'empeche pas la sauvegarde.
+fn backup_file(data_dir: &Path, file: &str) {
+    let path = data_dir.join(file);
+    if !path.exists() {
+        return;
+    }
+    let dir = data_dir.join(BACKUPS_DIR);
+    let backup = dir.join(format!("{}.{}", file, Local::now().format("%Y%m%d-%H%M%S-%3f")));
+    if let Err(e) = fs::create_dir_all(&dir).and_then(|_| fs::copy(&path, &backup)) {
+        tracing::warn!(file, "Copie de sauvegarde impossible: {}", e);
+        return;
+    }
+    for old in list_backups(data_dir, file).into_iter().skip(MAX_BACKUPS) {
+        let _ = fs::remove_file(old);
+    }
+}
+
+/// Met de cote un fichier corrompu (renomme avec la date) et relit la plus recente
+/// sauvegarde lisible. Le contenu restaure doit etre reecrit par l'appelant.
+fn recover<T: DeserializeOwned>(
+    data_dir: &Path,
+    file: &str,
+    migrations: &[Migration],
+    reason: String,
+) -> Result<(Option<T>, Recovery), String> {
+    let now = Local::now();
+    let quarantined = data_dir.join(format!("{}.corrupt-{}", file, now.format("%Y%m%d-%H%M%S")));
+    fs::rename(data_dir.join(file), &quarantined)
+        .map_err(|e| format!("Impossible de mettre de cote {}: {}", file, e))?;
+    tracing::warn!(path = %quarantined.display(), "Fichier corrompu mis de cote");
+
+    let restored = list_backups(data_dir, file).into_iter().find_map(|backup| {
+        let content = fs::read_to_string(&backup).ok()?;
+        match parse_versioned::<T>(file, &content, migrations) {
+            Ok(Parsed::Valid(content, _)) => Some((content, backup)),
+            _ => None,
+        }
+    });
+    if let Some((_, backup)) = restored.as_ref() {
+        tracing::info!(path = %backup.display(), "Sauvegarde restauree");
+    }
+
+    let (content, restored_from) = match restored {
+        Some((content, backup)) => (Some(content), Some(backup)),
+        None => (None, None),
+    };
+    Ok((content, Recovery {
+        file: file.to_string(),
+        reason,
+        quarantined,
+        restored_from,
+        recovered_at: now,
+    }))
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_patch() {
+        let mut config = AppConfig::default();
+        let patch: ConfigPatch = serde_json::from_str(r#"{"default_volume": 40, "spotify_client_id": " ", "locale": "en", "time_format": "12h", "world_clocks": ["Asia/Tokyo"]}"#).unwrap();
+        config.apply(patch).unwrap();
+
+        assert_eq!(config.default_volume, 40);
+        assert_eq!(config.default_fade_in_duration, 300); // Inchange
+        assert_eq!(config.spotify_client_id, None);
+        assert_eq!(config.locale, Locale::En);
+        assert_eq!(config.time_format, TimeFormat::H12);
+        assert_eq!(config.spotify_keep_alive_minutes, 3);
+        assert_eq!(config.world_clocks, ["Asia/Tokyo"]);
+
+        let invalid = ConfigPatch {
+            spotify_redirect_uri: Some("localhost:8888".to_string()),
+            default_volume: Some(10),
+            ..Default::default()
+        };
+        assert!(config.apply(invalid).is_err());
+        let unknown_zone = ConfigPatch { world_clocks: Some(vec!["Mars/Olympus".to_string()]), ..Default::default() };
+        assert!(config.apply(unknown_zone).is_err());
+        assert_eq!(config.default_volume, 40); // Rien n'est applique en cas d'erreur
+    }
+
+    #[test]
+    fn test_schema_migrations() {
+        // Fichier anterieur aux versions de schema: liste nue
+        let legacy = serde_json::json!([{ "id": "a1" }]);
+        let (value, migrated) = migrate(ALARMS_FILE, legacy, ALARMS_MIGRATIONS).unwrap();
+        assert!(migrated);
+        assert_eq!(value, serde_json::json!({ "schema_version": 1, "alarms": [{ "id": "a1" }] }));
+        let (_, migrated) = migrate(ALARMS_FILE, value, ALARMS_MIGRATIONS).unwrap();
+        assert!(!migrated);
+        assert!(migrate(ALARMS_FILE, serde_json::json!({ "alarms": [] }), ALARMS_MIGRATIONS).is_err());
+
+        // Fichier d'une version future: refuse plutot qu'ecrase
+        assert!(migrate(CONFIG_FILE, serde_json::json!({ "schema_version": 99 }), CONFIG_MIGRATIONS).is_err());
+
+        // La configuration enregistree porte sa version et se relit telle quelle
+        let config = AppConfig::default();
+        let saved = serde_json::to_value(ConfigFileRef { schema_version: CONFIG_SCHEMA_VERSION, config: &config }).unwrap();
+        assert_eq!(schema_version(&saved), u64::from(CONFIG_SCHEMA_VERSION));
+        assert_eq!(serde_json::from_value::<AppConfig>(saved).unwrap().default_volume, config.default_volume);
+    }
+
+    #[test]
+    fn test_corrupt_file_recovery() {
+        let dir = std::env::temp_dir().join(format!("charmed-recovery-{}", uuid::Uuid::new_v4()));
+        let alarm = AlarmEntry { days: Vec::new(), ..crate::alarm::test_support::alarm("a1", "07:00") };
+        save_alarms(&dir, &[alarm]).unwrap();
+
+        // Fichier tronque: mis de cote, derniere sauvegarde restauree
+        fs::write(dir.join(ALARMS_FILE), r#"{"schema_version": 1, "alarms": [{"id""#).unwrap();
+        assert!(read_alarms(&dir).is_err()); // Lecture seule: le fichier reste en place
+        assert!(dir.join(ALARMS_FILE).exists());
+        let (alarms, recovery) = load_alarms(&dir).unwrap();
+        let recovery = recovery.unwrap();
+        assert_eq!(alarms.len(), 1);
+        assert!(recovery.quarantined.exists());
+        assert!(recovery.restored_from.is_some());
+        assert_eq!(load_alarms(&dir).unwrap().0.len(), 1); // Fichier reecrit
+
+        // Sans sauvegarde: valeurs par defaut, fichier corrompu conserve a part
+        fs::write(dir.join(CONFIG_FILE), "{ not json").unwrap();
+        let (config, recovery) = load_config(&dir).unwrap();
+        assert_eq!(config.default_volume, AppConfig::default().default_volume);
+        assert!(recovery.unwrap().restored_from.is_none());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,549 @@
+// sync.rs - Synchronisation des alarmes et de la configuration entre plusieurs machines
+// via un stockage fourni par l'utilisateur (dossier WebDAV ou bucket compatible S3).
+// Un seul document JSON est partage; en cas de conflit la modification la plus recente
+// (updated_at) l'emporte, alarme par alarme
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::bundle;
+use crate::storage::AppConfig;
+use crate::AlarmEntry;
+
+/// Nom du document partage sur le stockage distant
+pub const SYNC_FILE: &str = "charmed-sync.json";
+
+/// Version du document partage (incrementee a chaque changement incompatible)
+pub const SYNC_VERSION: u32 = 1;
+
+/// Evenement Tauri emis quand une synchronisation a modifie alarmes ou configuration
+pub const SYNC_EVENT: &str = "sync-completed";
+
+/// Etat local de la synchronisation (suppressions, derniere config partagee)
+const SYNC_STATE_FILE: &str = "sync_state.json";
+
+/// Duree de conservation des suppressions, pour les machines restees hors ligne
+const TOMBSTONE_RETENTION_DAYS: i64 = 90;
+
+/// Delai maximal d'une requete vers le stockage distant
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+/// Stockage distant choisi par l'utilisateur. Le mot de passe WebDAV ou la cle
+/// secrete S3 sont ranges dans le trousseau, jamais dans config.json.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SyncBackend {
+    WebDav {
+        url: String, // Dossier WebDAV qui recoit le document
+        username: String,
+    },
+    S3 {
+        endpoint: String, // "https://s3.eu-west-3.amazonaws.com", "http://nas:9000"...
+        bucket: String,
+        region: String,
+        access_key_id: String,
+    },
+}
+
+/// Reglages de la synchronisation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncSettings {
+    pub backend: SyncBackend,
+    #[serde(default = "default_auto")]
+    pub auto: bool, // Synchroniser periodiquement en arriere-plan
+}
+
+fn default_auto() -> bool {
+    true
+}
+
+impl SyncSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        match &self.backend {
+            SyncBackend::WebDav { url, username } => {
+                check_url(url)?;
+                if username.trim().is_empty() {
+                    return Err("Le nom d'utilisateur WebDAV est vide".to_string());
+                }
+            }
+            SyncBackend::S3 { endpoint, bucket, region, access_key_id } => {
+                check_url(endpoint)?;
+                let valid_bucket = !bucket.is_empty()
+                    && bucket.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.');
+                if !valid_bucket {
+                    return Err(format!("Nom de bucket invalide: {}", bucket));
+                }
+                if region.trim().is_empty() || access_key_id.trim().is_empty() {
+                    return Err("La region et l'identifiant de cle S3 sont requis".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn check_url(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| format!("Adresse invalide: {}", url))?;
+    if !matches!(parsed.scheme(), "http" | "https") || parsed.host_str().is_none() {
+        return Err(format!("Adresse invalide: {}", url));
+    }
+    Ok(())
+}
+
+/// Alarme supprimee, pour que la suppression gagne aussi sur les autres machines
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tombstone {
+    pub id: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Document partage sur le stockage distant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncDocument {
+    pub version: u32,
+    pub updated_at: DateTime<Utc>,
+    pub alarms: Vec<AlarmEntry>,
+    #[serde(default)]
+    pub deleted: Vec<Tombstone>,
+    pub config: AppConfig,
+    #[serde(default)]
+    pub config_updated_at: Option<DateTime<Utc>>,
+}
+
+/// Etat local conserve entre deux synchronisations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SyncState {
+    #[serde(default)]
+    pub last_sync: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub deleted: Vec<Tombstone>,
+    #[serde(default)]
+    pub shared_config: Option<serde_json::Value>, // Configuration partagee lors de la derniere synchronisation
+    #[serde(default)]
+    pub config_updated_at: Option<DateTime<Utc>>,
+}
+
+/// Resultat d'une synchronisation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SyncReport {
+    pub synced_at: DateTime<Utc>,
+    pub alarms: usize,
+    pub received: usize, // Alarmes ajoutees ou modifiees depuis une autre machine
+    pub removed: usize,  // Alarmes supprimees sur une autre machine
+    pub config_received: bool,
+}
+
+/// Charge l'etat local (vide si absent ou illisible)
+pub fn load_state(data_dir: &Path) -> SyncState {
+    fs::read_to_string(data_dir.join(SYNC_STATE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Sauvegarde l'etat local
+pub fn save_state(data_dir: &Path, state: &SyncState) -> Result<(), String> {
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Impossible de créer le dossier: {}", e))?;
+
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+    fs::write(data_dir.join(SYNC_STATE_FILE), json)
+        .map_err(|e| format!("Erreur écriture fichier: {}", e))
+}
+
+/// Memorise la suppression d'alarmes pour la prochaine synchronisation
+pub fn record_deletions(data_dir: &Path, ids: &[String]) -> Result<(), String> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let now = Utc::now();
+    let mut state = load_state(data_dir);
+    let deleted = ids.iter().map(|id| Tombstone { id: id.clone(), deleted_at: now }).collect();
+    state.deleted = merge_tombstones(&state.deleted, deleted, now);
+    save_state(data_dir, &state)
+}
+
+/// Configuration partagee: sans secret ni reglages propres a cette machine
+pub fn shared_config(config: &AppConfig) -> AppConfig {
+    let defaults = AppConfig::default();
+    AppConfig {
+        spotify_client_secret: None,
+        output_device: defaults.output_device,
+        hue_bridge: defaults.hue_bridge,
+        remote_api: defaults.remote_api,
+        music_library: defaults.music_library,
+        sync: None,
+        ..config.clone()
+    }
+}
+
+/// Configuration recue d'une autre machine: les reglages propres a celle-ci sont conserves
+pub fn apply_remote_config(current: &AppConfig, remote: AppConfig) -> AppConfig {
+    AppConfig {
+        output_device: current.output_device.clone(),
+        hue_bridge: current.hue_bridge.clone(),
+        remote_api: current.remote_api.clone(),
+        music_library: current.music_library.clone(),
+        sync: current.sync.clone(),
+        ..bundle::replace_config(current, remote)
+    }
+}
+
+/// Fusionne deux listes de suppressions (la plus recente par alarme) et oublie les plus anciennes
+fn merge_tombstones(local: &[Tombstone], remote: Vec<Tombstone>, now: DateTime<Utc>) -> Vec<Tombstone> {
+    let cutoff = now - chrono::Duration::days(TOMBSTONE_RETENTION_DAYS);
+    let mut merged: Vec<Tombstone> = Vec::new();
+    for tombstone in local.iter().cloned().chain(remote) {
+        match merged.iter_mut().find(|t| t.id == tombstone.id) {
+            Some(existing) => existing.deleted_at = existing.deleted_at.max(tombstone.deleted_at),
+            None => merged.push(tombstone),
+        }
+    }
+    merged.retain(|t| t.deleted_at > cutoff);
+    merged
+}
+
+/// Resultat de la fusion des alarmes
+#[derive(Debug, Clone)]
+pub struct AlarmMerge {
+    pub alarms: Vec<AlarmEntry>,
+    pub received: usize,
+    pub removed: usize,
+}
+
+/// Fusionne les alarmes locales et distantes: pour un meme identifiant la plus recente
+/// l'emporte (egalite: la version locale), puis les alarmes supprimees apres leur
+/// derniere modification sont retirees
+pub fn merge_alarms(local: &[AlarmEntry], remote: Vec<AlarmEntry>, deleted: &[Tombstone]) -> AlarmMerge {
+    let mut alarms = local.to_vec();
+    let mut received = 0;
+    for alarm in remote {
+        match alarms.iter_mut().find(|a| a.id == alarm.id) {
+            Some(existing) if alarm.updated_at > existing.updated_at => {
+                *existing = alarm;
+                received += 1;
+            }
+            Some(_) => {}
+            None => {
+                alarms.push(alarm);
+                received += 1;
+            }
+        }
+    }
+
+    let deleted_at: HashMap<&str, DateTime<Utc>> = deleted.iter().map(|t| (t.id.as_str(), t.deleted_at)).collect();
+    let is_deleted = |alarm: &AlarmEntry| {
+        deleted_at.get(alarm.id.as_str()).is_some_and(|at| alarm.updated_at.is_none_or(|updated| *at >= updated))
+    };
+    let removed = local.iter().filter(|a| is_deleted(a)).count();
+    alarms.retain(|a| !is_deleted(a));
+
+    AlarmMerge { alarms, received, removed }
+}
+
+/// Resultat d'un rapprochement entre l'etat local et le document distant
+#[derive(Debug, Clone)]
+pub struct Reconciled {
+    pub alarms: Vec<AlarmEntry>,
+    pub config: Option<AppConfig>, // Configuration distante a appliquer
+    pub document: SyncDocument,    // A envoyer au stockage distant
+    pub state: SyncState,
+    pub report: SyncReport,
+}
+
+/// Rapproche alarmes et configuration locales du document distant (None = premier envoi).
+/// La configuration locale est datee par `config_modified` si elle a change depuis la
+/// derniere synchronisation. Lors de la premiere synchronisation d'une machine, la
+/// configuration distante l'emporte pour ne pas l'ecraser par des reglages par defaut.
+pub fn reconcile(
+    alarms: &[AlarmEntry],
+    config: &AppConfig,
+    config_modified: Option<DateTime<Utc>>,
+    state: &SyncState,
+    remote: Option<SyncDocument>,
+    now: DateTime<Utc>,
+) -> Reconciled {
+    let (remote_alarms, remote_deleted, remote_config) = match remote {
+        Some(doc) => (doc.alarms, doc.deleted, Some((doc.config, doc.config_updated_at))),
+        None => (Vec::new(), Vec::new(), None),
+    };
+
+    let deleted = merge_tombstones(&state.deleted, remote_deleted, now);
+    let merge = merge_alarms(alarms, remote_alarms, &deleted);
+
+    let local_shared = shared_config(config);
+    let local_json = serde_json::to_value(&local_shared).ok();
+    let local_updated_at = if local_json.is_some() && local_json == state.shared_config {
+        state.config_updated_at
+    } else {
+        Some(config_modified.unwrap_or(now))
+    };
+
+    let (shared, applied, config_updated_at) = match remote_config {
+        Some((remote, remote_updated_at)) if state.last_sync.is_none() || remote_updated_at > local_updated_at => {
+            let applied = apply_remote_config(config, remote);
+            (shared_config(&applied), Some(applied), remote_updated_at)
+        }
+        _ => (local_shared, None, local_updated_at),
+    };
+
+    let report = SyncReport {
+        synced_at: now,
+        alarms: merge.alarms.len(),
+        received: merge.received,
+        removed: merge.removed,
+        config_received: applied.is_some(),
+    };
+    Reconciled {
+        state: SyncState {
+            last_sync: Some(now),
+            deleted: deleted.clone(),
+            shared_config: serde_json::to_value(&shared).ok(),
+            config_updated_at,
+        },
+        document: SyncDocument {
+            version: SYNC_VERSION,
+            updated_at: now,
+            alarms: merge.alarms.clone(),
+            deleted,
+            config: shared,
+            config_updated_at,
+        },
+        alarms: merge.alarms,
+        config: applied,
+        report,
+    }
+}
+
+/// Lit le document distant et verifie sa version
+pub fn parse_document(body: &str) -> Result<SyncDocument, String> {
+    let document: SyncDocument = serde_json::from_str(body)
+        .map_err(|e| format!("Document de synchronisation invalide: {}", e))?;
+    if document.version == 0 || document.version > SYNC_VERSION {
+        return Err(format!(
+            "Version de synchronisation non supportee: {} (maximum {})",
+            document.version, SYNC_VERSION
+        ));
+    }
+    Ok(document)
+}
+
+// -- STOCKAGE DISTANT --
+
+/// Client du stockage distant
+pub struct SyncClient {
+    http: reqwest::Client,
+    backend: SyncBackend,
+    secret: String,
+}
+
+impl SyncClient {
+    pub fn new(backend: SyncBackend, secret: String) -> Result<Self, String> {
+        let http = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { http, backend, secret })
+    }
+
+    /// Adresse du document partage
+    fn document_url(&self) -> Result<reqwest::Url, String> {
+        let url = match &self.backend {
+            SyncBackend::WebDav { url, .. } => format!("{}/{}", url.trim_end_matches('/'), SYNC_FILE),
+            SyncBackend::S3 { endpoint, bucket, .. } => {
+                format!("{}/{}/{}", endpoint.trim_end_matches('/'), bucket, SYNC_FILE)
+            }
+        };
+        reqwest::Url::parse(&url).map_err(|e| format!("Adresse invalide: {}", e))
+    }
+
+    fn request(&self, method: reqwest::Method, body: Vec<u8>) -> Result<reqwest::RequestBuilder, String> {
+        let url = self.document_url()?;
+        let request = match &self.backend {
+            SyncBackend::WebDav { username, .. } => self.http
+                .request(method, url)
+                .basic_auth(username, Some(&self.secret)),
+            SyncBackend::S3 { region, access_key_id, .. } => {
+                let signed = sign_s3(method.as_str(), &url, region, access_key_id, &self.secret, &body, Utc::now());
+                signed.into_iter().fold(self.http.request(method, url), |r, (name, value)| r.header(name, value))
+            }
+        };
+        Ok(request.body(body))
+    }
+
+    /// Telecharge le document partage (None s'il n'existe pas encore)
+    pub async fn fetch(&self) -> Result<Option<SyncDocument>, String> {
+        let response = self.request(reqwest::Method::GET, Vec::new())?
+            .send()
+            .await
+            .map_err(|e| format!("Stockage distant injoignable: {}", e))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(format!("Stockage distant: lecture refusee ({})", response.status()));
+        }
+        let body = response.text().await.map_err(|e| e.to_string())?;
+        parse_document(&body).map(Some)
+    }
+
+    /// Envoie le document partage
+    pub async fn upload(&self, document: &SyncDocument) -> Result<(), String> {
+        let body = serde_json::to_vec_pretty(document)
+            .map_err(|e| format!("Erreur sérialisation: {}", e))?;
+        let response = self.request(reqwest::Method::PUT, body)?
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .send()
+            .await
+            .map_err(|e| format!("Stockage distant injoignable: {}", e))?;
+        if !response.status().is_success() {
+            return Err(format!("Stockage distant: envoi refuse ({})", response.status()));
+        }
+        Ok(())
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepte toute longueur de cle");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// En-tetes d'une requete S3 signee (AWS Signature Version 4, adressage par chemin)
+fn sign_s3(
+    method: &str,
+    url: &reqwest::Url,
+    region: &str,
+    access_key_id: &str,
+    secret: &str,
+    body: &[u8],
+    now: DateTime<Utc>,
+) -> Vec<(&'static str, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+    let payload_hash = hex(&Sha256::digest(body));
+
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method,
+        url.path(),
+        url.query().unwrap_or_default(),
+        host,
+        payload_hash,
+        amz_date,
+        signed_headers,
+        payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+
+    let key = ["s3", "aws4_request"].iter().fold(
+        hmac_sha256(&hmac_sha256(format!("AWS4{}", secret).as_bytes(), &date), region),
+        |key, part| hmac_sha256(&key, part),
+    );
+    let signature = hex(&hmac_sha256(&key, &string_to_sign));
+
+    vec![
+        ("x-amz-date", amz_date),
+        ("x-amz-content-sha256", payload_hash),
+        (
+            "authorization",
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                access_key_id, scope, signed_headers, signature
+            ),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    use crate::alarm::test_support;
+
+    fn alarm(id: &str, time: &str, updated_at: Option<DateTime<Utc>>) -> AlarmEntry {
+        AlarmEntry { updated_at, ..test_support::alarm(id, time) }
+    }
+
+    #[test]
+    fn test_reconcile_newest_wins() {
+        let at = |h| Some(Utc.with_ymd_and_hms(2024, 3, 12, h, 0, 0).unwrap());
+        let now = at(12).unwrap();
+
+        // "a" modifiee plus recemment a distance, "b" localement, "c" supprimee ailleurs, "d" nouvelle
+        let local = vec![alarm("a", "07:00", at(8)), alarm("b", "08:00", at(10)), alarm("c", "09:00", at(8))];
+        let remote = SyncDocument {
+            version: SYNC_VERSION,
+            updated_at: now,
+            alarms: vec![alarm("a", "06:30", at(9)), alarm("b", "08:30", at(9)), alarm("d", "10:00", at(9))],
+            deleted: vec![Tombstone { id: "c".to_string(), deleted_at: at(9).unwrap() }],
+            config: AppConfig { default_volume: 40, ..Default::default() },
+            config_updated_at: at(9),
+        };
+        let state = SyncState { last_sync: at(7), ..Default::default() };
+        let config = AppConfig { output_device: Some("USB".to_string()), ..Default::default() };
+
+        // Configuration locale modifiee a 11h: plus recente que la distante
+        let result = reconcile(&local, &config, at(11), &state, Some(remote.clone()), now);
+        let times: Vec<&str> = result.alarms.iter().map(|a| a.time.as_str()).collect();
+        assert_eq!(times, ["06:30", "08:00", "10:00"]);
+        assert_eq!((result.report.received, result.report.removed), (2, 1));
+        assert!(result.config.is_none());
+        assert_eq!(result.document.config_updated_at, at(11));
+        assert!(result.document.config.output_device.is_none()); // Reglage propre a la machine
+
+        // Configuration locale inchangee depuis la derniere synchronisation: la distante l'emporte
+        let state = SyncState {
+            shared_config: serde_json::to_value(shared_config(&config)).ok(),
+            config_updated_at: at(6),
+            ..state
+        };
+        let result = reconcile(&local, &config, at(11), &state, Some(remote.clone()), now);
+        let applied = result.config.unwrap();
+        assert_eq!(applied.default_volume, 40);
+        assert_eq!(applied.output_device.as_deref(), Some("USB"));
+        assert_eq!(result.state.config_updated_at, at(9));
+
+        let newer = SyncDocument { version: SYNC_VERSION + 1, ..remote };
+        assert!(parse_document(&serde_json::to_string(&newer).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_sign_s3() {
+        let url = reqwest::Url::parse("http://nas.local:9000/alarms/charmed-sync.json").unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 3, 12, 7, 0, 0).unwrap();
+        let headers = sign_s3("PUT", &url, "us-east-1", "AKID", "SECRET", b"{}", now);
+
+        assert_eq!(headers[0], ("x-amz-date", "20240312T070000Z".to_string()));
+        assert_eq!(headers[1].1, "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a");
+        assert_eq!(
+            headers[2].1,
+            "AWS4-HMAC-SHA256 Credential=AKID/20240312/us-east-1/s3/aws4_request, \
+             SignedHeaders=host;x-amz-content-sha256;x-amz-date, \
+             Signature=d361e3c9cd82c6ca84349fd8b64985f7e4b6f1a0a31f7912febc03591c793d8e"
+        );
+    }
+}
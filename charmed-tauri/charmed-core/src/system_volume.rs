@@ -0,0 +1,179 @@
+// system_volume.rs - Volume principal du systeme (mixeur de l'OS)
+// Lu, remonte puis restaure autour d'une sonnerie: une alarme a 100% reste muette
+// si le systeme est coupe. macOS: osascript, Linux: pactl, Windows: Core Audio via PowerShell.
+
+use std::process::Command;
+use serde::{Deserialize, Serialize};
+
+/// Volume principal du systeme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MasterVolume {
+    pub level: u8, // 0-100
+    pub muted: bool,
+}
+
+impl MasterVolume {
+    /// Volume garantissant une alarme audible: son reactive et niveau au moins `min_level`
+    pub fn raised(self, min_level: u8) -> Self {
+        Self {
+            level: self.level.max(min_level.min(100)),
+            muted: false,
+        }
+    }
+}
+
+/// Execute une commande et retourne sa sortie standard
+fn run(command: &mut Command) -> Result<String, String> {
+    let output = command
+        .output()
+        .map_err(|e| format!("Mixeur systeme indisponible: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "Mixeur systeme en echec: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Premier pourcentage d'une sortie `pactl get-sink-volume` ("... / 50% / ...")
+#[cfg_attr(any(target_os = "macos", target_os = "windows"), allow(dead_code))]
+fn parse_percent(text: &str) -> Option<u8> {
+    text.split_whitespace()
+        .find_map(|word| word.strip_suffix('%'))
+        .and_then(|value| value.parse::<u32>().ok())
+        .map(|value| value.min(100) as u8)
+}
+
+#[cfg(target_os = "macos")]
+pub fn get_master_volume() -> Result<MasterVolume, String> {
+    let settings = run(Command::new("osascript").args([
+        "-e",
+        "set s to get volume settings",
+        "-e",
+        "return (output volume of s as text) & \",\" & (output muted of s as text)",
+    ]))?;
+    let (level, muted) = settings
+        .split_once(',')
+        .ok_or_else(|| format!("Volume systeme illisible: {}", settings))?;
+    Ok(MasterVolume {
+        level: level.trim().parse().map_err(|_| format!("Volume systeme illisible: {}", settings))?,
+        muted: muted.trim() == "true",
+    })
+}
+
+#[cfg(target_os = "macos")]
+pub fn set_master_volume(volume: MasterVolume) -> Result<(), String> {
+    let muted = if volume.muted { "with" } else { "without" };
+    let script = format!("set volume output volume {} {} output muted", volume.level.min(100), muted);
+    run(Command::new("osascript").args(["-e", &script])).map(|_| ())
+}
+
+#[cfg(target_os = "windows")]
+const CORE_AUDIO_SCRIPT: &str = r#"
+Add-Type -TypeDefinition @'
+using System;
+using System.Runtime.InteropServices;
+[Guid("5CDF2C82-841E-4546-9722-0CF74078229A"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown), ComImport]
+interface IAudioEndpointVolume {
+    int f(); int g(); int h(); int i();
+    int SetMasterVolumeLevelScalar(float level, Guid context);
+    int j();
+    int GetMasterVolumeLevelScalar(out float level);
+    int k(); int l(); int m(); int n();
+    int SetMute([MarshalAs(UnmanagedType.Bool)] bool mute, Guid context);
+    int GetMute(out bool mute);
+}
+[Guid("D666063F-1587-4E43-81F1-B948E807363F"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown), ComImport]
+interface IMMDevice {
+    int Activate(ref Guid id, int clsCtx, int activationParams, out IAudioEndpointVolume endpoint);
+}
+[Guid("A95664D2-9614-4F35-A746-DE8DB63617E6"), InterfaceType(ComInterfaceType.InterfaceIsIUnknown), ComImport]
+interface IMMDeviceEnumerator {
+    int f();
+    int GetDefaultAudioEndpoint(int dataFlow, int role, out IMMDevice endpoint);
+}
+[ComImport, Guid("BCDE0395-E52F-467C-8E3D-C4579291692E")] class MMDeviceEnumerator { }
+public static class MasterAudio {
+    static IAudioEndpointVolume Endpoint() {
+        var enumerator = (IMMDeviceEnumerator)new MMDeviceEnumerator();
+        IMMDevice device;
+        Marshal.ThrowExceptionForHR(enumerator.GetDefaultAudioEndpoint(0, 1, out device));
+        IAudioEndpointVolume endpoint;
+        var id = typeof(IAudioEndpointVolume).GUID;
+        Marshal.ThrowExceptionForHR(device.Activate(ref id, 23, 0, out endpoint));
+        return endpoint;
+    }
+    public static float Level {
+        get { float v; Marshal.ThrowExceptionForHR(Endpoint().GetMasterVolumeLevelScalar(out v)); return v; }
+        set { Marshal.ThrowExceptionForHR(Endpoint().SetMasterVolumeLevelScalar(value, Guid.Empty)); }
+    }
+    public static bool Muted {
+        get { bool m; Marshal.ThrowExceptionForHR(Endpoint().GetMute(out m)); return m; }
+        set { Marshal.ThrowExceptionForHR(Endpoint().SetMute(value, Guid.Empty)); }
+    }
+}
+'@
+"#;
+
+#[cfg(target_os = "windows")]
+fn powershell(body: &str) -> Result<String, String> {
+    let script = format!("{}{}", CORE_AUDIO_SCRIPT, body);
+    run(Command::new("powershell").args(["-NoProfile", "-NonInteractive", "-Command", &script]))
+}
+
+#[cfg(target_os = "windows")]
+pub fn get_master_volume() -> Result<MasterVolume, String> {
+    let settings = powershell("[int][math]::Round([MasterAudio]::Level * 100); [MasterAudio]::Muted")?;
+    let mut lines = settings.lines().map(str::trim);
+    let level = lines.next().and_then(|l| l.parse::<u8>().ok());
+    let muted = lines.next().map(|l| l.eq_ignore_ascii_case("true"));
+    match (level, muted) {
+        (Some(level), Some(muted)) => Ok(MasterVolume { level: level.min(100), muted }),
+        _ => Err(format!("Volume systeme illisible: {}", settings)),
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub fn set_master_volume(volume: MasterVolume) -> Result<(), String> {
+    let body = format!(
+        "[MasterAudio]::Level = {}; [MasterAudio]::Muted = ${}",
+        f32::from(volume.level.min(100)) / 100.0,
+        volume.muted
+    );
+    powershell(&body).map(|_| ())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn get_master_volume() -> Result<MasterVolume, String> {
+    let volume = run(Command::new("pactl").args(["get-sink-volume", "@DEFAULT_SINK@"]))?;
+    let mute = run(Command::new("pactl").args(["get-sink-mute", "@DEFAULT_SINK@"]))?;
+    Ok(MasterVolume {
+        level: parse_percent(&volume).ok_or_else(|| format!("Volume systeme illisible: {}", volume))?,
+        muted: mute.ends_with("yes"),
+    })
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn set_master_volume(volume: MasterVolume) -> Result<(), String> {
+    let level = format!("{}%", volume.level.min(100));
+    run(Command::new("pactl").args(["set-sink-volume", "@DEFAULT_SINK@", &level]))?;
+    let mute = if volume.muted { "1" } else { "0" };
+    run(Command::new("pactl").args(["set-sink-mute", "@DEFAULT_SINK@", mute])).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_master_volume() {
+        let muted = MasterVolume { level: 30, muted: true };
+        assert_eq!(muted.raised(70), MasterVolume { level: 70, muted: false });
+        assert_eq!(MasterVolume { level: 90, muted: false }.raised(70).level, 90);
+
+        let pactl = "Volume: front-left: 32768 /  50% / -18,06 dB,   front-right: 32768 /  50% / -18,06 dB";
+        assert_eq!(parse_percent(pactl), Some(50));
+        assert_eq!(parse_percent("Volume: inconnu"), None);
+    }
+}
@@ -0,0 +1,176 @@
+// telemetry.rs - Telemetrie d'usage anonyme, desactivee par defaut
+// Seuls des evenements de fiabilite sont collectes (sonnerie, repli sur le son local, erreur
+// d'un service), sans identifiant d'alarme, nom de playlist ni URI, et a l'heure pres.
+// Les evenements attendent dans une file locale et partent par lots vers l'adresse configuree.
+
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{DateTime, DurationRound, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::FallbackReason;
+use crate::query::ProviderKind;
+
+const QUEUE_FILE: &str = "telemetry_queue.json";
+
+/// Evenements gardes hors ligne (les plus anciens sont oublies)
+pub const MAX_QUEUE_EVENTS: usize = 500;
+
+/// Evenements envoyes par requete
+pub const BATCH_SIZE: usize = 100;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Reglages de la telemetrie
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TelemetrySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub endpoint: Option<String>, // Adresse de collecte (None = rien n'est envoye)
+    #[serde(default)]
+    pub install_id: Option<String>, // Identifiant aleatoire, renouvele a chaque activation
+}
+
+impl TelemetrySettings {
+    /// Active ou desactive la collecte. Chaque activation tire un nouvel identifiant:
+    /// rien ne relie deux periodes d'activation.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.enabled {
+            self.install_id = Some(uuid::Uuid::new_v4().to_string());
+        }
+        if !enabled {
+            self.install_id = None;
+        }
+        self.enabled = enabled;
+    }
+}
+
+/// Verifie une adresse de collecte (https uniquement: les lots passent par Internet)
+pub fn validate_endpoint(endpoint: &str) -> Result<(), String> {
+    if !endpoint.starts_with("https://") || endpoint.len() <= "https://".len() {
+        return Err(format!("Adresse de telemetrie invalide (https requis): {}", endpoint));
+    }
+    Ok(())
+}
+
+/// Nature d'un evenement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryKind {
+    AlarmFired,
+    FallbackUsed,  // Son local joue a la place de la playlist
+    ProviderError, // Lecture refusee par un service de musique
+}
+
+/// Evenement anonymise
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TelemetryEvent {
+    pub kind: TelemetryKind,
+    #[serde(default)]
+    pub provider: Option<ProviderKind>,
+    #[serde(default)]
+    pub fallback: Option<FallbackReason>,
+    pub hour: DateTime<Utc>, // Heure de l'evenement arrondie a l'heure inferieure
+}
+
+impl TelemetryEvent {
+    pub fn new(kind: TelemetryKind, provider: Option<ProviderKind>, fallback: Option<FallbackReason>, now: DateTime<Utc>) -> Self {
+        Self {
+            kind,
+            provider,
+            fallback,
+            hour: now.duration_trunc(chrono::Duration::hours(1)).unwrap_or(now),
+        }
+    }
+}
+
+/// Lot envoye a l'adresse de collecte
+#[derive(Debug, Serialize)]
+struct Batch<'a> {
+    install_id: &'a str,
+    app_version: &'a str,
+    os: &'static str,
+    events: &'a [TelemetryEvent],
+}
+
+/// Charge la file d'attente (vide si absente ou illisible)
+pub fn load_queue(data_dir: &Path) -> Vec<TelemetryEvent> {
+    fs::read_to_string(data_dir.join(QUEUE_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Sauvegarde la file d'attente
+pub fn save_queue(data_dir: &Path, queue: &[TelemetryEvent]) -> Result<(), String> {
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Impossible de creer le dossier: {}", e))?;
+    let json = serde_json::to_string(queue)
+        .map_err(|e| format!("Erreur serialisation: {}", e))?;
+    fs::write(data_dir.join(QUEUE_FILE), json)
+        .map_err(|e| format!("Erreur ecriture fichier: {}", e))
+}
+
+/// Ajoute un evenement a la file d'attente
+pub fn enqueue(data_dir: &Path, event: TelemetryEvent) -> Result<(), String> {
+    let mut queue = load_queue(data_dir);
+    queue.push(event);
+    let excess = queue.len().saturating_sub(MAX_QUEUE_EVENTS);
+    queue.drain(..excess);
+    save_queue(data_dir, &queue)
+}
+
+/// Envoie un lot d'evenements
+pub async fn send(endpoint: &str, install_id: &str, app_version: &str, events: &[TelemetryEvent]) -> Result<(), String> {
+    let batch = Batch { install_id, app_version, os: std::env::consts::OS, events };
+    let body = serde_json::to_vec(&batch).map_err(|e| format!("Erreur serialisation: {}", e))?;
+    let response = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?
+        .post(endpoint)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("Telemetrie injoignable: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Telemetrie: envoi refuse ({})", response.status()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_telemetry_queue() {
+        let mut settings = TelemetrySettings::default();
+        assert!(!settings.enabled && settings.install_id.is_none());
+        settings.set_enabled(true);
+        let first = settings.install_id.clone().unwrap();
+        settings.set_enabled(false);
+        assert!(settings.install_id.is_none());
+        settings.set_enabled(true);
+        assert_ne!(settings.install_id.as_deref(), Some(first.as_str()));
+
+        let now = Utc.with_ymd_and_hms(2024, 3, 12, 7, 42, 13).unwrap();
+        let event = TelemetryEvent::new(TelemetryKind::FallbackUsed, None, Some(FallbackReason::Offline), now);
+        assert_eq!(event.hour, Utc.with_ymd_and_hms(2024, 3, 12, 7, 0, 0).unwrap());
+
+        let dir = std::env::temp_dir().join(format!("charmed-telemetry-{}", uuid::Uuid::new_v4()));
+        for _ in 0..MAX_QUEUE_EVENTS + 3 {
+            enqueue(&dir, event.clone()).unwrap();
+        }
+        assert_eq!(load_queue(&dir).len(), MAX_QUEUE_EVENTS);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(validate_endpoint("https://telemetry.example.com/v1").is_ok());
+        assert!(validate_endpoint("http://telemetry.example.com").is_err());
+    }
+}
@@ -0,0 +1,83 @@
+// timer.rs - Minuteurs a rebours (cuisine, lecture, pause...)
+// Independants des alarmes: pas de playlist ni de defi, juste un carillon a la fin
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{self, Locale};
+
+/// Duree maximale d'un minuteur (24 heures)
+pub const MAX_TIMER_SECONDS: u32 = 24 * 60 * 60;
+
+/// Minuteur en cours, jamais persiste (comme les siestes)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountdownTimer {
+    pub id: String,
+    pub label: String,
+    pub seconds: u32, // Duree demandee
+    pub started_at: DateTime<Local>,
+    pub ends_at: DateTime<Local>,
+}
+
+impl CountdownTimer {
+    /// Cree un minuteur qui se termine `seconds` secondes apres `now`
+    pub fn new(label: String, seconds: u32, now: DateTime<Local>) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            label,
+            seconds,
+            started_at: now,
+            ends_at: now + chrono::Duration::seconds(i64::from(seconds)),
+        }
+    }
+
+    /// Secondes restantes (0 une fois termine)
+    pub fn remaining_secs(&self, now: DateTime<Local>) -> u32 {
+        u32::try_from(self.ends_at.signed_duration_since(now).num_seconds().max(0)).unwrap_or(u32::MAX)
+    }
+
+    pub fn is_finished(&self, now: DateTime<Local>) -> bool {
+        self.ends_at <= now
+    }
+}
+
+/// Retire et retourne les minuteurs termines a `now`, dans l'ordre de fin
+pub fn take_finished(timers: &mut Vec<CountdownTimer>, now: DateTime<Local>) -> Vec<CountdownTimer> {
+    let (mut finished, pending): (Vec<_>, Vec<_>) = timers.drain(..).partition(|t| t.is_finished(now));
+    *timers = pending;
+    finished.sort_by_key(|t| t.ends_at);
+    finished
+}
+
+/// Titre et corps de la notification de fin de minuteur
+pub fn finished_text(locale: Locale, timer: &CountdownTimer) -> (String, String) {
+    let duration = i18n::format_duration(locale, i64::from(timer.seconds));
+    match locale {
+        Locale::Fr => (timer.label.clone(), format!("Minuteur de {} termine", duration)),
+        Locale::En => (timer.label.clone(), format!("{} timer finished", duration)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_take_finished() {
+        let now = Local.with_ymd_and_hms(2024, 3, 12, 12, 0, 0).unwrap();
+        let tea = CountdownTimer::new("The".to_string(), 180, now);
+        let pasta = CountdownTimer::new("Pates".to_string(), 600, now);
+        let eggs = CountdownTimer::new("Oeufs".to_string(), 120, now);
+        let mut timers = vec![tea, pasta, eggs];
+
+        assert_eq!(timers[0].remaining_secs(now + chrono::Duration::seconds(30)), 150);
+        assert!(take_finished(&mut timers, now + chrono::Duration::seconds(119)).is_empty());
+
+        let done = take_finished(&mut timers, now + chrono::Duration::minutes(3));
+        let labels: Vec<&str> = done.iter().map(|t| t.label.as_str()).collect();
+        assert_eq!(labels, ["Oeufs", "The"]);
+        assert_eq!(timers.len(), 1);
+        assert_eq!(timers[0].remaining_secs(now + chrono::Duration::hours(1)), 0);
+    }
+}
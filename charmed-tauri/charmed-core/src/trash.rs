@@ -0,0 +1,91 @@
+// trash.rs - Corbeille des alarmes supprimees (annulation d'une suppression accidentelle)
+
+use std::fs;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::{tr, Locale, Msg};
+use crate::AlarmEntry;
+
+const TRASH_FILE: &str = "trash.json";
+
+/// Nombre d'alarmes conservees dans la corbeille (les plus anciennes sont oubliees)
+pub const MAX_TRASH_ENTRIES: usize = 50;
+
+/// Alarme supprimee, restaurable
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrashedAlarm {
+    pub alarm: AlarmEntry,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Place une alarme supprimee en tete de corbeille
+pub fn push(trash: &mut Vec<TrashedAlarm>, alarm: AlarmEntry, now: DateTime<Utc>) {
+    trash.retain(|t| t.alarm.id != alarm.id);
+    trash.insert(0, TrashedAlarm { alarm, deleted_at: now });
+    trash.truncate(MAX_TRASH_ENTRIES);
+}
+
+/// Retire une alarme de la corbeille pour la restaurer.
+/// La date de modification est mise a jour pour que la restauration
+/// l'emporte sur la suppression deja transmise aux machines synchronisees.
+pub fn take(
+    trash: &mut Vec<TrashedAlarm>,
+    alarm_id: &str,
+    now: DateTime<Utc>,
+    locale: Locale,
+) -> Result<AlarmEntry, String> {
+    let position = trash
+        .iter()
+        .position(|t| t.alarm.id == alarm_id)
+        .ok_or_else(|| tr(locale, Msg::AlarmNotFound, &[&alarm_id]))?;
+    let mut alarm = trash.remove(position).alarm;
+    alarm.updated_at = Some(now);
+    Ok(alarm)
+}
+
+/// Charge la corbeille (vide si absente ou illisible)
+pub fn load(data_dir: &Path) -> Vec<TrashedAlarm> {
+    fs::read_to_string(data_dir.join(TRASH_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Sauvegarde la corbeille
+pub fn save(data_dir: &Path, trash: &[TrashedAlarm]) -> Result<(), String> {
+    fs::create_dir_all(data_dir)
+        .map_err(|e| format!("Impossible de creer le dossier: {}", e))?;
+    let json = serde_json::to_string_pretty(trash)
+        .map_err(|e| format!("Erreur serialisation: {}", e))?;
+    fs::write(data_dir.join(TRASH_FILE), json)
+        .map_err(|e| format!("Erreur ecriture fichier: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_trash_restore() {
+        let t0 = Utc.with_ymd_and_hms(2024, 3, 12, 7, 0, 0).unwrap();
+        let alarm: AlarmEntry = serde_json::from_str(
+            r#"{"id":"a","time":"07:00","playlist_name":"Alarme","playlist_uri":"local","volume":50,"active":true,"days":[],"fade_in":false,"fade_in_duration":0}"#,
+        )
+        .unwrap();
+
+        let mut trash = Vec::new();
+        push(&mut trash, alarm.clone(), t0);
+        push(&mut trash, AlarmEntry { id: "b".to_string(), ..alarm.clone() }, t0);
+        assert_eq!(trash[0].alarm.id, "b");
+
+        let later = t0 + chrono::Duration::minutes(1);
+        let restored = take(&mut trash, "a", later, Locale::Fr).unwrap();
+        assert_eq!(restored.updated_at, Some(later));
+        assert_eq!(trash.len(), 1);
+        assert!(take(&mut trash, "a", later, Locale::Fr).is_err());
+    }
+}
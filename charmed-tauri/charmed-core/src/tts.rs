@@ -0,0 +1,159 @@
+// tts.rs - Annonce vocale de l'heure et de la meteo au reveil
+// Utilise la synthese vocale du systeme (say, SAPI via PowerShell, speech-dispatcher/espeak)
+
+use std::process::Command;
+use chrono::{NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+use crate::i18n::Locale;
+use crate::weather::{Condition, WakeWeather};
+
+/// Delai maximal avant l'annonce (secondes)
+pub const MAX_ANNOUNCEMENT_DELAY: u32 = 600;
+
+/// Annonce vocale configuree sur une alarme
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Announcement {
+    #[serde(default = "default_delay")]
+    pub delay_seconds: u32, // Delai apres le debut de la sonnerie (0 = immediat)
+    #[serde(default = "default_duck_volume")]
+    pub duck_volume: u8, // Volume de la musique pendant l'annonce (0 = silence)
+    #[serde(default = "default_include_weather")]
+    pub include_weather: bool,
+}
+
+fn default_delay() -> u32 {
+    10
+}
+
+fn default_duck_volume() -> u8 {
+    20
+}
+
+fn default_include_weather() -> bool {
+    true
+}
+
+impl Announcement {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.delay_seconds > MAX_ANNOUNCEMENT_DELAY {
+            return Err(format!("Le delai d'annonce doit etre au plus {} secondes", MAX_ANNOUNCEMENT_DELAY));
+        }
+        if self.duck_volume > 100 {
+            return Err("Le volume pendant l'annonce doit etre entre 0 et 100".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn describe_condition(locale: Locale, condition: Condition) -> &'static str {
+    match (locale, condition) {
+        (Locale::Fr, Condition::Clear) => "ciel degage",
+        (Locale::Fr, Condition::Cloudy) => "temps nuageux",
+        (Locale::Fr, Condition::Fog) => "brouillard",
+        (Locale::Fr, Condition::Rain) => "pluie",
+        (Locale::Fr, Condition::Snow) => "neige",
+        (Locale::Fr, Condition::Storm) => "orages",
+        (Locale::En, Condition::Clear) => "clear skies",
+        (Locale::En, Condition::Cloudy) => "cloudy",
+        (Locale::En, Condition::Fog) => "foggy",
+        (Locale::En, Condition::Rain) => "raining",
+        (Locale::En, Condition::Snow) => "snowing",
+        (Locale::En, Condition::Storm) => "stormy",
+    }
+}
+
+/// Texte de l'annonce ("Il est 7 heures, 12 degres, pluie")
+pub fn announcement_text(locale: Locale, time: NaiveTime, weather: Option<&WakeWeather>) -> String {
+    let (hour, minute) = (time.hour(), time.minute());
+    let mut text = match locale {
+        Locale::Fr if minute == 0 => format!("Il est {} heures", hour),
+        Locale::Fr => format!("Il est {} heures {:02}", hour, minute),
+        Locale::En => {
+            let hour12 = match hour % 12 {
+                0 => 12,
+                h => h,
+            };
+            if minute == 0 {
+                format!("It's {} o'clock", hour12)
+            } else {
+                format!("It's {}:{:02}", hour12, minute)
+            }
+        }
+    };
+
+    if let Some(weather) = weather {
+        let degrees = weather.temperature.round() as i64;
+        let condition = describe_condition(locale, weather.condition);
+        match locale {
+            Locale::Fr => text.push_str(&format!(", {} degres, {}", degrees, condition)),
+            Locale::En => text.push_str(&format!(", {} degrees and {}", degrees, condition)),
+        }
+    }
+    text
+}
+
+/// Commandes de synthese vocale a essayer, par ordre de preference
+fn speech_commands(text: &str, locale: Locale) -> Vec<Command> {
+    let lang = match locale {
+        Locale::Fr => "fr",
+        Locale::En => "en",
+    };
+
+    if cfg!(target_os = "macos") {
+        let mut say = Command::new("say");
+        say.arg(text);
+        vec![say]
+    } else if cfg!(target_os = "windows") {
+        let script = format!(
+            "Add-Type -AssemblyName System.Speech; (New-Object System.Speech.Synthesis.SpeechSynthesizer).Speak('{}')",
+            text.replace('\'', "''")
+        );
+        let mut powershell = Command::new("powershell");
+        powershell.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+        vec![powershell]
+    } else {
+        let mut spd = Command::new("spd-say");
+        spd.args(["--wait", "-l", lang, text]);
+        let mut espeak_ng = Command::new("espeak-ng");
+        espeak_ng.args(["-v", lang, text]);
+        let mut espeak = Command::new("espeak");
+        espeak.args(["-v", lang, text]);
+        vec![spd, espeak_ng, espeak]
+    }
+}
+
+/// Prononce le texte et attend la fin de l'annonce (appel bloquant)
+pub fn speak(text: &str, locale: Locale) -> Result<(), String> {
+    let mut last_error = "Aucun moteur de synthese vocale".to_string();
+    for mut command in speech_commands(text, locale) {
+        match command.status() {
+            Ok(status) if status.success() => return Ok(()),
+            Ok(status) => last_error = format!("Synthese vocale en echec ({})", status),
+            Err(e) => last_error = format!("Synthese vocale indisponible: {}", e),
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_announcement_text() {
+        let weather = WakeWeather {
+            condition: Condition::Rain,
+            weather_code: 61,
+            temperature: 11.6,
+            precipitation: 0.4,
+            fetched_at: Utc::now(),
+        };
+        let seven = NaiveTime::from_hms_opt(7, 0, 0).unwrap();
+
+        assert_eq!(announcement_text(Locale::En, seven, Some(&weather)), "It's 7 o'clock, 12 degrees and raining");
+        assert_eq!(announcement_text(Locale::Fr, seven, Some(&weather)), "Il est 7 heures, 12 degres, pluie");
+        assert_eq!(announcement_text(Locale::En, NaiveTime::from_hms_opt(13, 5, 0).unwrap(), None), "It's 1:05");
+    }
+}
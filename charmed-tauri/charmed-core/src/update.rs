@@ -0,0 +1,63 @@
+// update.rs - Canal des mises a jour automatiques et garde-fou avant les alarmes
+// Une installation redemarre l'application: jamais pendant une sonnerie ni juste avant une alarme
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Aucune installation moins de deux heures avant la prochaine alarme (minutes)
+pub const INSTALL_GUARD_MINUTES: i64 = 120;
+
+/// Canal de publication suivi
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateChannel {
+    #[default]
+    Stable,
+    Beta, // Versions de test, publiees avant la version stable
+}
+
+impl UpdateChannel {
+    /// Manifeste des mises a jour du canal (publie avec chaque version)
+    pub fn endpoint(self) -> &'static str {
+        match self {
+            UpdateChannel::Stable => "https://github.com/Lemniscate-world/Charmed/releases/latest/download/latest.json",
+            UpdateChannel::Beta => "https://github.com/Lemniscate-world/Charmed/releases/download/beta/latest.json",
+        }
+    }
+}
+
+/// Mise a jour disponible
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub current_version: String,
+    pub channel: UpdateChannel,
+    pub date: Option<String>,
+    pub notes: Option<String>,
+    pub install_allowed: bool, // Faux pendant une sonnerie ou a l'approche d'une alarme
+}
+
+/// L'installation (et le redemarrage) peut avoir lieu maintenant
+pub fn install_allowed(now: DateTime<Local>, next_alarm: Option<DateTime<Local>>, ringing: bool) -> bool {
+    !ringing && next_alarm.is_none_or(|at| at - now >= chrono::Duration::minutes(INSTALL_GUARD_MINUTES))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_install_guard() {
+        let now = Local.with_ymd_and_hms(2024, 3, 12, 5, 30, 0).unwrap();
+        let at = |h, m| Some(Local.with_ymd_and_hms(2024, 3, 12, h, m, 0).unwrap());
+        assert!(!install_allowed(now, at(7, 0), false));
+        assert!(install_allowed(now, at(7, 30), false));
+        assert!(install_allowed(now, None, false));
+        assert!(!install_allowed(now, None, true));
+
+        let channel: UpdateChannel = serde_json::from_str("\"beta\"").unwrap();
+        assert!(channel.endpoint().contains("/beta/"));
+        assert_eq!(UpdateChannel::default(), UpdateChannel::Stable);
+    }
+}
@@ -0,0 +1,92 @@
+// wake_window.rs - Fenetre de reveil alignee sur les debuts de titres
+// La playlist demarre en silence au debut de la fenetre (ex: 6:50 pour 7:00); l'alarme
+// sonne au debut du dernier titre qui commence dans la fenetre, jamais au milieu d'une chanson
+
+use std::time::Duration;
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+
+/// Duree maximale d'une fenetre de reveil (minutes)
+pub const MAX_WINDOW_MINUTES: u32 = 60;
+
+/// Titres lus pour calculer les debuts de titres (largement assez pour une heure)
+pub const TRACK_LOOKAHEAD: u32 = 50;
+
+/// Fenetre precedant l'heure de l'alarme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WakeWindow {
+    pub minutes: u32,
+}
+
+impl WakeWindow {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.minutes == 0 || self.minutes > MAX_WINDOW_MINUTES {
+            return Err(format!("La fenetre de reveil doit durer entre 1 et {} minutes", MAX_WINDOW_MINUTES));
+        }
+        Ok(())
+    }
+
+    pub fn duration(&self) -> chrono::Duration {
+        chrono::Duration::minutes(i64::from(self.minutes))
+    }
+}
+
+/// Sonnerie planifiee d'une occurrence: la fenetre prend la place du declenchement habituel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WakePlan {
+    pub alarm_at: DateTime<Local>, // Heure de l'alarme (fin de fenetre)
+    pub ring_at: DateTime<Local>,  // Debut de titre retenu
+    pub preroll: bool,             // Playlist deja lancee en silence
+    pub rung: bool,
+}
+
+impl WakePlan {
+    /// Plan sans lecture prealable: sonne a l'heure de l'alarme
+    pub fn at_alarm_time(alarm_at: DateTime<Local>) -> Self {
+        Self { alarm_at, ring_at: alarm_at, preroll: false, rung: false }
+    }
+
+    /// Le plan couvre l'occurrence jusqu'a la fin de la minute de l'alarme
+    pub fn is_expired(&self, now: DateTime<Local>) -> bool {
+        now >= self.alarm_at + chrono::Duration::minutes(1)
+    }
+}
+
+/// Decalage du reveil depuis le debut de la lecture: dernier debut de titre
+/// avant la fin de la fenetre (0 = premier titre si celui-ci couvre toute la fenetre)
+pub fn ring_offset(durations: &[Duration], window: Duration) -> Duration {
+    let mut start = Duration::ZERO;
+    let mut best = Duration::ZERO;
+    for duration in durations {
+        if start > window {
+            break;
+        }
+        best = start;
+        start += *duration;
+    }
+    if start <= window {
+        best = start;
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ring_offset() {
+        let minutes = |m: u64| Duration::from_secs(m * 60);
+        let tracks = [minutes(3), minutes(4), minutes(5)];
+
+        // Debuts: 0, 3, 7, 12 -> dernier avant 10 minutes: 7
+        assert_eq!(ring_offset(&tracks, minutes(10)), minutes(7));
+        assert_eq!(ring_offset(&tracks, minutes(7)), minutes(7));
+        assert_eq!(ring_offset(&tracks, minutes(2)), Duration::ZERO);
+        assert_eq!(ring_offset(&tracks, minutes(20)), minutes(12));
+        assert_eq!(ring_offset(&[], minutes(10)), Duration::ZERO);
+
+        assert!(WakeWindow { minutes: 0 }.validate().is_err());
+        assert!(WakeWindow { minutes: 10 }.validate().is_ok());
+    }
+}
@@ -0,0 +1,199 @@
+// weather.rs - Meteo du reveil via Open-Meteo (sans cle d'API)
+// Permet de choisir une playlist differente les jours de pluie ou de soleil
+
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::AlarmEntry;
+
+/// Point d'acces des previsions Open-Meteo
+const FORECAST_URL: &str = "https://api.open-meteo.com/v1/forecast";
+
+/// Delai maximal d'une requete meteo
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Duree de validite d'un releve (secondes)
+pub const WEATHER_TTL: i64 = 30 * 60;
+
+/// Position utilisee pour la meteo
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Location {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[serde(default)]
+    pub name: Option<String>, // Libelle affiche ("Paris", ...)
+}
+
+impl Location {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(-90.0..=90.0).contains(&self.latitude) || !(-180.0..=180.0).contains(&self.longitude) {
+            return Err(format!("Coordonnees invalides: {}, {}", self.latitude, self.longitude));
+        }
+        Ok(())
+    }
+}
+
+/// Conditions simplifiees issues des codes meteo WMO
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    Clear,
+    Cloudy,
+    Fog,
+    Rain,
+    Snow,
+    Storm,
+}
+
+impl Condition {
+    /// Convertit un code WMO (0-99) en condition
+    pub fn from_wmo_code(code: u8) -> Self {
+        match code {
+            0 | 1 => Condition::Clear,
+            45 | 48 => Condition::Fog,
+            51..=67 | 80..=82 => Condition::Rain,
+            71..=77 | 85 | 86 => Condition::Snow,
+            95..=99 => Condition::Storm,
+            _ => Condition::Cloudy,
+        }
+    }
+
+    /// Precipitations (pluie, neige ou orage)
+    pub fn is_wet(self) -> bool {
+        matches!(self, Condition::Rain | Condition::Snow | Condition::Storm)
+    }
+}
+
+/// Meteo actuelle au lieu configure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WakeWeather {
+    pub condition: Condition,
+    pub weather_code: u8,
+    pub temperature: f64,   // °C
+    pub precipitation: f64, // mm
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl WakeWeather {
+    pub fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        now.signed_duration_since(self.fetched_at).num_seconds() < WEATHER_TTL
+    }
+}
+
+/// Playlist de remplacement
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeatherPlaylist {
+    pub uri: String,
+    pub name: String,
+}
+
+/// Playlists remplacant celle de l'alarme selon la meteo
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct WeatherPlaylists {
+    #[serde(default)]
+    pub rainy: Option<WeatherPlaylist>, // Pluie, neige ou orage
+    #[serde(default)]
+    pub sunny: Option<WeatherPlaylist>, // Ciel degage
+}
+
+impl WeatherPlaylists {
+    /// Playlist a jouer pour ces conditions (None = playlist de l'alarme)
+    pub fn playlist_for(&self, condition: Condition) -> Option<&WeatherPlaylist> {
+        if condition.is_wet() {
+            self.rainy.as_ref()
+        } else if condition == Condition::Clear {
+            self.sunny.as_ref()
+        } else {
+            None
+        }
+    }
+}
+
+/// Remplace la playlist de l'alarme selon la meteo. Retourne true si elle a change.
+pub fn apply_override(alarm: &mut AlarmEntry, weather: &WakeWeather) -> bool {
+    let Some(playlist) = alarm.weather_playlists.as_ref()
+        .and_then(|overrides| overrides.playlist_for(weather.condition))
+        .cloned()
+    else {
+        return false;
+    };
+    alarm.playlist_uri = playlist.uri;
+    alarm.playlist_name = playlist.name;
+    true
+}
+
+#[derive(Deserialize)]
+struct ForecastResponse {
+    current: CurrentWeather,
+}
+
+#[derive(Deserialize)]
+struct CurrentWeather {
+    temperature_2m: f64,
+    weather_code: u8,
+    #[serde(default)]
+    precipitation: f64,
+}
+
+/// Lit une reponse Open-Meteo (`current=temperature_2m,weather_code,precipitation`)
+fn parse_forecast(body: &str, fetched_at: DateTime<Utc>) -> Result<WakeWeather, String> {
+    let response: ForecastResponse = serde_json::from_str(body)
+        .map_err(|e| format!("Reponse meteo invalide: {}", e))?;
+    let current = response.current;
+
+    Ok(WakeWeather {
+        condition: Condition::from_wmo_code(current.weather_code),
+        weather_code: current.weather_code,
+        temperature: current.temperature_2m,
+        precipitation: current.precipitation,
+        fetched_at,
+    })
+}
+
+/// Recupere la meteo actuelle
+pub async fn fetch_weather(location: &Location) -> Result<WakeWeather, String> {
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let body = client
+        .get(FORECAST_URL)
+        .query(&[
+            ("latitude", location.latitude.to_string()),
+            ("longitude", location.longitude.to_string()),
+            ("current", "temperature_2m,weather_code,precipitation".to_string()),
+        ])
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("Erreur requete meteo: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Erreur requete meteo: {}", e))?;
+
+    parse_forecast(&body, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_weather_playlist() {
+        let body = r#"{"latitude":48.86,"longitude":2.34,"current":{"time":"2024-03-12T07:00","interval":900,"temperature_2m":9.4,"weather_code":61,"precipitation":0.6}}"#;
+        let weather = parse_forecast(body, Utc::now()).unwrap();
+        assert_eq!(weather.condition, Condition::Rain);
+        assert_eq!(weather.temperature, 9.4);
+
+        let overrides = WeatherPlaylists {
+            rainy: Some(WeatherPlaylist { uri: "spotify:playlist:rain".to_string(), name: "Pluie".to_string() }),
+            sunny: None,
+        };
+        assert_eq!(overrides.playlist_for(weather.condition).unwrap().name, "Pluie");
+        assert!(overrides.playlist_for(Condition::Clear).is_none());
+        assert_eq!(Condition::from_wmo_code(95), Condition::Storm);
+        assert_eq!(Condition::from_wmo_code(3), Condition::Cloudy);
+    }
+}
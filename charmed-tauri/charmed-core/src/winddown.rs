@@ -0,0 +1,134 @@
+// winddown.rs - Routine du soir: l'inverse du reveil
+// A l'heure choisie, une playlist calme demarre a faible volume puis descend jusqu'au
+// silence (minuteur de sommeil), et une notification rappelle l'heure du coucher
+
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime};
+use serde::{Deserialize, Serialize};
+
+use crate::alarm::weekday_to_string;
+use crate::bedtime::SuggestedBedtime;
+use crate::fade::FadeCurve;
+use crate::i18n::{self, Locale, TimeFormat};
+
+/// Duree maximale de la descente de volume (minutes)
+pub const MAX_WIND_DOWN_MINUTES: u32 = 180;
+
+/// Routine du soir configuree
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindDown {
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub time: String, // "HH:MM"
+    #[serde(default)]
+    pub days: Vec<String>, // Vide = tous les jours
+    pub playlist_name: String,
+    pub playlist_uri: String, // "local" pour le son local
+    #[serde(default)]
+    pub account: Option<String>, // Profil Spotify (None = compte actif)
+    #[serde(default = "default_start_volume")]
+    pub start_volume: u8, // Volume de depart, descendu jusqu'a zero
+    #[serde(default = "default_duration_minutes")]
+    pub duration_minutes: u32, // Duree de la descente avant la pause
+    #[serde(default)]
+    pub fade_curve: FadeCurve,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_start_volume() -> u8 {
+    30
+}
+
+fn default_duration_minutes() -> u32 {
+    30
+}
+
+impl WindDown {
+    pub fn validate(&self) -> Result<(), String> {
+        NaiveTime::parse_from_str(&self.time, "%H:%M")
+            .map_err(|_| "Format d'heure invalide. Utilisez HH:MM".to_string())?;
+        if self.start_volume > 100 {
+            return Err("Le volume de depart doit etre entre 0 et 100".to_string());
+        }
+        if self.duration_minutes == 0 || self.duration_minutes > MAX_WIND_DOWN_MINUTES {
+            return Err(format!("La routine du soir doit durer entre 1 et {} minutes", MAX_WIND_DOWN_MINUTES));
+        }
+        if self.playlist_uri.trim().is_empty() {
+            return Err("La routine du soir demande une playlist".to_string());
+        }
+        Ok(())
+    }
+
+    /// Verifie si la routine doit demarrer pendant la minute de `now`
+    /// (`started_on`: dernier jour ou elle a demarre)
+    pub fn is_due(&self, now: DateTime<Local>, started_on: Option<NaiveDate>) -> bool {
+        let Ok(time) = NaiveTime::parse_from_str(&self.time, "%H:%M") else {
+            return false;
+        };
+        let today = now.date_naive();
+        let elapsed = now.time().signed_duration_since(time);
+        self.enabled
+            && started_on != Some(today)
+            && (self.days.is_empty() || self.days.iter().any(|d| d == weekday_to_string(today.weekday())))
+            && elapsed >= chrono::Duration::zero()
+            && elapsed < chrono::Duration::minutes(1)
+    }
+}
+
+/// Titre et texte de la notification de debut de routine
+pub fn notification_text(
+    locale: Locale,
+    time_format: TimeFormat,
+    routine: &WindDown,
+    bedtime: Option<&SuggestedBedtime>,
+) -> (String, String) {
+    let bedtime = bedtime.map(|b| i18n::format_clock(b.bedtime.time(), time_format, false));
+    match locale {
+        Locale::Fr => (
+            "Routine du soir".to_string(),
+            match bedtime {
+                Some(at) => format!("{} en fond sonore. Coucher conseille a {}", routine.playlist_name, at),
+                None => format!("{} en fond sonore. Bonne nuit", routine.playlist_name),
+            },
+        ),
+        Locale::En => (
+            "Wind-down".to_string(),
+            match bedtime {
+                Some(at) => format!("Playing {}. Suggested bedtime: {}", routine.playlist_name, at),
+                None => format!("Playing {}. Good night", routine.playlist_name),
+            },
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_wind_down_due() {
+        let routine: WindDown = serde_json::from_str(
+            r#"{"time": "22:00", "days": ["Tuesday"], "playlist_name": "Chill", "playlist_uri": "spotify:playlist:1"}"#,
+        )
+        .unwrap();
+        assert!(routine.validate().is_ok());
+        assert_eq!(routine.start_volume, 30);
+
+        // Mardi 12 mars 2024
+        let at = |d, h, m, s| Local.with_ymd_and_hms(2024, 3, d, h, m, s).unwrap();
+        let tuesday = NaiveDate::from_ymd_opt(2024, 3, 12).unwrap();
+        assert!(routine.is_due(at(12, 22, 0, 30), None));
+        assert!(!routine.is_due(at(12, 22, 0, 30), Some(tuesday)));
+        assert!(!routine.is_due(at(12, 22, 1, 0), None));
+        assert!(!routine.is_due(at(13, 22, 0, 0), None));
+        assert!(!WindDown { enabled: false, ..routine.clone() }.is_due(at(12, 22, 0, 0), None));
+
+        let (_, body) = notification_text(Locale::En, TimeFormat::H24, &routine, None);
+        assert_eq!(body, "Playing Chill. Good night");
+        assert!(WindDown { duration_minutes: 0, ..routine.clone() }.validate().is_err());
+        assert!(WindDown { time: "10pm".to_string(), ..routine }.validate().is_err());
+    }
+}
@@ -0,0 +1,76 @@
+// worldclock.rs - Horloges mondiales: heure courante dans une liste de fuseaux IANA
+
+use chrono::{DateTime, Local, NaiveDate, Offset};
+use serde::Serialize;
+
+use crate::alarm::parse_timezone;
+use crate::i18n::{self, TimeFormat};
+
+/// Nombre maximum d'horloges mondiales configurees
+pub const MAX_WORLD_CLOCKS: usize = 12;
+
+/// Heure d'un fuseau a un instant donne
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorldTime {
+    pub zone: String,
+    pub time: String,       // Heure affichee selon le format configure
+    pub date: NaiveDate,
+    pub utc_offset: String, // Ex: "+05:30"
+    pub offset_seconds: i32,
+    pub day_difference: i64, // -1 = veille, 0 = meme jour, 1 = lendemain (par rapport a l'heure locale)
+}
+
+/// Heure de `zone` a `now`
+pub fn world_time(zone: &str, now: DateTime<Local>, time_format: TimeFormat) -> Result<WorldTime, String> {
+    let tz = parse_timezone(zone.trim())?;
+    let there = now.with_timezone(&tz);
+    let offset_seconds = there.offset().fix().local_minus_utc();
+    Ok(WorldTime {
+        zone: zone.trim().to_string(),
+        time: i18n::format_clock(there.time(), time_format, false),
+        date: there.date_naive(),
+        utc_offset: there.format("%:z").to_string(),
+        offset_seconds,
+        day_difference: there.date_naive().signed_duration_since(now.date_naive()).num_days(),
+    })
+}
+
+/// Valide une liste de fuseaux: noms IANA connus, sans doublon, ordre conserve
+pub fn normalize_zones(zones: &[String]) -> Result<Vec<String>, String> {
+    let mut result: Vec<String> = Vec::new();
+    for zone in zones.iter().map(|z| z.trim()).filter(|z| !z.is_empty()) {
+        parse_timezone(zone)?;
+        if !result.iter().any(|z| z == zone) {
+            result.push(zone.to_string());
+        }
+    }
+    if result.len() > MAX_WORLD_CLOCKS {
+        return Err(format!("Au plus {} horloges mondiales", MAX_WORLD_CLOCKS));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_world_time() {
+        // 23:30 UTC: deja le lendemain a Tokyo, encore la veille a Honolulu
+        let now = Utc.with_ymd_and_hms(2024, 3, 12, 23, 30, 0).unwrap().with_timezone(&Local);
+        let tokyo = world_time("Asia/Tokyo", now, TimeFormat::H24).unwrap();
+        assert_eq!(tokyo.time, "08:30");
+        assert_eq!(tokyo.utc_offset, "+09:00");
+        assert_eq!(tokyo.offset_seconds, 9 * 3600);
+        let honolulu = world_time("Pacific/Honolulu", now, TimeFormat::H12).unwrap();
+        assert_eq!(honolulu.time, "1:30 PM");
+        assert_eq!(tokyo.day_difference - honolulu.day_difference, 1);
+        assert_eq!(world_time("Asia/Kolkata", now, TimeFormat::H24).unwrap().utc_offset, "+05:30");
+        assert!(world_time("Mars/Olympus", now, TimeFormat::H24).is_err());
+
+        let zones = vec![" Asia/Tokyo ".to_string(), "UTC".to_string(), "Asia/Tokyo".to_string(), String::new()];
+        assert_eq!(normalize_zones(&zones).unwrap(), ["Asia/Tokyo", "UTC"]);
+        assert!(normalize_zones(&["Nowhere/City".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,421 @@
+// youtube_music.rs - YouTube Music comme musique d'alarme
+// Connexion OAuth Google (retour par charmed://auth/youtube), playlists lues par l'API
+// YouTube Data v3. YouTube Music n'a pas d'API de lecture: la playlist est ouverte
+// dans le navigateur (ou l'app, via le lien universel music.youtube.com)
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::provider::{MusicProvider, ProviderPlaylist};
+use crate::secrets;
+
+const AUTH_URL: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+const TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
+const PLAYLISTS_URL: &str = "https://www.googleapis.com/youtube/v3/playlists";
+const WATCH_URL: &str = "https://music.youtube.com/watch";
+
+/// Lecture seule de la bibliotheque
+const SCOPE: &str = "https://www.googleapis.com/auth/youtube.readonly";
+
+/// Delai maximal d'une requete vers Google
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Marge avant expiration du jeton d'acces
+const TOKEN_EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+/// Playlists demandees par page (maximum de l'API)
+const PLAYLIST_PAGE_SIZE: &str = "50";
+
+/// Prefixe des URI de playlists ("youtubemusic:playlist:PLxxxx")
+pub const PLAYLIST_URI_PREFIX: &str = "youtubemusic:playlist:";
+
+/// Prefixe des URI de titres, pour le titre d'ouverture ("youtubemusic:track:dQw4w9WgXcQ")
+pub const TRACK_URI_PREFIX: &str = "youtubemusic:track:";
+
+/// Identifiants du compte, ranges dans le trousseau
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct YouTubeMusicCredentials {
+    pub client_id: String,
+    pub client_secret: String,
+    pub refresh_token: String,
+}
+
+/// Identifiants enregistres pour le profil utilisateur (None si YouTube Music n'est pas connecte)
+pub fn load_credentials(user: Option<&str>) -> Result<Option<YouTubeMusicCredentials>, String> {
+    secrets::get_secret(&secrets::youtube_music_key(user))?
+        .map(|json| serde_json::from_str(&json).map_err(|e| format!("Identifiants YouTube Music illisibles: {}", e)))
+        .transpose()
+}
+
+pub fn save_credentials(user: Option<&str>, credentials: &YouTubeMusicCredentials) -> Result<(), String> {
+    let json = serde_json::to_string(credentials).map_err(|e| format!("Erreur sérialisation: {}", e))?;
+    secrets::set_secret(&secrets::youtube_music_key(user), &json)
+}
+
+pub fn delete_credentials(user: Option<&str>) -> Result<(), String> {
+    secrets::delete_secret(&secrets::youtube_music_key(user))
+}
+
+/// Connexion en attente du code de retour OAuth
+#[derive(Debug, Clone)]
+pub struct PendingLogin {
+    pub client_id: String,
+    pub client_secret: String,
+    pub redirect_uri: String,
+}
+
+impl PendingLogin {
+    pub fn new(client_id: String, client_secret: String, redirect_uri: String) -> Result<Self, String> {
+        if client_id.trim().is_empty() || client_secret.trim().is_empty() {
+            return Err("Client ID et secret Google requis".to_string());
+        }
+        Ok(Self {
+            client_id: client_id.trim().to_string(),
+            client_secret: client_secret.trim().to_string(),
+            redirect_uri,
+        })
+    }
+
+    /// Page de consentement Google (acces hors ligne pour obtenir un jeton de rafraichissement)
+    pub fn auth_url(&self) -> String {
+        Url::parse_with_params(AUTH_URL, [
+            ("client_id", self.client_id.as_str()),
+            ("redirect_uri", self.redirect_uri.as_str()),
+            ("response_type", "code"),
+            ("scope", SCOPE),
+            ("access_type", "offline"),
+            ("prompt", "consent"),
+        ])
+        .map(String::from)
+        .unwrap_or_default()
+    }
+
+    /// Echange le code de retour contre les identifiants du compte
+    pub async fn complete(&self, code: &str) -> Result<YouTubeMusicClient, String> {
+        let http = http_client()?;
+        let body = http
+            .post(TOKEN_URL)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("redirect_uri", self.redirect_uri.as_str()),
+            ])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| format!("Echange du code refuse: {}", e))?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        let token = parse_token(&body)?;
+        let refresh_token = token.refresh_token.clone()
+            .ok_or_else(|| "Google n'a pas renvoye de jeton de rafraichissement".to_string())?;
+
+        let client = YouTubeMusicClient::with_http(http, YouTubeMusicCredentials {
+            client_id: self.client_id.clone(),
+            client_secret: self.client_secret.clone(),
+            refresh_token,
+        });
+        client.store_access_token(&token);
+        Ok(client)
+    }
+}
+
+/// Reponse du point de jetons Google
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+    refresh_token: Option<String>, // Seulement au premier echange
+}
+
+fn parse_token(body: &str) -> Result<TokenResponse, String> {
+    serde_json::from_str(body).map_err(|e| format!("Reponse Google invalide: {}", e))
+}
+
+/// Page de playlists de l'API YouTube Data
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistPage {
+    #[serde(default)]
+    items: Vec<PlaylistItem>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PlaylistItem {
+    id: String,
+    snippet: Snippet,
+    content_details: Option<ContentDetails>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Snippet {
+    title: String,
+    #[serde(default)]
+    thumbnails: Thumbnails,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Thumbnails {
+    medium: Option<Thumbnail>,
+    default: Option<Thumbnail>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Thumbnail {
+    url: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContentDetails {
+    item_count: u32,
+}
+
+impl From<PlaylistItem> for ProviderPlaylist {
+    fn from(item: PlaylistItem) -> Self {
+        let thumbnails = item.snippet.thumbnails;
+        Self {
+            uri: format!("{}{}", PLAYLIST_URI_PREFIX, item.id),
+            name: item.snippet.title,
+            image_url: thumbnails.medium.or(thumbnails.default).map(|t| t.url),
+            track_count: item.content_details.map(|d| d.item_count),
+        }
+    }
+}
+
+/// Lit une page de playlists et le jeton de la suivante
+fn parse_playlist_page(body: &str) -> Result<(Vec<ProviderPlaylist>, Option<String>), String> {
+    let page: PlaylistPage = serde_json::from_str(body)
+        .map_err(|e| format!("Reponse YouTube invalide: {}", e))?;
+    Ok((page.items.into_iter().map(ProviderPlaylist::from).collect(), page.next_page_token))
+}
+
+/// Adresse de lecture d'une playlist, ouverte sur le titre d'ouverture s'il y en a un
+pub fn watch_url(playlist_uri: &str, first_track: Option<&str>) -> Result<String, String> {
+    let list = playlist_uri
+        .strip_prefix(PLAYLIST_URI_PREFIX)
+        .filter(|id| !id.is_empty())
+        .ok_or_else(|| format!("Playlist YouTube Music invalide: {}", playlist_uri))?;
+    let mut params = vec![("list", list)];
+    if let Some(video) = first_track.and_then(|t| t.strip_prefix(TRACK_URI_PREFIX)) {
+        params.insert(0, ("v", video));
+    }
+    Url::parse_with_params(WATCH_URL, params)
+        .map(String::from)
+        .map_err(|e| e.to_string())
+}
+
+/// Ouvre une adresse dans le navigateur par defaut
+async fn open_in_browser(url: &str) -> Result<(), String> {
+    #[cfg(target_os = "macos")]
+    let mut command = tokio::process::Command::new("open");
+    // rundll32 plutot que `start`: cmd couperait l'adresse au premier &
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        let mut command = tokio::process::Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    };
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = tokio::process::Command::new("xdg-open");
+
+    let status = command
+        .arg(url)
+        .status()
+        .await
+        .map_err(|e| format!("Navigateur indisponible: {}", e))?;
+    if !status.success() {
+        return Err(format!("Ouverture du navigateur en echec ({})", status));
+    }
+    Ok(())
+}
+
+fn http_client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())
+}
+
+/// Client YouTube Music d'un profil utilisateur
+#[derive(Clone)]
+pub struct YouTubeMusicClient {
+    http: reqwest::Client,
+    credentials: YouTubeMusicCredentials,
+    access_token: Arc<Mutex<Option<(String, Instant)>>>, // Jeton d'acces et son expiration
+}
+
+impl YouTubeMusicClient {
+    pub fn new(credentials: YouTubeMusicCredentials) -> Result<Self, String> {
+        Ok(Self::with_http(http_client()?, credentials))
+    }
+
+    fn with_http(http: reqwest::Client, credentials: YouTubeMusicCredentials) -> Self {
+        Self { http, credentials, access_token: Arc::new(Mutex::new(None)) }
+    }
+
+    pub fn credentials(&self) -> &YouTubeMusicCredentials {
+        &self.credentials
+    }
+
+    fn store_access_token(&self, token: &TokenResponse) {
+        let expires_at = Instant::now() + Duration::from_secs(token.expires_in).saturating_sub(TOKEN_EXPIRY_MARGIN);
+        if let Ok(mut cached) = self.access_token.lock() {
+            *cached = Some((token.access_token.clone(), expires_at));
+        }
+    }
+
+    /// Jeton d'acces valide (rafraichi aupres de Google s'il a expire)
+    async fn access_token(&self) -> Result<String, String> {
+        let cached = self.access_token.lock().map_err(|e| e.to_string())?
+            .as_ref()
+            .filter(|(_, expires_at)| Instant::now() < *expires_at)
+            .map(|(token, _)| token.clone());
+        if let Some(token) = cached {
+            return Ok(token);
+        }
+
+        let response = self.http
+            .post(TOKEN_URL)
+            .form(&[
+                ("grant_type", "refresh_token"),
+                ("refresh_token", self.credentials.refresh_token.as_str()),
+                ("client_id", self.credentials.client_id.as_str()),
+                ("client_secret", self.credentials.client_secret.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| format!("Google injoignable: {}", e))?;
+        if response.status() == reqwest::StatusCode::BAD_REQUEST || response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            return Err("Acces YouTube Music revoque ou expire: reconnectez YouTube Music".to_string());
+        }
+        let body = response
+            .error_for_status()
+            .map_err(|e| format!("Rafraichissement du jeton refuse: {}", e))?
+            .text()
+            .await
+            .map_err(|e| e.to_string())?;
+        let token = parse_token(&body)?;
+        self.store_access_token(&token);
+        Ok(token.access_token)
+    }
+
+    /// Verifie l'acces au compte (rafraichit le jeton d'acces si besoin)
+    pub async fn verify(&self) -> Result<(), String> {
+        self.access_token().await.map(|_| ())
+    }
+}
+
+impl MusicProvider for YouTubeMusicClient {
+    const URI_PREFIX: &'static str = "youtubemusic:";
+
+    /// Playlists du compte (toutes les pages)
+    async fn playlists(&self) -> Result<Vec<ProviderPlaylist>, String> {
+        let token = self.access_token().await?;
+        let mut result = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut query = vec![
+                ("part", "snippet,contentDetails"),
+                ("mine", "true"),
+                ("maxResults", PLAYLIST_PAGE_SIZE),
+            ];
+            if let Some(page) = page_token.as_deref() {
+                query.push(("pageToken", page));
+            }
+            let body = self.http
+                .get(PLAYLISTS_URL)
+                .bearer_auth(&token)
+                .query(&query)
+                .send()
+                .await
+                .and_then(|response| response.error_for_status())
+                .map_err(|e| format!("Erreur requete YouTube: {}", e))?
+                .text()
+                .await
+                .map_err(|e| e.to_string())?;
+            let (playlists, next) = parse_playlist_page(&body)?;
+            // Arreter sur une page vide par securite
+            let done = next.is_none() || playlists.is_empty();
+            result.extend(playlists);
+            if done {
+                return Ok(result);
+            }
+            page_token = next;
+        }
+    }
+
+    /// Ouvre la playlist dans le navigateur, qui la lance aussitot
+    async fn play(&self, playlist_uri: &str, first_track: Option<&str>) -> Result<(), String> {
+        open_in_browser(&watch_url(playlist_uri, first_track)?).await
+    }
+
+    /// Le navigateur ne se pilote pas: le volume reste celui du lecteur
+    async fn set_volume(&self, volume: u8) -> Result<(), String> {
+        tracing::debug!(volume, "Volume YouTube Music non pilotable");
+        Ok(())
+    }
+
+    /// Le navigateur ne se pilote pas: la lecture s'arrete dans l'onglet
+    async fn pause(&self) -> Result<(), String> {
+        tracing::debug!("Pause YouTube Music non pilotable");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_playlist_page() {
+        let body = r#"{
+            "kind": "youtube#playlistListResponse",
+            "nextPageToken": "CDIQAA",
+            "items": [
+                {"id": "PLabc", "snippet": {"title": "Reveil", "thumbnails": {
+                    "default": {"url": "https://i.ytimg.com/vi/x/default.jpg"},
+                    "medium": {"url": "https://i.ytimg.com/vi/x/mqdefault.jpg"}
+                }}, "contentDetails": {"itemCount": 42}},
+                {"id": "PLdef", "snippet": {"title": "Vide"}}
+            ]
+        }"#;
+        let (playlists, next) = parse_playlist_page(body).unwrap();
+        assert_eq!(next.as_deref(), Some("CDIQAA"));
+        assert_eq!(playlists[0].uri, "youtubemusic:playlist:PLabc");
+        assert_eq!(playlists[0].image_url.as_deref(), Some("https://i.ytimg.com/vi/x/mqdefault.jpg"));
+        assert_eq!(playlists[0].track_count, Some(42));
+        assert_eq!(playlists[1].image_url, None);
+        assert!(YouTubeMusicClient::handles(&playlists[1].uri));
+        assert!(parse_playlist_page("<html>").is_err());
+    }
+
+    #[test]
+    fn test_watch_url() {
+        assert_eq!(
+            watch_url("youtubemusic:playlist:PLabc", None).unwrap(),
+            "https://music.youtube.com/watch?list=PLabc"
+        );
+        assert_eq!(
+            watch_url("youtubemusic:playlist:PLabc", Some("youtubemusic:track:dQw4w9WgXcQ")).unwrap(),
+            "https://music.youtube.com/watch?v=dQw4w9WgXcQ&list=PLabc"
+        );
+        // Titre d'ouverture d'un autre service ignore
+        assert_eq!(
+            watch_url("youtubemusic:playlist:PLabc", Some("spotify:track:4uLU6hMCjMI75M1A2tKUQC")).unwrap(),
+            "https://music.youtube.com/watch?list=PLabc"
+        );
+        assert!(watch_url("spotify:playlist:37i9", None).is_err());
+
+        let pending = PendingLogin::new("id.apps".into(), "secret".into(), "charmed://auth/youtube".into()).unwrap();
+        assert!(pending.auth_url().contains("redirect_uri=charmed%3A%2F%2Fauth%2Fyoutube"));
+        assert!(PendingLogin::new(" ".into(), "secret".into(), String::new()).is_err());
+    }
+}
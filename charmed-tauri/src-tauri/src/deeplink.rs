@@ -0,0 +1,41 @@
+// deeplink.rs - Protocole charmed:// (retour OAuth Spotify et YouTube Music, actions rapides)
+// L'analyse des liens est dans charmed_core::deeplink
+
+use tauri::{AppHandle, Manager, Url};
+
+pub use charmed_core::deeplink::{parse, DeepLink};
+
+use crate::{complete_spotify_login, complete_youtube_music_login, dismiss_ringing, ringing_alarm_id, snooze_ringing, AppState};
+
+/// Execute l'action d'un lien recu (au lancement ou application ouverte)
+pub fn handle(app_handle: &AppHandle, url: Url) {
+    let link = match parse(&url) {
+        Ok(link) => link,
+        Err(e) => {
+            tracing::warn!("Lien ignore: {}", e);
+            return;
+        }
+    };
+    tracing::info!(link = ?link, "Lien charmed:// recu");
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let result = match link {
+            DeepLink::AuthCallback { code } => complete_spotify_login(&app_handle, &state, code).await,
+            DeepLink::YouTubeMusicCallback { code } => complete_youtube_music_login(&app_handle, &state, code).await,
+            DeepLink::AuthError { error } => Err(format!("Connexion refusee: {}", error)),
+            DeepLink::Snooze { minutes } => match ringing_alarm_id(&state) {
+                Ok(alarm_id) => snooze_ringing(&app_handle, &state, &alarm_id, minutes).await.map(|_| ()),
+                Err(e) => Err(e),
+            },
+            DeepLink::Dismiss => match ringing_alarm_id(&state) {
+                Ok(alarm_id) => dismiss_ringing(&app_handle, &state, &alarm_id).await,
+                Err(e) => Err(e),
+            },
+        };
+        if let Err(e) = result {
+            tracing::warn!("Lien charmed:// en echec: {}", e);
+        }
+    });
+}
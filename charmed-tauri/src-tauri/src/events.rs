@@ -0,0 +1,68 @@
+// events.rs - Evenements de sonnerie emis vers le frontend (Tauri) et les clients
+// externes (WebSocket de l'API distante)
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::broadcast;
+
+use charmed_core::power::PowerStatus;
+
+use crate::{AlarmEntry, AppState};
+
+/// Nom de l'evenement Tauri
+pub const ALARM_EVENT: &str = "alarm-event";
+
+/// Evenements gardes pour un client lent avant qu'il n'en perde
+const EVENT_BUFFER: usize = 64;
+
+/// Changement d'etat d'une sonnerie
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlarmEvent {
+    Triggered { alarm: Box<AlarmEntry> },
+    Snoozed { alarm_id: String, fires_at: DateTime<Local> },
+    Dismissed { alarm_id: String },
+    Missed { alarm_id: String }, // Duree maximale de sonnerie atteinte sans report
+    BackupTriggered { alarm_id: String }, // Sonnerie sans reponse: sirene de secours a plein volume
+    GroupToggled { group: String, active: bool, alarm_ids: Vec<String> }, // Groupe active ou desactive d'un coup
+    PowerWarning { alarm_id: String, alarm_at: DateTime<Local>, status: PowerStatus }, // Sur batterie avec veille automatique le soir
+    NowPlaying { alarm_id: Option<String>, playlist_uri: String }, // "local" = son local
+    TrashPurged { alarm_ids: Vec<String> }, // Corbeille videe definitivement
+}
+
+/// Canal de diffusion des evenements (un recepteur par client WebSocket)
+pub fn channel() -> broadcast::Sender<AlarmEvent> {
+    broadcast::channel(EVENT_BUFFER).0
+}
+
+/// Emet l'evenement vers le frontend et les clients externes
+pub fn emit(app_handle: &AppHandle, event: AlarmEvent) {
+    if let Err(e) = app_handle.emit(ALARM_EVENT, &event) {
+        tracing::warn!("Evenement non emis: {}", e);
+    }
+    // Erreur = aucun client externe connecte
+    let _ = app_handle.state::<AppState>().events.send(event);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_event_payload() {
+        let event = AlarmEvent::NowPlaying {
+            alarm_id: Some("a1".to_string()),
+            playlist_uri: "spotify:playlist:abc".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            serde_json::json!({ "type": "now_playing", "alarm_id": "a1", "playlist_uri": "spotify:playlist:abc" })
+        );
+
+        let sender = channel();
+        let mut receiver = sender.subscribe();
+        sender.send(AlarmEvent::Dismissed { alarm_id: "a1".to_string() }).unwrap();
+        assert!(matches!(receiver.try_recv().unwrap(), AlarmEvent::Dismissed { alarm_id } if alarm_id == "a1"));
+    }
+}
@@ -1,390 +1,3235 @@
 // Charmed — Rust Backend (Tauri IPC Commands)
 // Application de reveil Spotify avec interface glassmorphism moderne
 
-mod alarm;
-mod spotify;
-mod storage;
-mod audio;
+// Les commandes async ne gardent jamais un verrou std::sync::Mutex pendant un .await
+#![deny(clippy::await_holding_lock)]
 
-use std::sync::Mutex;
-use serde::{Deserialize, Serialize};
-use tauri::{State, Manager};
-use chrono::Datelike;
+mod scheduler;
+mod logging;
+mod shortcuts;
+mod deeplink;
+mod remote;
+mod events;
+mod net;
+mod miniclock;
+mod wakescreen;
+mod watchdog;
 
-// -- STRUCTURES DE DONNÉES --
+use charmed_core::{
+    alarm, alarm_list, apple_music, audio, bedtime, bundle, calendar, challenge, escalation, fade, history, holidays, i18n,
+    ical, health, library, lights, moods, nightmode, podcast, power, preview, profiles, provider, query, radio, readiness, recurrence, secrets, spotify, stats, stopwatch, storage, sync,
+    system_volume, telemetry, timer, trash, tts, update, wake_window, weather, winddown, worldclock, youtube_music,
+};
+use charmed_core::provider::MusicProvider;
+pub use charmed_core::AlarmEntry;
+
+use chrono::Timelike;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::Serialize;
+use tauri::{Emitter, State, Manager};
+use tauri_plugin_deep_link::DeepLinkExt;
 
-/// Représente une alarme programmée
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AlarmEntry {
-    pub id: String,
-    pub time: String,           // Format "HH:MM"
-    pub playlist_name: String,
-    pub playlist_uri: String,
-    pub volume: u8,             // 0-100
-    pub active: bool,
+use i18n::{tr, Msg};
 
 // -- STRUCTURES DE DONNÉES --
 
-/// Représente une alarme programmée
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AlarmEntry {
-    pub id: String,
-    pub time: String,           // Format "HH:MM"
-    pub playlist_name: String,
-    pub playlist_uri: String,
-    pub volume: u8,             // 0-100
-    pub active: bool,
-    pub days: Vec<String>,      // ["Monday", "Tuesday", ...]
-    pub fade_in: bool,
-    pub fade_in_duration: u16,  // Secondes
-}
-
-/// État global de l'application partagé entre tous les appels IPC
+/// État global de l'application partagé entre tous les appels IPC.
+/// Les verrous sont courts: les commandes async clonent ce dont elles ont besoin
+/// (le client Spotify partage son jeton via un Arc) puis relâchent le verrou
+/// avant tout appel réseau.
 pub struct AppState {
     pub alarms: Mutex<Vec<AlarmEntry>>,
+    pub naps: Mutex<Vec<alarm::NapTimer>>,
+    pub timers: Mutex<Vec<timer::CountdownTimer>>, // Minuteurs a rebours (non persistes)
     pub config: Mutex<storage::AppConfig>,
     pub spotify_client: Mutex<Option<spotify::SpotifyClient>>,
+    pub spotify_pending_login: Mutex<Option<spotify::SpotifyClient>>,
+    pub spotify_accounts: Mutex<HashMap<String, spotify::SpotifyClient>>,
+    pub apple_music: Mutex<Option<apple_music::AppleMusicClient>>, // Compte Apple Music du profil
+    pub youtube_music: Mutex<Option<youtube_music::YouTubeMusicClient>>, // Compte YouTube Music du profil
+    pub youtube_music_pending_login: Mutex<Option<youtube_music::PendingLogin>>,
+    pub playlist_cache: Mutex<Option<spotify::PlaylistCache>>,
+    pub sleep_timer: Mutex<Option<fade::SleepTimer>>,
+    pub ringing: Mutex<Option<alarm::RingingAlarm>>,
+    pub deferred_rings: Mutex<Vec<(AlarmEntry, u32)>>, // Alarmes arrivees pendant une autre sonnerie
+    pub weather: Mutex<Option<weather::WakeWeather>>, // Dernier releve meteo
+    pub saved_system_volume: Mutex<Option<system_volume::MasterVolume>>, // A restaurer apres la sonnerie
+    pub saved_spotify_volume: Mutex<Option<spotify::SavedDeviceVolumes>>, // Volume de l'appareil Spotify a restaurer
+    pub remote_server: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>, // Serveur de l'API distante
+    pub events: tokio::sync::broadcast::Sender<events::AlarmEvent>, // Evenements pour les clients WebSocket
+    pub sunrise_lights: Mutex<HashMap<String, chrono::DateTime<chrono::Local>>>, // Occurrence deja eclairee par alarme
+    pub spotify_kept_alive: Mutex<HashMap<String, chrono::DateTime<chrono::Local>>>, // Occurrence dont l'appareil a deja ete reveille
+    pub pre_alarms: Mutex<HashMap<String, chrono::DateTime<chrono::Local>>>, // Occurrence deja annoncee par la pre-alarme
+    pub readiness_checked: Mutex<HashMap<String, chrono::DateTime<chrono::Local>>>, // Occurrence deja verifiee par alarme
+    pub network: Mutex<net::NetworkStatus>, // Dernier etat du reseau releve par net::spawn
+    pub calendar: Mutex<Option<calendar::CalendarCache>>, // Dernier releve de l'agenda
+    pub wake_plans: Mutex<HashMap<String, wake_window::WakePlan>>, // Fenetre de reveil en cours par alarme
+    pub fired: Mutex<alarm::FiredLedger>, // Dernier declenchement par alarme (persiste)
+    pub bedtime_reminded: Mutex<Option<chrono::DateTime<chrono::Local>>>, // Alarme dont le coucher a deja ete rappele
+    pub wind_down_started: Mutex<Option<chrono::NaiveDate>>, // Dernier soir ou la routine a demarre
+    pub night_display: Mutex<Option<u8>>, // Luminosite a restaurer quand l'ecran est assombri par le mode nuit
+    pub night_woken: Mutex<bool>, // Une alarme a sonne pendant la nuit en cours (l'ecran reste eclaire)
+    pub power_warned: Mutex<Option<chrono::NaiveDate>>, // Dernier soir averti d'un fonctionnement sur batterie
+    pub hardware_wake: Mutex<Option<chrono::DateTime<chrono::Local>>>, // Reveil materiel programme (power::schedule_wake)
+    pub scheduler_task: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>, // Boucle du planificateur en cours
+    pub scheduler_beat: Mutex<Option<chrono::DateTime<chrono::Local>>>, // Dernier tour du planificateur
+    pub scheduler_aborted: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>, // Boucle abandonnee pas encore terminee
+    pub scheduler_restarts: Mutex<(u32, Option<chrono::DateTime<chrono::Local>>)>, // Relances par le superviseur (nombre, derniere)
+    pub ringing_persisted: Mutex<Option<(String, chrono::DateTime<chrono::Local>)>>, // Sonnerie ecrite dans ringing.json
+    pub profile: Mutex<Option<String>>, // Profil utilisateur actif (None = profil par defaut)
+    pub storage_recoveries: Mutex<Vec<storage::Recovery>>, // Fichiers corrompus recuperes au chargement du profil
+    pub alarms_watcher: Mutex<Option<storage::AlarmsWatcher>>, // Surveillance de alarms.json du profil actif
+}
+
+/// Langue configuree pour les messages (francais si la config est inaccessible).
+/// A lire avant de prendre d'autres verrous.
+fn locale(state: &AppState) -> i18n::Locale {
+    state.config.lock().map(|c| c.locale).unwrap_or_default()
+}
+
+/// Profil utilisateur actif (None = profil par defaut)
+pub(crate) fn user_profile(state: &AppState) -> Result<Option<String>, String> {
+    Ok(state.profile.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Dossier de donnees du profil utilisateur actif (alarmes, configuration, comptes)
+pub(crate) fn data_dir(app_handle: &tauri::AppHandle) -> Result<std::path::PathBuf, String> {
+    let root = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let profile = user_profile(&app_handle.state::<AppState>())?;
+    Ok(profiles::profile_dir(&root, profile.as_deref()))
+}
+
+/// Evenement emis quand l'etat d'authentification d'un service change
+const AUTH_EVENT: &str = "auth-changed";
+
+/// Charge utile de l'evenement d'authentification
+#[derive(Debug, Clone, Serialize)]
+struct AuthState {
+    provider: query::ProviderKind,
+    authenticated: bool,
+}
+
+/// Notifie toutes les fenetres d'un changement d'etat d'authentification
+fn emit_auth_state(app_handle: &tauri::AppHandle, provider: query::ProviderKind, authenticated: bool) {
+    let _ = app_handle.emit(AUTH_EVENT, AuthState { provider, authenticated });
+}
+
+/// Previent toutes les fenetres (principale, reveil, barre systeme) que les alarmes ont change
+pub(crate) fn emit_alarms_changed(app_handle: &tauri::AppHandle, alarms: &[AlarmEntry]) {
+    if let Err(e) = app_handle.emit(storage::ALARMS_CHANGED_EVENT, alarms) {
+        tracing::warn!("Evenement non emis: {}", e);
+    }
+}
+
+/// Previent toutes les fenetres que la configuration a change
+fn emit_config_changed(app_handle: &tauri::AppHandle, config: &storage::AppConfig) {
+    if let Err(e) = app_handle.emit(storage::CONFIG_CHANGED_EVENT, config) {
+        tracing::warn!("Evenement non emis: {}", e);
+    }
+}
+
+/// Sauvegarde la configuration (si le dossier de donnees est disponible) et previent les fenetres
+fn persist_config(app_handle: &tauri::AppHandle, config: &storage::AppConfig) -> Result<(), String> {
+    if let Ok(app_data_dir) = data_dir(app_handle) {
+        storage::save_config(&app_data_dir, config)
+            .map_err(|e| tr(config.locale, Msg::ConfigSaveError, &[&e]))?;
+    }
+    emit_config_changed(app_handle, config);
+    Ok(())
+}
+
+/// Ajoute un evenement a la file de telemetrie (sans effet tant qu'elle n'est pas activee)
+pub(crate) fn record_telemetry(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    kind: telemetry::TelemetryKind,
+    provider: Option<query::ProviderKind>,
+    fallback: Option<alarm::FallbackReason>,
+) {
+    if !state.config.lock().is_ok_and(|c| c.telemetry.enabled) {
+        return;
+    }
+    let event = telemetry::TelemetryEvent::new(kind, provider, fallback, chrono::Utc::now());
+    if let Err(e) = data_dir(app_handle).and_then(|dir| telemetry::enqueue(&dir, event)) {
+        tracing::warn!("Telemetrie: {}", e);
+    }
 }
 
 // -- COMMANDES IPC --
 
-/// Retourne l'heure actuelle du système
+/// Retourne l'heure actuelle du système, au format d'heure configure
 #[tauri::command]
-fn get_current_time() -> String {
-    chrono::Local::now().format("%H:%M:%S").to_string()
+fn get_current_time(state: State<'_, AppState>) -> String {
+    let time_format = state.config.lock().map(|c| c.time_format).unwrap_or_default();
+    i18n::format_clock(chrono::Local::now().time(), time_format, true)
 }
 
 /// Ajoute une nouvelle alarme
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 fn set_alarm(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
     time: String,
     playlist_name: String,
     playlist_uri: String,
-    volume: u8,
+    volume: Option<u8>,
     days: Vec<String>,
     fade_in: bool,
-    fade_in_duration: u16,
-) -> Result<AlarmEntry, String> {
-    // Valider le format de l'heure (HH:MM)
-    chrono::NaiveTime::parse_from_str(&time, "%H:%M")
-        .map_err(|_| "Format d'heure invalide. Utilisez HH:MM".to_string())?;
+    fade_in_duration: Option<u16>,
+    account: Option<String>,
+    recurrence: Option<recurrence::RecurrenceRule>,
+    timezone: Option<String>,
+    challenge: Option<challenge::ChallengeConfig>,
+    escalation: Option<escalation::EscalationPolicy>,
+    ring_limit: Option<alarm::RingLimit>,
+    weather_playlists: Option<weather::WeatherPlaylists>,
+    announcement: Option<tts::Announcement>,
+    sunrise: Option<bool>,
+    output_device: Option<String>,
+    fade_curve: Option<fade::FadeCurve>,
+    lights: Option<lights::SunriseLights>,
+    calendar: Option<calendar::CalendarRule>,
+    wake_window: Option<wake_window::WakeWindow>,
+    first_track_uri: Option<String>,
+    weekend_profile: Option<alarm::SoundProfile>,
+    group_playback: Option<alarm::GroupPlayback>,
+    after_wake_queue: Option<alarm::AfterWakeQueue>,
+    interval: Option<alarm::IntervalRule>,
+    pre_alarm: Option<alarm::PreAlarm>,
+    backup: Option<alarm::BackupAlarm>,
+    group: Option<String>,
+    color: Option<String>,
+    icon: Option<String>,
+) -> Result<SavedAlarm, String> {
+    // Valeurs par defaut de la configuration si non precisees
+    let (default_volume, default_fade_in_duration) = {
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        (config.default_volume, config.default_fade_in_duration)
+    };
 
-    let alarm = AlarmEntry {
-        id: uuid::Uuid::new_v4().to_string(),
-        time,
+    let settings = alarm::AlarmSettings {
         playlist_name,
         playlist_uri,
-        volume: volume.min(100),
-        active: true,
+        volume: volume.unwrap_or(default_volume),
         days,
         fade_in,
-        fade_in_duration,
+        fade_in_duration: fade_in_duration.unwrap_or(default_fade_in_duration),
+        account,
+        recurrence,
+        timezone,
+        challenge,
+        escalation,
+        ring_limit,
+        weather_playlists,
+        announcement,
+        sunrise: sunrise.unwrap_or(false),
+        output_device,
+        fade_curve: fade_curve.unwrap_or_default(),
+        lights,
+        calendar,
+        wake_window,
+        first_track_uri,
+        weekend_profile,
+        group_playback,
+        after_wake_queue,
+        interval,
+        pre_alarm,
+        backup,
+        group,
+        color,
+        icon,
     };
+    let alarm = settings.into_alarm(time)?;
+    store_new_alarm(&app_handle, &state, alarm)
+}
+
+/// Alarme enregistree et avertissements non bloquants (doublon, volume nul...)
+#[derive(Debug, Clone, Serialize)]
+struct SavedAlarm {
+    #[serde(flatten)]
+    alarm: AlarmEntry,
+    warnings: Vec<alarm_list::AlarmWarning>,
+}
 
+/// Ajoute une alarme a la liste et la persiste
+fn store_new_alarm(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    alarm: AlarmEntry,
+) -> Result<SavedAlarm, String> {
     // Ajouter à la liste en mémoire
     let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    let warnings = alarm_list::warnings(&alarms, &alarm);
+    if !warnings.is_empty() {
+        tracing::warn!(alarm_id = %alarm.id, "Alarme enregistree avec avertissements: {:?}", warnings);
+    }
     alarms.push(alarm.clone());
 
-    // Persister sur disque
-    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-        let _ = storage::save_alarms(&app_data_dir, &alarms);
-    }
+    // Persister sur disque avant de prevenir l'interface
+    storage::save_alarms(&data_dir(app_handle)?, &alarms)?;
+    emit_alarms_changed(app_handle, &alarms);
 
-    Ok(alarm)
+    Ok(SavedAlarm { alarm, warnings })
 }
 
-/// Retourne la liste de toutes les alarmes
+/// Modifie une alarme existante. `expected_updated_at` est la date de modification
+/// de la version affichee: une modification faite entre-temps ailleurs est signalee
+/// par une erreur de conflit au lieu d'etre ecrasee.
 #[tauri::command]
-fn get_alarms(state: State<'_, AppState>) -> Result<Vec<AlarmEntry>, String> {
-    let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
-    Ok(alarms.clone())
+fn update_alarm(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    alarm_id: String,
+    expected_updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    time: String,
+    settings: alarm::AlarmSettings,
+) -> Result<SavedAlarm, String> {
+    let locale = locale(&state);
+    let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    let alarm = alarm_list::update(&mut alarms, &alarm_id, expected_updated_at, time, settings, locale)?;
+    let warnings = alarm_list::warnings(&alarms, &alarm);
+
+    storage::save_alarms(&data_dir(&app_handle)?, &alarms)?;
+    emit_alarms_changed(&app_handle, &alarms);
+    Ok(SavedAlarm { alarm, warnings })
 }
 
-/// Active ou désactive une alarme
+// -- EXPORT / IMPORT --
+
+/// Exporte alarmes, modeles et configuration dans un fichier JSON versionne
 #[tauri::command]
-fn toggle_alarm(
+fn export_alarms(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    alarm_id: String,
-) -> Result<bool, String> {
-    let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
-    
-    if let Some(alarm) = alarms.iter_mut().find(|a| a.id == alarm_id) {
-        alarm.active = !alarm.active;
-        let new_state = alarm.active;
-        
-        // Persister
-        if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-            let _ = storage::save_alarms(&app_data_dir, &alarms);
+    path: String,
+) -> Result<(), String> {
+    let alarms = state.alarms.lock().map_err(|e| e.to_string())?.clone();
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let templates = match data_dir(&app_handle) {
+        Ok(app_data_dir) => storage::load_templates(&app_data_dir)?,
+        Err(_) => Vec::new(),
+    };
+
+    let bundle = bundle::AlarmBundle::new(alarms, config, templates);
+    bundle::write_bundle(std::path::Path::new(&path), &bundle)
+}
+
+/// Importe un fichier d'export selon la strategie choisie
+#[tauri::command]
+fn import_alarms(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    merge_strategy: bundle::MergeStrategy,
+) -> Result<bundle::ImportReport, String> {
+    let imported = bundle::read_bundle(std::path::Path::new(&path))?;
+    let app_data_dir = data_dir(&app_handle)?;
+
+    let report = {
+        let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+        let (merged, report) = bundle::merge_alarms(&alarms, imported.alarms, merge_strategy);
+        let removed = alarm_list::removed_ids(&alarms, &merged);
+        *alarms = merged;
+        storage::save_alarms(&app_data_dir, &alarms)?;
+        sync::record_deletions(&app_data_dir, &removed)?;
+        emit_alarms_changed(&app_handle, &alarms);
+        report
+    };
+
+    // Modeles: ajoutes s'ils n'existent pas (tous remplaces en mode remplacement)
+    let mut templates = match merge_strategy {
+        bundle::MergeStrategy::Replace => Vec::new(),
+        _ => storage::load_templates(&app_data_dir)?,
+    };
+    for template in imported.templates {
+        if !templates.iter().any(|t| t.name == template.name) {
+            templates.push(template);
         }
-        
-        Ok(new_state)
-    } else {
-        Err(format!("Alarme '{}' introuvable", alarm_id))
     }
+    storage::save_templates(&app_data_dir, &templates)?;
+
+    if merge_strategy == bundle::MergeStrategy::Replace {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        *config = bundle::replace_config(&config, imported.config);
+        storage::save_config(&app_data_dir, &config)?;
+        emit_config_changed(&app_handle, &config);
+    }
+
+    Ok(report)
 }
 
-/// Supprime une alarme
+// -- SYNCHRONISATION --
+
+/// Configure la synchronisation avec d'autres machines (None pour la couper).
+/// `secret` (mot de passe WebDAV ou cle secrete S3) va dans le trousseau; absent = inchange.
 #[tauri::command]
-fn delete_alarm(
+fn set_sync(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    alarm_id: String,
+    settings: Option<sync::SyncSettings>,
+    secret: Option<String>,
 ) -> Result<(), String> {
-    let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
-    let before = alarms.len();
-    alarms.retain(|a| a.id != alarm_id);
-    
-    if alarms.len() < before {
-        // Persister
-        if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-            let _ = storage::save_alarms(&app_data_dir, &alarms);
-        }
-        Ok(())
-    } else {
-        Err(format!("Alarme '{}' introuvable", alarm_id))
+    if let Some(settings) = settings.as_ref() {
+        settings.validate()?;
+    }
+    let key = secrets::sync_secret_key(user_profile(&state)?.as_deref());
+    match secret.filter(|s| !s.is_empty()) {
+        _ if settings.is_none() => secrets::delete_secret(&key)?,
+        Some(secret) => secrets::set_secret(&key, &secret)?,
+        None => {}
     }
+
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.sync = settings;
+    persist_config(&app_handle, &config)?;
+    Ok(())
 }
 
-/// Vérifie si une alarme doit sonner maintenant
+/// Synchronise immediatement alarmes et configuration
 #[tauri::command]
-fn check_alarms(state: State<'_, AppState>) -> Result<Option<AlarmEntry>, String> {
-    let now = chrono::Local::now();
-    let current_time = now.format("%H:%M").to_string();
-    let today = match now.weekday() {
-        chrono::Weekday::Mon => "Monday",
-        chrono::Weekday::Tue => "Tuesday",
-        chrono::Weekday::Wed => "Wednesday",
-        chrono::Weekday::Thu => "Thursday",
-        chrono::Weekday::Fri => "Friday",
-        chrono::Weekday::Sat => "Saturday",
-        chrono::Weekday::Sun => "Sunday",
+async fn sync_now(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<sync::SyncReport, String> {
+    sync_alarms(&app_handle, &state).await
+}
+
+/// Echange alarmes et configuration avec le stockage distant: telecharge le document
+/// partage, fusionne (la modification la plus recente l'emporte) puis renvoie le resultat
+pub(crate) async fn sync_alarms(app_handle: &tauri::AppHandle, state: &AppState) -> Result<sync::SyncReport, String> {
+    let locale = locale(state);
+    let settings = state.config.lock().map_err(|e| e.to_string())?.sync.clone()
+        .ok_or_else(|| tr(locale, Msg::SyncNotConfigured, &[]))?;
+    let key = secrets::sync_secret_key(user_profile(state)?.as_deref());
+    let secret = secrets::get_secret(&key)?
+        .ok_or_else(|| tr(locale, Msg::SyncSecretMissing, &[]))?;
+    let app_data_dir = data_dir(app_handle)?;
+
+    let client = sync::SyncClient::new(settings.backend, secret)?;
+    let remote = client.fetch().await?;
+
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let reconciled = {
+        let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+        let reconciled = sync::reconcile(
+            &alarms,
+            &config,
+            storage::config_modified_at(&app_data_dir),
+            &sync::load_state(&app_data_dir),
+            remote,
+            chrono::Utc::now(),
+        );
+        *alarms = reconciled.alarms.clone();
+        storage::save_alarms(&app_data_dir, &alarms)?;
+        emit_alarms_changed(app_handle, &alarms);
+        reconciled
     };
+    if let Some(remote_config) = reconciled.config {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        *config = remote_config;
+        persist_config(app_handle, &config)?;
+    }
+    sync::save_state(&app_data_dir, &reconciled.state)?;
+    client.upload(&reconciled.document).await?;
 
-    let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
-    for alarm in alarms.iter() {
-        if alarm.active
-            && alarm.time == current_time
-            && (alarm.days.is_empty() || alarm.days.iter().any(|d| d == today))
-        {
-            return Ok(Some(alarm.clone()));
+    let report = reconciled.report;
+    tracing::info!(received = report.received, removed = report.removed, config = report.config_received, "Synchronisation terminee");
+    if report.received > 0 || report.removed > 0 || report.config_received {
+        if let Err(e) = app_handle.emit(sync::SYNC_EVENT, &report) {
+            tracing::warn!("Evenement non emis: {}", e);
         }
     }
-    Ok(None)
+    Ok(report)
 }
 
-// -- COMMANDES SPOTIFY --
+/// Exporte les reveils des prochaines semaines au format iCalendar (.ics)
+#[tauri::command]
+fn export_ics(state: State<'_, AppState>, path: String, weeks: u32) -> Result<(), String> {
+    if weeks == 0 || weeks > ical::MAX_EXPORT_WEEKS {
+        return Err(tr(locale(&state), Msg::ExportWeeksInvalid, &[&ical::MAX_EXPORT_WEEKS]));
+    }
 
-/// Initie l'authentification Spotify OAuth
+    let alarms = state.alarms.lock().map_err(|e| e.to_string())?.clone();
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let calendar = ical::build_calendar(&alarms, &config, chrono::Local::now(), weeks);
+    ical::write_calendar(std::path::Path::new(&path), &calendar)
+}
+
+// -- MODELES D'ALARMES --
+
+/// Enregistre (ou remplace) un modele d'alarme reutilisable
 #[tauri::command]
-async fn spotify_login(
+fn save_alarm_template(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    client_id: String,
-    client_secret: String,
-) -> Result<String, String> {
-    // Sauvegarder le client_id dans la config
-    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-        let mut config = state.config.lock().map_err(|e| e.to_string())?;
-        config.spotify_client_id = Some(client_id.clone());
-        let _ = storage::save_config(&app_data_dir, &config);
+    name: String,
+    settings: alarm::AlarmSettings,
+) -> Result<alarm::AlarmTemplate, String> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(tr(locale(&state), Msg::TemplateNameEmpty, &[]));
     }
+    let settings = settings.normalized()?;
 
-    let mut client = spotify::SpotifyClient::new(client_id, client_secret);
-    let auth_url = client.get_auth_url();
-    
-    let mut spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
-    *spotify_guard = Some(client);
-    
-    Ok(auth_url)
+    let app_data_dir = data_dir(&app_handle)?;
+    let mut templates = storage::load_templates(&app_data_dir)?;
+    let template = alarm::AlarmTemplate { name, settings };
+    templates.retain(|t| t.name != template.name);
+    templates.push(template.clone());
+    storage::save_templates(&app_data_dir, &templates)?;
+
+    Ok(template)
 }
 
-/// Récupère la configuration actuelle
+/// Retourne les modeles d'alarmes enregistres
 #[tauri::command]
-fn get_config(state: State<'_, AppState>) -> Result<storage::AppConfig, String> {
-    let config = state.config.lock().map_err(|e| e.to_string())?;
-    Ok(config.clone())
+fn list_alarm_templates(app_handle: tauri::AppHandle) -> Result<Vec<alarm::AlarmTemplate>, String> {
+    let app_data_dir = data_dir(&app_handle)?;
+    storage::load_templates(&app_data_dir)
 }
 
-/// Met à jour la configuration
+/// Supprime un modele d'alarme
 #[tauri::command]
-fn update_config(
+fn delete_alarm_template(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    config: storage::AppConfig,
+    name: String,
 ) -> Result<(), String> {
-    let mut current_config = state.config.lock().map_err(|e| e.to_string())?;
-    *current_config = config;
-    
-    if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-        storage::save_config(&app_data_dir, &current_config)
-            .map_err(|e| format!("Erreur sauvegarde config: {}", e))?;
+    let app_data_dir = data_dir(&app_handle)?;
+    let mut templates = storage::load_templates(&app_data_dir)?;
+    let before = templates.len();
+    templates.retain(|t| t.name != name);
+
+    if templates.len() == before {
+        return Err(tr(locale(&state), Msg::TemplateNotFound, &[&name]));
     }
-    
-    Ok(())
+    storage::save_templates(&app_data_dir, &templates)
 }
 
-/// Complete l'authentification avec le code callback
+/// Cree une alarme a partir d'un modele enregistre
 #[tauri::command]
-async fn spotify_callback(
+fn create_alarm_from_template(
+    app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    code: String,
+    name: String,
+    time: String,
+) -> Result<SavedAlarm, String> {
+    let locale = locale(&state);
+    let app_data_dir = data_dir(&app_handle)?;
+    let template = storage::load_templates(&app_data_dir)?
+        .into_iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| tr(locale, Msg::TemplateNotFound, &[&name]))?;
+
+    let alarm = template.settings.into_alarm(time)?;
+    store_new_alarm(&app_handle, &state, alarm)
+}
+
+/// Retourne la liste des alarmes (hors archivees)
+#[tauri::command]
+fn get_alarms(state: State<'_, AppState>) -> Result<Vec<AlarmEntry>, String> {
+    let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    Ok(alarms.iter().filter(|a| !a.archived).cloned().collect())
+}
+
+/// Recherche, filtre et trie les alarmes
+#[tauri::command]
+fn query_alarms(state: State<'_, AppState>, filter: Option<query::AlarmFilter>) -> Result<Vec<AlarmEntry>, String> {
+    let now = chrono::Local::now();
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let events = calendar_events(&state)?;
+    let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    Ok(query::query(&alarms, &filter.unwrap_or_default(), |alarm| {
+        calendar::next_trigger(alarm, &events, now, &config)
+    }))
+}
+
+/// Icones disponibles pour les alarmes
+#[tauri::command]
+fn get_alarm_icons() -> Vec<&'static str> {
+    alarm::ALARM_ICONS.to_vec()
+}
+
+/// Retourne les alarmes archivees
+#[tauri::command]
+fn get_archived_alarms(state: State<'_, AppState>) -> Result<Vec<AlarmEntry>, String> {
+    let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    Ok(alarms.iter().filter(|a| a.archived).cloned().collect())
+}
+
+/// Archive ou desarchive une alarme
+#[tauri::command]
+fn set_alarm_archived(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    alarm_id: String,
+    archived: bool,
 ) -> Result<(), String> {
-    // Cloner le client si present pour liberer le lock
-    let client_opt = {
-        let spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
-        spotify_guard.clone()
-    };
-    
-    if let Some(mut client) = client_opt {
-        client.complete_auth(code).await
-            .map_err(|e| format!("Erreur auth Spotify: {}", e))?;
-        
-        // Mettre a jour le client authentifie
-        let mut spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
-        *spotify_guard = Some(client);
-        Ok(())
-    } else {
-        Err("Client Spotify non initialise".to_string())
-    }
+    let locale = locale(&state);
+    let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    alarm_list::set_archived(&mut alarms, &alarm_id, archived, locale)?;
+    storage::save_alarms(&data_dir(&app_handle)?, &alarms)?;
+    emit_alarms_changed(&app_handle, &alarms);
+    Ok(())
 }
 
-/// Recupere les playlists de l'utilisateur
+/// Active ou désactive une alarme
 #[tauri::command]
-async fn get_spotify_playlists(
+fn toggle_alarm(
+    app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-) -> Result<Vec<spotify::SpotifyPlaylist>, String> {
-    // Cloner le client si present pour liberer le lock
-    let client_opt = {
-        let spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
-        spotify_guard.clone()
-    };
-    
-    if let Some(client) = client_opt {
-        client.get_playlists().await
-            .map_err(|e| format!("Erreur recuperation playlists: {}", e))
-    } else {
-        Err("Non connecte a Spotify".to_string())
-    }
+    alarm_id: String,
+) -> Result<bool, String> {
+    toggle_alarm_entry(&app_handle, &state, &alarm_id)
 }
 
-/// Lance la lecture d'une playlist
+/// Inverse l'etat actif d'une alarme et le persiste (commande et API distante)
+pub(crate) fn toggle_alarm_entry(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    alarm_id: &str,
+) -> Result<bool, String> {
+    let locale = locale(state);
+    let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    let new_state = alarm_list::toggle(&mut alarms, alarm_id, locale)?;
+
+    // Persister
+    storage::save_alarms(&data_dir(app_handle)?, &alarms)?;
+    emit_alarms_changed(app_handle, &alarms);
+
+    Ok(new_state)
+}
+
+/// Active ou desactive toutes les alarmes d'un groupe
 #[tauri::command]
-async fn play_spotify_playlist(
+fn toggle_group(
+    app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    playlist_uri: String,
-) -> Result<(), String> {
-    // Cloner le client si present pour liberer le lock
-    let client_opt = {
-        let spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
-        spotify_guard.clone()
+    name: String,
+    active: bool,
+) -> Result<Vec<String>, String> {
+    let locale = locale(&state);
+    let alarm_ids = {
+        let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+        let ids = alarm_list::toggle_group(&mut alarms, &name, active, locale)?;
+        storage::save_alarms(&data_dir(&app_handle)?, &alarms)?;
+        emit_alarms_changed(&app_handle, &alarms);
+        ids
     };
-    
-    if let Some(client) = client_opt {
-        client.play_playlist(&playlist_uri).await
-            .map_err(|e| format!("Erreur lecture: {}", e))
-    } else {
-        Err("Non connecte a Spotify".to_string())
-    }
+    events::emit(&app_handle, events::AlarmEvent::GroupToggled { group: name, active, alarm_ids: alarm_ids.clone() });
+    Ok(alarm_ids)
 }
 
-/// Regle le volume Spotify
+/// Supprime une alarme (placee dans la corbeille, restaurable)
 #[tauri::command]
-async fn set_spotify_volume(
+fn delete_alarm(
+    app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    volume: u8,
+    alarm_id: String,
 ) -> Result<(), String> {
-    // Cloner le client si present pour liberer le lock
-    let client_opt = {
-        let spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
-        spotify_guard.clone()
-    };
-    
-    if let Some(client) = client_opt {
-        client.set_volume(volume).await
-            .map_err(|e| format!("Erreur volume: {}", e))
-    } else {
-        Err("Non connecte a Spotify".to_string())
+    let locale = locale(&state);
+    let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    let removed = alarm_list::remove(&mut alarms, &alarm_id, locale)?;
+
+    // Persister (la suppression est transmise aux autres machines synchronisees)
+    let app_data_dir = data_dir(&app_handle)?;
+    storage::save_alarms(&app_data_dir, &alarms)?;
+    let mut trashed = trash::load(&app_data_dir);
+    trash::push(&mut trashed, removed, chrono::Utc::now());
+    if let Err(e) = trash::save(&app_data_dir, &trashed) {
+        tracing::warn!(alarm_id = %alarm_id, "Corbeille non sauvegardee: {}", e);
     }
+    if let Err(e) = sync::record_deletions(&app_data_dir, &[alarm_id]) {
+        tracing::warn!("Synchronisation: suppression non memorisee: {}", e);
+    }
+    emit_alarms_changed(&app_handle, &alarms);
+    Ok(())
 }
 
-/// Vérifie si l'utilisateur est authentifié
+/// Alarmes supprimees, la plus recente en tete
 #[tauri::command]
-fn is_spotify_authenticated(state: State<'_, AppState>) -> bool {
-    if let Ok(spotify_guard) = state.spotify_client.lock() {
-        if let Some(client) = spotify_guard.as_ref() {
-            return client.is_authenticated();
-        }
-    }
-    false
+fn list_trash(app_handle: tauri::AppHandle) -> Result<Vec<trash::TrashedAlarm>, String> {
+    Ok(trash::load(&data_dir(&app_handle)?))
 }
 
-// -- COMMANDES AUDIO --
-
-/// Joue l'alarme locale (fallback)
+/// Restaure une alarme supprimee
 #[tauri::command]
-fn play_local_alarm() -> Result<(), String> {
-    audio::play_alarm_sound()
-        .map_err(|e| format!("Erreur audio: {}", e))
+fn restore_alarm(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    alarm_id: String,
+) -> Result<AlarmEntry, String> {
+    let locale = locale(&state);
+    let app_data_dir = data_dir(&app_handle)?;
+    let mut trashed = trash::load(&app_data_dir);
+    let alarm = trash::take(&mut trashed, &alarm_id, chrono::Utc::now(), locale)?;
+
+    let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    alarms.retain(|a| a.id != alarm.id);
+    alarms.push(alarm.clone());
+    storage::save_alarms(&app_data_dir, &alarms)?;
+    trash::save(&app_data_dir, &trashed)?;
+    emit_alarms_changed(&app_handle, &alarms);
+    Ok(alarm)
 }
 
-/// Arrête l'alarme locale
+/// Vide definitivement la corbeille
 #[tauri::command]
-fn stop_local_alarm() -> Result<(), String> {
-    audio::stop_alarm_sound()
-        .map_err(|e| format!("Erreur audio: {}", e))
+fn purge_trash(app_handle: tauri::AppHandle) -> Result<(), String> {
+    let app_data_dir = data_dir(&app_handle)?;
+    let alarm_ids = trash::load(&app_data_dir).into_iter().map(|t| t.alarm.id).collect();
+    trash::save(&app_data_dir, &[])?;
+    events::emit(&app_handle, events::AlarmEvent::TrashPurged { alarm_ids });
+    Ok(())
 }
 
-// -- POINT D'ENTRÉE PRINCIPAL --
+/// Detecte l'alarme (ou la sieste) qui doit sonner a `now` et la marque
+/// comme en cours de sonnerie. Appele par le planificateur et par `check_alarms`.
+pub(crate) fn poll_alarms(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<Option<AlarmEntry>, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    // Les siestes sont ponctuelles: retirees des qu'elles sonnent
+    let due_nap = {
+        let mut naps = state.naps.lock().map_err(|e| e.to_string())?;
+        naps.iter()
+            .position(|n| n.is_due(now))
+            .map(|pos| naps.remove(pos))
+    };
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .plugin(tauri_plugin_opener::init())
-        .plugin(tauri_plugin_fs::init())
-        .plugin(tauri_plugin_shell::init())
-        .setup(|app| {
-            let app_data_dir = app.path().app_data_dir().expect("Failed to get app data directory");
-            
-            // Charger les alarmes sauvegardees
-            if let Ok(alarms) = storage::load_alarms(&app_data_dir) {
-                let state = app.state::<AppState>();
-                if let Ok(mut stored_alarms) = state.alarms.lock() {
-                    *stored_alarms = alarms;
-                };
-            }
+    let triggered = match due_nap {
+        Some(nap) => Some((nap.alarm, nap.auto_snoozes, Vec::new())),
+        None => {
+            let events = calendar_events(state)?;
+            let fired = state.fired.lock().map_err(|e| e.to_string())?;
+            let mut plans = state.wake_plans.lock().map_err(|e| e.to_string())?;
+            let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+            alarm::due_alarm(&alarms, &mut plans, &fired, &events, now, &config)
+                .map(|due| (due.alarm, 0, due.absorbed))
+        }
+    };
 
-            // Charger la configuration
-            if let Ok(config) = storage::load_config(&app_data_dir) {
+    // Une seule sonnerie par occurrence: le registre survit a l'arret de l'alarme et au redemarrage
+    if let Some((alarm, auto_snoozes, absorbed)) = triggered {
+        {
+            let mut fired = state.fired.lock().map_err(|e| e.to_string())?;
+            alarm::record_fired(&mut fired, &alarm.id, now);
+            // Alarmes en collision: notees pour ne pas remplacer la sonnerie au tick suivant
+            for id in &absorbed {
+                tracing::info!(alarm_id = %id, winner = %alarm.id, "Alarme absorbee par une alarme simultanee");
+                alarm::record_fired(&mut fired, id, now);
+            }
+            if let Err(e) = data_dir(app_handle).and_then(|dir| history::save_fired(&dir, &fired)) {
+                tracing::warn!(alarm_id = %alarm.id, "Declenchement non enregistre: {}", e);
+            }
+        }
+        return ring_or_defer(app_handle, state, alarm, auto_snoozes, now, &config);
+    }
+    // La sonnerie precedente est terminee: l'alarme mise en attente sonne a son tour
+    if state.ringing.lock().map_err(|e| e.to_string())?.is_none() {
+        let next = {
+            let mut deferred = state.deferred_rings.lock().map_err(|e| e.to_string())?;
+            (!deferred.is_empty()).then(|| deferred.remove(0))
+        };
+        if let Some((alarm, auto_snoozes)) = next {
+            return ring_or_defer(app_handle, state, alarm, auto_snoozes, now, &config);
+        }
+    }
+    Ok(None)
+}
+
+/// Fait sonner l'alarme, ou la met en attente si une autre sonne encore:
+/// la sonnerie en cours garde son defi, son historique et son nettoyage.
+fn ring_or_defer(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    alarm: AlarmEntry,
+    auto_snoozes: u32,
+    now: chrono::DateTime<chrono::Local>,
+    config: &storage::AppConfig,
+) -> Result<Option<AlarmEntry>, String> {
+    if state.ringing.lock().map_err(|e| e.to_string())?.is_some() {
+        tracing::info!(alarm_id = %alarm.id, "Une autre alarme sonne encore: sonnerie differee");
+        state.deferred_rings.lock().map_err(|e| e.to_string())?.push((alarm, auto_snoozes));
+        return Ok(None);
+    }
+    let provider = query::ProviderKind::of(&alarm.playlist_uri);
+    let pending = alarm.clone();
+    let mut started = start_ringing(state, alarm, now, config)?;
+    started.auto_snoozes = auto_snoozes;
+    record_telemetry(app_handle, state, telemetry::TelemetryKind::AlarmFired, Some(provider), None);
+    if let Some(reason) = started.fallback {
+        record_telemetry(app_handle, state, telemetry::TelemetryKind::FallbackUsed, Some(provider), Some(reason));
+    }
+    let alarm = started.alarm.clone();
+    let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+    if ringing.is_some() {
+        // Un autre declenchement a pris la place entre-temps
+        drop(ringing);
+        state.deferred_rings.lock().map_err(|e| e.to_string())?.push((pending, auto_snoozes));
+        return Ok(None);
+    }
+    *ringing = Some(started);
+    Ok(Some(alarm))
+}
+
+/// Prepare la sonnerie d'une alarme qui se declenche: profil du jour, meteo,
+/// repli hors ligne et sortie audio. Commun au planificateur et au test de l'alarme.
+fn start_ringing(
+    state: &AppState,
+    mut alarm: AlarmEntry,
+    now: chrono::DateTime<chrono::Local>,
+    config: &storage::AppConfig,
+) -> Result<alarm::RingingAlarm, String> {
+    let weather = state.weather.lock().map_err(|e| e.to_string())?.clone();
+    alarm::prepare_ring(&mut alarm, now, weather.as_ref());
+    // Reseau coupe: son local tout de suite plutot qu'une lecture qui echouerait en silence
+    let online = net::is_online(state)?;
+    let fallback = (!online && alarm::fall_back_offline(&mut alarm)).then_some(alarm::FallbackReason::Offline);
+    if fallback.is_some() {
+        tracing::warn!(alarm_id = %alarm.id, "Reseau coupe: son local a la place de la playlist");
+    }
+    // Sortie de l'alarme, sinon sortie globale
+    audio::select_output_device(alarm.output_device.clone().or(config.output_device.clone()))?;
+    tracing::info!(alarm_id = %alarm.id, time = %alarm.time, playlist = %alarm.playlist_uri, "Alarme declenchee");
+    let mut started = alarm::RingingAlarm::new(alarm, now);
+    started.fallback = fallback;
+    Ok(started)
+}
+
+/// Resultat d'un test de l'alarme
+#[derive(Debug, Clone, Serialize)]
+struct TestFire {
+    alarm: AlarmEntry, // Reglages effectivement utilises (profil du jour, meteo, repli)
+    issues: Vec<readiness::ReadinessIssue>, // Problemes detectes avant la sonnerie
+    fallback: Option<alarm::FallbackReason>,
+}
+
+/// Fait sonner une alarme maintenant par le meme chemin que le planificateur
+/// (verification, lecture, fondu, repli, evenements). La sonnerie s'arrete comme
+/// une vraie alarme et son entree d'historique est marquee comme test.
+#[tauri::command]
+async fn test_alarm(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    alarm_id: String,
+) -> Result<TestFire, String> {
+    let locale = locale(&state);
+    if state.ringing.lock().map_err(|e| e.to_string())?.is_some() {
+        return Err(tr(locale, Msg::AlarmAlreadyRinging, &[]));
+    }
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let alarm = state.alarms.lock().map_err(|e| e.to_string())?
+        .iter()
+        .find(|a| a.id == alarm_id)
+        .cloned()
+        .ok_or_else(|| tr(locale, Msg::AlarmNotFound, &[&alarm_id]))?;
+
+    let context = readiness_context(&state, &alarm, &config)?;
+    let issues = readiness::check(&alarm, &context).await;
+    if !issues.is_empty() {
+        tracing::warn!(alarm_id = %alarm.id, issues = ?issues, "Test de l'alarme: probleme detecte");
+    }
+
+    let mut started = start_ringing(&state, alarm, chrono::Local::now(), &config)?;
+    started.test = true;
+    let fallback = started.fallback;
+    let alarm = started.alarm.clone();
+    {
+        // Une vraie alarme a pu se declencher pendant la verification
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        if ringing.is_some() {
+            return Err(tr(locale, Msg::AlarmAlreadyRinging, &[]));
+        }
+        *ringing = Some(started);
+    }
+    scheduler::ring_triggered(&app_handle, &state, &alarm)?;
+    events::emit(&app_handle, events::AlarmEvent::Triggered { alarm: Box::new(alarm.clone()) });
+    Ok(TestFire { alarm, issues, fallback })
+}
+
+/// Comptes et reglages dont depend la lecture de l'alarme
+pub(crate) fn readiness_context(
+    state: &AppState,
+    alarm: &AlarmEntry,
+    config: &storage::AppConfig,
+) -> Result<readiness::ReadinessContext, String> {
+    Ok(readiness::ReadinessContext {
+        spotify: spotify_client_for(state, alarm.account.as_deref())?,
+        apple_music: state.apple_music.lock().map_err(|e| e.to_string())?.clone(),
+        youtube_music: state.youtube_music.lock().map_err(|e| e.to_string())?.clone(),
+        music_library: config.music_library.clone(),
+    })
+}
+
+/// Retourne l'alarme en train de sonner, le cas echeant
+#[tauri::command]
+fn check_alarms(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<Option<AlarmEntry>, String> {
+    if let Some(alarm) = poll_alarms(&app_handle, &state, chrono::Local::now())? {
+        scheduler::persist_ringing(&app_handle, &state)?;
+        scheduler::ring_triggered(&app_handle, &state, &alarm)?;
+        events::emit(&app_handle, events::AlarmEvent::Triggered { alarm: Box::new(alarm) });
+    }
+    Ok(state.ringing.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|r| r.alarm.clone()))
+}
+
+// -- ARRET DE L'ALARME --
+
+/// Retourne l'alarme en cours de sonnerie
+#[tauri::command]
+fn get_ringing_alarm(state: State<'_, AppState>) -> Result<Option<alarm::RingingAlarm>, String> {
+    Ok(state.ringing.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Retourne le defi a resoudre pour arreter l'alarme (None si aucun defi requis).
+/// Le meme defi est renvoye tant qu'il n'est pas resolu.
+#[tauri::command]
+fn get_dismiss_challenge(
+    state: State<'_, AppState>,
+    alarm_id: String,
+) -> Result<Option<challenge::Challenge>, String> {
+    let locale = locale(&state);
+    let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+    let ringing = ringing.as_mut()
+        .filter(|r| r.alarm.id == alarm_id)
+        .ok_or_else(|| tr(locale, Msg::AlarmNotRinging, &[&alarm_id]))?;
+
+    let Some(config) = ringing.alarm.challenge.clone() else {
+        return Ok(None);
+    };
+    let challenge = ringing.challenge.get_or_insert_with(|| {
+        challenge::Challenge::generate(&alarm_id, &config, &mut rand::thread_rng())
+    });
+    Ok(Some(challenge.clone()))
+}
+
+/// Soumet une reponse au defi. Retourne true si elle est correcte.
+#[tauri::command]
+fn submit_challenge_answer(
+    state: State<'_, AppState>,
+    alarm_id: String,
+    answer: String,
+) -> Result<bool, String> {
+    let locale = locale(&state);
+    let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+    let ringing = ringing.as_mut()
+        .filter(|r| r.alarm.id == alarm_id)
+        .ok_or_else(|| tr(locale, Msg::AlarmNotRinging, &[&alarm_id]))?;
+
+    let challenge = ringing.challenge.as_mut()
+        .ok_or_else(|| tr(locale, Msg::NoActiveChallenge, &[]))?;
+    let correct = challenge.check(&answer);
+    if correct {
+        ringing.solved = true;
+    }
+    Ok(correct)
+}
+
+/// Retourne l'historique des sonneries (plus recentes en premier)
+#[tauri::command]
+fn get_alarm_history(app_handle: tauri::AppHandle) -> Result<Vec<history::HistoryEntry>, String> {
+    let app_data_dir = data_dir(&app_handle)?;
+    let mut entries = history::load_history(&app_data_dir);
+    entries.reverse();
+    Ok(entries)
+}
+
+/// Retourne les verifications d'avant sonnerie (plus recentes en premier)
+#[tauri::command]
+fn get_readiness_history(app_handle: tauri::AppHandle) -> Result<Vec<readiness::ReadinessReport>, String> {
+    let app_data_dir = data_dir(&app_handle)?;
+    let mut reports = history::load_readiness(&app_data_dir);
+    reports.reverse();
+    Ok(reports)
+}
+
+/// Etat du reseau pour l'affichage initial (les changements arrivent ensuite par evenement)
+#[tauri::command]
+fn get_network_status(state: State<'_, AppState>) -> Result<net::NetworkStatus, String> {
+    Ok(state.network.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Humeurs attribuees aux playlists (par URI)
+#[tauri::command]
+fn get_playlist_moods(app_handle: tauri::AppHandle) -> Result<moods::PlaylistMoods, String> {
+    Ok(moods::load_moods(&data_dir(&app_handle)?))
+}
+
+/// Remplace les humeurs d'une playlist (liste vide = aucune)
+#[tauri::command]
+fn set_playlist_moods(
+    app_handle: tauri::AppHandle,
+    uri: String,
+    moods: Vec<moods::Mood>,
+) -> Result<moods::PlaylistMoods, String> {
+    moods::set_moods(&data_dir(&app_handle)?, &uri, moods)
+}
+
+/// Playlists recommandees pour l'heure donnee (heure courante par defaut)
+#[tauri::command]
+fn recommend_playlists(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    hour: Option<u32>,
+) -> Result<Vec<moods::Recommendation>, String> {
+    let hour = hour.unwrap_or_else(|| chrono::Local::now().hour());
+    if hour > 23 {
+        return Err(tr(locale(&state), Msg::HourInvalid, &[]));
+    }
+    Ok(moods::recommend(&moods::load_moods(&data_dir(&app_handle)?), hour))
+}
+
+/// Statistiques de reveil sur la periode demandee
+#[tauri::command]
+fn get_wake_stats(app_handle: tauri::AppHandle, range: stats::StatsRange) -> Result<stats::WakeStats, String> {
+    let app_data_dir = data_dir(&app_handle)?;
+    let entries = history::load_history(&app_data_dir);
+    Ok(stats::compute(&entries, range, chrono::Local::now()))
+}
+
+/// Id de l'alarme qui sonne (actions sans id: liens, API distante)
+pub(crate) fn ringing_alarm_id(state: &AppState) -> Result<String, String> {
+    let locale = locale(state);
+    state.ringing.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|r| r.alarm.id.clone())
+        .ok_or_else(|| tr(locale, Msg::NoRingingAlarm, &[]))
+}
+
+/// Verifie que l'alarme en cours peut etre arretee (defi resolu)
+fn ensure_dismissable(state: &AppState) -> Result<(), String> {
+    let locale = locale(state);
+    let ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+    match ringing.as_ref() {
+        Some(r) if !r.can_dismiss() => Err(tr(locale, Msg::ChallengeUnsolved, &[])),
+        _ => Ok(()),
+    }
+}
+
+/// Arrete l'alarme en cours (son local et lecture Spotify).
+/// Refuse tant que le defi configure n'a pas ete resolu.
+#[tauri::command]
+async fn dismiss_alarm(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    alarm_id: String,
+) -> Result<(), String> {
+    dismiss_ringing(&app_handle, &state, &alarm_id).await
+}
+
+/// Arrete l'alarme qui sonne (commande et raccourci global)
+pub(crate) async fn dismiss_ringing(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    alarm_id: &str,
+) -> Result<(), String> {
+    let locale = locale(state);
+    // Retirer la sonnerie d'abord: le planificateur cesse l'escalade
+    let ringing = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        let current = ringing.as_ref()
+            .filter(|r| r.alarm.id == alarm_id)
+            .ok_or_else(|| tr(locale, Msg::AlarmNotRinging, &[&alarm_id]))?;
+        if !current.can_dismiss() {
+            return Err(tr(locale, Msg::ChallengeUnsolved, &[]));
+        }
+        ringing.take().ok_or_else(|| tr(locale, Msg::AlarmNotRinging, &[&alarm_id]))?
+    };
+    tracing::info!(alarm_id = %alarm_id, "Alarme arretee par l'utilisateur");
+    finish_ringing(app_handle, state, ringing, history::AlarmOutcome::Dismissed).await?;
+    events::emit(app_handle, events::AlarmEvent::Dismissed { alarm_id: alarm_id.to_string() });
+    Ok(())
+}
+
+/// Reporte l'alarme qui sonne de `minutes` (duree de report configuree par defaut).
+/// Possible meme si un defi est configure.
+#[tauri::command]
+async fn snooze_alarm(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    alarm_id: String,
+    minutes: Option<u32>,
+) -> Result<alarm::NapTimer, String> {
+    snooze_ringing(&app_handle, &state, &alarm_id, minutes).await
+}
+
+/// Reporte l'alarme qui sonne (commande et raccourci global)
+pub(crate) async fn snooze_ringing(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    alarm_id: &str,
+    minutes: Option<u32>,
+) -> Result<alarm::NapTimer, String> {
+    let locale = locale(state);
+    let minutes = match minutes {
+        Some(minutes) => minutes,
+        None => state.config.lock().map_err(|e| e.to_string())?.snooze_minutes,
+    };
+    if minutes == 0 || minutes > alarm::MAX_NAP_MINUTES {
+        return Err(tr(locale, Msg::InvalidDuration, &[&alarm::MAX_NAP_MINUTES]));
+    }
+
+    let ringing = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        ringing.as_ref()
+            .filter(|r| r.alarm.id == alarm_id)
+            .ok_or_else(|| tr(locale, Msg::AlarmNotRinging, &[&alarm_id]))?;
+        ringing.take().ok_or_else(|| tr(locale, Msg::AlarmNotRinging, &[&alarm_id]))?
+    };
+    let nap = alarm::NapTimer::snooze(&ringing, chrono::Local::now(), minutes);
+    state.naps.lock().map_err(|e| e.to_string())?.push(nap.clone());
+    tracing::info!(alarm_id = %alarm_id, minutes, "Alarme reportee par l'utilisateur");
+
+    finish_ringing(app_handle, state, ringing, history::AlarmOutcome::Snoozed).await?;
+    events::emit(app_handle, events::AlarmEvent::Snoozed { alarm_id: alarm_id.to_string(), fires_at: nap.fires_at });
+    Ok(nap)
+}
+
+/// Termine une sonnerie retiree de l'etat: historique, arret du son local et de la playlist
+async fn finish_ringing(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    ringing: alarm::RingingAlarm,
+    outcome: history::AlarmOutcome,
+) -> Result<(), String> {
+    let locale = locale(state);
+    ringing.stop_fades();
+    let alarm = ringing.alarm.clone();
+
+    if let Ok(app_data_dir) = data_dir(app_handle) {
+        let entry = history::HistoryEntry::new(&ringing, chrono::Local::now(), outcome);
+        let _ = history::record(&app_data_dir, entry);
+    }
+
+    audio::stop_alarm_sound()
+        .map_err(|e| tr(locale, Msg::AudioError, &[&e]))?;
+    let target = playback_target(app_handle, state, &alarm)?;
+    if !matches!(target, fade::FadeTarget::Local) {
+        target.pause().await
+            .map_err(|e| tr(locale, Msg::PauseError, &[&e]))?;
+    }
+    Ok(())
+}
+
+/// Retourne la prochaine alarme a sonner (siestes comprises), avec l'instant
+/// exact et le temps restant
+#[tauri::command]
+fn get_next_alarm(state: State<'_, AppState>) -> Result<Option<alarm::NextAlarm>, String> {
+    next_alarm(&state, chrono::Local::now())
+}
+
+/// Prochaine alarme a sonner apres `now` (commande, mini horloge)
+pub(crate) fn next_alarm(state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<Option<alarm::NextAlarm>, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let naps = state.naps.lock().map_err(|e| e.to_string())?.clone();
+    let events = calendar_events(state)?;
+    let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+
+    Ok(alarm::next_alarm_by(&alarms, &naps, now, config.locale, |alarm| {
+        calendar::next_trigger(alarm, &events, now, &config)
+    }))
+}
+
+/// Sonneries des `days` prochains jours (7 par defaut), occurrences annulees comprises
+#[tauri::command]
+fn preview_schedule(state: State<'_, AppState>, days: Option<u32>) -> Result<Vec<preview::ScheduledRing>, String> {
+    let now = chrono::Local::now();
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let naps = state.naps.lock().map_err(|e| e.to_string())?.clone();
+    let events = calendar_events(&state)?;
+    let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    Ok(preview::preview(&alarms, &naps, &events, now, days.unwrap_or(7), &config))
+}
+
+/// Heure de coucher conseillee pour la prochaine alarme (siestes exclues)
+pub(crate) fn suggested_bedtime(
+    state: &AppState,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<Option<bedtime::SuggestedBedtime>, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let events = calendar_events(state)?;
+    let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+
+    Ok(alarm::next_alarm_by(&alarms, &[], now, config.locale, |alarm| calendar::next_trigger(alarm, &events, now, &config))
+        .map(|next| bedtime::suggest(&next, &config.bedtime)))
+}
+
+/// Retourne l'heure de coucher conseillee d'apres la duree de sommeil visee
+#[tauri::command]
+fn get_suggested_bedtime(state: State<'_, AppState>) -> Result<Option<bedtime::SuggestedBedtime>, String> {
+    suggested_bedtime(&state, chrono::Local::now())
+}
+
+/// Configure la duree de sommeil visee et le rappel de coucher
+#[tauri::command]
+fn set_bedtime_reminder(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: bedtime::BedtimeSettings,
+) -> Result<(), String> {
+    settings.validate()?;
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.bedtime = settings;
+
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Saute uniquement la prochaine occurrence d'une alarme.
+/// Retourne la date de l'occurrence sautee.
+#[tauri::command]
+fn skip_next(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    alarm_id: String,
+) -> Result<chrono::NaiveDate, String> {
+    let locale = locale(&state);
+    let mut alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+    let skipped = alarm_list::skip_next(&mut alarms, &alarm_id, chrono::Local::now(), locale)?;
+
+    // Persister
+    storage::save_alarms(&data_dir(&app_handle)?, &alarms)?;
+    emit_alarms_changed(&app_handle, &alarms);
+
+    Ok(skipped)
+}
+
+/// Suspend toutes les alarmes jusqu'a une date incluse (None pour reprendre)
+#[tauri::command]
+fn vacation_mode(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    until_date: Option<chrono::NaiveDate>,
+) -> Result<(), String> {
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.vacation_until = until_date;
+
+    persist_config(&app_handle, &config)?;
+
+    Ok(())
+}
+
+/// Configure le calendrier des jours feries (preset pays et dates personnalisees)
+#[tauri::command]
+fn set_holiday_calendar(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    country: Option<String>,
+    custom_dates: Vec<chrono::NaiveDate>,
+) -> Result<(), String> {
+    let country = country.map(|c| c.trim().to_uppercase()).filter(|c| !c.is_empty());
+    if let Some(code) = country.as_deref() {
+        if !holidays::SUPPORTED_COUNTRIES.contains(&code) {
+            return Err(tr(locale(&state), Msg::UnknownHolidayCalendar, &[&code]));
+        }
+    }
+
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.holiday_country = country;
+    config.custom_holidays = custom_dates;
+
+    persist_config(&app_handle, &config)?;
+
+    Ok(())
+}
+
+// -- METEO --
+
+/// Configure le lieu utilise pour la meteo du reveil (None pour desactiver)
+#[tauri::command]
+fn set_weather_location(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    location: Option<weather::Location>,
+) -> Result<(), String> {
+    if let Some(location) = location.as_ref() {
+        location.validate()?;
+    }
+
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.weather_location = location;
+    *state.weather.lock().map_err(|e| e.to_string())? = None;
+
+    persist_config(&app_handle, &config)?;
+
+    Ok(())
+}
+
+/// Recupere la meteo du lieu configure et la garde en cache
+/// (None si aucun lieu n'est configure)
+pub(crate) async fn refresh_weather(state: &AppState) -> Result<Option<weather::WakeWeather>, String> {
+    let location = state.config.lock().map_err(|e| e.to_string())?.weather_location.clone();
+    let Some(location) = location else {
+        return Ok(None);
+    };
+
+    let fetched = weather::fetch_weather(&location).await?;
+    *state.weather.lock().map_err(|e| e.to_string())? = Some(fetched.clone());
+    Ok(Some(fetched))
+}
+
+/// Retourne la meteo actuelle au lieu configure (depuis le cache si encore valide)
+#[tauri::command]
+async fn get_wake_weather(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<Option<weather::WakeWeather>, String> {
+    if !refresh.unwrap_or(false) {
+        let cached = state.weather.lock().map_err(|e| e.to_string())?.clone();
+        if let Some(cached) = cached.filter(|w| w.is_fresh(chrono::Utc::now())) {
+            return Ok(Some(cached));
+        }
+    }
+    refresh_weather(&state).await
+}
+
+/// Liste les jours feries d'une annee selon le calendrier configure
+#[tauri::command]
+fn get_holidays(state: State<'_, AppState>, year: i32) -> Result<Vec<chrono::NaiveDate>, String> {
+    use chrono::Datelike;
+
+    let config = state.config.lock().map_err(|e| e.to_string())?;
+    let mut dates = match config.holiday_country.as_deref() {
+        Some(country) => holidays::country_holidays(country, year)?,
+        None => Vec::new(),
+    };
+    dates.extend(config.custom_holidays.iter().filter(|d| d.year() == year));
+    dates.sort();
+    dates.dedup();
+    Ok(dates)
+}
+
+/// Programme une sieste qui sonnera dans `minutes` minutes (non persistee)
+#[tauri::command]
+fn set_nap_timer(
+    state: State<'_, AppState>,
+    minutes: u32,
+    playlist_name: Option<String>,
+    playlist_uri: Option<String>,
+) -> Result<alarm::NapTimer, String> {
+    let locale = locale(&state);
+    if minutes == 0 || minutes > alarm::MAX_NAP_MINUTES {
+        return Err(tr(locale, Msg::InvalidDuration, &[&alarm::MAX_NAP_MINUTES]));
+    }
+
+    let volume = state.config.lock().map_err(|e| e.to_string())?.default_volume;
+    let nap = alarm::NapTimer::new(
+        chrono::Local::now(),
+        minutes,
+        playlist_name.unwrap_or_else(|| tr(locale, Msg::DefaultNapName, &[])),
+        playlist_uri.unwrap_or_else(|| "local".to_string()),
+        volume,
+    );
+
+    state.naps.lock().map_err(|e| e.to_string())?.push(nap.clone());
+    Ok(nap)
+}
+
+/// Retourne les siestes en attente
+#[tauri::command]
+fn get_nap_timers(state: State<'_, AppState>) -> Result<Vec<alarm::NapTimer>, String> {
+    Ok(state.naps.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Annule une sieste en attente
+#[tauri::command]
+fn cancel_nap_timer(state: State<'_, AppState>, nap_id: String) -> Result<(), String> {
+    let locale = locale(&state);
+    let mut naps = state.naps.lock().map_err(|e| e.to_string())?;
+    let before = naps.len();
+    naps.retain(|n| n.alarm.id != nap_id);
+
+    if naps.len() < before {
+        Ok(())
+    } else {
+        Err(tr(locale, Msg::NapNotFound, &[&nap_id]))
+    }
+}
+
+// -- MINUTEURS --
+
+/// Lance un minuteur a rebours de `seconds` secondes (non persiste)
+#[tauri::command]
+fn start_timer(state: State<'_, AppState>, label: Option<String>, seconds: u32) -> Result<timer::CountdownTimer, String> {
+    let locale = locale(&state);
+    if seconds == 0 || seconds > timer::MAX_TIMER_SECONDS {
+        return Err(tr(locale, Msg::TimerDurationInvalid, &[&timer::MAX_TIMER_SECONDS]));
+    }
+
+    let label = label.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()).unwrap_or_else(|| tr(locale, Msg::DefaultTimerName, &[]));
+    let started = timer::CountdownTimer::new(label, seconds, chrono::Local::now());
+    state.timers.lock().map_err(|e| e.to_string())?.push(started.clone());
+    Ok(started)
+}
+
+/// Retourne les minuteurs en cours, le plus proche de la fin d'abord
+#[tauri::command]
+fn list_timers(state: State<'_, AppState>) -> Result<Vec<timer::CountdownTimer>, String> {
+    let mut timers = state.timers.lock().map_err(|e| e.to_string())?.clone();
+    timers.sort_by_key(|t| t.ends_at);
+    Ok(timers)
+}
+
+/// Annule un minuteur en cours
+#[tauri::command]
+fn cancel_timer(state: State<'_, AppState>, timer_id: String) -> Result<(), String> {
+    let locale = locale(&state);
+    let mut timers = state.timers.lock().map_err(|e| e.to_string())?;
+    let before = timers.len();
+    timers.retain(|t| t.id != timer_id);
+
+    if timers.len() < before {
+        Ok(())
+    } else {
+        Err(tr(locale, Msg::TimerNotFound, &[&timer_id]))
+    }
+}
+
+// -- HORLOGES MONDIALES --
+
+/// Heure courante dans chaque fuseau demande (fuseaux configures par defaut)
+#[tauri::command]
+fn get_world_times(state: State<'_, AppState>, zones: Option<Vec<String>>) -> Result<Vec<worldclock::WorldTime>, String> {
+    let (configured, time_format) = {
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        (config.world_clocks.clone(), config.time_format)
+    };
+    let now = chrono::Local::now();
+    zones.unwrap_or(configured)
+        .iter()
+        .map(|zone| worldclock::world_time(zone, now, time_format))
+        .collect()
+}
+
+// -- CHRONOMETRE --
+
+/// Applique `change` au chronometre persiste puis le sauvegarde
+fn update_stopwatch(
+    app_handle: &tauri::AppHandle,
+    change: impl FnOnce(&mut stopwatch::Stopwatch, chrono::DateTime<chrono::Local>),
+) -> Result<stopwatch::StopwatchStatus, String> {
+    let dir = data_dir(app_handle)?;
+    let now = chrono::Local::now();
+    let mut watch = stopwatch::load(&dir);
+    change(&mut watch, now);
+    stopwatch::save(&dir, &watch)?;
+    Ok(watch.status(now))
+}
+
+/// Retourne l'etat du chronometre
+#[tauri::command]
+fn get_stopwatch(app_handle: tauri::AppHandle) -> Result<stopwatch::StopwatchStatus, String> {
+    Ok(stopwatch::load(&data_dir(&app_handle)?).status(chrono::Local::now()))
+}
+
+/// Demarre ou reprend le chronometre
+#[tauri::command]
+fn stopwatch_start(app_handle: tauri::AppHandle) -> Result<stopwatch::StopwatchStatus, String> {
+    update_stopwatch(&app_handle, |watch, now| watch.start(now))
+}
+
+/// Enregistre un tour (erreur si le chronometre est arrete)
+#[tauri::command]
+fn stopwatch_lap(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<stopwatch::Lap, String> {
+    let dir = data_dir(&app_handle)?;
+    let mut watch = stopwatch::load(&dir);
+    let lap = watch.lap(chrono::Local::now())
+        .ok_or_else(|| tr(locale(&state), Msg::StopwatchNotRunning, &[]))?;
+    stopwatch::save(&dir, &watch)?;
+    Ok(lap)
+}
+
+/// Met le chronometre en pause
+#[tauri::command]
+fn stopwatch_stop(app_handle: tauri::AppHandle) -> Result<stopwatch::StopwatchStatus, String> {
+    update_stopwatch(&app_handle, |watch, now| watch.stop(now))
+}
+
+/// Remet le chronometre a zero et efface les tours
+#[tauri::command]
+fn stopwatch_reset(app_handle: tauri::AppHandle) -> Result<stopwatch::StopwatchStatus, String> {
+    update_stopwatch(&app_handle, |watch, _| watch.reset())
+}
+
+// -- COMMANDES SPOTIFY --
+
+/// Initie l'authentification Spotify OAuth
+#[tauri::command]
+async fn spotify_login(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    client_id: String,
+    client_secret: String,
+    profile: Option<String>,
+) -> Result<String, String> {
+    // Sauvegarder le client_id dans la config
+    let redirect_uri = {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.spotify_client_id = Some(client_id.clone());
+        persist_config(&app_handle, &config)?;
+        config.spotify_redirect_uri.clone()
+    };
+
+    let profile = profile
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .unwrap_or_else(|| spotify::DEFAULT_PROFILE.to_string());
+
+    // Le secret client n'est jamais ecrit dans config.json
+    if !client_secret.is_empty() {
+        secrets::set_secret(secrets::CLIENT_SECRET_KEY, &client_secret)?;
+    }
+
+    let mut client = spotify::SpotifyClient::new(client_id, client_secret, profile);
+    let auth_url = client.get_auth_url(&redirect_uri);
+    
+    // Le compte actif reste utilisable tant que le nouveau n'est pas authentifie
+    let mut pending_guard = state.spotify_pending_login.lock().map_err(|e| e.to_string())?;
+    *pending_guard = Some(client);
+    
+    Ok(auth_url)
+}
+
+/// Récupère la configuration actuelle
+#[tauri::command]
+fn get_config(state: State<'_, AppState>) -> Result<storage::AppConfig, String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?;
+    Ok(config.clone())
+}
+
+/// Met à jour les champs fournis de la configuration et retourne le résultat
+#[tauri::command]
+fn update_config(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    patch: storage::ConfigPatch,
+) -> Result<storage::AppConfig, String> {
+    let mut current_config = state.config.lock().map_err(|e| e.to_string())?;
+    current_config.apply(patch)?;
+    
+    persist_config(&app_handle, &current_config)?;
+    
+    Ok(current_config.clone())
+}
+
+/// Complete l'authentification avec le code callback
+#[tauri::command]
+async fn spotify_callback(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    code: String,
+) -> Result<(), String> {
+    complete_spotify_login(&app_handle, &state, code).await
+}
+
+/// Echange le code OAuth du compte en attente puis l'active
+/// (commande spotify_callback ou lien charmed://auth/callback)
+pub(crate) async fn complete_spotify_login(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    code: String,
+) -> Result<(), String> {
+    let locale = locale(state);
+    // Cloner le client en attente pour liberer le lock
+    let client_opt = {
+        let pending_guard = state.spotify_pending_login.lock().map_err(|e| e.to_string())?;
+        pending_guard.clone()
+    };
+    
+    if let Some(mut client) = client_opt {
+        client.complete_auth(code).await
+            .map_err(|e| tr(locale, Msg::SpotifyAuthError, &[&e.localized(locale)]))?;
+        
+        // Persister le compte sous son nom de profil (fichier + trousseau:
+        // appels bloquants executes hors des threads du runtime async)
+        let app_data_dir = data_dir(app_handle)?;
+        let user = user_profile(state)?;
+        if let Some(account) = client.to_stored().await {
+            tauri::async_runtime::spawn_blocking(move || {
+                spotify::upsert_account(&app_data_dir, user.as_deref(), account)
+            })
+            .await
+            .map_err(|e| e.to_string())??;
+        }
+
+        state.spotify_accounts.lock().map_err(|e| e.to_string())?
+            .insert(client.profile().to_string(), client.clone());
+        *state.spotify_pending_login.lock().map_err(|e| e.to_string())? = None;
+
+        activate_spotify_account(app_handle, state, client)
+    } else {
+        Err(tr(locale, Msg::SpotifyNotInitialized, &[]))
+    }
+}
+
+/// Rend un compte actif: client courant, cache des playlists et config
+fn activate_spotify_account(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    client: spotify::SpotifyClient,
+) -> Result<(), String> {
+    let profile = client.profile().to_string();
+    *state.spotify_client.lock().map_err(|e| e.to_string())? = Some(client);
+
+    // Les playlists en cache appartiennent au compte precedent
+    *state.playlist_cache.lock().map_err(|e| e.to_string())? = None;
+
+    if let Ok(app_data_dir) = data_dir(app_handle) {
+        let _ = spotify::clear_playlist_cache(&app_data_dir);
+    }
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.active_spotify_profile = Some(profile);
+        persist_config(app_handle, &config)?;
+    }
+
+    emit_auth_state(app_handle, query::ProviderKind::Spotify, true);
+    Ok(())
+}
+
+/// Bascule vers un autre compte Spotify deja authentifie
+#[tauri::command]
+fn switch_spotify_account(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile: String,
+) -> Result<(), String> {
+    let locale = locale(&state);
+    let client = state.spotify_accounts.lock().map_err(|e| e.to_string())?
+        .get(&profile)
+        .cloned()
+        .ok_or_else(|| tr(locale, Msg::SpotifyAccountNotFound, &[&profile]))?;
+
+    activate_spotify_account(&app_handle, &state, client)
+}
+
+/// Liste les profils Spotify authentifies
+#[tauri::command]
+fn list_spotify_accounts(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    let accounts = state.spotify_accounts.lock().map_err(|e| e.to_string())?;
+    let mut profiles: Vec<String> = accounts.keys().cloned().collect();
+    profiles.sort();
+    Ok(profiles)
+}
+
+/// Deconnecte un compte Spotify (le compte actif par defaut) et efface ses tokens
+#[tauri::command]
+fn spotify_logout(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    profile: Option<String>,
+) -> Result<(), String> {
+    let active_profile = state.spotify_client.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|c| c.profile().to_string());
+    let Some(profile) = profile.or(active_profile.clone()) else {
+        return Ok(());
+    };
+
+    state.spotify_accounts.lock().map_err(|e| e.to_string())?.remove(&profile);
+    let app_data_dir = data_dir(&app_handle).ok();
+    if let Some(dir) = app_data_dir.as_deref() {
+        spotify::remove_account(dir, user_profile(&state)?.as_deref(), &profile)?;
+    }
+
+    if active_profile.as_deref() == Some(profile.as_str()) {
+        *state.spotify_client.lock().map_err(|e| e.to_string())? = None;
+        *state.playlist_cache.lock().map_err(|e| e.to_string())? = None;
+
+        if let Some(dir) = app_data_dir.as_deref() {
+            spotify::clear_playlist_cache(dir)?;
+        }
+        {
+            let mut config = state.config.lock().map_err(|e| e.to_string())?;
+            config.active_spotify_profile = None;
+            persist_config(&app_handle, &config)?;
+        }
+
+        emit_auth_state(&app_handle, query::ProviderKind::Spotify, false);
+    }
+    Ok(())
+}
+
+/// Retourne le client du profil demande, ou le client actif par defaut
+pub(crate) fn spotify_client_for(
+    state: &AppState,
+    account: Option<&str>,
+) -> Result<Option<spotify::SpotifyClient>, String> {
+    let client = match account {
+        Some(profile) => state.spotify_accounts.lock().map_err(|e| e.to_string())?
+            .get(profile)
+            .cloned(),
+        None => state.spotify_client.lock().map_err(|e| e.to_string())?.clone(),
+    };
+    // Appareils preferes de la configuration, essayes avant l'appareil actif
+    let devices = state.config.lock().map_err(|e| e.to_string())?.spotify_devices.clone();
+    Ok(client.map(|c| c.with_device_preference(devices)))
+}
+
+/// Sortie sur laquelle joue l'alarme, selon le service de sa playlist
+/// (son local si le service n'est pas connecte), accompagnee du son local
+/// si la lecture groupee le demande
+pub(crate) fn playback_target(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    alarm: &AlarmEntry,
+) -> Result<fade::FadeTarget, String> {
+    let target = service_target(app_handle, state, alarm)?;
+    let local_sound = alarm.group_playback.as_ref().is_some_and(|g| g.local_sound);
+    Ok(match target {
+        fade::FadeTarget::Spotify(_) | fade::FadeTarget::AppleMusic(_) | fade::FadeTarget::YouTubeMusic(_)
+            if local_sound =>
+        {
+            fade::FadeTarget::Group(vec![target, fade::FadeTarget::Local])
+        }
+        target => target,
+    })
+}
+
+/// Service qui joue la playlist de l'alarme
+fn service_target(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    alarm: &AlarmEntry,
+) -> Result<fade::FadeTarget, String> {
+    if alarm.playlist_uri == "local" {
+        return Ok(fade::FadeTarget::Local);
+    }
+    if apple_music::AppleMusicClient::handles(&alarm.playlist_uri) {
+        return Ok(match state.apple_music.lock().map_err(|e| e.to_string())?.clone() {
+            Some(client) => fade::FadeTarget::AppleMusic(Box::new(client)),
+            None => fade::FadeTarget::Local,
+        });
+    }
+    if podcast::PodcastPlayer::handles(&alarm.playlist_uri) {
+        // Les episodes sont mis en cache dans le dossier du profil
+        let player = podcast::PodcastPlayer::new(Vec::new(), &data_dir(app_handle)?)?;
+        return Ok(fade::FadeTarget::Podcast(Box::new(player)));
+    }
+    if radio::RadioPlayer::handles(&alarm.playlist_uri) {
+        return Ok(fade::FadeTarget::Radio(Box::default()));
+    }
+    if library::MusicLibrary::handles(&alarm.playlist_uri) {
+        return Ok(match state.config.lock().map_err(|e| e.to_string())?.music_library.as_ref() {
+            Some(settings) => fade::FadeTarget::Library(Box::new(library::MusicLibrary::new(settings))),
+            None => fade::FadeTarget::Local,
+        });
+    }
+    if youtube_music::YouTubeMusicClient::handles(&alarm.playlist_uri) {
+        return Ok(match state.youtube_music.lock().map_err(|e| e.to_string())?.clone() {
+            Some(client) => fade::FadeTarget::YouTubeMusic(Box::new(client)),
+            None => fade::FadeTarget::Local,
+        });
+    }
+    let device = alarm.group_playback.as_ref().and_then(|g| g.spotify_device.as_deref());
+    Ok(match spotify_client_for(state, alarm.account.as_deref())? {
+        // Le groupe Spotify Connect de l'alarme passe avant les appareils preferes
+        Some(client) => match device {
+            Some(device) => fade::FadeTarget::Spotify(Box::new(client.prefer_device(device))),
+            None => fade::FadeTarget::Spotify(Box::new(client)),
+        },
+        None => fade::FadeTarget::Local,
+    })
+}
+
+/// Recupere les playlists de l'utilisateur (depuis le cache si encore valide)
+#[tauri::command]
+async fn get_spotify_playlists(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<Vec<spotify::SpotifyPlaylist>, String> {
+    let refresh = refresh.unwrap_or(false);
+    let (ttl, locale) = {
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        (config.playlist_cache_ttl, config.locale)
+    };
+    let now = chrono::Utc::now().timestamp();
+    let app_data_dir = data_dir(&app_handle).ok();
+
+    if !refresh {
+        // 1. Cache memoire
+        {
+            let cache = state.playlist_cache.lock().map_err(|e| e.to_string())?;
+            if let Some(cache) = cache.as_ref().filter(|c| c.is_fresh(ttl, now)) {
+                return Ok(cache.playlists.clone());
+            }
+        }
+
+        // 2. Cache disque
+        if let Some(cache) = app_data_dir
+            .as_deref()
+            .and_then(spotify::load_playlist_cache)
+            .filter(|c| c.is_fresh(ttl, now))
+        {
+            let playlists = cache.playlists.clone();
+            *state.playlist_cache.lock().map_err(|e| e.to_string())? = Some(cache);
+            return Ok(playlists);
+        }
+    }
+
+    // Cloner le client si present pour liberer le lock
+    let client_opt = {
+        let spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
+        spotify_guard.clone()
+    };
+    
+    if let Some(client) = client_opt {
+        let mut playlists = client.get_playlists().await
+            .map_err(|e| tr(locale, Msg::PlaylistFetchError, &[&e.localized(locale)]))?;
+        // Les playlists personnalisees (Daily Mix...) n'en font pas partie: les ajouter comme sources de reveil
+        match client.made_for_you_playlists().await {
+            Ok(made_for_you) => {
+                for playlist in made_for_you {
+                    if !playlists.iter().any(|p| p.uri == playlist.uri) {
+                        playlists.push(playlist);
+                    }
+                }
+            }
+            Err(e) => tracing::warn!(error = %e, "Playlists personnalisees indisponibles"),
+        }
+
+        // Le compte actif a pu changer pendant la requete: ne pas polluer son cache
+        let still_active = state.spotify_client.lock().map_err(|e| e.to_string())?
+            .as_ref()
+            .is_some_and(|active| active.profile() == client.profile());
+        if !still_active {
+            return Ok(playlists);
+        }
+
+        let cache = spotify::PlaylistCache::new(playlists.clone());
+        if let Some(dir) = app_data_dir.as_deref() {
+            let _ = spotify::save_playlist_cache(dir, &cache);
+        }
+        *state.playlist_cache.lock().map_err(|e| e.to_string())? = Some(cache);
+
+        Ok(playlists)
+    } else {
+        Err(tr(locale, Msg::SpotifyNotConnected, &[]))
+    }
+}
+
+/// Playlists personnalisees du compte actif (Discover Weekly, Release Radar, Daily Mix)
+#[tauri::command]
+async fn get_made_for_you_playlists(state: State<'_, AppState>) -> Result<Vec<spotify::SpotifyPlaylist>, String> {
+    let locale = locale(&state);
+    let client_opt = {
+        let spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
+        spotify_guard.clone()
+    };
+
+    match client_opt {
+        Some(client) => client.made_for_you_playlists().await
+            .map_err(|e| tr(locale, Msg::PlaylistFetchError, &[&e.localized(locale)])),
+        None => Err(tr(locale, Msg::SpotifyNotConnected, &[])),
+    }
+}
+
+/// Recherche playlists, albums et titres dans le catalogue Spotify
+#[tauri::command]
+async fn search_spotify(
+    state: State<'_, AppState>,
+    query: String,
+    types: Vec<String>,
+    limit: Option<u32>,
+) -> Result<spotify::SpotifySearchResults, String> {
+    let locale = locale(&state);
+    // Cloner le client si present pour liberer le lock
+    let client_opt = {
+        let spotify_guard = state.spotify_client.lock().map_err(|e| e.to_string())?;
+        spotify_guard.clone()
+    };
+
+    if let Some(client) = client_opt {
+        client.search_spotify(&query, &types, limit.unwrap_or(20)).await
+            .map_err(|e| e.localized(locale))
+    } else {
+        Err(tr(locale, Msg::SpotifyNotConnected, &[]))
+    }
+}
+
+/// Lance la lecture d'une playlist
+#[tauri::command]
+async fn play_spotify_playlist(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    playlist_uri: String,
+    account: Option<String>,
+) -> Result<(), String> {
+    let locale = locale(&state);
+    // Cloner le client si present pour liberer le lock
+    let client_opt = spotify_client_for(&state, account.as_deref())?;
+    
+    if let Some(client) = client_opt {
+        // Titre d'ouverture de l'alarme qui sonne avec cette playlist
+        let ringing_alarm = state.ringing.lock().map_err(|e| e.to_string())?
+            .as_ref()
+            .filter(|r| r.alarm.playlist_uri == playlist_uri)
+            .map(|r| r.alarm.clone());
+        let first_track = ringing_alarm.as_ref().and_then(|a| a.first_track_uri.clone());
+        if let Some(alarm) = ringing_alarm.as_ref() {
+            scheduler::remember_spotify_volume(&state, &client, alarm).await;
+        }
+        if let Err(e) = client.play_alarm_playlist(&playlist_uri, first_track.as_deref()).await {
+            record_telemetry(&app_handle, &state, telemetry::TelemetryKind::ProviderError, Some(query::ProviderKind::Spotify), None);
+            return Err(tr(locale, Msg::PlaybackError, &[&e.localized(locale)]));
+        }
+        let alarm_id = ringing_alarm_id(&state).ok();
+        events::emit(&app_handle, events::AlarmEvent::NowPlaying { alarm_id, playlist_uri });
+        // Commande acceptee ne veut pas dire musique audible: verification en arriere-plan
+        if let Some(alarm) = ringing_alarm {
+            let fading = alarm.fade_in && alarm.fade_in_duration > 0;
+            scheduler::verify_spotify_playback(&app_handle, client, alarm, !fading);
+        }
+        Ok(())
+    } else {
+        Err(tr(locale, Msg::SpotifyNotConnected, &[]))
+    }
+}
+
+/// Regle le volume Spotify
+#[tauri::command]
+async fn set_spotify_volume(
+    state: State<'_, AppState>,
+    volume: u8,
+    account: Option<String>,
+) -> Result<(), String> {
+    let locale = locale(&state);
+    // Cloner le client si present pour liberer le lock
+    let client_opt = spotify_client_for(&state, account.as_deref())?;
+    
+    if let Some(client) = client_opt {
+        client.set_volume(volume).await
+            .map_err(|e| tr(locale, Msg::VolumeError, &[&e.localized(locale)]))
+    } else {
+        Err(tr(locale, Msg::SpotifyNotConnected, &[]))
+    }
+}
+
+/// Vérifie si l'utilisateur est authentifié
+#[tauri::command]
+fn is_spotify_authenticated(state: State<'_, AppState>) -> bool {
+    if let Ok(spotify_guard) = state.spotify_client.lock() {
+        if let Some(client) = spotify_guard.as_ref() {
+            return client.is_authenticated();
+        }
+    }
+    false
+}
+
+// -- APPLE MUSIC --
+
+/// Connecte Apple Music avec les jetons MusicKit (jeton developpeur et jeton utilisateur
+/// obtenu par l'autorisation MusicKit JS), verifies aupres de l'API avant d'etre enregistres
+#[tauri::command]
+async fn apple_music_login(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    developer_token: String,
+    user_token: String,
+) -> Result<(), String> {
+    let tokens = apple_music::AppleMusicTokens {
+        developer_token: developer_token.trim().to_string(),
+        user_token: user_token.trim().to_string(),
+    };
+    let client = apple_music::AppleMusicClient::new(tokens)?;
+    client.verify().await?;
+
+    let user = user_profile(&state)?;
+    let saved = client.tokens().clone();
+    tauri::async_runtime::spawn_blocking(move || apple_music::save_tokens(user.as_deref(), &saved))
+        .await
+        .map_err(|e| e.to_string())??;
+    *state.apple_music.lock().map_err(|e| e.to_string())? = Some(client);
+    tracing::info!("Apple Music connecte");
+    emit_auth_state(&app_handle, query::ProviderKind::AppleMusic, true);
+    Ok(())
+}
+
+/// Deconnecte Apple Music et retire ses jetons du trousseau
+#[tauri::command]
+async fn apple_music_logout(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let user = user_profile(&state)?;
+    tauri::async_runtime::spawn_blocking(move || apple_music::delete_tokens(user.as_deref()))
+        .await
+        .map_err(|e| e.to_string())??;
+    *state.apple_music.lock().map_err(|e| e.to_string())? = None;
+    emit_auth_state(&app_handle, query::ProviderKind::AppleMusic, false);
+    Ok(())
+}
+
+/// Vérifie si Apple Music est connecte
+#[tauri::command]
+fn is_apple_music_authenticated(state: State<'_, AppState>) -> bool {
+    state.apple_music.lock().map(|client| client.is_some()).unwrap_or(false)
+}
+
+/// Playlists de la bibliotheque Apple Music
+#[tauri::command]
+async fn get_apple_music_playlists(state: State<'_, AppState>) -> Result<Vec<provider::ProviderPlaylist>, String> {
+    let locale = locale(&state);
+    // Cloner le client si present pour liberer le lock
+    let client = state.apple_music.lock().map_err(|e| e.to_string())?
+        .clone()
+        .ok_or_else(|| tr(locale, Msg::AppleMusicNotConnected, &[]))?;
+    client.playlists().await
+        .map_err(|e| tr(locale, Msg::PlaylistFetchError, &[&e]))
+}
+
+// -- YOUTUBE MUSIC --
+
+/// Initie la connexion OAuth Google de YouTube Music et retourne la page de consentement.
+/// Le code revient par charmed://auth/youtube (ou youtube_music_callback s'il est saisi).
+#[tauri::command]
+fn youtube_music_login(
+    state: State<'_, AppState>,
+    client_id: String,
+    client_secret: String,
+) -> Result<String, String> {
+    let pending = youtube_music::PendingLogin::new(
+        client_id,
+        client_secret,
+        charmed_core::deeplink::YOUTUBE_AUTH_REDIRECT_URI.to_string(),
+    )?;
+    let auth_url = pending.auth_url();
+    // Le compte connecte reste utilisable tant que le nouveau n'est pas autorise
+    *state.youtube_music_pending_login.lock().map_err(|e| e.to_string())? = Some(pending);
+    Ok(auth_url)
+}
+
+/// Complete la connexion YouTube Music avec le code de retour
+#[tauri::command]
+async fn youtube_music_callback(app_handle: tauri::AppHandle, state: State<'_, AppState>, code: String) -> Result<(), String> {
+    complete_youtube_music_login(&app_handle, &state, code).await
+}
+
+/// Echange le code OAuth de la connexion en attente puis enregistre le compte
+/// (commande youtube_music_callback ou lien charmed://auth/youtube)
+pub(crate) async fn complete_youtube_music_login(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    code: String,
+) -> Result<(), String> {
+    let locale = locale(state);
+    let pending = state.youtube_music_pending_login.lock().map_err(|e| e.to_string())?
+        .clone()
+        .ok_or_else(|| tr(locale, Msg::YouTubeMusicNotInitialized, &[]))?;
+    let client = pending.complete(&code).await
+        .map_err(|e| tr(locale, Msg::YouTubeMusicAuthError, &[&e]))?;
+
+    let user = user_profile(state)?;
+    let credentials = client.credentials().clone();
+    tauri::async_runtime::spawn_blocking(move || youtube_music::save_credentials(user.as_deref(), &credentials))
+        .await
+        .map_err(|e| e.to_string())??;
+    *state.youtube_music.lock().map_err(|e| e.to_string())? = Some(client);
+    *state.youtube_music_pending_login.lock().map_err(|e| e.to_string())? = None;
+    tracing::info!("YouTube Music connecte");
+    emit_auth_state(app_handle, query::ProviderKind::YoutubeMusic, true);
+    Ok(())
+}
+
+/// Deconnecte YouTube Music et retire ses identifiants du trousseau
+#[tauri::command]
+async fn youtube_music_logout(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let user = user_profile(&state)?;
+    tauri::async_runtime::spawn_blocking(move || youtube_music::delete_credentials(user.as_deref()))
+        .await
+        .map_err(|e| e.to_string())??;
+    *state.youtube_music.lock().map_err(|e| e.to_string())? = None;
+    emit_auth_state(&app_handle, query::ProviderKind::YoutubeMusic, false);
+    Ok(())
+}
+
+/// Vérifie si YouTube Music est connecte
+#[tauri::command]
+fn is_youtube_music_authenticated(state: State<'_, AppState>) -> bool {
+    state.youtube_music.lock().map(|client| client.is_some()).unwrap_or(false)
+}
+
+/// Playlists du compte YouTube Music
+#[tauri::command]
+async fn get_youtube_music_playlists(state: State<'_, AppState>) -> Result<Vec<provider::ProviderPlaylist>, String> {
+    let locale = locale(&state);
+    // Cloner le client si present pour liberer le lock
+    let client = state.youtube_music.lock().map_err(|e| e.to_string())?
+        .clone()
+        .ok_or_else(|| tr(locale, Msg::YouTubeMusicNotConnected, &[]))?;
+    client.playlists().await
+        .map_err(|e| tr(locale, Msg::PlaylistFetchError, &[&e]))
+}
+
+// -- BIBLIOTHEQUE LOCALE --
+
+/// Configure la bibliotheque musicale locale (None pour la retirer)
+#[tauri::command]
+fn set_music_library(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: Option<library::LibrarySettings>,
+) -> Result<(), String> {
+    if let Some(settings) = settings.as_ref() {
+        settings.validate()?;
+    }
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.music_library = settings;
+
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Dossiers et listes m3u de la bibliotheque locale (vide si aucune n'est configuree)
+#[tauri::command]
+async fn get_library_playlists(state: State<'_, AppState>) -> Result<Vec<provider::ProviderPlaylist>, String> {
+    let settings = state.config.lock().map_err(|e| e.to_string())?.music_library.clone();
+    match settings {
+        Some(settings) => library::MusicLibrary::new(&settings).playlists().await,
+        None => Ok(Vec::new()),
+    }
+}
+
+// -- RADIO --
+
+/// Remplace la liste des stations de radio proposees
+#[tauri::command]
+fn set_radio_stations(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    stations: Vec<radio::RadioStation>,
+) -> Result<(), String> {
+    radio::validate_stations(&stations)?;
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.radio_stations = stations;
+
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Stations de radio configurees, comme playlists d'alarme
+#[tauri::command]
+async fn get_radio_playlists(state: State<'_, AppState>) -> Result<Vec<provider::ProviderPlaylist>, String> {
+    let stations = state.config.lock().map_err(|e| e.to_string())?.radio_stations.clone();
+    radio::RadioPlayer::new(stations).playlists().await
+}
+
+// -- PODCASTS --
+
+/// Remplace la liste des flux RSS suivis
+#[tauri::command]
+fn set_podcast_feeds(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    feeds: Vec<podcast::PodcastFeed>,
+) -> Result<(), String> {
+    for feed in &feeds {
+        feed.validate()?;
+    }
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.podcast_feeds = feeds;
+
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Podcasts suivis, comme playlists d'alarme
+#[tauri::command]
+async fn get_podcast_playlists(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<provider::ProviderPlaylist>, String> {
+    let feeds = state.config.lock().map_err(|e| e.to_string())?.podcast_feeds.clone();
+    podcast::PodcastPlayer::new(feeds, &data_dir(&app_handle)?)?.playlists().await
+}
+
+/// Titre du dernier episode d'un flux (celui qui sonnerait maintenant)
+#[tauri::command]
+async fn get_latest_podcast_episode(app_handle: tauri::AppHandle, feed_url: String) -> Result<String, String> {
+    let player = podcast::PodcastPlayer::new(Vec::new(), &data_dir(&app_handle)?)?;
+    Ok(player.latest(&feed_url).await?.title)
+}
+
+// -- MINUTEUR DE SOMMEIL --
+
+/// Duree maximale d'un minuteur de sommeil (minutes)
+const MAX_SLEEP_TIMER_MINUTES: u32 = 180;
+
+/// Lance une playlist et baisse progressivement le volume jusqu'a zero,
+/// puis met la lecture en pause ("local" pour le son local)
+#[tauri::command]
+async fn start_sleep_timer(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    minutes: u32,
+    playlist_uri: String,
+) -> Result<fade::SleepTimer, String> {
+    let locale = locale(&state);
+    if minutes == 0 || minutes > MAX_SLEEP_TIMER_MINUTES {
+        return Err(tr(locale, Msg::InvalidDuration, &[&MAX_SLEEP_TIMER_MINUTES]));
+    }
+    start_sleep_fade(&app_handle, &state, minutes, playlist_uri, None, None, fade::FadeCurve::Linear).await
+}
+
+/// Demarre la lecture et la descente de volume d'un minuteur de sommeil
+/// (minuteur manuel ou routine du soir). Sans volume de depart, la descente part
+/// du volume actuel de l'appareil Spotify.
+pub(crate) async fn start_sleep_fade(
+    app_handle: &tauri::AppHandle,
+    state: &AppState,
+    minutes: u32,
+    playlist_uri: String,
+    account: Option<&str>,
+    start_volume: Option<u8>,
+    curve: fade::FadeCurve,
+) -> Result<fade::SleepTimer, String> {
+    let locale = locale(state);
+
+    // Un seul minuteur a la fois: annuler le precedent
+    if let Some(previous) = state.sleep_timer.lock().map_err(|e| e.to_string())?.take() {
+        previous.handle.cancel();
+    }
+
+    let default_volume = state.config.lock().map_err(|e| e.to_string())?.default_volume;
+
+    let (target, start_volume) = if playlist_uri == "local" {
+        audio::play_alarm_sound().map_err(|e| tr(locale, Msg::AudioError, &[&e]))?;
+        (fade::FadeTarget::Local, start_volume.unwrap_or(default_volume))
+    } else {
+        // Cloner le client si present pour liberer le lock
+        let client = spotify_client_for(state, account)?
+            .ok_or_else(|| tr(locale, Msg::SpotifyNotConnected, &[]))?;
+
+        client.play_playlist(&playlist_uri).await
+            .map_err(|e| tr(locale, Msg::PlaybackError, &[&e.localized(locale)]))?;
+
+        // Partir du volume actuel de l'appareil pour eviter un saut
+        let start_volume = match start_volume {
+            Some(volume) => volume,
+            None => client.get_devices().await
+                .ok()
+                .and_then(|devices| devices.into_iter().find(|d| d.is_active))
+                .map(|d| d.volume_percent)
+                .unwrap_or(default_volume),
+        };
+        (fade::FadeTarget::Spotify(Box::new(client)), start_volume)
+    };
+
+    let timer = fade::SleepTimer {
+        id: uuid::Uuid::new_v4().to_string(),
+        playlist_uri,
+        ends_at: (chrono::Local::now() + chrono::Duration::minutes(i64::from(minutes))).to_rfc3339(),
+        handle: fade::FadeHandle::new(),
+    };
+    *state.sleep_timer.lock().map_err(|e| e.to_string())? = Some(timer.clone());
+
+    let timer_id = timer.id.clone();
+    let handle = timer.handle.clone();
+    let duration = std::time::Duration::from_secs(u64::from(minutes) * 60);
+    let app_handle = app_handle.clone();
+
+    tauri::async_runtime::spawn(async move {
+        if let Ok(true) = fade::run_fade(&target, start_volume, 0, duration, curve, &handle).await {
+            let _ = target.pause().await;
+            // Restaurer le volume pour que la prochaine lecture ne soit pas muette
+            let _ = target.set_volume(start_volume).await;
+        }
+
+        let state = app_handle.state::<AppState>();
+        if let Ok(mut current) = state.sleep_timer.lock() {
+            if current.as_ref().is_some_and(|t| t.id == timer_id) {
+                *current = None;
+            }
+        };
+    });
+
+    Ok(timer)
+}
+
+/// Configure la routine du soir (None pour la supprimer)
+#[tauri::command]
+fn set_wind_down(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    routine: Option<winddown::WindDown>,
+) -> Result<(), String> {
+    if let Some(routine) = routine.as_ref() {
+        routine.validate()?;
+    }
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.wind_down = routine;
+
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Diagnostic: planificateur en vie, dossier de donnees accessible en ecriture et
+/// validite des comptes de musique
+#[tauri::command]
+async fn health_check(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<health::HealthReport, String> {
+    let now = chrono::Local::now();
+    let scheduler = watchdog::scheduler_health(&state, now)?;
+    let storage = match data_dir(&app_handle) {
+        Ok(dir) => tauri::async_runtime::spawn_blocking(move || health::check_storage(&dir))
+            .await
+            .map_err(|e| e.to_string())?,
+        Err(e) => health::StorageHealth { path: String::new(), writable: false, error: Some(e) },
+    };
+
+    let spotify = state.spotify_client.lock().map_err(|e| e.to_string())?.clone();
+    let (connected, expires_at) = match spotify.filter(|c| c.is_authenticated()) {
+        Some(client) => (true, client.token_expires_at().await),
+        None => (false, None),
+    };
+    let mut auth = vec![health::AuthHealth {
+        provider: query::ProviderKind::Spotify,
+        connected,
+        expires_at,
+        valid: connected && expires_at.is_none_or(|at| at > chrono::Utc::now()),
+    }];
+    for (provider, connected) in [
+        (query::ProviderKind::AppleMusic, state.apple_music.lock().map_err(|e| e.to_string())?.is_some()),
+        (query::ProviderKind::YoutubeMusic, state.youtube_music.lock().map_err(|e| e.to_string())?.is_some()),
+    ] {
+        auth.push(health::AuthHealth { provider, connected, expires_at: None, valid: connected });
+    }
+
+    let report = health::HealthReport { checked_at: now, scheduler, storage, auth };
+    if !report.is_healthy() {
+        tracing::warn!(report = ?report, "Diagnostic: probleme detecte");
+    }
+    Ok(report)
+}
+
+// -- MISES A JOUR --
+
+/// Refuse l'installation d'une mise a jour (qui redemarre l'application) pendant
+/// une sonnerie ou dans les deux heures precedant la prochaine alarme
+fn ensure_update_allowed(state: &AppState) -> Result<(), String> {
+    let locale = locale(state);
+    let now = chrono::Local::now();
+    let time_format = state.config.lock().map_err(|e| e.to_string())?.time_format;
+    let ringing = state.ringing.lock().map_err(|e| e.to_string())?.as_ref().map(|r| r.alarm.time.clone());
+    let next = next_alarm(state, now)?.map(|n| n.trigger_at);
+    if update::install_allowed(now, next, ringing.is_some()) {
+        return Ok(());
+    }
+    let when = ringing
+        .or_else(|| next.map(|at| i18n::format_clock(at.time(), time_format, false)))
+        .unwrap_or_default();
+    tracing::warn!(alarm = %when, "Mise a jour reportee: alarme trop proche");
+    Err(tr(locale, Msg::UpdateTooCloseToAlarm, &[&when]))
+}
+
+/// Verificateur de mises a jour branche sur le canal configure
+fn updater(
+    app_handle: &tauri::AppHandle,
+    channel: update::UpdateChannel,
+    locale: i18n::Locale,
+) -> Result<tauri_plugin_updater::Updater, String> {
+    use tauri_plugin_updater::UpdaterExt;
+
+    // Sans cle publique, chaque telechargement echouerait a la verification de signature
+    let configured = app_handle.config().plugins.0.get("updater")
+        .and_then(|updater| updater.get("pubkey"))
+        .and_then(|pubkey| pubkey.as_str())
+        .is_some_and(|pubkey| !pubkey.trim().is_empty());
+    if !configured {
+        return Err(tr(locale, Msg::UpdaterNotConfigured, &[]));
+    }
+
+    let endpoint = tauri::Url::parse(channel.endpoint()).map_err(|e| e.to_string())?;
+    app_handle
+        .updater_builder()
+        .endpoints(vec![endpoint])
+        .and_then(|builder| builder.build())
+        .map_err(|e| tr(locale, Msg::UpdateError, &[&e]))
+}
+
+/// Cherche une nouvelle version sur le canal configure (None si a jour)
+#[tauri::command]
+async fn check_for_update(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Option<update::UpdateInfo>, String> {
+    let locale = locale(&state);
+    let channel = state.config.lock().map_err(|e| e.to_string())?.update_channel;
+    let found = updater(&app_handle, channel, locale)?
+        .check()
+        .await
+        .map_err(|e| tr(locale, Msg::UpdateError, &[&e]))?;
+    let Some(found) = found else {
+        return Ok(None);
+    };
+
+    let now = chrono::Local::now();
+    let ringing = state.ringing.lock().map_err(|e| e.to_string())?.is_some();
+    let next = next_alarm(&state, now)?.map(|n| n.trigger_at);
+    Ok(Some(update::UpdateInfo {
+        version: found.version.clone(),
+        current_version: found.current_version.clone(),
+        channel,
+        date: found.date.map(|d| d.to_string()),
+        notes: found.body.clone(),
+        install_allowed: update::install_allowed(now, next, ringing),
+    }))
+}
+
+/// Telecharge et installe la nouvelle version puis redemarre l'application.
+/// Refuse pendant une sonnerie ou a moins de deux heures de la prochaine alarme.
+#[tauri::command]
+async fn install_update(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let locale = locale(&state);
+    ensure_update_allowed(&state)?;
+    let channel = state.config.lock().map_err(|e| e.to_string())?.update_channel;
+    let found = updater(&app_handle, channel, locale)?
+        .check()
+        .await
+        .map_err(|e| tr(locale, Msg::UpdateError, &[&e]))?
+        .ok_or_else(|| tr(locale, Msg::UpdateNotAvailable, &[]))?;
+
+    tracing::info!(version = %found.version, channel = ?channel, "Telechargement de la mise a jour");
+    let bytes = found
+        .download(|_, _| {}, || {})
+        .await
+        .map_err(|e| tr(locale, Msg::UpdateError, &[&e]))?;
+    // Le telechargement a pu durer: une alarme a pu se rapprocher entre-temps
+    ensure_update_allowed(&state)?;
+    found.install(bytes).map_err(|e| tr(locale, Msg::UpdateError, &[&e]))?;
+
+    tracing::info!(version = %found.version, "Mise a jour installee, redemarrage");
+    app_handle.restart()
+}
+
+/// Etat de l'alimentation (batterie, veille automatique)
+#[tauri::command]
+async fn get_power_status() -> Result<power::PowerStatus, String> {
+    tauri::async_runtime::spawn_blocking(power::read_status)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Heure a laquelle le reveil materiel sortira l'ordinateur de veille (None = aucun)
+#[tauri::command]
+fn get_scheduled_wake(state: State<'_, AppState>) -> Result<Option<chrono::DateTime<chrono::Local>>, String> {
+    Ok(*state.hardware_wake.lock().map_err(|e| e.to_string())?)
+}
+
+/// Reglages du mode nuit de l'ecran (None = desactive)
+#[tauri::command]
+fn get_night_mode(state: State<'_, AppState>) -> Result<Option<nightmode::NightModeSettings>, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.night_mode.clone())
+}
+
+/// Configure le mode nuit de l'ecran (None pour le supprimer).
+/// Applique ou leve au tour suivant du planificateur.
+#[tauri::command]
+fn set_night_mode(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: Option<nightmode::NightModeSettings>,
+) -> Result<(), String> {
+    if let Some(settings) = settings.as_ref() {
+        settings.validate()?;
+    }
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.night_mode = settings;
+
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Active ou desactive la telemetrie d'usage anonyme. La desactivation
+/// efface l'identifiant et les evenements encore en attente.
+#[tauri::command]
+fn set_telemetry(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    enabled: bool,
+) -> Result<telemetry::TelemetrySettings, String> {
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.telemetry.set_enabled(enabled);
+    if !enabled {
+        telemetry::save_queue(&data_dir(&app_handle)?, &[])?;
+    }
+
+    persist_config(&app_handle, &config)?;
+    Ok(config.telemetry.clone())
+}
+
+/// Annule le minuteur de sommeil en cours (la lecture continue)
+#[tauri::command]
+fn cancel_sleep_timer(state: State<'_, AppState>) -> Result<(), String> {
+    if let Some(timer) = state.sleep_timer.lock().map_err(|e| e.to_string())?.take() {
+        timer.handle.cancel();
+    }
+    Ok(())
+}
+
+/// Retourne le minuteur de sommeil en cours
+#[tauri::command]
+fn get_sleep_timer(state: State<'_, AppState>) -> Result<Option<fade::SleepTimer>, String> {
+    Ok(state.sleep_timer.lock().map_err(|e| e.to_string())?.clone())
+}
+
+// -- COMMANDES AUDIO --
+
+/// Monte le volume Spotify de l'alarme qui sonne selon son fondu d'entree
+/// (volume final direct si l'alarme n'a pas de fondu)
+#[tauri::command]
+async fn fade_in_spotify_alarm(state: State<'_, AppState>, alarm_id: String) -> Result<(), String> {
+    let locale = locale(&state);
+    let alarm = state.ringing.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .filter(|r| r.alarm.id == alarm_id)
+        .map(|r| r.alarm.clone())
+        .ok_or_else(|| tr(locale, Msg::AlarmNotRinging, &[&alarm_id]))?;
+    let client = spotify_client_for(&state, alarm.account.as_deref())?
+        .ok_or_else(|| tr(locale, Msg::SpotifyNotConnected, &[]))?;
+    let target = fade::FadeTarget::Spotify(Box::new(client));
+
+    match prepare_fade_in(&state)? {
+        Some(AlarmFadeIn { volume, duration, curve, handle }) => {
+            target.set_volume(0).await?;
+            tauri::async_runtime::spawn(async move {
+                let _ = fade::run_fade(&target, 0, volume, duration, curve, &handle).await;
+            });
+            Ok(())
+        }
+        None => target.set_volume(alarm.volume).await,
+    }
+}
+
+/// Configure les raccourcis globaux de report et d'arret (actifs pendant une sonnerie)
+#[tauri::command]
+fn set_alarm_shortcuts(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    shortcuts: shortcuts::ShortcutSettings,
+) -> Result<(), String> {
+    shortcuts::validate(&shortcuts)?;
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.shortcuts = shortcuts;
+    // Reenregistres avec les nouvelles touches au prochain passage du planificateur
+    shortcuts::reset(&app_handle)?;
+
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Etat de l'API REST locale (port et jeton a saisir sur l'autre appareil)
+#[tauri::command]
+fn get_remote_api(state: State<'_, AppState>) -> Result<remote::RemoteApiInfo, String> {
+    let settings = state.config.lock().map_err(|e| e.to_string())?.remote_api.clone();
+    remote::RemoteApiInfo::new(&settings)
+}
+
+/// Active, deplace ou coupe l'API REST locale. `regenerate_token` invalide l'ancien jeton.
+#[tauri::command]
+async fn set_remote_api(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: remote::RemoteApiSettings,
+    regenerate_token: Option<bool>,
+) -> Result<remote::RemoteApiInfo, String> {
+    settings.validate()?;
+    if regenerate_token.unwrap_or(false) {
+        secrets::delete_secret(secrets::REMOTE_API_TOKEN_KEY)?;
+    }
+    remote::restart(&app_handle, &settings).await?;
+
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.remote_api = settings.clone();
+        persist_config(&app_handle, &config)?;
+    }
+    remote::RemoteApiInfo::new(&settings)
+}
+
+/// Evenements de l'agenda en cache (vide si aucun agenda n'a ete lu)
+pub(crate) fn calendar_events(state: &AppState) -> Result<Vec<calendar::CalendarEvent>, String> {
+    Ok(state.calendar.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .map(|cache| cache.events.clone())
+        .unwrap_or_default())
+}
+
+/// Relit l'agenda configure. L'ancien releve est conserve en cas d'echec.
+pub(crate) async fn refresh_calendar(state: &AppState) -> Result<Vec<calendar::CalendarEvent>, String> {
+    let url = state.config.lock().map_err(|e| e.to_string())?.calendar_url.clone();
+    let Some(url) = url else {
+        *state.calendar.lock().map_err(|e| e.to_string())? = None;
+        return Ok(Vec::new());
+    };
+    let events = calendar::fetch_events(&url).await?;
+    tracing::info!(count = events.len(), "Agenda mis a jour");
+    *state.calendar.lock().map_err(|e| e.to_string())? = Some(calendar::CalendarCache {
+        events: events.clone(),
+        fetched_at: chrono::Utc::now(),
+    });
+    Ok(events)
+}
+
+/// Configure l'agenda des alarmes calees sur les reunions (None = aucun) et le lit
+#[tauri::command]
+async fn set_calendar_url(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    url: Option<String>,
+) -> Result<Vec<calendar::CalendarEvent>, String> {
+    let url = url
+        .filter(|u| !u.trim().is_empty())
+        .map(|u| calendar::normalize_url(&u))
+        .transpose()?;
+    {
+        let mut config = state.config.lock().map_err(|e| e.to_string())?;
+        config.calendar_url = url;
+        persist_config(&app_handle, &config)?;
+    }
+    refresh_calendar(&state).await
+}
+
+/// Evenements a venir de l'agenda (`refresh` force une nouvelle lecture)
+#[tauri::command]
+async fn get_calendar_events(
+    state: State<'_, AppState>,
+    refresh: Option<bool>,
+) -> Result<Vec<calendar::CalendarEvent>, String> {
+    let events = if refresh.unwrap_or(false) {
+        refresh_calendar(&state).await?
+    } else {
+        calendar_events(&state)?
+    };
+    let now = chrono::Utc::now();
+    Ok(events.into_iter().filter(|e| e.start >= now).collect())
+}
+
+/// Recherche les ponts Philips Hue du reseau local
+#[tauri::command]
+async fn discover_hue_bridges() -> Result<Vec<lights::HueBridge>, String> {
+    lights::discover_bridges().await
+}
+
+/// Appaire le pont Hue a l'adresse donnee (presser le bouton du pont avant l'appel)
+#[tauri::command]
+async fn pair_hue_bridge(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    address: String,
+) -> Result<(), String> {
+    let address = address.trim().to_string();
+    let username = lights::pair(&address).await?;
+    secrets::set_secret(secrets::HUE_USERNAME_KEY, &username)?;
+
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.hue_bridge = Some(address);
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Adresse et identifiant du pont Hue appaire
+pub(crate) fn hue_credentials(state: &AppState) -> Result<(String, String), String> {
+    let locale = locale(state);
+    let address = state.config.lock().map_err(|e| e.to_string())?.hue_bridge.clone();
+    match (address, secrets::get_secret(secrets::HUE_USERNAME_KEY)?) {
+        (Some(address), Some(username)) => Ok((address, username)),
+        _ => Err(tr(locale, Msg::HueNotPaired, &[])),
+    }
+}
+
+/// Liste les lampes du pont Hue appaire
+#[tauri::command]
+async fn list_hue_lights(state: State<'_, AppState>) -> Result<Vec<lights::HueLight>, String> {
+    let (address, username) = hue_credentials(&state)?;
+    lights::list_lights(&address, &username).await
+}
+
+/// Liste les sorties audio disponibles pour le son local
+#[tauri::command]
+fn list_audio_devices() -> Result<Vec<audio::AudioDevice>, String> {
+    audio::list_output_devices()
+}
+
+/// Choisit la sortie audio globale du son local (None = sortie par defaut)
+#[tauri::command]
+fn set_output_device(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    device: Option<String>,
+) -> Result<(), String> {
+    let device = device.filter(|d| !d.trim().is_empty());
+    let mut config = state.config.lock().map_err(|e| e.to_string())?;
+    config.output_device = device.clone();
+    audio::select_output_device(device)?;
+
+    persist_config(&app_handle, &config)?;
+    Ok(())
+}
+
+/// Joue l'alarme locale (fallback)
+#[tauri::command]
+fn play_local_alarm(app_handle: tauri::AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let locale = locale(&state);
+    let fade_in = prepare_fade_in(&state)?;
+
+    match fade_in.as_ref() {
+        Some(AlarmFadeIn { duration, .. }) => {
+            audio::set_alarm_volume(0).map_err(|e| tr(locale, Msg::AudioError, &[&e]))?;
+            audio::play_alarm_sound_for(*duration + audio::ALARM_SOUND_DURATION)
+        }
+        None => audio::play_alarm_sound(),
+    }
+    .map_err(|e| tr(locale, Msg::AudioError, &[&e]))?;
+    let alarm_id = ringing_alarm_id(&state).ok();
+    events::emit(&app_handle, events::AlarmEvent::NowPlaying { alarm_id, playlist_uri: "local".to_string() });
+
+    if let Some(AlarmFadeIn { volume, duration, curve, handle }) = fade_in {
+        tauri::async_runtime::spawn(async move {
+            let _ = fade::run_fade(&fade::FadeTarget::Local, 0, volume, duration, curve, &handle).await;
+        });
+    }
+    Ok(())
+}
+
+/// Fondu d'entree de l'alarme qui sonne, a lancer
+pub(crate) struct AlarmFadeIn {
+    pub volume: u8,
+    pub duration: std::time::Duration,
+    pub curve: fade::FadeCurve,
+    pub handle: fade::FadeHandle,
+}
+
+/// Prepare le fondu d'entree selon l'alarme qui sonne
+/// (None si pas de fondu a faire)
+pub(crate) fn prepare_fade_in(state: &AppState) -> Result<Option<AlarmFadeIn>, String> {
+    let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+    // L'escalade pilote elle-meme le volume
+    let Some(r) = ringing.as_mut().filter(|r| {
+        r.alarm.fade_in && r.alarm.fade_in_duration > 0 && r.alarm.escalation.is_none()
+    }) else {
+        return Ok(None);
+    };
+
+    if let Some(previous) = r.fade_in.take() {
+        previous.cancel();
+    }
+    let handle = fade::FadeHandle::new();
+    r.fade_in = Some(handle.clone());
+    let duration = std::time::Duration::from_secs(u64::from(r.alarm.fade_in_duration));
+    Ok(Some(AlarmFadeIn {
+        volume: r.alarm.volume,
+        duration,
+        curve: r.alarm.fade_curve,
+        handle,
+    }))
+}
+
+/// Arrête l'alarme locale (sauf si un défi reste à résoudre)
+#[tauri::command]
+fn stop_local_alarm(state: State<'_, AppState>) -> Result<(), String> {
+    ensure_dismissable(&state)?;
+    if let Some(ringing) = state.ringing.lock().map_err(|e| e.to_string())?.as_ref() {
+        ringing.stop_fades();
+    }
+    audio::stop_alarm_sound()
+        .map_err(|e| tr(locale(&state), Msg::AudioError, &[&e]))
+}
+
+// -- PROFILS UTILISATEUR --
+
+/// Charge dans l'etat les alarmes, la configuration et les comptes Spotify d'un
+/// profil utilisateur. Rien n'est remplace si ses alarmes sont illisibles.
+/// Appels bloquants (fichiers, trousseau): hors des threads du runtime async.
+fn load_profile(
+    state: &AppState,
+    data_dir: &std::path::Path,
+    user: Option<&str>,
+) -> Result<Vec<storage::Recovery>, String> {
+    // Un fichier corrompu est mis de cote et remplace par sa derniere sauvegarde
+    let (alarms, alarms_recovery) = storage::load_alarms(data_dir)?;
+
+    // Configuration (le secret client migre vers le trousseau)
+    let (mut config, config_recovery) = storage::load_config(data_dir)?;
+    if let Err(e) = secrets::migrate_config_secret(data_dir, &mut config) {
+        tracing::warn!("Migration du secret Spotify impossible: {}", e);
+    }
+    let _ = audio::select_output_device(config.output_device.clone());
+
+    // Comptes Spotify du profil, dernier compte utilise reactive
+    if let Err(e) = spotify::migrate_account_secrets(data_dir, user) {
+        tracing::warn!("Migration des jetons Spotify impossible: {}", e);
+    }
+    let accounts: HashMap<String, spotify::SpotifyClient> = spotify::load_accounts(data_dir, user)
+        .into_iter()
+        .map(|account| {
+            let client = spotify::SpotifyClient::from_stored(account, &config.spotify_redirect_uri);
+            (client.profile().to_string(), client)
+        })
+        .collect();
+    let active = config.active_spotify_profile.as_ref().and_then(|p| accounts.get(p).cloned());
+
+    // Comptes Apple Music et YouTube Music du profil (trousseau)
+    let apple_music = match apple_music::load_tokens(user) {
+        Ok(tokens) => tokens.and_then(|t| apple_music::AppleMusicClient::new(t).ok()),
+        Err(e) => {
+            tracing::warn!("Jetons Apple Music illisibles: {}", e);
+            None
+        }
+    };
+    let youtube_music = match youtube_music::load_credentials(user) {
+        Ok(credentials) => credentials.and_then(|c| youtube_music::YouTubeMusicClient::new(c).ok()),
+        Err(e) => {
+            tracing::warn!("Identifiants YouTube Music illisibles: {}", e);
+            None
+        }
+    };
+
+    *state.profile.lock().map_err(|e| e.to_string())? = user.map(str::to_string);
+    *state.alarms.lock().map_err(|e| e.to_string())? = alarms;
+    *state.config.lock().map_err(|e| e.to_string())? = config;
+    *state.spotify_accounts.lock().map_err(|e| e.to_string())? = accounts;
+    *state.spotify_client.lock().map_err(|e| e.to_string())? = active;
+    *state.apple_music.lock().map_err(|e| e.to_string())? = apple_music;
+    *state.youtube_music.lock().map_err(|e| e.to_string())? = youtube_music;
+    *state.youtube_music_pending_login.lock().map_err(|e| e.to_string())? = None;
+    *state.fired.lock().map_err(|e| e.to_string())? = history::load_fired(data_dir);
+    let recoveries: Vec<storage::Recovery> = alarms_recovery.into_iter().chain(config_recovery).collect();
+    *state.storage_recoveries.lock().map_err(|e| e.to_string())? = recoveries.clone();
+    tracing::info!(profile = user.unwrap_or(profiles::DEFAULT_PROFILE), "Profil utilisateur charge");
+    Ok(recoveries)
+}
+
+/// Surveille alarms.json du profil actif (remplace la surveillance du profil precedent)
+fn watch_alarms_file(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let handle = app_handle.clone();
+    let watcher = storage::watch_alarms(&data_dir(app_handle)?, move || {
+        if let Err(e) = reload_external_alarms(&handle) {
+            tracing::warn!("Modification externe des alarmes ignoree: {}", e);
+        }
+    })?;
+    *app_handle.state::<AppState>().alarms_watcher.lock().map_err(|e| e.to_string())? = Some(watcher);
+    Ok(())
+}
+
+/// Recharge alarms.json modifie par un autre programme (outil de synchronisation, edition
+/// manuelle) et previent le frontend. Les ecritures de l'application n'ont aucun effet:
+/// le fichier correspond deja aux alarmes en memoire.
+fn reload_external_alarms(app_handle: &tauri::AppHandle) -> Result<(), String> {
+    let app_data_dir = data_dir(app_handle)?;
+    let state = app_handle.state::<AppState>();
+    let alarms = {
+        // Lecture sous le verrou: aucune sauvegarde de l'application ne peut s'intercaler
+        let mut current = state.alarms.lock().map_err(|e| e.to_string())?;
+        let alarms = storage::read_alarms(&app_data_dir)?;
+        if serde_json::to_value(&alarms).ok() == serde_json::to_value(&*current).ok() {
+            return Ok(());
+        }
+        // Les suppressions externes sont aussi transmises aux machines synchronisees
+        let removed = alarm_list::removed_ids(&current, &alarms);
+        sync::record_deletions(&app_data_dir, &removed)?;
+        *current = alarms.clone();
+        alarms
+    };
+
+    tracing::info!(count = alarms.len(), "Alarmes rechargees apres modification externe");
+    emit_alarms_changed(app_handle, &alarms);
+    Ok(())
+}
+
+/// Previent le frontend des fichiers corrompus recuperes
+fn emit_storage_recoveries(app_handle: &tauri::AppHandle, recoveries: &[storage::Recovery]) {
+    for recovery in recoveries {
+        if let Err(e) = app_handle.emit(storage::RECOVERED_EVENT, recovery) {
+            tracing::warn!("Evenement non emis: {}", e);
+        }
+    }
+}
+
+/// Fichiers corrompus recuperes au chargement du profil actif (l'evenement
+/// `storage-recovered` part avant que la fenetre n'ecoute au demarrage)
+#[tauri::command]
+fn get_storage_recoveries(state: State<'_, AppState>) -> Result<Vec<storage::Recovery>, String> {
+    Ok(state.storage_recoveries.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// Liste les profils utilisateur ("default" en tete)
+#[tauri::command]
+fn list_profiles(app_handle: tauri::AppHandle) -> Result<Vec<String>, String> {
+    let root = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    Ok(profiles::list_profiles(&root))
+}
+
+/// Retourne le nom du profil utilisateur actif
+#[tauri::command]
+fn get_active_profile(state: State<'_, AppState>) -> Result<String, String> {
+    Ok(user_profile(&state)?.unwrap_or_else(|| profiles::DEFAULT_PROFILE.to_string()))
+}
+
+/// Bascule vers un profil utilisateur (cree s'il n'existe pas): ses alarmes,
+/// sa configuration et ses comptes Spotify remplacent ceux du profil precedent
+#[tauri::command]
+async fn switch_profile(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<String, String> {
+    let locale = locale(&state);
+    let user = profiles::normalize_name(&name)?;
+    if state.ringing.lock().map_err(|e| e.to_string())?.is_some() {
+        return Err(tr(locale, Msg::ProfileSwitchWhileRinging, &[]));
+    }
+
+    let root = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    let handle = app_handle.clone();
+    let loaded = user.clone();
+    let recoveries = tauri::async_runtime::spawn_blocking(move || {
+        let dir = profiles::profile_dir(&root, loaded.as_deref());
+        let recoveries = load_profile(&handle.state::<AppState>(), &dir, loaded.as_deref())?;
+        profiles::save_active(&root, loaded.as_deref())?;
+        Ok::<_, String>(recoveries)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+    emit_storage_recoveries(&app_handle, &recoveries);
+    if let Err(e) = watch_alarms_file(&app_handle) {
+        tracing::warn!("{}", e);
+    }
+
+    // Etat propre au profil precedent
+    state.naps.lock().map_err(|e| e.to_string())?.clear();
+    *state.spotify_pending_login.lock().map_err(|e| e.to_string())? = None;
+    *state.playlist_cache.lock().map_err(|e| e.to_string())? = None;
+    *state.weather.lock().map_err(|e| e.to_string())? = None;
+    *state.calendar.lock().map_err(|e| e.to_string())? = None;
+    state.wake_plans.lock().map_err(|e| e.to_string())?.clear();
+    state.sunrise_lights.lock().map_err(|e| e.to_string())?.clear();
+    state.spotify_kept_alive.lock().map_err(|e| e.to_string())?.clear();
+    state.pre_alarms.lock().map_err(|e| e.to_string())?.clear();
+    state.readiness_checked.lock().map_err(|e| e.to_string())?.clear();
+    *state.bedtime_reminded.lock().map_err(|e| e.to_string())? = None;
+    *state.wind_down_started.lock().map_err(|e| e.to_string())? = None;
+
+    // Raccourcis et API distante selon la configuration du nouveau profil
+    shortcuts::reset(&app_handle)?;
+    let remote_settings = state.config.lock().map_err(|e| e.to_string())?.remote_api.clone();
+    if let Err(e) = remote::restart(&app_handle, &remote_settings).await {
+        tracing::warn!("API distante indisponible: {}", e);
+    }
+    if let Err(e) = refresh_calendar(&state).await {
+        tracing::warn!("Agenda: {}", e);
+    }
+
+    let authenticated = state.spotify_client.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .is_some_and(|c| c.is_authenticated());
+    emit_auth_state(&app_handle, query::ProviderKind::Spotify, authenticated);
+    emit_auth_state(&app_handle, query::ProviderKind::AppleMusic, state.apple_music.lock().is_ok_and(|c| c.is_some()));
+    emit_auth_state(&app_handle, query::ProviderKind::YoutubeMusic, state.youtube_music.lock().is_ok_and(|c| c.is_some()));
+    emit_alarms_changed(&app_handle, &state.alarms.lock().map_err(|e| e.to_string())?);
+    emit_config_changed(&app_handle, &state.config.lock().map_err(|e| e.to_string())?);
+
+    Ok(user.unwrap_or_else(|| profiles::DEFAULT_PROFILE.to_string()))
+}
+
+// -- MINI HORLOGE --
+
+/// Ouvre la petite horloge flottante (heure et prochaine alarme)
+#[tauri::command]
+fn open_mini_clock(app_handle: tauri::AppHandle) -> Result<(), String> {
+    miniclock::open(&app_handle)
+}
+
+/// Ferme la petite horloge flottante
+#[tauri::command]
+fn close_mini_clock(app_handle: tauri::AppHandle) -> Result<(), String> {
+    miniclock::close(&app_handle)
+}
+
+/// Garde ou non la petite horloge au-dessus des autres fenetres
+#[tauri::command]
+fn set_mini_clock_on_top(app_handle: tauri::AppHandle, always_on_top: bool) -> Result<(), String> {
+    miniclock::set_always_on_top(&app_handle, always_on_top)
+}
+
+/// Contenu de la petite horloge: seule commande dont la fenetre a besoin pour se rafraichir
+#[tauri::command]
+fn get_mini_clock(state: State<'_, AppState>) -> Result<miniclock::MiniClockView, String> {
+    let now = chrono::Local::now();
+    let time_format = state.config.lock().map_err(|e| e.to_string())?.time_format;
+    let next = next_alarm(&state, now)?;
+    let ringing = state.ringing.lock().map_err(|e| e.to_string())?.is_some();
+    Ok(miniclock::view(now, next.as_ref(), ringing, time_format))
+}
+
+// -- DIAGNOSTIC --
+
+/// Retourne les dernieres entrees de journal (niveau minimum, plus recentes en premier)
+#[tauri::command]
+fn get_recent_logs(
+    app_handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    level: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<logging::LogEntry>, String> {
+    let level = match level.as_deref() {
+        Some(name) => name.parse::<tracing::Level>()
+            .map_err(|_| tr(locale(&state), Msg::UnknownLogLevel, &[&name]))?,
+        None => tracing::Level::INFO,
+    };
+    let app_data_dir = app_handle.path().app_data_dir().map_err(|e| e.to_string())?;
+    logging::recent_logs(&app_data_dir, level, limit.unwrap_or(200))
+}
+
+// -- POINT D'ENTRÉE PRINCIPAL --
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        // Une seule instance: les liens charmed:// ouverts ensuite sont transmis a celle-ci
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            if let Some(window) = app.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+            }
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_opener::init())
+        .plugin(tauri_plugin_fs::init())
+        .plugin(tauri_plugin_shell::init())
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(shortcuts::plugin())
+        .setup(|app| {
+            let app_data_dir = app.path().app_data_dir().expect("Failed to get app data directory");
+
+            // Journaux en premier pour tracer le chargement
+            if let Err(e) = logging::init(&app_data_dir) {
+                eprintln!("{}", e);
+            }
+            tracing::info!(version = env!("CARGO_PKG_VERSION"), "Demarrage de Charmed");
+            
+            // Profil utilisateur du dernier lancement: alarmes, configuration et comptes Spotify
+            {
                 let state = app.state::<AppState>();
-                if let Ok(mut stored_config) = state.config.lock() {
-                    *stored_config = config;
+                let user = profiles::load_active(&app_data_dir);
+                let profile_dir = profiles::profile_dir(&app_data_dir, user.as_deref());
+                match load_profile(&state, &profile_dir, user.as_deref()) {
+                    Ok(recoveries) => emit_storage_recoveries(app.handle(), &recoveries),
+                    Err(e) => tracing::error!(profile = ?user, "Chargement du profil impossible: {}", e),
+                }
+                if let Err(e) = watch_alarms_file(app.handle()) {
+                    tracing::warn!("{}", e);
+                }
+                // Sonnerie interrompue par un plantage ou un arret force
+                if let Err(e) = scheduler::resume_ringing(app.handle()) {
+                    tracing::error!("Reprise de la sonnerie: {}", e);
                 }
             }
+
+            // Liens charmed:// (retour OAuth Spotify, actions rapides des notifications)
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                tracing::warn!("Enregistrement du protocole charmed:// impossible: {}", e);
+            }
+            let handle = app.handle().clone();
+            app.deep_link().on_open_url(move |event| {
+                for url in event.urls() {
+                    deeplink::handle(&handle, url);
+                }
+            });
+            if let Ok(Some(urls)) = app.deep_link().get_current() {
+                for url in urls {
+                    deeplink::handle(app.handle(), url);
+                }
+            }
+
+            // API REST locale (desactivee par defaut)
+            let handle = app.handle().clone();
+            tauri::async_runtime::spawn(async move {
+                let settings = handle.state::<AppState>().config.lock()
+                    .map(|c| c.remote_api.clone())
+                    .unwrap_or_default();
+                if let Err(e) = remote::restart(&handle, &settings).await {
+                    tracing::warn!("API distante indisponible: {}", e);
+                }
+            });
+
+            // Planificateur backend (detection et escalade des alarmes)
+            watchdog::spawn(app.handle().clone());
+            scheduler::spawn_weather_refresh(app.handle().clone());
+            scheduler::spawn_calendar_refresh(app.handle().clone());
+            scheduler::spawn_sync(app.handle().clone());
+            scheduler::spawn_power_watch(app.handle().clone());
+            scheduler::spawn_telemetry(app.handle().clone());
+            net::spawn(app.handle().clone());
+            scheduler::spawn_token_refresh(app.handle().clone());
             Ok(())
         })
         .manage(AppState {
             alarms: Mutex::new(Vec::new()),
+            naps: Mutex::new(Vec::new()),
+            timers: Mutex::new(Vec::new()),
             config: Mutex::new(storage::AppConfig::default()),
             spotify_client: Mutex::new(None),
+            spotify_pending_login: Mutex::new(None),
+            spotify_accounts: Mutex::new(HashMap::new()),
+            playlist_cache: Mutex::new(None),
+            sleep_timer: Mutex::new(None),
+            ringing: Mutex::new(None),
+            deferred_rings: Mutex::new(Vec::new()),
+            weather: Mutex::new(None),
+            saved_system_volume: Mutex::new(None),
+            saved_spotify_volume: Mutex::new(None),
+            remote_server: Mutex::new(None),
+            events: events::channel(),
+            sunrise_lights: Mutex::new(HashMap::new()),
+            spotify_kept_alive: Mutex::new(HashMap::new()),
+            pre_alarms: Mutex::new(HashMap::new()),
+            readiness_checked: Mutex::new(HashMap::new()),
+            network: Mutex::new(net::NetworkStatus::new(true, chrono::Local::now())),
+            calendar: Mutex::new(None),
+            wake_plans: Mutex::new(HashMap::new()),
+            fired: Mutex::new(HashMap::new()),
+            bedtime_reminded: Mutex::new(None),
+            wind_down_started: Mutex::new(None),
+            night_display: Mutex::new(None),
+            night_woken: Mutex::new(false),
+            power_warned: Mutex::new(None),
+            hardware_wake: Mutex::new(None),
+            scheduler_task: Mutex::new(None),
+            scheduler_beat: Mutex::new(None),
+            scheduler_aborted: Mutex::new(None),
+            scheduler_restarts: Mutex::new((0, None)),
+            ringing_persisted: Mutex::new(None),
+            profile: Mutex::new(None),
+            storage_recoveries: Mutex::new(Vec::new()),
+            alarms_watcher: Mutex::new(None),
+            apple_music: Mutex::new(None),
+            youtube_music: Mutex::new(None),
+            youtube_music_pending_login: Mutex::new(None),
         })
         .invoke_handler(tauri::generate_handler![
             get_current_time,
             set_alarm,
             get_alarms,
+            get_archived_alarms,
+            query_alarms,
+            get_alarm_icons,
+            set_alarm_archived,
+            save_alarm_template,
+            list_alarm_templates,
+            delete_alarm_template,
+            create_alarm_from_template,
+            export_alarms,
+            import_alarms,
+            export_ics,
+            set_sync,
+            sync_now,
             toggle_alarm,
+            update_alarm,
+            toggle_group,
             delete_alarm,
+            list_trash,
+            restore_alarm,
+            purge_trash,
             check_alarms,
+            get_next_alarm,
+            preview_schedule,
+            open_mini_clock,
+            close_mini_clock,
+            set_mini_clock_on_top,
+            get_mini_clock,
+            get_suggested_bedtime,
+            set_bedtime_reminder,
+            get_ringing_alarm,
+            get_dismiss_challenge,
+            submit_challenge_answer,
+            dismiss_alarm,
+            snooze_alarm,
+            set_alarm_shortcuts,
+            get_alarm_history,
+            get_readiness_history,
+            get_wake_stats,
+            skip_next,
+            vacation_mode,
+            set_holiday_calendar,
+            get_holidays,
+            set_weather_location,
+            get_wake_weather,
+            set_nap_timer,
+            get_nap_timers,
+            cancel_nap_timer,
+            start_timer,
+            list_timers,
+            cancel_timer,
+            test_alarm,
+            get_stopwatch,
+            stopwatch_start,
+            stopwatch_lap,
+            stopwatch_stop,
+            stopwatch_reset,
+            get_world_times,
             spotify_login,
             spotify_callback,
+            spotify_logout,
+            switch_spotify_account,
+            list_spotify_accounts,
             get_spotify_playlists,
+            get_made_for_you_playlists,
+            get_network_status,
+            get_playlist_moods,
+            set_playlist_moods,
+            recommend_playlists,
+            search_spotify,
             play_spotify_playlist,
             set_spotify_volume,
             is_spotify_authenticated,
+            apple_music_login,
+            apple_music_logout,
+            is_apple_music_authenticated,
+            get_apple_music_playlists,
+            youtube_music_login,
+            youtube_music_callback,
+            youtube_music_logout,
+            is_youtube_music_authenticated,
+            get_youtube_music_playlists,
+            set_music_library,
+            get_library_playlists,
+            set_radio_stations,
+            get_radio_playlists,
+            set_podcast_feeds,
+            get_podcast_playlists,
+            get_latest_podcast_episode,
+            start_sleep_timer,
+            cancel_sleep_timer,
+            get_sleep_timer,
+            set_wind_down,
+            health_check,
+            check_for_update,
+            install_update,
+            get_power_status,
+            get_scheduled_wake,
+            get_night_mode,
+            set_night_mode,
+            set_telemetry,
             play_local_alarm,
+            fade_in_spotify_alarm,
+            list_audio_devices,
+            set_output_device,
+            get_remote_api,
+            set_remote_api,
+            set_calendar_url,
+            get_calendar_events,
+            discover_hue_bridges,
+            pair_hue_bridge,
+            list_hue_lights,
             stop_local_alarm,
+            get_recent_logs,
+            get_storage_recoveries,
+            list_profiles,
+            get_active_profile,
+            switch_profile,
             get_config,
             update_config,
         ])
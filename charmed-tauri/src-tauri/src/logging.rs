@@ -0,0 +1,166 @@
+// logging.rs - Journaux structures (tracing) dans des fichiers tournants
+// Un fichier JSON par jour sous <app_data>/logs, relus par get_recent_logs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use serde::{Deserialize, Serialize};
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::EnvFilter;
+
+/// Sous-dossier des journaux dans le dossier de donnees
+const LOG_DIR: &str = "logs";
+
+/// Prefixe des fichiers de journaux
+const LOG_PREFIX: &str = "charmed";
+
+/// Nombre de fichiers journaliers conserves
+const MAX_LOG_FILES: usize = 7;
+
+/// Nombre maximum d'entrees retournees par get_recent_logs
+pub const MAX_LOG_ENTRIES: usize = 2000;
+
+/// Garde du writer non bloquant: les journaux sont vides a sa destruction
+static GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+/// Dossier des journaux
+pub fn log_dir(data_dir: &Path) -> PathBuf {
+    data_dir.join(LOG_DIR)
+}
+
+/// Installe le subscriber global (une seule fois par processus).
+/// Niveau par defaut INFO, modifiable via RUST_LOG.
+pub fn init(data_dir: &Path) -> Result<(), String> {
+    if GUARD.get().is_some() {
+        return Ok(());
+    }
+
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(log_dir(data_dir))
+        .map_err(|e| format!("Impossible de créer les journaux: {}", e))?;
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::fmt()
+        .json()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .try_init()
+        .map_err(|e| format!("Journalisation deja initialisee: {}", e))?;
+
+    let _ = GUARD.set(guard);
+    Ok(())
+}
+
+/// Entree de journal renvoyee au frontend
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogEntry {
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// Ligne JSON telle qu'ecrite par tracing-subscriber
+#[derive(Deserialize)]
+struct RawLine {
+    timestamp: String,
+    level: String,
+    #[serde(default)]
+    target: String,
+    #[serde(default)]
+    fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Convertit une ligne de journal; les champs autres que le message sont ajoutes en cle=valeur
+fn parse_line(line: &str) -> Option<LogEntry> {
+    let mut raw: RawLine = serde_json::from_str(line).ok()?;
+    let mut message = match raw.fields.remove("message") {
+        Some(serde_json::Value::String(text)) => text,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    for (key, value) in raw.fields {
+        let value = match value {
+            serde_json::Value::String(text) => text,
+            other => other.to_string(),
+        };
+        message.push_str(&format!(" {}={}", key, value));
+    }
+
+    Some(LogEntry {
+        timestamp: raw.timestamp,
+        level: raw.level,
+        target: raw.target,
+        message,
+    })
+}
+
+/// Conserve les entrees au moins aussi graves que `min_level`, plus recentes en premier
+pub fn filter_entries(lines: &[String], min_level: Level, limit: usize) -> Vec<LogEntry> {
+    lines
+        .iter()
+        .rev()
+        .filter_map(|line| parse_line(line))
+        .filter(|entry| Level::from_str(&entry.level).is_ok_and(|level| level <= min_level))
+        .take(limit.min(MAX_LOG_ENTRIES))
+        .collect()
+}
+
+/// Lit les derniers journaux (tous fichiers confondus)
+pub fn recent_logs(data_dir: &Path, min_level: Level, limit: usize) -> Result<Vec<LogEntry>, String> {
+    let dir = log_dir(data_dir);
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut files: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(|e| format!("Erreur lecture journaux: {}", e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with(LOG_PREFIX))
+        })
+        .collect();
+    // Noms dates (charmed.AAAA-MM-JJ.log): l'ordre alphabetique est chronologique
+    files.sort();
+
+    let lines: Vec<String> = files
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .flat_map(|content| content.lines().map(str::to_string).collect::<Vec<_>>())
+        .collect();
+
+    Ok(filter_entries(&lines, min_level, limit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_entries() {
+        let lines = vec![
+            r#"{"timestamp":"2024-03-12T07:00:00Z","level":"INFO","fields":{"message":"Alarme declenchee","alarm_id":"a1"},"target":"charmed_tauri_lib::scheduler"}"#.to_string(),
+            r#"{"timestamp":"2024-03-12T07:00:01Z","level":"DEBUG","fields":{"message":"tick"},"target":"charmed_tauri_lib::scheduler"}"#.to_string(),
+            "ligne corrompue".to_string(),
+            r#"{"timestamp":"2024-03-12T07:00:02Z","level":"ERROR","fields":{"message":"Erreur lecture"},"target":"charmed_tauri_lib::spotify"}"#.to_string(),
+        ];
+
+        let entries = filter_entries(&lines, Level::INFO, 10);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].level, "ERROR"); // Plus recent en premier
+        assert_eq!(entries[1].message, "Alarme declenchee alarm_id=a1");
+
+        assert_eq!(filter_entries(&lines, Level::ERROR, 10).len(), 1);
+        assert_eq!(filter_entries(&lines, Level::TRACE, 1).len(), 1);
+    }
+}
@@ -0,0 +1,86 @@
+// miniclock.rs - Fenetre de la mini horloge: sans bordure, au premier plan, position memorisee
+// Reglages dans le dossier de l'application (propres a la machine, pas au profil)
+
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager, PhysicalPosition, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+pub use charmed_core::miniclock::{view, MiniClockSettings, MiniClockView, Placement};
+
+/// Etiquette de la fenetre (voir capabilities/mini-clock.json). Le frontend
+/// charge la meme page et affiche la mini horloge d'apres cette etiquette.
+pub const LABEL: &str = "mini-clock";
+
+const WIDTH: f64 = 220.0;
+const HEIGHT: f64 = 90.0;
+
+fn settings_dir(app_handle: &AppHandle) -> Result<PathBuf, String> {
+    app_handle.path().app_data_dir().map_err(|e| e.to_string())
+}
+
+/// Reglages de la mini horloge
+fn settings(app_handle: &AppHandle) -> Result<MiniClockSettings, String> {
+    Ok(charmed_core::miniclock::load(&settings_dir(app_handle)?))
+}
+
+fn save_settings(app_handle: &AppHandle, settings: &MiniClockSettings) -> Result<(), String> {
+    charmed_core::miniclock::save(&settings_dir(app_handle)?, settings)
+}
+
+/// Ouvre la mini horloge (ou la ramene au premier plan si elle existe)
+pub fn open(app_handle: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        window.show().map_err(|e| e.to_string())?;
+        return window.set_focus().map_err(|e| e.to_string());
+    }
+
+    let settings = settings(app_handle)?;
+    let window = WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::default())
+        .title("Charmed")
+        .inner_size(WIDTH, HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .skip_taskbar(true)
+        .always_on_top(settings.always_on_top)
+        .build()
+        .map_err(|e| e.to_string())?;
+    // Position en pixels physiques, comme celle memorisee au deplacement
+    if let Some(placement) = settings.placement {
+        window
+            .set_position(PhysicalPosition::new(placement.x, placement.y))
+            .map_err(|e| e.to_string())?;
+    }
+
+    let handle = app_handle.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Moved(position) = event {
+            let result = settings_dir(&handle).and_then(|dir| {
+                let mut settings = charmed_core::miniclock::load(&dir);
+                settings.placement = Some(Placement { x: position.x, y: position.y });
+                charmed_core::miniclock::save(&dir, &settings)
+            });
+            if let Err(e) = result {
+                tracing::warn!("Position de la mini horloge non memorisee: {}", e);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Ferme la mini horloge si elle est ouverte
+pub fn close(app_handle: &AppHandle) -> Result<(), String> {
+    match app_handle.get_webview_window(LABEL) {
+        Some(window) => window.close().map_err(|e| e.to_string()),
+        None => Ok(()),
+    }
+}
+
+/// Garde ou non la mini horloge au-dessus des autres fenetres
+pub fn set_always_on_top(app_handle: &AppHandle, always_on_top: bool) -> Result<(), String> {
+    let mut settings = settings(app_handle)?;
+    settings.always_on_top = always_on_top;
+    save_settings(app_handle, &settings)?;
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        window.set_always_on_top(always_on_top).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,88 @@
+// net.rs - Surveillance du reseau
+// Le planificateur s'appuie sur le dernier etat releve pour ses replis (son local hors ligne),
+// et le frontend recoit chaque changement pour griser les fonctions Spotify
+
+use std::time::Duration;
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use charmed_core::readiness;
+
+use crate::AppState;
+
+/// Evenement Tauri emis quand le reseau revient
+pub const NETWORK_ONLINE_EVENT: &str = "network-online";
+
+/// Evenement Tauri emis quand le reseau est coupe
+pub const NETWORK_OFFLINE_EVENT: &str = "network-offline";
+
+/// Intervalle entre deux verifications du reseau
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Etat du reseau transmis au frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkStatus {
+    pub online: bool,
+    pub since: DateTime<Local>, // Dernier changement d'etat (ou demarrage)
+}
+
+impl NetworkStatus {
+    pub fn new(online: bool, since: DateTime<Local>) -> Self {
+        Self { online, since }
+    }
+
+    fn event_name(&self) -> &'static str {
+        if self.online {
+            NETWORK_ONLINE_EVENT
+        } else {
+            NETWORK_OFFLINE_EVENT
+        }
+    }
+}
+
+/// Dernier etat releve (en ligne tant qu'aucune verification n'a echoue)
+pub fn is_online(state: &AppState) -> Result<bool, String> {
+    Ok(state.network.lock().map_err(|e| e.to_string())?.online)
+}
+
+/// Lance la verification periodique du reseau pour toute la duree de vie de l'application
+pub fn spawn(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            let online = readiness::network_up().await;
+
+            let changed = {
+                let state = app_handle.state::<AppState>();
+                let Ok(mut current) = state.network.lock() else {
+                    continue;
+                };
+                if current.online == online {
+                    continue;
+                }
+                *current = NetworkStatus::new(online, Local::now());
+                current.clone()
+            };
+            tracing::info!(online, "Etat du reseau modifie");
+            if let Err(e) = app_handle.emit(changed.event_name(), &changed) {
+                tracing::warn!("Evenement reseau non emis: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_network_status_event() {
+        let since = Local::now();
+        let offline = NetworkStatus::new(false, since);
+        assert_eq!(offline.event_name(), "network-offline");
+        assert_eq!(NetworkStatus::new(true, since).event_name(), "network-online");
+        assert_eq!(serde_json::to_value(&offline).unwrap()["online"], serde_json::json!(false));
+    }
+}
@@ -0,0 +1,282 @@
+// remote.rs - API REST locale pour piloter l'alarme depuis un autre appareil du reseau
+// (navigateur du telephone). Desactivee par defaut; chaque requete /api exige le jeton,
+// en en-tete `Authorization: Bearer <jeton>` ou en parametre `?token=<jeton>`.
+// /api/events diffuse en WebSocket les evenements de sonnerie (voir events.rs).
+
+use std::net::{Ipv4Addr, SocketAddr};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, Request, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{Html, IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::alarm::{NapTimer, RingingAlarm};
+use crate::events::AlarmEvent;
+use crate::{
+    dismiss_ringing, ringing_alarm_id, secrets, snooze_ringing, toggle_alarm_entry, AlarmEntry, AppState,
+};
+
+pub use charmed_core::remote::RemoteApiSettings;
+
+/// Etat de l'API renvoye a l'interface pour configurer le client distant
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteApiInfo {
+    pub enabled: bool,
+    pub port: u16,
+    pub token: Option<String>, // Absent tant que l'API n'a jamais ete activee
+}
+
+impl RemoteApiInfo {
+    pub fn new(settings: &RemoteApiSettings) -> Result<Self, String> {
+        Ok(Self {
+            enabled: settings.enabled,
+            port: settings.port,
+            token: secrets::get_secret(secrets::REMOTE_API_TOKEN_KEY)?,
+        })
+    }
+}
+
+/// Jeton d'acces, genere au premier usage et range dans le trousseau
+fn access_token() -> Result<String, String> {
+    if let Some(token) = secrets::get_secret(secrets::REMOTE_API_TOKEN_KEY)? {
+        return Ok(token);
+    }
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    secrets::set_secret(secrets::REMOTE_API_TOKEN_KEY, &token)?;
+    Ok(token)
+}
+
+/// Comparaison en temps constant (la duree ne revele pas le prefixe correct)
+fn token_matches(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected.bytes().zip(provided.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[derive(Clone)]
+struct ServerState {
+    app_handle: AppHandle,
+    token: String,
+}
+
+type ApiError = (StatusCode, String);
+
+#[derive(Deserialize)]
+struct TokenQuery {
+    #[serde(default)]
+    token: Option<String>,
+}
+
+async fn require_token(
+    State(server): State<ServerState>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Response {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    let provided = bearer.or(query.token.as_deref()).unwrap_or_default();
+
+    if !token_matches(&server.token, provided) {
+        return (StatusCode::UNAUTHORIZED, "Jeton invalide").into_response();
+    }
+    next.run(request).await
+}
+
+async fn list_alarms(State(server): State<ServerState>) -> Result<Json<Vec<AlarmEntry>>, ApiError> {
+    let state = server.app_handle.state::<AppState>();
+    let alarms = state.alarms.lock()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(alarms.clone()))
+}
+
+#[derive(Serialize)]
+struct ToggleResult {
+    active: bool,
+}
+
+async fn toggle_alarm(
+    State(server): State<ServerState>,
+    Path(alarm_id): Path<String>,
+) -> Result<Json<ToggleResult>, ApiError> {
+    let state = server.app_handle.state::<AppState>();
+    toggle_alarm_entry(&server.app_handle, &state, &alarm_id)
+        .map(|active| Json(ToggleResult { active }))
+        .map_err(|e| (StatusCode::NOT_FOUND, e))
+}
+
+async fn get_ringing(State(server): State<ServerState>) -> Result<Json<Option<RingingAlarm>>, ApiError> {
+    let state = server.app_handle.state::<AppState>();
+    let ringing = state.ringing.lock()
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(ringing.clone()))
+}
+
+#[derive(Deserialize)]
+struct SnoozeQuery {
+    #[serde(default)]
+    minutes: Option<u32>,
+}
+
+async fn snooze(
+    State(server): State<ServerState>,
+    Query(query): Query<SnoozeQuery>,
+) -> Result<Json<NapTimer>, ApiError> {
+    let state = server.app_handle.state::<AppState>();
+    let alarm_id = ringing_alarm_id(&state).map_err(|e| (StatusCode::CONFLICT, e))?;
+    snooze_ringing(&server.app_handle, &state, &alarm_id, query.minutes).await
+        .map(Json)
+        .map_err(|e| (StatusCode::CONFLICT, e))
+}
+
+async fn dismiss(State(server): State<ServerState>) -> Result<StatusCode, ApiError> {
+    let state = server.app_handle.state::<AppState>();
+    let alarm_id = ringing_alarm_id(&state).map_err(|e| (StatusCode::CONFLICT, e))?;
+    dismiss_ringing(&server.app_handle, &state, &alarm_id).await
+        .map(|_| StatusCode::NO_CONTENT)
+        .map_err(|e| (StatusCode::CONFLICT, e))
+}
+
+/// Abonne le client aux evenements de sonnerie (jeton en `?token=`, les navigateurs
+/// ne pouvant pas ajouter d'en-tete a une connexion WebSocket)
+async fn events_socket(State(server): State<ServerState>, ws: WebSocketUpgrade) -> Response {
+    let receiver = server.app_handle.state::<AppState>().events.subscribe();
+    ws.on_upgrade(move |socket| forward_events(socket, receiver))
+}
+
+/// Transmet chaque evenement en JSON jusqu'a la deconnexion du client
+async fn forward_events(mut socket: WebSocket, mut receiver: broadcast::Receiver<AlarmEvent>) {
+    loop {
+        tokio::select! {
+            event = receiver.recv() => match event {
+                Ok(event) => {
+                    let Ok(json) = serde_json::to_string(&event) else {
+                        continue;
+                    };
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Client WebSocket trop lent, evenements perdus");
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+/// Page de telecommande minimale (le jeton est lu dans l'URL de la page)
+const REMOTE_PAGE: &str = r#"<!doctype html>
+<html lang="fr">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Charmed</title>
+<style>
+body { font-family: system-ui, sans-serif; background: #111; color: #eee; text-align: center; padding: 2rem; }
+button { display: block; width: 100%; margin: 1rem 0; padding: 1.5rem; font-size: 1.5rem; border: 0; border-radius: 1rem; }
+#snooze { background: #1DB954; } #dismiss { background: #e04848; color: #fff; }
+</style>
+</head>
+<body>
+<h1>Charmed</h1>
+<p id="status">...</p>
+<button id="snooze">Reporter</button>
+<button id="dismiss">Arreter</button>
+<script>
+const token = new URLSearchParams(location.search).get("token") || "";
+const api = (path, method) => fetch("/api" + path, { method, headers: { Authorization: "Bearer " + token } });
+async function refresh() {
+  const res = await api("/ringing", "GET");
+  const ringing = res.ok ? await res.json() : null;
+  document.getElementById("status").textContent =
+    !res.ok ? "Jeton invalide" : ringing ? "Sonne: " + ringing.alarm.time + " - " + ringing.alarm.playlist_name : "Aucune alarme ne sonne";
+}
+for (const action of ["snooze", "dismiss"]) {
+  document.getElementById(action).onclick = async () => {
+    const res = await api("/" + action, "POST");
+    if (!res.ok) alert(await res.text());
+    refresh();
+  };
+}
+refresh();
+setInterval(refresh, 5000);
+const scheme = location.protocol === "https:" ? "wss://" : "ws://";
+new WebSocket(scheme + location.host + "/api/events?token=" + encodeURIComponent(token)).onmessage = refresh;
+</script>
+</body>
+</html>
+"#;
+
+async fn remote_page() -> Html<&'static str> {
+    Html(REMOTE_PAGE)
+}
+
+fn router(server: ServerState) -> Router {
+    let api = Router::new()
+        .route("/alarms", get(list_alarms))
+        .route("/alarms/{id}/toggle", post(toggle_alarm))
+        .route("/ringing", get(get_ringing))
+        .route("/snooze", post(snooze))
+        .route("/dismiss", post(dismiss))
+        .route("/events", get(events_socket))
+        .route_layer(middleware::from_fn_with_state(server.clone(), require_token));
+
+    Router::new()
+        .route("/", get(remote_page))
+        .nest("/api", api)
+        .with_state(server)
+}
+
+/// Arrete le serveur en cours puis le relance si l'API est activee.
+/// Ecoute sur toutes les interfaces pour etre joignable depuis le reseau local.
+pub async fn restart(app_handle: &AppHandle, settings: &RemoteApiSettings) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    if let Some(server) = state.remote_server.lock().map_err(|e| e.to_string())?.take() {
+        server.abort();
+    }
+    if !settings.enabled {
+        return Ok(());
+    }
+
+    let token = access_token()?;
+    let addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, settings.port));
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Port {} indisponible: {}", settings.port, e))?;
+    let app = router(ServerState { app_handle: app_handle.clone(), token });
+
+    let server = tauri::async_runtime::spawn(async move {
+        if let Err(e) = axum::serve(listener, app).await {
+            tracing::error!("API distante arretee: {}", e);
+        }
+    });
+    *state.remote_server.lock().map_err(|e| e.to_string())? = Some(server);
+    tracing::info!(port = settings.port, "API distante demarree");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches("abc123", "abc123"));
+        assert!(!token_matches("abc123", "abc124"));
+        assert!(!token_matches("abc123", "abc"));
+        assert!(!token_matches("abc123", ""));
+    }
+}
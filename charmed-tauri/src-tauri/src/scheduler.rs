@@ -0,0 +1,1704 @@
+// scheduler.rs - Planificateur backend: detecte les alarmes et pilote leur sonnerie
+// Tourne independamment du frontend (fenetre fermee comprise)
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::alarm::{self, NapTimer, PreAlarmSound, RingTimeoutAction, MAX_AUTO_SNOOZES};
+use crate::bedtime;
+use crate::escalation::EscalationStage;
+use crate::events::{self, AlarmEvent};
+use crate::fade::{self, FadeHandle, FadeTarget};
+use crate::history::{self, AlarmOutcome, HistoryEntry};
+use crate::shortcuts;
+use crate::wakescreen;
+use crate::system_volume;
+use crate::nightmode;
+use crate::power;
+use crate::query::ProviderKind;
+use crate::telemetry::{self, TelemetryKind};
+use crate::timer;
+use crate::tts;
+use crate::lights;
+use crate::net;
+use crate::calendar;
+use crate::readiness::{self, ReadinessReport};
+use crate::spotify::{self, SpotifyClient};
+use crate::wake_window::{self, WakePlan};
+use crate::winddown;
+use crate::{
+    audio, calendar_events, data_dir, hue_credentials, playback_target, poll_alarms, prepare_fade_in, readiness_context, refresh_calendar,
+    next_alarm, record_telemetry, refresh_weather, spotify_client_for, start_sleep_fade, suggested_bedtime, sync_alarms, user_profile, AlarmEntry,
+    AlarmFadeIn, AppState,
+};
+
+/// Intervalle entre deux passages du planificateur
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Intervalle entre deux releves meteo (inferieur a la validite d'un releve)
+const WEATHER_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Intervalle entre deux lectures de l'agenda
+const CALENDAR_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Intervalle entre deux synchronisations automatiques
+const SYNC_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Intervalle entre deux lectures de l'alimentation le soir
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Intervalle entre deux envois de la file de telemetrie
+const TELEMETRY_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Duree pendant laquelle une lecture Spotify remplacee par le son local peut encore prendre le relais
+const LATE_PLAYBACK_WINDOW: Duration = Duration::from_secs(2 * 60);
+
+/// Intervalle de sondage du lecteur Spotify pendant cette attente
+const LATE_PLAYBACK_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Intervalle entre deux controles de l'expiration des jetons Spotify
+const TOKEN_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Marge avant expiration a laquelle un jeton Spotify est renouvele d'avance (minutes)
+const TOKEN_REFRESH_MARGIN_MINUTES: i64 = 10;
+
+/// Evenement Tauri emis quand un compte Spotify doit etre reconnecte
+pub const AUTH_EXPIRED_EVENT: &str = "spotify-auth-expired";
+
+/// Evenement Tauri emis a la fin d'un minuteur
+pub const TIMER_FINISHED_EVENT: &str = "timer-finished";
+
+/// Intervalle entre deux lectures du titre en cours (file d'apres reveil)
+const QUEUE_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Lance la boucle du planificateur (surveillee et relancee au besoin par watchdog::spawn)
+pub fn spawn(app_handle: AppHandle) -> tauri::async_runtime::JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TICK_INTERVAL);
+        let io_busy = Arc::new(AtomicBool::new(false));
+        loop {
+            interval.tick().await;
+            let state = app_handle.state::<AppState>();
+            let now = chrono::Local::now();
+            if let Ok(mut beat) = state.scheduler_beat.lock() {
+                *beat = Some(now);
+            }
+
+            if let Err(e) = start_sunrise_lights(&state, now) {
+                tracing::error!("Lever de soleil lumineux: {}", e);
+            }
+            if let Err(e) = keep_spotify_devices_alive(&state, now) {
+                tracing::warn!("Appareil Spotify: {}", e);
+            }
+            if let Err(e) = play_pre_alarms(&state, now) {
+                tracing::warn!("Pre-alarme: {}", e);
+            }
+            if let Err(e) = check_readiness(&app_handle, &state, now) {
+                tracing::warn!("Verification avant sonnerie: {}", e);
+            }
+            if let Err(e) = plan_wake_windows(&app_handle, &state, now) {
+                tracing::error!("Fenetre de reveil: {}", e);
+            }
+            match poll_alarms(&app_handle, &state, now) {
+                Ok(Some(alarm)) => {
+                    if let Err(e) = ring_triggered(&app_handle, &state, &alarm) {
+                        tracing::error!("Lecture de l'alarme: {}", e);
+                    }
+                    events::emit(&app_handle, AlarmEvent::Triggered { alarm: Box::new(alarm) });
+                }
+                Ok(None) => {}
+                Err(e) => tracing::error!("Planificateur: {}", e),
+            }
+            if let Err(e) = persist_ringing(&app_handle, &state) {
+                tracing::error!("Sonnerie non enregistree: {}", e);
+            }
+            if let Err(e) = finish_timers(&app_handle, &state, now) {
+                tracing::warn!("Minuteurs: {}", e);
+            }
+            // Etapes qui attendent le reseau ou le systeme: hors du tour, une seule serie a la fois
+            if !io_busy.swap(true, Ordering::SeqCst) {
+                let app_handle = app_handle.clone();
+                let busy = BusyGuard(io_busy.clone());
+                tauri::async_runtime::spawn(async move {
+                    drive_ringing(&app_handle, now).await;
+                    drop(busy);
+                });
+            }
+            let ringing = state.ringing.lock().map(|r| r.is_some()).unwrap_or(false);
+            if let Err(e) = shortcuts::sync(&app_handle, ringing) {
+                tracing::warn!("Raccourcis globaux: {}", e);
+            }
+            if let Err(e) = wakescreen::sync(&app_handle, ringing) {
+                tracing::warn!("Ecran de reveil: {}", e);
+            }
+            if let Err(e) = start_soundscape(&app_handle, &state) {
+                tracing::error!("Lever de soleil: {}", e);
+            }
+            if let Err(e) = start_announcement(&app_handle, &state, now) {
+                tracing::error!("Annonce vocale: {}", e);
+            }
+            if let Err(e) = start_after_wake_queue(&app_handle, &state) {
+                tracing::warn!("File d'apres reveil: {}", e);
+            }
+            if let Err(e) = remind_bedtime(&app_handle, &state, now) {
+                tracing::warn!("Rappel de coucher: {}", e);
+            }
+            if let Err(e) = start_wind_down(&app_handle, &state, now) {
+                tracing::warn!("Routine du soir: {}", e);
+            }
+        }
+    })
+}
+
+/// Libere la serie d'etapes du tour en cours, meme si elle panique
+struct BusyGuard(Arc<AtomicBool>);
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Etapes du planificateur qui attendent Spotify ou le systeme (jusqu'a plusieurs dizaines
+/// de secondes avec les nouvelles tentatives). Lancees hors du tour: une requete lente ne
+/// retarde ni le declenchement des alarmes ni le battement surveille par le superviseur.
+async fn drive_ringing(app_handle: &AppHandle, now: chrono::DateTime<chrono::Local>) {
+    let state = app_handle.state::<AppState>();
+    if let Err(e) = enforce_ring_limit(app_handle, &state, now).await {
+        tracing::error!("Duree de sonnerie: {}", e);
+    }
+    if let Err(e) = fire_backup_alarm(app_handle, &state, now).await {
+        tracing::error!("Alarme de secours: {}", e);
+    }
+    if let Err(e) = drive_escalation(app_handle, &state, now).await {
+        tracing::error!("Escalade: {}", e);
+    }
+    if let Err(e) = sync_system_volume(&state).await {
+        tracing::warn!("Volume systeme: {}", e);
+    }
+    if let Err(e) = restore_spotify_volume(&state).await {
+        tracing::warn!("Volume Spotify: {}", e);
+    }
+    if let Err(e) = sync_night_mode(&state, now).await {
+        tracing::warn!("Mode nuit: {}", e);
+    }
+    if let Err(e) = sync_hardware_wake(&state, now).await {
+        tracing::warn!("Reveil materiel: {}", e);
+    }
+}
+
+/// Tient la meteo a jour tant qu'une alarme active en depend, pour que le
+/// planificateur n'attende jamais le reseau au moment de sonner
+pub fn spawn_weather_refresh(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(WEATHER_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app_handle.state::<AppState>();
+
+            let needed = match state.alarms.lock() {
+                Ok(alarms) => alarms.iter().any(|a| {
+                    alarm::is_enabled(a)
+                        && (a.weather_playlists.is_some()
+                            || a.announcement.as_ref().is_some_and(|an| an.include_weather))
+                }),
+                Err(_) => false,
+            };
+            if !needed {
+                continue;
+            }
+            if let Err(e) = refresh_weather(&state).await {
+                tracing::warn!("Meteo: {}", e);
+            }
+        }
+    });
+}
+
+/// Synchronise periodiquement alarmes et configuration avec le stockage distant
+/// quand la synchronisation automatique est active
+pub fn spawn_sync(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(SYNC_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app_handle.state::<AppState>();
+
+            let enabled = match state.config.lock() {
+                Ok(config) => config.sync.as_ref().is_some_and(|s| s.auto),
+                Err(_) => false,
+            };
+            if !enabled {
+                continue;
+            }
+            if let Err(e) = sync_alarms(&app_handle, &state).await {
+                tracing::warn!("Synchronisation: {}", e);
+            }
+        }
+    });
+}
+
+/// Surveille l'alimentation le soir: un portable sur batterie avec mise en veille
+/// automatique dormirait pendant l'alarme (un seul avertissement par soir)
+pub fn spawn_power_watch(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(POWER_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = warn_on_battery(&app_handle, chrono::Local::now()).await {
+                tracing::warn!("Alimentation: {}", e);
+            }
+        }
+    });
+}
+
+async fn warn_on_battery(app_handle: &AppHandle, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    if !power::is_evening(now)
+        || *state.power_warned.lock().map_err(|e| e.to_string())? == Some(now.date_naive())
+    {
+        return Ok(());
+    }
+    let Some(next) = next_alarm(&state, now)? else {
+        return Ok(());
+    };
+    let status = tauri::async_runtime::spawn_blocking(power::read_status)
+        .await
+        .map_err(|e| e.to_string())??;
+    if !power::should_warn(&status, now, next.trigger_at) {
+        return Ok(());
+    }
+    *state.power_warned.lock().map_err(|e| e.to_string())? = Some(now.date_naive());
+
+    tracing::warn!(alarm_id = %next.alarm.id, battery = ?status.battery_percent, "Sur batterie avec veille automatique");
+    let (locale, time_format) = {
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        (config.locale, config.time_format)
+    };
+    let (title, body) = power::warning_text(locale, time_format, &status, next.trigger_at);
+    if let Err(e) = notify(app_handle, title, body) {
+        tracing::warn!("Notification: {}", e);
+    }
+    events::emit(
+        app_handle,
+        AlarmEvent::PowerWarning { alarm_id: next.alarm.id, alarm_at: next.trigger_at, status },
+    );
+    Ok(())
+}
+
+/// Envoie periodiquement la file de telemetrie quand elle est activee et
+/// qu'une adresse de collecte est configuree (les evenements attendent sinon)
+pub fn spawn_telemetry(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TELEMETRY_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = flush_telemetry(&app_handle).await {
+                tracing::warn!("Telemetrie: {}", e);
+            }
+        }
+    });
+}
+
+async fn flush_telemetry(app_handle: &AppHandle) -> Result<(), String> {
+    let settings = app_handle.state::<AppState>().config.lock().map_err(|e| e.to_string())?.telemetry.clone();
+    let (true, Some(endpoint), Some(install_id)) = (settings.enabled, settings.endpoint, settings.install_id) else {
+        return Ok(());
+    };
+    let dir = data_dir(app_handle)?;
+    loop {
+        let queue = telemetry::load_queue(&dir);
+        if queue.is_empty() {
+            return Ok(());
+        }
+        let batch = &queue[..queue.len().min(telemetry::BATCH_SIZE)];
+        telemetry::send(&endpoint, &install_id, env!("CARGO_PKG_VERSION"), batch).await?;
+        tracing::info!(events = batch.len(), "Telemetrie envoyee");
+        // Relue apres l'envoi: des evenements ont pu s'ajouter entre-temps
+        let mut queue = telemetry::load_queue(&dir);
+        queue.drain(..batch.len().min(queue.len()));
+        telemetry::save_queue(&dir, &queue)?;
+    }
+}
+
+/// Compte Spotify dont le jeton n'a pas pu etre renouvele
+#[derive(Debug, Clone, Serialize)]
+struct AuthExpired {
+    profile: String,
+    active: bool, // Compte actif de l'application
+    error: String,
+}
+
+/// Renouvelle les jetons Spotify avant leur expiration, pour que le planificateur
+/// trouve toujours un jeton valide au moment de sonner
+pub fn spawn_token_refresh(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(TOKEN_CHECK_INTERVAL);
+        // Expiration du jeton refuse par compte: pas de nouvel essai tant qu'il n'a pas change
+        let mut expired: HashMap<String, Option<chrono::DateTime<chrono::Utc>>> = HashMap::new();
+        loop {
+            interval.tick().await;
+            let state = app_handle.state::<AppState>();
+            if !net::is_online(&state).unwrap_or(true) {
+                continue;
+            }
+            let Ok(clients) = state.spotify_accounts.lock().map(|a| a.values().cloned().collect::<Vec<_>>()) else {
+                continue;
+            };
+            let active = state.spotify_client.lock().ok()
+                .and_then(|c| c.as_ref().map(|c| c.profile().to_string()));
+
+            let margin = chrono::Duration::minutes(TOKEN_REFRESH_MARGIN_MINUTES);
+            let now = chrono::Utc::now();
+            for client in clients {
+                let expires_at = client.token_expires_at().await;
+                if expires_at.is_some_and(|at| at - now > margin)
+                    || expired.get(client.profile()) == Some(&expires_at)
+                {
+                    continue;
+                }
+
+                match client.refresh_access_token().await {
+                    Ok(()) => {
+                        expired.remove(client.profile());
+                        if let Err(e) = persist_spotify_account(&app_handle, &state, &client).await {
+                            tracing::warn!(profile = %client.profile(), "Jeton renouvele non enregistre: {}", e);
+                        }
+                    }
+                    // Reseau indisponible malgre les essais: retente au prochain controle
+                    Err(e) if e.is_transient() => {
+                        tracing::warn!(profile = %client.profile(), "Jeton Spotify non renouvele: {}", e);
+                    }
+                    Err(e) => {
+                        tracing::error!(profile = %client.profile(), "Jeton Spotify refuse, reconnexion necessaire: {}", e);
+                        expired.insert(client.profile().to_string(), expires_at);
+                        let payload = AuthExpired {
+                            profile: client.profile().to_string(),
+                            active: active.as_deref() == Some(client.profile()),
+                            error: e.to_string(),
+                        };
+                        if let Err(e) = app_handle.emit(AUTH_EXPIRED_EVENT, &payload) {
+                            tracing::warn!("Evenement non emis: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Enregistre le jeton courant d'un compte (fichier + trousseau, hors des threads du runtime)
+async fn persist_spotify_account(app_handle: &AppHandle, state: &AppState, client: &SpotifyClient) -> Result<(), String> {
+    let Some(account) = client.to_stored().await else {
+        return Ok(());
+    };
+    let app_data_dir = data_dir(app_handle)?;
+    let user = user_profile(state)?;
+    tauri::async_runtime::spawn_blocking(move || spotify::upsert_account(&app_data_dir, user.as_deref(), account))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Relit l'agenda tant qu'une alarme active en depend: le planificateur
+/// calcule l'heure du jour a partir du dernier releve, sans attendre le reseau
+pub fn spawn_calendar_refresh(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CALENDAR_INTERVAL);
+        loop {
+            interval.tick().await;
+            let state = app_handle.state::<AppState>();
+
+            let needed = match state.alarms.lock() {
+                Ok(alarms) => alarms.iter().any(|a| alarm::is_enabled(a) && a.calendar.is_some()),
+                Err(_) => false,
+            };
+            if !needed {
+                continue;
+            }
+            if let Err(e) = refresh_calendar(&state).await {
+                tracing::warn!("Agenda: {}", e);
+            }
+        }
+    });
+}
+
+/// Ecrit la sonnerie en cours sur disque des qu'elle change (efface le fichier
+/// quand plus rien ne sonne). Les tests de l'alarme ne sont pas repris.
+pub(crate) fn persist_ringing(app_handle: &AppHandle, state: &AppState) -> Result<(), String> {
+    let record = state.ringing.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .filter(|r| !r.test)
+        .map(history::RingingRecord::of);
+    let key = record.as_ref().map(|r| (r.alarm.id.clone(), r.started_at));
+    if *state.ringing_persisted.lock().map_err(|e| e.to_string())? == key {
+        return Ok(());
+    }
+    // Cle notee seulement apres l'ecriture: un echec est retente au tick suivant
+    history::save_ringing(&data_dir(app_handle)?, record.as_ref())?;
+    *state.ringing_persisted.lock().map_err(|e| e.to_string())? = key;
+    Ok(())
+}
+
+/// Reprend au demarrage la sonnerie interrompue par un arret brutal de l'application:
+/// l'alarme sonne de nouveau (escalade comprise) au lieu d'etre oubliee.
+/// Une sonnerie trop ancienne est notee comme manquee.
+pub fn resume_ringing(app_handle: &AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let app_data_dir = data_dir(app_handle)?;
+    let Some(record) = history::load_ringing(&app_data_dir) else {
+        return Ok(());
+    };
+    let now = chrono::Local::now();
+
+    if record.is_stale(now) {
+        tracing::warn!(alarm_id = %record.alarm.id, since = %record.started_at, "Sonnerie interrompue trop ancienne, notee comme manquee");
+        let ringing = record.resume();
+        history::record(&app_data_dir, HistoryEntry::new(&ringing, now, AlarmOutcome::Missed))?;
+        return history::save_ringing(&app_data_dir, None);
+    }
+
+    tracing::warn!(alarm_id = %record.alarm.id, since = %record.started_at, "Reprise d'une sonnerie interrompue");
+    let ringing = record.resume();
+    let alarm = ringing.alarm.clone();
+    *state.ringing_persisted.lock().map_err(|e| e.to_string())? = Some((alarm.id.clone(), ringing.started_at));
+    *state.ringing.lock().map_err(|e| e.to_string())? = Some(ringing);
+    ring_triggered(app_handle, &state, &alarm)?;
+    events::emit(app_handle, AlarmEvent::Triggered { alarm: Box::new(alarm) });
+    Ok(())
+}
+
+/// Arrete une alarme qui sonne depuis trop longtemps, puis la reporte
+/// ou la note comme manquee selon sa configuration
+async fn enforce_ring_limit(
+    app_handle: &AppHandle,
+    state: &AppState,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<(), String> {
+    let expired = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        let exceeded = ringing.as_ref().is_some_and(|r| {
+            r.alarm.ring_limit.is_some_and(|limit| limit.is_exceeded(r.started_at, now))
+        });
+        if !exceeded {
+            return Ok(());
+        }
+        ringing.take()
+    };
+    let Some(expired) = expired else {
+        return Ok(());
+    };
+    expired.stop_fades();
+    let Some(limit) = expired.alarm.ring_limit else {
+        return Ok(());
+    };
+
+    // Couper le son local et la playlist
+    let _ = audio::stop_alarm_sound();
+    let target = playback_target(app_handle, state, &expired.alarm)?;
+    if !matches!(target, FadeTarget::Local) {
+        let _ = target.pause().await;
+    }
+
+    tracing::info!(alarm_id = %expired.alarm.id, max_minutes = limit.max_minutes, "Duree maximale de sonnerie atteinte");
+
+    let alarm_id = expired.alarm.id.clone();
+    let (outcome, event) = match limit.on_timeout {
+        RingTimeoutAction::Snooze { minutes } if expired.auto_snoozes < MAX_AUTO_SNOOZES => {
+            let snooze = NapTimer::snooze(&expired, now, minutes);
+            let fires_at = snooze.fires_at;
+            state.naps.lock().map_err(|e| e.to_string())?.push(snooze);
+            (AlarmOutcome::Snoozed, AlarmEvent::Snoozed { alarm_id, fires_at })
+        }
+        _ => (AlarmOutcome::Missed, AlarmEvent::Missed { alarm_id }),
+    };
+    events::emit(app_handle, event);
+
+    let app_data_dir = data_dir(app_handle)?;
+    history::record(&app_data_dir, HistoryEntry::new(&expired, now, outcome))
+}
+
+/// Declenche l'alarme de secours d'une sonnerie restee sans reponse: la playlist
+/// est coupee et remplacee par la sirene locale a plein volume (une seule fois)
+async fn fire_backup_alarm(
+    app_handle: &AppHandle,
+    state: &AppState,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<(), String> {
+    let alarm = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        match ringing.as_mut() {
+            Some(r) if r.backup_due(now) => {
+                r.backup_fired = true;
+                r.stop_fades();
+                r.alarm.clone()
+            }
+            _ => return Ok(()),
+        }
+    };
+
+    let target = playback_target(app_handle, state, &alarm)?;
+    if !matches!(target, FadeTarget::Local) {
+        let _ = target.pause().await;
+    }
+    audio::set_alarm_volume(100)?;
+    audio::play_siren()?;
+    tracing::warn!(alarm_id = %alarm.id, "Sonnerie sans reponse: alarme de secours declenchee");
+    events::emit(app_handle, AlarmEvent::BackupTriggered { alarm_id: alarm.id.clone() });
+
+    // Arretee pendant le basculement: couper la sirene qui vient d'etre lancee
+    let still_ringing = state.ringing.lock().map_err(|e| e.to_string())?
+        .as_ref()
+        .is_some_and(|r| r.alarm.id == alarm.id && r.backup_fired);
+    if !still_ringing {
+        let _ = audio::stop_alarm_sound();
+    }
+    Ok(())
+}
+
+/// Fait avancer la machine a etats d'escalade de l'alarme en cours
+async fn drive_escalation(
+    app_handle: &AppHandle,
+    state: &AppState,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<(), String> {
+    let (alarm, started_at, current, sunrise) = {
+        let ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        match ringing.as_ref() {
+            // Apres l'alarme de secours, la sirene ne redescend plus
+            Some(r) if r.alarm.escalation.is_some() && !r.backup_fired => {
+                (r.alarm.clone(), r.started_at, r.escalation_stage, r.sunrise_duration())
+            }
+            _ => return Ok(()),
+        }
+    };
+    let Some(policy) = alarm.escalation.as_ref() else {
+        return Ok(());
+    };
+
+    // L'escalade commence apres le lever de soleil sonore
+    let elapsed = now.signed_duration_since(started_at).num_seconds() - sunrise.as_secs() as i64;
+    if elapsed < 0 {
+        return Ok(());
+    }
+    let mut next = policy.stage_at(alarm.volume, elapsed);
+    if current == Some(next) || current == Some(EscalationStage::Siren) {
+        return Ok(());
+    }
+
+    let target = playback_target(app_handle, state, &alarm)?;
+    if let Some(client) = target.spotify() {
+        remember_spotify_volume(state, client, &alarm).await;
+    }
+
+    if let EscalationStage::Playlist { volume } = next {
+        // Premier palier: demarrer la lecture; en cas d'echec, sirene immediate
+        let started = match current {
+            Some(_) => Ok(()),
+            None => target.play(&alarm.playlist_uri, alarm.first_track_uri.as_deref()).await,
+        };
+        match started {
+            Ok(()) => {
+                let _ = target.set_volume(volume).await;
+                if current.is_none() {
+                    events::emit(app_handle, AlarmEvent::NowPlaying {
+                        alarm_id: Some(alarm.id.clone()),
+                        playlist_uri: now_playing_uri(&target, &alarm),
+                    });
+                }
+            }
+            Err(e) => {
+                tracing::warn!(alarm_id = %alarm.id, "Escalade: lecture impossible ({}), bascule sur la sirene", e);
+                next = EscalationStage::Siren;
+            }
+        }
+    }
+
+    if next == EscalationStage::Siren {
+        if !matches!(target, FadeTarget::Local) {
+            let _ = target.pause().await;
+        }
+        audio::set_alarm_volume(100)?;
+        audio::play_siren()?;
+    }
+
+    tracing::info!(alarm_id = %alarm.id, stage = ?next, "Palier d'escalade applique");
+
+    // N'enregistrer l'etape que si l'alarme n'a pas ete arretee entre-temps
+    let still_ringing = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        match ringing.as_mut() {
+            Some(r) if r.alarm.id == alarm.id && r.started_at == started_at => {
+                r.escalation_stage = Some(next);
+                true
+            }
+            _ => false,
+        }
+    };
+
+    // Arretee pendant l'application du palier: couper ce qui vient d'etre lance
+    if !still_ringing {
+        let _ = audio::stop_alarm_sound();
+        if !matches!(target, FadeTarget::Local) {
+            let _ = target.pause().await;
+        }
+    }
+    Ok(())
+}
+
+/// Remonte le volume du systeme au debut d'une sonnerie (si active dans la config)
+/// et restaure le volume d'origine une fois la sonnerie terminee
+async fn sync_system_volume(state: &AppState) -> Result<(), String> {
+    // Une seule tentative par sonnerie (pas de nouvel essai chaque seconde si le mixeur echoue)
+    let (ringing, first_check) = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        match ringing.as_mut() {
+            Some(r) => (true, !std::mem::replace(&mut r.system_volume_checked, true)),
+            None => (false, false),
+        }
+    };
+    let saved = *state.saved_system_volume.lock().map_err(|e| e.to_string())?;
+
+    match (ringing, saved) {
+        (true, None) if first_check => {
+            let (enabled, min_level) = {
+                let config = state.config.lock().map_err(|e| e.to_string())?;
+                (config.raise_system_volume, config.system_volume_min)
+            };
+            if !enabled {
+                return Ok(());
+            }
+            let current = tauri::async_runtime::spawn_blocking(system_volume::get_master_volume)
+                .await
+                .map_err(|e| e.to_string())??;
+            // Memoriser avant de modifier: la restauration aura lieu meme en cas d'echec
+            *state.saved_system_volume.lock().map_err(|e| e.to_string())? = Some(current);
+
+            let raised = current.raised(min_level);
+            if raised != current {
+                tracing::info!(from = current.level, to = raised.level, muted = current.muted, "Volume systeme remonte");
+                tauri::async_runtime::spawn_blocking(move || system_volume::set_master_volume(raised))
+                    .await
+                    .map_err(|e| e.to_string())??;
+            }
+        }
+        (false, Some(previous)) => {
+            *state.saved_system_volume.lock().map_err(|e| e.to_string())? = None;
+            tracing::info!(level = previous.level, muted = previous.muted, "Volume systeme restaure");
+            tauri::async_runtime::spawn_blocking(move || system_volume::set_master_volume(previous))
+                .await
+                .map_err(|e| e.to_string())??;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Releve le volume des appareils Spotify avant que la sonnerie n'impose le sien
+/// (une fois par sonnerie), pour le restaurer a son arret
+pub(crate) async fn remember_spotify_volume(state: &AppState, client: &SpotifyClient, alarm: &AlarmEntry) {
+    let Some(started_at) = ringing_since(state, &alarm.id) else {
+        return;
+    };
+    let known = state.saved_spotify_volume.lock()
+        .map(|saved| saved.as_ref().is_some_and(|s| s.started_at == started_at))
+        .unwrap_or(true);
+    if known {
+        return;
+    }
+    match client.get_devices().await {
+        Ok(devices) => {
+            if let Ok(mut saved) = state.saved_spotify_volume.lock() {
+                *saved = Some(spotify::SavedDeviceVolumes { started_at, account: alarm.account.clone(), devices });
+            }
+        }
+        Err(e) => tracing::warn!(alarm_id = %alarm.id, "Volume Spotify non releve: {}", e),
+    }
+}
+
+/// Rend a l'appareil Spotify de l'alarme son volume d'avant la sonnerie, une fois celle-ci arretee
+async fn restore_spotify_volume(state: &AppState) -> Result<(), String> {
+    if state.ringing.lock().map_err(|e| e.to_string())?.is_some() {
+        return Ok(());
+    }
+    let Some(saved) = state.saved_spotify_volume.lock().map_err(|e| e.to_string())?.take() else {
+        return Ok(());
+    };
+    let Some(client) = spotify_client_for(state, saved.account.as_deref())? else {
+        return Ok(());
+    };
+    let devices = client.get_devices().await.map_err(|e| e.to_string())?;
+    if let Some((device_id, volume)) = saved.to_restore(&devices) {
+        tracing::info!(device = %device_id, volume, "Volume Spotify restaure");
+        client.set_device_volume(&device_id, volume).await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Garde le reveil materiel cale sur la prochaine alarme: programme a l'avance,
+/// il sort de veille ou d'hibernation une machine endormie avant que l'alarme ne sonne
+async fn sync_hardware_wake(state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let enabled = state.config.lock().map_err(|e| e.to_string())?.wake_from_sleep;
+    let wanted = if enabled {
+        next_alarm(state, now)?
+            .map(|next| power::wake_time(next.trigger_at))
+            .filter(|at| *at > now)
+    } else {
+        None
+    };
+    // Memoriser avant de programmer: une seule tentative par reveil (droits manquants, outil absent)
+    let programmed = {
+        let mut programmed = state.hardware_wake.lock().map_err(|e| e.to_string())?;
+        if *programmed == wanted {
+            return Ok(());
+        }
+        std::mem::replace(&mut *programmed, wanted)
+    };
+
+    match (wanted, programmed) {
+        (Some(at), previous) => {
+            tracing::info!(wake_at = %at, "Reveil materiel programme");
+            tauri::async_runtime::spawn_blocking(move || power::schedule_wake(at, previous))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+        // Reveil deja passe: rien a annuler
+        (None, Some(previous)) if previous > now => {
+            tracing::info!(wake_at = %previous, "Reveil materiel annule");
+            tauri::async_runtime::spawn_blocking(move || power::cancel_wake(previous))
+                .await
+                .map_err(|e| e.to_string())?
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Assombrit l'ecran pendant les heures de nuit et restaure sa luminosite a la fin
+/// de la nuit ou des qu'une alarme sonne (l'ecran ne se rassombrit pas apres le reveil)
+async fn sync_night_mode(state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let night = {
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        config.night_mode.clone().filter(|n| n.enabled && n.is_night(now.time()))
+    };
+    let ringing = state.ringing.lock().map_err(|e| e.to_string())?.is_some();
+    let woken = {
+        let mut woken = state.night_woken.lock().map_err(|e| e.to_string())?;
+        if night.is_none() {
+            *woken = false;
+        } else if ringing {
+            *woken = true;
+        }
+        *woken
+    };
+    let saved = *state.night_display.lock().map_err(|e| e.to_string())?;
+
+    match (night.filter(|_| !woken), saved) {
+        (Some(settings), None) => {
+            // Une luminosite illisible est restauree au maximum
+            let current = tauri::async_runtime::spawn_blocking(nightmode::get_brightness)
+                .await
+                .map_err(|e| e.to_string())?
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Luminosite de l'ecran: {}", e);
+                    100
+                });
+            // Memoriser avant de modifier: une seule tentative par nuit, restauration assuree
+            *state.night_display.lock().map_err(|e| e.to_string())? = Some(current);
+            tracing::info!(from = current, to = settings.brightness, kelvin = settings.color_temperature, "Ecran assombri");
+            tauri::async_runtime::spawn_blocking(move || nightmode::set_display(settings.brightness, settings.color_temperature))
+                .await
+                .map_err(|e| e.to_string())??;
+        }
+        (None, Some(previous)) => {
+            *state.night_display.lock().map_err(|e| e.to_string())? = None;
+            tracing::info!(brightness = previous, "Luminosite de l'ecran restauree");
+            tauri::async_runtime::spawn_blocking(move || nightmode::set_display(previous, nightmode::NEUTRAL_TEMPERATURE))
+                .await
+                .map_err(|e| e.to_string())??;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Allume les lampes Hue d'une alarme des que sa prochaine occurrence entre dans
+/// la fenetre de fondu (une seule fois par occurrence)
+fn start_sunrise_lights(state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    if config.hue_bridge.is_none() {
+        return Ok(());
+    }
+
+    let events = calendar_events(state)?;
+    let due: Vec<_> = {
+        let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+        alarms.iter()
+            .filter_map(|alarm| {
+                let lights = alarm.lights.clone()?;
+                let at = calendar::next_trigger(alarm, &events, now, &config)?;
+                // Duree de fondu du profil du jour (week-end ou semaine)
+                let mut alarm = alarm.clone();
+                alarm::apply_day_profile(&mut alarm, alarm::wall_clock(&alarm, at).date());
+                let lead = chrono::Duration::seconds(i64::from(alarm.fade_in_duration));
+                (now >= at - lead).then(|| (alarm.id.clone(), at, lights))
+            })
+            .collect()
+    };
+
+    for (alarm_id, at, settings) in due {
+        {
+            let mut started = state.sunrise_lights.lock().map_err(|e| e.to_string())?;
+            if started.get(&alarm_id) == Some(&at) {
+                continue;
+            }
+            started.insert(alarm_id.clone(), at);
+        }
+        let (address, username) = hue_credentials(state)?;
+        let duration = at.signed_duration_since(now).to_std().unwrap_or_default();
+
+        tracing::info!(alarm_id = %alarm_id, seconds = duration.as_secs(), "Lever de soleil lumineux");
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = lights::start_sunrise(&address, &username, &settings, duration).await {
+                tracing::warn!(alarm_id = %alarm_id, "Lever de soleil lumineux: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Reveille l'appareil Spotify Connect des alarmes Spotify peu avant leur prochaine
+/// occurrence, pour qu'il ne soit pas en veille quand la lecture est lancee
+/// (une seule fois par occurrence)
+fn keep_spotify_devices_alive(state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    if config.spotify_keep_alive_minutes == 0 {
+        return Ok(());
+    }
+    let lead = chrono::Duration::minutes(i64::from(config.spotify_keep_alive_minutes));
+
+    let events = calendar_events(state)?;
+    let due: Vec<_> = {
+        let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+        alarms.iter()
+            .filter_map(|alarm| {
+                let at = calendar::next_trigger(alarm, &events, now, &config)?;
+                // Playlist du profil du jour (elle peut changer de service le week-end)
+                let mut alarm = alarm.clone();
+                alarm::apply_day_profile(&mut alarm, alarm::wall_clock(&alarm, at).date());
+                (SpotifyClient::handles(&alarm.playlist_uri) && now >= at - lead).then_some((alarm, at))
+            })
+            .collect()
+    };
+
+    for (alarm, at) in due {
+        {
+            let mut kept_alive = state.spotify_kept_alive.lock().map_err(|e| e.to_string())?;
+            if kept_alive.get(&alarm.id) == Some(&at) {
+                continue;
+            }
+            kept_alive.insert(alarm.id.clone(), at);
+        }
+        let Some(client) = spotify_client_for(state, alarm.account.as_deref())? else {
+            continue;
+        };
+
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = client.keep_device_alive().await {
+                tracing::warn!(alarm_id = %alarm.id, "Appareil Spotify non reveille: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Fait entendre la pre-alarme des alarmes dont la prochaine occurrence approche:
+/// carillon local discret ou playlist Spotify a tres faible volume
+/// (une seule fois par occurrence, jamais pendant une sonnerie ou une lecture locale)
+fn play_pre_alarms(state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let events = calendar_events(state)?;
+    let due: Vec<_> = {
+        let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+        alarms.iter()
+            .filter_map(|alarm| {
+                let pre_alarm = alarm.pre_alarm?;
+                let at = calendar::next_trigger(alarm, &events, now, &config)?;
+                let lead = chrono::Duration::minutes(i64::from(pre_alarm.minutes));
+                (now >= at - lead).then(|| (alarm.clone(), at, pre_alarm))
+            })
+            .collect()
+    };
+
+    for (alarm, at, pre_alarm) in due {
+        {
+            let mut played = state.pre_alarms.lock().map_err(|e| e.to_string())?;
+            if played.get(&alarm.id) == Some(&at) {
+                continue;
+            }
+            played.insert(alarm.id.clone(), at);
+        }
+        if state.ringing.lock().map_err(|e| e.to_string())?.is_some() || audio::is_playing() {
+            tracing::info!(alarm_id = %alarm.id, "Pre-alarme ignoree: lecture deja en cours");
+            continue;
+        }
+
+        tracing::info!(alarm_id = %alarm.id, minutes = pre_alarm.minutes, sound = ?pre_alarm.sound, "Pre-alarme");
+        match pre_alarm.sound {
+            PreAlarmSound::Chime => {
+                if let Err(e) = audio::play_soft_chime(pre_alarm.volume) {
+                    tracing::warn!(alarm_id = %alarm.id, "Carillon de pre-alarme: {}", e);
+                }
+            }
+            PreAlarmSound::Spotify => {
+                let Some(client) = spotify_client_for(state, alarm.account.as_deref())? else {
+                    continue;
+                };
+                tauri::async_runtime::spawn(async move {
+                    // Volume coupe avant le lancement, puis remonte au niveau de la pre-alarme
+                    let _ = client.set_volume(0).await;
+                    let started = match client.play_playlist(&alarm.playlist_uri).await {
+                        Ok(()) => client.set_volume(pre_alarm.volume).await,
+                        Err(e) => Err(e),
+                    };
+                    if let Err(e) = started {
+                        tracing::warn!(alarm_id = %alarm.id, "Pre-alarme Spotify: {}", e);
+                    }
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Verifie reseau, compte et appareil des alarmes dont la prochaine occurrence approche,
+/// journalise le resultat et previent si la playlist risque de ne pas jouer
+/// (une seule fois par occurrence)
+fn check_readiness(app_handle: &AppHandle, state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    if config.readiness_check_minutes == 0 {
+        return Ok(());
+    }
+    let lead = chrono::Duration::minutes(i64::from(config.readiness_check_minutes));
+
+    let events = calendar_events(state)?;
+    let due: Vec<_> = {
+        let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+        alarms.iter()
+            .filter_map(|alarm| {
+                let at = calendar::next_trigger(alarm, &events, now, &config)?;
+                let mut alarm = alarm.clone();
+                alarm::apply_day_profile(&mut alarm, alarm::wall_clock(&alarm, at).date());
+                (now >= at - lead).then_some((alarm, at))
+            })
+            .collect()
+    };
+
+    for (alarm, at) in due {
+        {
+            let mut checked = state.readiness_checked.lock().map_err(|e| e.to_string())?;
+            if checked.get(&alarm.id) == Some(&at) {
+                continue;
+            }
+            checked.insert(alarm.id.clone(), at);
+        }
+        let context = readiness_context(state, &alarm, &config)?;
+        let app_data_dir = data_dir(app_handle)?;
+        let (locale, time_format) = (config.locale, config.time_format);
+
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let report = ReadinessReport {
+                issues: readiness::check(&alarm, &context).await,
+                alarm_id: alarm.id.clone(),
+                alarm_at: at,
+                playlist_name: alarm.playlist_name.clone(),
+                checked_at: chrono::Local::now(),
+            };
+            if report.is_ready() {
+                tracing::info!(alarm_id = %alarm.id, "Verification avant sonnerie: pret");
+            } else {
+                tracing::warn!(alarm_id = %alarm.id, issues = ?report.issues, "Verification avant sonnerie: probleme");
+                let (title, body) = readiness::warning_text(locale, time_format, &report);
+                if let Err(e) = notify(&app_handle, title, body) {
+                    tracing::warn!("Notification: {}", e);
+                }
+            }
+            if let Err(e) = history::record_readiness(&app_data_dir, report) {
+                tracing::warn!("Historique des verifications: {}", e);
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Notifie l'heure de coucher conseillee pour la prochaine alarme
+/// (une seule fois par occurrence, si le rappel est active)
+fn remind_bedtime(app_handle: &AppHandle, state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let (enabled, locale, time_format) = {
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        (config.bedtime.reminder, config.locale, config.time_format)
+    };
+    if !enabled {
+        return Ok(());
+    }
+    let Some(suggested) = suggested_bedtime(state, now)?.filter(|s| s.is_reminder_due(now)) else {
+        return Ok(());
+    };
+    {
+        let mut reminded = state.bedtime_reminded.lock().map_err(|e| e.to_string())?;
+        if *reminded == Some(suggested.alarm_at) {
+            return Ok(());
+        }
+        *reminded = Some(suggested.alarm_at);
+    }
+
+    tracing::info!(alarm_id = %suggested.alarm_id, bedtime = %suggested.bedtime, "Rappel de coucher");
+    let (title, body) = bedtime::reminder_text(locale, time_format, &suggested);
+    notify(app_handle, title, body)
+}
+
+/// Demarre la routine du soir a son heure: notification de coucher, puis playlist
+/// calme a faible volume descendue jusqu'au silence (minuteur de sommeil)
+fn start_wind_down(app_handle: &AppHandle, state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let (routine, locale, time_format) = {
+        let config = state.config.lock().map_err(|e| e.to_string())?;
+        (config.wind_down.clone(), config.locale, config.time_format)
+    };
+    let Some(routine) = routine else {
+        return Ok(());
+    };
+    {
+        let mut started = state.wind_down_started.lock().map_err(|e| e.to_string())?;
+        if !routine.is_due(now, *started) {
+            return Ok(());
+        }
+        *started = Some(now.date_naive());
+    }
+    // Une alarme qui sonne garde la main sur la lecture
+    if state.ringing.lock().map_err(|e| e.to_string())?.is_some() {
+        return Ok(());
+    }
+
+    tracing::info!(playlist = %routine.playlist_uri, minutes = routine.duration_minutes, "Routine du soir");
+    let bedtime = suggested_bedtime(state, now)?;
+    let (title, body) = winddown::notification_text(locale, time_format, &routine, bedtime.as_ref());
+    notify(app_handle, title, body)?;
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let started = start_sleep_fade(
+            &app_handle,
+            &state,
+            routine.duration_minutes,
+            routine.playlist_uri.clone(),
+            routine.account.as_deref(),
+            Some(routine.start_volume),
+            routine.fade_curve,
+        )
+        .await;
+        if let Err(e) = started {
+            tracing::warn!("Routine du soir: lecture impossible ({})", e);
+        }
+    });
+    Ok(())
+}
+
+/// Termine les minuteurs arrives a zero: carillon (sauf si une alarme sonne deja),
+/// notification systeme et evenement pour le frontend
+fn finish_timers(app_handle: &AppHandle, state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let finished = timer::take_finished(&mut *state.timers.lock().map_err(|e| e.to_string())?, now);
+    if finished.is_empty() {
+        return Ok(());
+    }
+
+    let locale = state.config.lock().map_err(|e| e.to_string())?.locale;
+    let ringing = state.ringing.lock().map_err(|e| e.to_string())?.is_some();
+    if !ringing {
+        if let Err(e) = audio::play_timer_chime() {
+            tracing::warn!("Carillon du minuteur: {}", e);
+        }
+    }
+    for done in finished {
+        tracing::info!(timer_id = %done.id, label = %done.label, "Minuteur termine");
+        let (title, body) = timer::finished_text(locale, &done);
+        if let Err(e) = notify(app_handle, title, body) {
+            tracing::warn!("Notification du minuteur: {}", e);
+        }
+        if let Err(e) = app_handle.emit(TIMER_FINISHED_EVENT, &done) {
+            tracing::warn!("Evenement du minuteur non emis: {}", e);
+        }
+    }
+    Ok(())
+}
+
+/// Affiche une notification systeme
+fn notify(app_handle: &AppHandle, title: String, body: String) -> Result<(), String> {
+    app_handle.notification()
+        .builder()
+        .title(title)
+        .body(body)
+        .show()
+        .map_err(|e| e.to_string())
+}
+
+/// Lance en silence la playlist des alarmes dont la fenetre de reveil s'ouvre, puis
+/// retient le dernier debut de titre avant l'heure de l'alarme. Sans Spotify (ou si la
+/// lecture echoue), le plan reste cale sur l'heure de l'alarme.
+fn plan_wake_windows(app_handle: &AppHandle, state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let config = state.config.lock().map_err(|e| e.to_string())?.clone();
+    let events = calendar_events(state)?;
+    let due: Vec<_> = {
+        let alarms = state.alarms.lock().map_err(|e| e.to_string())?;
+        alarms.iter()
+            .filter_map(|alarm| {
+                let window = alarm.wake_window?;
+                let at = calendar::next_trigger(alarm, &events, now, &config)?;
+                (now >= at - window.duration()).then(|| {
+                    // Playlist du profil du jour lancee en silence
+                    let mut alarm = alarm.clone();
+                    alarm::apply_day_profile(&mut alarm, alarm::wall_clock(&alarm, at).date());
+                    (alarm, at)
+                })
+            })
+            .collect()
+    };
+
+    for (alarm, at) in due {
+        {
+            let mut plans = state.wake_plans.lock().map_err(|e| e.to_string())?;
+            if plans.get(&alarm.id).is_some_and(|p| p.alarm_at == at) {
+                continue;
+            }
+            plans.insert(alarm.id.clone(), WakePlan::at_alarm_time(at));
+        }
+        // Hors ligne: pas de pre-lecture, l'alarme sonnera a son heure (son local)
+        if !net::is_online(state)? {
+            continue;
+        }
+        let Some(client) = spotify_client_for(state, alarm.account.as_deref())? else {
+            continue;
+        };
+
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            let ring_at = match start_preroll(&client, &alarm, at).await {
+                Ok(ring_at) => ring_at,
+                Err(e) => {
+                    tracing::warn!(alarm_id = %alarm.id, "Fenetre de reveil: {}, sonnerie a l'heure de l'alarme", e);
+                    return;
+                }
+            };
+            tracing::info!(alarm_id = %alarm.id, ring_at = %ring_at, "Fenetre de reveil: debut de titre retenu");
+
+            let state = app_handle.state::<AppState>();
+            let Ok(mut plans) = state.wake_plans.lock() else {
+                return;
+            };
+            if let Some(plan) = plans.get_mut(&alarm.id).filter(|p| p.alarm_at == at && !p.rung) {
+                plan.ring_at = ring_at;
+                plan.preroll = true;
+            }
+        });
+    }
+    Ok(())
+}
+
+/// Lance la playlist dans l'ordre, volume a zero, et calcule l'heure du debut de
+/// titre ou l'alarme doit sonner
+async fn start_preroll(
+    client: &SpotifyClient,
+    alarm: &AlarmEntry,
+    at: chrono::DateTime<chrono::Local>,
+) -> Result<chrono::DateTime<chrono::Local>, String> {
+    let durations = client
+        .playlist_track_durations(&alarm.playlist_uri, wake_window::TRACK_LOOKAHEAD)
+        .await
+        .map_err(|e| e.to_string())?;
+    // Couper avant le lancement si un appareil est deja actif, puis une fois la lecture transferee
+    let _ = client.set_volume(0).await;
+    client.play_playlist_in_order(&alarm.playlist_uri).await.map_err(|e| e.to_string())?;
+    if let Err(e) = client.set_volume(0).await {
+        let _ = client.pause().await;
+        return Err(e.to_string());
+    }
+
+    let started = chrono::Local::now();
+    let window = at.signed_duration_since(started).to_std().unwrap_or_default();
+    let offset = wake_window::ring_offset(&durations, window);
+    Ok(started + chrono::Duration::from_std(offset).map_err(|e| e.to_string())?)
+}
+
+/// Fait entendre une alarme a fenetre de reveil qui vient de sonner: remonte le volume
+/// de la playlist deja lancee, ou la lance maintenant (son local en dernier recours)
+fn ring_wake_window(app_handle: &AppHandle, state: &AppState, alarm: &AlarmEntry) -> Result<(), String> {
+    let preroll = state.wake_plans.lock().map_err(|e| e.to_string())?
+        .get(&alarm.id)
+        .is_some_and(|p| p.preroll && p.rung);
+    let target = playback_target(app_handle, state, alarm)?;
+    let fade_in = prepare_fade_in(state)?;
+
+    let app_handle = app_handle.clone();
+    let alarm = alarm.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        if !preroll {
+            if let Err(e) = start_playlist(&app_handle, &state, &alarm).await {
+                tracing::warn!(alarm_id = %alarm.id, "Fenetre de reveil: playlist impossible ({}), son local", e);
+                let _ = audio::set_alarm_volume(alarm.volume);
+                let _ = audio::play_alarm_sound();
+            }
+            return;
+        }
+
+        // Le titre d'ouverture remplace le titre retenu, deja a son debut
+        if let (Some(client), Some(track_uri)) = (target.spotify(), alarm.first_track_uri.as_deref()) {
+            if let Err(e) = client.play_track_next(track_uri).await {
+                tracing::warn!(alarm_id = %alarm.id, "Titre d'ouverture impossible: {}", e);
+            }
+        }
+        events::emit(&app_handle, AlarmEvent::NowPlaying {
+            alarm_id: Some(alarm.id.clone()),
+            playlist_uri: now_playing_uri(&target, &alarm),
+        });
+        match fade_in {
+            Some(AlarmFadeIn { volume, duration, curve, handle }) => {
+                let _ = fade::run_fade(&target, 0, volume, duration, curve, &handle).await;
+            }
+            None => {
+                if let Err(e) = target.set_volume(alarm.volume).await {
+                    tracing::warn!(alarm_id = %alarm.id, "Fenetre de reveil: volume non remonte ({})", e);
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Lance la lecture d'une alarme hors Spotify (Apple Music, YouTube Music, bibliotheque
+/// locale, radio, podcast) ou en lecture groupee qui vient de sonner, avec son fondu d'entree (son local si la lecture echoue)
+fn ring_provider(app_handle: &AppHandle, state: &AppState, alarm: &AlarmEntry) -> Result<(), String> {
+    let target = playback_target(app_handle, state, alarm)?;
+    let fade_in = prepare_fade_in(state)?;
+
+    let app_handle = app_handle.clone();
+    let alarm = alarm.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Some(client) = target.spotify() {
+            remember_spotify_volume(&app_handle.state::<AppState>(), client, &alarm).await;
+        }
+        if fade_in.is_some() {
+            let _ = target.set_volume(0).await;
+        }
+        let played = match (&target, fade_in.as_ref()) {
+            // Le son integre doit durer tout le fondu
+            (FadeTarget::Local, Some(f)) => audio::play_alarm_sound_for(f.duration + audio::ALARM_SOUND_DURATION),
+            _ => target.play(&alarm.playlist_uri, alarm.first_track_uri.as_deref()).await,
+        };
+        if let Err(e) = played {
+            tracing::warn!(alarm_id = %alarm.id, "Lecture impossible ({}), son local", e);
+            let provider = ProviderKind::of(&alarm.playlist_uri);
+            record_telemetry(&app_handle, &app_handle.state::<AppState>(), TelemetryKind::ProviderError, Some(provider), None);
+            let _ = audio::set_alarm_volume(alarm.volume);
+            let _ = audio::play_alarm_sound();
+            return;
+        }
+        events::emit(&app_handle, AlarmEvent::NowPlaying {
+            alarm_id: Some(alarm.id.clone()),
+            playlist_uri: now_playing_uri(&target, &alarm),
+        });
+        // Commande acceptee ne veut pas dire musique audible
+        if let Some(client) = target.spotify() {
+            verify_spotify_playback(&app_handle, client.clone(), alarm.clone(), fade_in.is_none());
+        }
+        match fade_in {
+            Some(AlarmFadeIn { volume, duration, curve, handle }) => {
+                let _ = fade::run_fade(&target, 0, volume, duration, curve, &handle).await;
+            }
+            None => {
+                if let Err(e) = target.set_volume(alarm.volume).await {
+                    tracing::warn!(alarm_id = %alarm.id, "Volume de lecture non regle ({})", e);
+                }
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Lance la lecture d'une alarme qui vient de sonner, quel que soit le service: le frontend
+/// ne fait qu'afficher la sonnerie. Appele par le planificateur, `check_alarms` et `test_alarm`.
+pub(crate) fn ring_triggered(app_handle: &AppHandle, state: &AppState, alarm: &AlarmEntry) -> Result<(), String> {
+    if alarm.wake_window.is_some() {
+        return ring_wake_window(app_handle, state, alarm);
+    }
+    // Escalade et lever de soleil lancent eux-memes la playlist
+    if alarm.escalation.is_none() && !alarm.sunrise {
+        return ring_provider(app_handle, state, alarm);
+    }
+    Ok(())
+}
+
+/// Demarre le chorus de l'aube d'une alarme qui vient de sonner: fondu local
+/// sur la duree de fondu, puis lancement de la playlist (sauf escalade, qui prend le relais)
+fn start_soundscape(app_handle: &AppHandle, state: &AppState) -> Result<(), String> {
+    let (alarm, started_at, duration, handle) = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        let Some(r) = ringing.as_mut() else {
+            return Ok(());
+        };
+        if !r.alarm.sunrise || r.soundscape.is_some() {
+            return Ok(());
+        }
+        let handle = FadeHandle::new();
+        r.soundscape = Some(handle.clone());
+        (r.alarm.clone(), r.started_at, r.sunrise_duration(), handle)
+    };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        tracing::info!(alarm_id = %alarm.id, seconds = duration.as_secs(), "Lever de soleil sonore");
+        let faded = match audio::play_dawn_chorus(duration) {
+            Ok(()) => fade::run_fade(&FadeTarget::Local, 0, alarm.volume, duration, alarm.fade_curve, &handle).await,
+            Err(e) => Err(e),
+        };
+        match faded {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(e) => tracing::warn!(alarm_id = %alarm.id, "Lever de soleil interrompu: {}", e),
+        }
+
+        let state = app_handle.state::<AppState>();
+        let still_ringing = match state.ringing.lock() {
+            Ok(ringing) => ringing.as_ref().is_some_and(|r| r.alarm.id == alarm.id && r.started_at == started_at),
+            Err(_) => false,
+        };
+        if handle.is_cancelled() || !still_ringing {
+            let _ = audio::stop_alarm_sound();
+            return;
+        }
+        if alarm.escalation.is_some() {
+            return;
+        }
+
+        if let Err(e) = start_playlist(&app_handle, &state, &alarm).await {
+            tracing::warn!(alarm_id = %alarm.id, "Playlist impossible apres le lever de soleil ({}), son local", e);
+            record_telemetry(&app_handle, &state, TelemetryKind::ProviderError, Some(ProviderKind::of(&alarm.playlist_uri)), None);
+            let _ = audio::set_alarm_volume(alarm.volume);
+            let _ = audio::play_alarm_sound();
+        }
+    });
+    Ok(())
+}
+
+/// Lance la playlist de l'alarme a son volume
+async fn start_playlist(app_handle: &AppHandle, state: &AppState, alarm: &AlarmEntry) -> Result<(), String> {
+    let target = playback_target(app_handle, state, alarm)?;
+    if let Some(client) = target.spotify() {
+        remember_spotify_volume(state, client, alarm).await;
+    }
+    target.play(&alarm.playlist_uri, alarm.first_track_uri.as_deref()).await?;
+    target.set_volume(alarm.volume).await?;
+    if let FadeTarget::Spotify(client) = &target {
+        verify_spotify_playback(app_handle, client.as_ref().clone(), alarm.clone(), true);
+    }
+    events::emit(app_handle, AlarmEvent::NowPlaying {
+        alarm_id: Some(alarm.id.clone()),
+        playlist_uri: now_playing_uri(&target, alarm),
+    });
+    Ok(())
+}
+
+/// Verifie en arriere-plan que la playlist Spotify de l'alarme qui sonne joue vraiment:
+/// sans musique audible, la lecture est relancee une fois, puis le son local prend le relais
+pub(crate) fn verify_spotify_playback(app_handle: &AppHandle, client: SpotifyClient, alarm: AlarmEntry, require_volume: bool) {
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let Some(started_at) = ringing_since(&state, &alarm.id) else {
+            return;
+        };
+        for retry in [true, false] {
+            match client.verify_playback(require_volume).await {
+                Ok(true) => return,
+                Ok(false) => tracing::warn!(alarm_id = %alarm.id, "Spotify ne joue pas malgre la commande de lecture"),
+                Err(e) => tracing::warn!(alarm_id = %alarm.id, "Etat du lecteur Spotify illisible: {}", e),
+            }
+            if ringing_since(&state, &alarm.id) != Some(started_at) {
+                return;
+            }
+            if retry {
+                tracing::info!(alarm_id = %alarm.id, "Nouvelle tentative de lecture Spotify");
+                if let Err(e) = client.play_alarm_playlist(&alarm.playlist_uri, alarm.first_track_uri.as_deref()).await {
+                    tracing::warn!(alarm_id = %alarm.id, "Nouvelle tentative refusee: {}", e);
+                    continue;
+                }
+                // Sans fondu d'entree, le volume de l'alarme est reapplique
+                if require_volume {
+                    let _ = client.set_volume(alarm.volume).await;
+                }
+            }
+        }
+        if let Err(e) = fall_back_to_local(&app_handle, &state, &client, &alarm, started_at).await {
+            tracing::error!(alarm_id = %alarm.id, "Son local de secours: {}", e);
+            return;
+        }
+        if let Err(e) = await_late_spotify(&app_handle, &state, &client, &alarm, started_at).await {
+            tracing::warn!(alarm_id = %alarm.id, "Reprise de Spotify: {}", e);
+        }
+    });
+}
+
+/// Debut de la sonnerie en cours si c'est cette alarme
+fn ringing_since(state: &AppState, alarm_id: &str) -> Option<chrono::DateTime<chrono::Local>> {
+    state.ringing.lock().ok()?
+        .as_ref()
+        .filter(|r| r.alarm.id == alarm_id)
+        .map(|r| r.started_at)
+}
+
+/// Remplace une lecture Spotify muette par le son local
+async fn fall_back_to_local(
+    app_handle: &AppHandle,
+    state: &AppState,
+    client: &SpotifyClient,
+    alarm: &AlarmEntry,
+    started_at: chrono::DateTime<chrono::Local>,
+) -> Result<(), String> {
+    {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        let Some(r) = ringing.as_mut().filter(|r| r.alarm.id == alarm.id && r.started_at == started_at) else {
+            return Ok(());
+        };
+        if let Some(fade_in) = r.fade_in.take() {
+            fade_in.cancel();
+        }
+        r.fallback = Some(alarm::FallbackReason::PlaybackFailed);
+    }
+    tracing::warn!(alarm_id = %alarm.id, "Lecture Spotify non confirmee: son local");
+    // Spotify reste muet plutot qu'en pause: s'il demarre en retard, le son local lui cede la place
+    let _ = client.set_volume(0).await;
+    audio::set_alarm_volume(alarm.volume)?;
+    audio::play_alarm_sound()?;
+    record_telemetry(
+        app_handle,
+        state,
+        TelemetryKind::FallbackUsed,
+        Some(ProviderKind::Spotify),
+        Some(alarm::FallbackReason::PlaybackFailed),
+    );
+    events::emit(app_handle, AlarmEvent::NowPlaying {
+        alarm_id: Some(alarm.id.clone()),
+        playlist_uri: "local".to_string(),
+    });
+    Ok(())
+}
+
+/// Surveille une lecture Spotify remplacee par le son local: si elle finit par demarrer,
+/// le son local s'efface par un fondu enchaine au lieu d'etre coupe net.
+/// Au-dela de `LATE_PLAYBACK_WINDOW`, Spotify est mis en pause et le son local garde la main.
+async fn await_late_spotify(
+    app_handle: &AppHandle,
+    state: &AppState,
+    client: &SpotifyClient,
+    alarm: &AlarmEntry,
+    started_at: chrono::DateTime<chrono::Local>,
+) -> Result<(), String> {
+    let deadline = tokio::time::Instant::now() + LATE_PLAYBACK_WINDOW;
+    loop {
+        tokio::time::sleep(LATE_PLAYBACK_POLL_INTERVAL).await;
+        let still_fallback = state.ringing.lock().map_err(|e| e.to_string())?
+            .as_ref()
+            .is_some_and(|r| {
+                r.alarm.id == alarm.id
+                    && r.started_at == started_at
+                    && r.fallback == Some(alarm::FallbackReason::PlaybackFailed)
+                    && r.escalation_stage != Some(EscalationStage::Siren)
+            });
+        if !still_fallback {
+            return Ok(());
+        }
+        // Volume nul attendu: Spotify a ete rendu muet au passage au son local
+        let playing = match client.playback_status().await {
+            Ok(status) => status.is_some_and(|s| s.is_audible(false)),
+            Err(e) => {
+                tracing::debug!(alarm_id = %alarm.id, "Etat du lecteur Spotify illisible: {}", e);
+                false
+            }
+        };
+        if playing {
+            break;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            tracing::info!(alarm_id = %alarm.id, "Spotify n'a pas demarre: le son local continue");
+            return client.pause().await.map_err(|e| e.to_string());
+        }
+    }
+
+    let handle = FadeHandle::new();
+    {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        let Some(r) = ringing.as_mut().filter(|r| r.alarm.id == alarm.id && r.started_at == started_at) else {
+            return Ok(());
+        };
+        // Enregistre comme fondu d'entree: l'arret de l'alarme l'interrompt
+        if let Some(previous) = r.fade_in.replace(handle.clone()) {
+            previous.cancel();
+        }
+    }
+    tracing::info!(alarm_id = %alarm.id, "Spotify a demarre en retard: fondu enchaine depuis le son local");
+    let spotify = FadeTarget::Spotify(Box::new(client.clone()));
+    let done = fade::run_crossfade(&FadeTarget::Local, &spotify, alarm.volume, fade::CROSSFADE_DURATION, alarm.fade_curve, &handle).await?;
+    if !done {
+        return Ok(());
+    }
+
+    if let Some(r) = state.ringing.lock().map_err(|e| e.to_string())?
+        .as_mut()
+        .filter(|r| r.alarm.id == alarm.id && r.started_at == started_at)
+    {
+        r.fallback = None;
+    }
+    events::emit(app_handle, AlarmEvent::NowPlaying {
+        alarm_id: Some(alarm.id.clone()),
+        playlist_uri: alarm.playlist_uri.clone(),
+    });
+    Ok(())
+}
+
+/// Ce qui joue reellement ("local" si la playlist est remplacee par le son local)
+fn now_playing_uri(target: &FadeTarget, alarm: &AlarmEntry) -> String {
+    match target {
+        FadeTarget::Local => "local".to_string(),
+        _ => alarm.playlist_uri.clone(),
+    }
+}
+
+/// Suit les titres joues par l'alarme Spotify qui sonne et ajoute sa file d'apres reveil
+/// pendant le dernier titre d'ouverture, pour qu'elle passe juste apres lui
+fn start_after_wake_queue(app_handle: &AppHandle, state: &AppState) -> Result<(), String> {
+    let (alarm, started_at, queue) = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        let Some(r) = ringing.as_mut() else {
+            return Ok(());
+        };
+        let Some(queue) = r.alarm.after_wake_queue.clone() else {
+            return Ok(());
+        };
+        if r.after_wake_queue_started {
+            return Ok(());
+        }
+        r.after_wake_queue_started = true;
+        (r.alarm.clone(), r.started_at, queue)
+    };
+    let Some(client) = playback_target(app_handle, state, &alarm)?.spotify().cloned() else {
+        return Ok(());
+    };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut current: Option<String> = None;
+        let mut played = 0;
+        loop {
+            // Arreter le suivi si la sonnerie est terminee ou passee a la sirene
+            let still_playing = {
+                let state = app_handle.state::<AppState>();
+                let Ok(ringing) = state.ringing.lock() else {
+                    return;
+                };
+                ringing.as_ref().is_some_and(|r| {
+                    r.alarm.id == alarm.id
+                        && r.started_at == started_at
+                        && r.escalation_stage != Some(EscalationStage::Siren)
+                })
+            };
+            if !still_playing {
+                return;
+            }
+
+            match client.current_track_uri().await {
+                Ok(Some(uri)) if current.as_deref() != Some(uri.as_str()) => {
+                    current = Some(uri);
+                    played += 1;
+                }
+                Ok(_) => {}
+                Err(e) => tracing::debug!(alarm_id = %alarm.id, "Titre en cours inconnu: {}", e),
+            }
+            if played >= queue.after_tracks {
+                match client.queue_tracks(&queue.uris).await {
+                    Ok(()) => tracing::info!(alarm_id = %alarm.id, after = played, "File d'apres reveil ajoutee"),
+                    Err(e) => tracing::warn!(alarm_id = %alarm.id, "File d'apres reveil impossible: {}", e),
+                }
+                return;
+            }
+            tokio::time::sleep(QUEUE_POLL_INTERVAL).await;
+        }
+    });
+    Ok(())
+}
+
+/// Lance l'annonce vocale une fois le delai ecoule: la musique est baissee
+/// pendant l'annonce puis remise au volume courant de la sonnerie
+fn start_announcement(
+    app_handle: &AppHandle,
+    state: &AppState,
+    now: chrono::DateTime<chrono::Local>,
+) -> Result<(), String> {
+    let (alarm, started_at, announcement, siren) = {
+        let mut ringing = state.ringing.lock().map_err(|e| e.to_string())?;
+        let Some(r) = ringing.as_mut() else {
+            return Ok(());
+        };
+        let Some(announcement) = r.alarm.announcement.clone() else {
+            return Ok(());
+        };
+        let elapsed = now.signed_duration_since(r.started_at).num_seconds();
+        if r.announced || elapsed < i64::from(announcement.delay_seconds) {
+            return Ok(());
+        }
+        r.announced = true;
+        (r.alarm.clone(), r.started_at, announcement, r.escalation_stage == Some(EscalationStage::Siren))
+    };
+
+    let (locale, weather) = {
+        let locale = state.config.lock().map_err(|e| e.to_string())?.locale;
+        let weather = state.weather.lock().map_err(|e| e.to_string())?
+            .clone()
+            .filter(|w| announcement.include_weather && w.is_fresh(now.with_timezone(&chrono::Utc)));
+        (locale, weather)
+    };
+    let text = tts::announcement_text(locale, alarm::wall_clock(&alarm, now).time(), weather.as_ref());
+    let target = if siren { FadeTarget::Local } else { playback_target(app_handle, state, &alarm)? };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let _ = target.set_volume(announcement.duck_volume).await;
+
+        tracing::info!(alarm_id = %alarm.id, "Annonce vocale: {}", text);
+        let spoken = tauri::async_runtime::spawn_blocking(move || tts::speak(&text, locale)).await;
+        match spoken {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => tracing::warn!(alarm_id = %alarm.id, "Annonce vocale impossible: {}", e),
+            Err(e) => tracing::warn!(alarm_id = %alarm.id, "Annonce vocale interrompue: {}", e),
+        }
+
+        // Remettre le volume seulement si la meme sonnerie continue
+        let state = app_handle.state::<AppState>();
+        let restore = match state.ringing.lock() {
+            Ok(ringing) => ringing.as_ref()
+                .filter(|r| r.alarm.id == alarm.id && r.started_at == started_at)
+                .map(|r| match r.escalation_stage {
+                    Some(EscalationStage::Playlist { volume }) => volume,
+                    Some(EscalationStage::Siren) => 100,
+                    None => r.alarm.volume,
+                }),
+            Err(_) => None,
+        };
+        if let Some(volume) = restore {
+            let _ = target.set_volume(volume).await;
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,109 @@
+// shortcuts.rs - Raccourcis globaux pour reporter ou arreter l'alarme qui sonne
+// Enregistres seulement pendant une sonnerie, pour ne pas capturer Espace/Echap le reste du temps
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Wry};
+use tauri_plugin_global_shortcut::{Builder, GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::{dismiss_ringing, snooze_ringing, AppState};
+
+pub use charmed_core::shortcuts::{ShortcutAction, ShortcutSettings};
+
+/// Verifie que chaque raccourci est reconnu
+pub fn validate(settings: &ShortcutSettings) -> Result<(), String> {
+    for (_, keys) in settings.entries() {
+        keys.parse::<Shortcut>()
+            .map_err(|e| format!("Raccourci invalide '{}': {}", keys, e))?;
+    }
+    Ok(())
+}
+
+fn action_for(settings: &ShortcutSettings, shortcut: &Shortcut) -> Option<ShortcutAction> {
+    settings.entries()
+        .find(|(_, keys)| keys.parse::<Shortcut>().is_ok_and(|s| s == *shortcut))
+        .map(|(action, _)| action)
+}
+
+/// Raccourcis actuellement enregistres aupres du systeme
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+/// Plugin de raccourcis globaux relie aux commandes de report et d'arret
+pub fn plugin() -> TauriPlugin<Wry> {
+    Builder::new().with_handler(handle_shortcut).build()
+}
+
+fn handle_shortcut(app_handle: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let state = app_handle.state::<AppState>();
+    let Some(action) = state.config.lock().ok().and_then(|c| action_for(&c.shortcuts, shortcut)) else {
+        return;
+    };
+    let Some(alarm_id) = state.ringing.lock().ok().and_then(|r| r.as_ref().map(|r| r.alarm.id.clone())) else {
+        return;
+    };
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let result = match action {
+            ShortcutAction::Snooze => snooze_ringing(&app_handle, &state, &alarm_id, None).await.map(|_| ()),
+            ShortcutAction::Dismiss => dismiss_ringing(&app_handle, &state, &alarm_id).await,
+        };
+        if let Err(e) = result {
+            tracing::warn!(alarm_id = %alarm_id, action = ?action, "Raccourci global: {}", e);
+        }
+    });
+}
+
+/// Enregistre les raccourcis au debut d'une sonnerie et les retire a la fin
+/// (appele a chaque passage du planificateur)
+pub fn sync(app_handle: &AppHandle, ringing: bool) -> Result<(), String> {
+    if REGISTERED.swap(ringing, Ordering::SeqCst) == ringing {
+        return Ok(());
+    }
+    let manager = app_handle.global_shortcut();
+    if !ringing {
+        return manager.unregister_all().map_err(|e| e.to_string());
+    }
+
+    let settings = app_handle.state::<AppState>()
+        .config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .shortcuts
+        .clone();
+    // Un raccourci deja pris par une autre application n'empeche pas l'autre
+    for (action, keys) in settings.entries() {
+        if let Err(e) = manager.register(keys) {
+            tracing::warn!(action = ?action, "Raccourci '{}' indisponible: {}", keys, e);
+        }
+    }
+    Ok(())
+}
+
+/// Retire les raccourcis enregistres (reenregistres au prochain passage si une alarme sonne)
+pub fn reset(app_handle: &AppHandle) -> Result<(), String> {
+    if REGISTERED.swap(false, Ordering::SeqCst) {
+        app_handle.global_shortcut().unregister_all().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcut_settings() {
+        let settings = ShortcutSettings::default();
+        assert!(validate(&settings).is_ok());
+        assert_eq!(action_for(&settings, &"Escape".parse().unwrap()), Some(ShortcutAction::Dismiss));
+        assert_eq!(action_for(&settings, &"Enter".parse().unwrap()), None);
+
+        let invalid = ShortcutSettings { snooze: Some("Ctrl+Nope".to_string()), dismiss: None };
+        assert!(validate(&invalid).is_err());
+    }
+}
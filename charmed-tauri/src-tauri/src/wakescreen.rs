@@ -0,0 +1,62 @@
+// wakescreen.rs - Ecran de reveil plein ecran, au premier plan, impossible a fermer pendant la sonnerie
+// Sa vie est pilotee par le planificateur a partir de l'etat de sonnerie: la page peut
+// planter ou etre rechargee, la musique continue et la fenetre est recreee au tour suivant
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent};
+
+use crate::AppState;
+
+/// Etiquette de la fenetre (voir capabilities/wake-screen.json). Le frontend
+/// charge la meme page et affiche l'ecran de reveil d'apres cette etiquette.
+pub const LABEL: &str = "wake-screen";
+
+/// Ouvre, ramene au premier plan ou ferme l'ecran de reveil selon l'etat de la sonnerie.
+/// Appele a chaque tour du planificateur: l'arret (defi compris) ou le report ferme l'ecran.
+pub fn sync(app_handle: &AppHandle, ringing: bool) -> Result<(), String> {
+    match (ringing, app_handle.get_webview_window(LABEL)) {
+        (true, Some(window)) => raise(&window),
+        (true, None) => open(app_handle),
+        // destroy() ignore le refus de fermeture pose a la creation
+        (false, Some(window)) => window.destroy().map_err(|e| e.to_string()),
+        (false, None) => Ok(()),
+    }
+}
+
+fn open(app_handle: &AppHandle) -> Result<(), String> {
+    let window = WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::default())
+        .title("Charmed")
+        .fullscreen(true)
+        .always_on_top(true)
+        .decorations(false)
+        .closable(false)
+        .minimizable(false)
+        .skip_taskbar(true)
+        .focused(true)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let handle = app_handle.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { api, .. } = event {
+            let ringing = handle.state::<AppState>().ringing.lock().map(|r| r.is_some()).unwrap_or(true);
+            if ringing {
+                api.prevent_close();
+            }
+        }
+    });
+    tracing::info!("Ecran de reveil ouvert");
+    Ok(())
+}
+
+/// Reaffiche l'ecran s'il a ete cache ou reduit
+fn raise(window: &WebviewWindow) -> Result<(), String> {
+    let hidden = !window.is_visible().map_err(|e| e.to_string())?
+        || window.is_minimized().map_err(|e| e.to_string())?;
+    if hidden {
+        window.unminimize().map_err(|e| e.to_string())?;
+        window.show().map_err(|e| e.to_string())?;
+        window.set_fullscreen(true).map_err(|e| e.to_string())?;
+        window.set_focus().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
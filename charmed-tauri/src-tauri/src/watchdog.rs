@@ -0,0 +1,108 @@
+// watchdog.rs - Superviseur du planificateur: relance la boucle si elle s'arrete (panique)
+// ou se bloque (battement trop ancien), et consigne chaque incident
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use charmed_core::health::{self, SchedulerHealth};
+
+use crate::scheduler;
+use crate::AppState;
+
+/// Intervalle entre deux controles du planificateur
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Demarre le planificateur puis le surveille pour toute la duree de vie de l'application
+pub fn spawn(app_handle: AppHandle) {
+    if let Err(e) = start_scheduler(&app_handle) {
+        tracing::error!("Demarrage du planificateur: {}", e);
+    }
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        let mut last_check = chrono::Local::now();
+        loop {
+            interval.tick().await;
+            let now = chrono::Local::now();
+            // Ecart anormal entre deux controles: la machine sortait de veille, le
+            // planificateur n'a pas encore eu l'occasion de battre
+            let resumed = now - last_check > chrono::Duration::from_std(CHECK_INTERVAL * 3).unwrap_or_default();
+            last_check = now;
+            if resumed {
+                continue;
+            }
+            if let Err(e) = supervise(&app_handle, now) {
+                tracing::error!("Superviseur du planificateur: {}", e);
+            }
+        }
+    });
+}
+
+/// Lance une nouvelle boucle du planificateur (l'ancienne est abandonnee)
+fn start_scheduler(app_handle: &AppHandle) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    *state.scheduler_beat.lock().map_err(|e| e.to_string())? = Some(chrono::Local::now());
+    let task = scheduler::spawn(app_handle.clone());
+    if let Some(previous) = state.scheduler_task.lock().map_err(|e| e.to_string())?.replace(task) {
+        // L'abandon n'agit qu'au prochain .await: une boucle bloquee sur un verrou continue de tourner
+        previous.abort();
+        if !previous.inner().is_finished() {
+            *state.scheduler_aborted.lock().map_err(|e| e.to_string())? = Some(previous);
+        }
+    }
+    Ok(())
+}
+
+/// Vrai si la boucle abandonnee a la relance precedente tourne encore
+fn aborted_still_running(state: &AppState) -> Result<bool, String> {
+    let mut aborted = state.scheduler_aborted.lock().map_err(|e| e.to_string())?;
+    if aborted.as_ref().is_some_and(|task| task.inner().is_finished()) {
+        *aborted = None;
+    }
+    Ok(aborted.is_some())
+}
+
+fn supervise(app_handle: &AppHandle, now: chrono::DateTime<chrono::Local>) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let finished = state
+        .scheduler_task
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .is_none_or(|task| task.inner().is_finished());
+    let last_tick = *state.scheduler_beat.lock().map_err(|e| e.to_string())?;
+    if !finished && !health::is_stalled(last_tick, now) {
+        return Ok(());
+    }
+
+    // Une nouvelle boucle resterait bloquee sur le meme verrou: pas de relance en chaine
+    if aborted_still_running(&state)? {
+        tracing::error!(last_tick = ?last_tick, "Boucle precedente toujours en cours malgre l'abandon, relance differee");
+        return Ok(());
+    }
+
+    tracing::error!(finished, last_tick = ?last_tick, "Planificateur arrete ou bloque, relance");
+    {
+        let mut restarts = state.scheduler_restarts.lock().map_err(|e| e.to_string())?;
+        restarts.0 += 1;
+        restarts.1 = Some(now);
+    }
+    start_scheduler(app_handle)
+}
+
+/// Etat du planificateur pour `health_check`
+pub fn scheduler_health(state: &AppState, now: chrono::DateTime<chrono::Local>) -> Result<SchedulerHealth, String> {
+    let running = state
+        .scheduler_task
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .is_some_and(|task| !task.inner().is_finished());
+    let last_tick = *state.scheduler_beat.lock().map_err(|e| e.to_string())?;
+    let (restarts, last_restart) = *state.scheduler_restarts.lock().map_err(|e| e.to_string())?;
+    Ok(SchedulerHealth {
+        alive: running && !health::is_stalled(last_tick, now),
+        last_tick,
+        restarts,
+        last_restart,
+    })
+}
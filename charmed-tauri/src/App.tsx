@@ -1,7 +1,8 @@
 import { useState, useEffect, useCallback, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { openUrl } from "@tauri-apps/plugin-opener";
-import { Bell, Music2, Plus, Trash2, Power, ExternalLink, Check, Loader2 } from "lucide-react";
+import { Bell, BellRing, Music2, Plus, Trash2, Power, ExternalLink, Check, Loader2 } from "lucide-react";
 import "./index.css";
 
 // Type miroir de la struct Rust AlarmEntry
@@ -12,9 +13,15 @@ interface AlarmEntry {
   playlist_uri: string;
   volume: number;
   active: boolean;
+  archived?: boolean;
   days: string[];
   fade_in: boolean;
   fade_in_duration: number;
+  account?: string | null;
+  escalation?: { start_volume: number; step: number; siren_after_minutes: number } | null;
+  sunrise?: boolean;
+  wake_window?: { minutes: number } | null;
+  group_playback?: { spotify_device?: string | null; local_sound?: boolean } | null;
 }
 
 // Type miroir de la struct Rust SpotifyPlaylist
@@ -40,6 +47,7 @@ export default function App() {
   const [alarmTime, setAlarmTime] = useState("08:00");
   const [alarms, setAlarms] = useState<AlarmEntry[]>([]);
   const [triggeredAlarm, setTriggeredAlarm] = useState<AlarmEntry | null>(null);
+  const [deletedAlarmId, setDeletedAlarmId] = useState<string | null>(null);
   const lastTriggeredRef = useRef<string | null>(null);
 
   // Spotify state
@@ -52,6 +60,7 @@ export default function App() {
   const [showCodeInput, setShowCodeInput] = useState(false);
   const [clientIdInput, setClientIdInput] = useState("");
   const [showClientIdInput, setShowClientIdInput] = useState(false);
+  const [isOnline, setIsOnline] = useState(true);
 
   // Charger les données au démarrage
   useEffect(() => {
@@ -60,6 +69,36 @@ export default function App() {
     checkSpotifyAuth();
   }, []);
 
+  // État du réseau: les fonctions Spotify sont grisées hors ligne
+  useEffect(() => {
+    invoke<{ online: boolean }>("get_network_status")
+      .then((status) => setIsOnline(status.online))
+      .catch(() => {});
+    const unlisteners = [
+      listen("network-online", () => setIsOnline(true)),
+      listen("network-offline", () => setIsOnline(false)),
+      // Alarmes modifiées ailleurs (autre fenêtre, API distante, synchronisation)
+      listen<AlarmEntry[]>("alarms-changed", (event) => {
+        setAlarms(event.payload.filter((alarm) => !alarm.archived));
+      }),
+      listen<{ provider: string; authenticated: boolean }>("auth-changed", (event) => {
+        if (event.payload.provider === "spotify") {
+          setIsSpotifyAuthenticated(event.payload.authenticated);
+        }
+      }),
+      // Jeton Spotify refusé: le compte actif doit être reconnecté
+      listen<{ active: boolean }>("spotify-auth-expired", (event) => {
+        if (event.payload.active) {
+          setIsSpotifyAuthenticated(false);
+          setShowSpotifyConnect(true);
+        }
+      }),
+    ];
+    return () => {
+      unlisteners.forEach((p) => p.then((unlisten) => unlisten()));
+    };
+  }, []);
+
   const loadConfig = async () => {
     try {
       const config = await invoke<AppConfig>("get_config");
@@ -88,7 +127,13 @@ export default function App() {
   const loadPlaylists = async () => {
     try {
       const list = await invoke<SpotifyPlaylist[]>("get_spotify_playlists");
-      setPlaylists(list);
+      // Suggestions du moment (humeurs adaptées à l'heure) en tête de liste
+      const picks = await invoke<{ uri: string }[]>("recommend_playlists").catch(() => []);
+      const rank = (p: SpotifyPlaylist) => {
+        const i = picks.findIndex((r) => r.uri === p.uri);
+        return i === -1 ? picks.length : i;
+      };
+      setPlaylists([...list].sort((a, b) => rank(a) - rank(b)));
     } catch {
       // Silencieux
     }
@@ -118,24 +163,8 @@ export default function App() {
           const key = `${triggered.id}-${triggered.time}-${dateKey}`;
           if (lastTriggeredRef.current !== key) {
             lastTriggeredRef.current = key;
+            // Lecture (tous services, son local de secours) pilotée par le backend
             setTriggeredAlarm(triggered);
-
-            try {
-              if (triggered.playlist_uri && triggered.playlist_uri !== "local") {
-                // Tenter lecture Spotify
-                await invoke("play_spotify_playlist", { playlistUri: triggered.playlist_uri });
-                // Optionnel: régler le volume
-                await invoke("set_spotify_volume", { volume: triggered.volume });
-              } else {
-                // Fallback local
-                await invoke("play_local_alarm");
-              }
-            } catch (e) {
-              console.error("Erreur déclenchement alarme:", e);
-              // Fallback de sécurité
-              await invoke("play_local_alarm");
-            }
-
             setTimeout(() => setTriggeredAlarm(null), 30000);
           }
         }
@@ -157,7 +186,7 @@ export default function App() {
   // Créer une alarme
   const handleSetAlarm = async () => {
     try {
-      await invoke("set_alarm", {
+      const saved = await invoke<{ warnings: { kind: string }[] }>("set_alarm", {
         time: alarmTime,
         playlistName: selectedPlaylist?.name || "Alarme",
         playlistUri: selectedPlaylist?.uri || "local",
@@ -166,6 +195,9 @@ export default function App() {
         fadeIn: false,
         fadeInDuration: 10,
       });
+      if (saved?.warnings?.length) {
+        console.warn("Alarme enregistrée avec avertissements:", saved.warnings);
+      }
       await refreshAlarms();
       setAlarmTime("08:00");
     } catch (e) {
@@ -183,21 +215,49 @@ export default function App() {
     }
   };
 
+  // Tester l'alarme (meme chemin qu'une vraie sonnerie, arret habituel)
+  const handleTest = async (id: string) => {
+    try {
+      await invoke("test_alarm", { alarmId: id });
+    } catch (e) {
+      console.error("Erreur test alarme:", e);
+    }
+  };
+
   // Supprimer alarme
   const handleDelete = async (id: string) => {
     try {
       await invoke("delete_alarm", { alarmId: id });
+      setDeletedAlarmId(id);
       await refreshAlarms();
     } catch (e) {
       console.error("Erreur suppression:", e);
     }
   };
 
+  // Annuler la dernière suppression
+  const handleUndoDelete = async () => {
+    if (!deletedAlarmId) return;
+    try {
+      await invoke("restore_alarm", { alarmId: deletedAlarmId });
+      setDeletedAlarmId(null);
+      await refreshAlarms();
+    } catch (e) {
+      console.error("Erreur restauration:", e);
+    }
+  };
+
   // Arrêter l'alarme
   const handleStopAlarm = async () => {
     try {
-      await invoke("stop_local_alarm");
-    } catch { }
+      if (triggeredAlarm) {
+        await invoke("dismiss_alarm", { alarmId: triggeredAlarm.id });
+      } else {
+        await invoke("stop_local_alarm");
+      }
+    } catch (e) {
+      console.error("Erreur arrêt alarme:", e);
+    }
     setTriggeredAlarm(null);
   };
 
@@ -304,10 +364,17 @@ export default function App() {
               <span className="text-sm font-medium">{hasActiveAlarm ? "Actif" : "Inactif"}</span>
             </div>
 
+            {!isOnline && (
+              <div className="flex items-center gap-2 px-4 py-2 rounded-full bg-red-500/20 text-red-400" title="Spotify indisponible: les alarmes joueront le son local">
+                <span className="text-sm font-medium">Hors ligne</span>
+              </div>
+            )}
+
             {/* Spotify connect button */}
             <button
               onClick={() => setShowSpotifyConnect(!showSpotifyConnect)}
-              className={`flex items-center gap-2 px-4 py-2 rounded-full transition-colors ${isSpotifyAuthenticated ? "bg-[#1DB954]/20 text-[#1DB954]" : "bg-white/5 text-white/60 hover:bg-white/10"}`}
+              disabled={!isOnline}
+              className={`flex items-center gap-2 px-4 py-2 rounded-full transition-colors disabled:opacity-40 ${isSpotifyAuthenticated ? "bg-[#1DB954]/20 text-[#1DB954]" : "bg-white/5 text-white/60 hover:bg-white/10"}`}
             >
               {isSpotifyAuthenticated ? <Check size={18} /> : <ExternalLink size={18} />}
               <span className="text-sm font-medium">{isSpotifyAuthenticated ? "Spotify connecté" : "Connecter Spotify"}</span>
@@ -429,6 +496,7 @@ export default function App() {
               <label className="text-white/40 text-sm mb-2 block">Heure de l'alarme</label>
               <input
                 type="time"
+                step={1}
                 value={alarmTime}
                 onChange={(e) => setAlarmTime(e.target.value)}
                 className="bg-white/5 border border-white/10 rounded-2xl px-6 py-4 text-3xl text-white outline-none focus:border-[#1DB954] transition-colors w-full"
@@ -439,6 +507,7 @@ export default function App() {
               <div className="flex-1">
                 <label className="text-white/40 text-sm mb-2 block">Playlist (optionnel)</label>
                 <select
+                  disabled={!isOnline}
                   value={selectedPlaylist?.id || ""}
                   onChange={(e) => {
                     const playlist = playlists.find(p => p.id === e.target.value);
@@ -464,6 +533,19 @@ export default function App() {
           </div>
         </div>
 
+        {/* Undo delete */}
+        {deletedAlarmId && (
+          <div className="glass-panel rounded-2xl p-4 mb-4 flex items-center justify-between">
+            <span className="text-white/60 text-sm">Alarme supprimée</span>
+            <button
+              onClick={handleUndoDelete}
+              className="px-4 py-2 rounded-xl bg-white/10 text-white hover:bg-white/20 transition-colors text-sm"
+            >
+              Annuler
+            </button>
+          </div>
+        )}
+
         {/* Alarms list */}
         {alarms.length > 0 && (
           <div className="space-y-3">
@@ -486,6 +568,13 @@ export default function App() {
                 >
                   <Power size={20} />
                 </button>
+                <button
+                  onClick={() => handleTest(alarm.id)}
+                  aria-label="Tester l'alarme"
+                  className="p-3 rounded-xl bg-white/5 text-white/40 hover:text-white hover:bg-white/10 transition-colors"
+                >
+                  <BellRing size={20} />
+                </button>
                 <button
                   onClick={() => handleDelete(alarm.id)}
                   aria-label="Supprimer l'alarme"
@@ -49,7 +49,7 @@ export default function SetupGuide({ onComplete }: SetupGuideProps) {
               <ul className="list-disc list-inside ml-6 mt-2 text-white/40">
                 <li><strong>App name</strong>: Charmed</li>
                 <li><strong>App description</strong>: Spotify Alarm Clock</li>
-                <li><strong>Redirect URI</strong>: http://localhost:8888/callback</li>
+                <li><strong>Redirect URI</strong>: charmed://auth/callback</li>
               </ul>
             </li>
             <li>Cochez "I understand and agree..." et cliquez "Save"</li>
@@ -68,7 +68,7 @@ export default function SetupGuide({ onComplete }: SetupGuideProps) {
             <li>Cliquez "Add URI" et entrez:</li>
           </ol>
           <div className="bg-white/5 border border-white/10 rounded-xl p-4 font-mono text-sm">
-            http://localhost:8888/callback
+            charmed://auth/callback
           </div>
           <p className="text-white/40 text-sm">
             Cette URL est utilisee pour recevoir le code d'autorisation apres la connexion Spotify.